@@ -30,4 +30,72 @@ pub enum Expr {
     Not(Box<Self>),
 
     InterpolatedString(Vec<InterpolationSegment>),
+
+    Call(Function, Vec<Self>),
+}
+
+/// Functions callable by name inside expressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    /// `json_path(value, path)`: the values at a JSONPath-like path.
+    JsonPath,
+    /// `xpath(element, path)`: the elements, attributes or text at an `XPath` subset path.
+    XPath,
+}
+
+impl Function {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json_path" => Some(Self::JsonPath),
+            "xpath" => Some(Self::XPath),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::JsonPath => "json_path",
+            Self::XPath => "xpath",
+        }
+    }
+
+    #[must_use]
+    pub const fn arity(self) -> usize {
+        match self {
+            Self::JsonPath | Self::XPath => 2,
+        }
+    }
+}
+
+impl Expr {
+    /// The sub-expressions this expression is built from.
+    pub(crate) fn children(&self) -> Vec<&Self> {
+        match self {
+            Self::Const(_) | Self::Load(_) => Vec::new(),
+            Self::Add(a, b)
+            | Self::Sub(a, b)
+            | Self::Mul(a, b)
+            | Self::Div(a, b)
+            | Self::Mod(a, b)
+            | Self::Eq(a, b)
+            | Self::Ne(a, b)
+            | Self::Gt(a, b)
+            | Self::Ge(a, b)
+            | Self::Lt(a, b)
+            | Self::Le(a, b)
+            | Self::And(a, b)
+            | Self::Or(a, b) => vec![a.as_ref(), b.as_ref()],
+            Self::Neg(e) | Self::Not(e) => vec![e.as_ref()],
+            Self::InterpolatedString(segments) => segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    InterpolationSegment::Expression(expr) => Some(expr.as_ref()),
+                    InterpolationSegment::Literal(_) => None,
+                })
+                .collect(),
+            Self::Call(_, args) => args.iter().collect(),
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use crate::ast::{Expr, InterpolationSegment};
+use crate::query::call_function;
 use crate::value::{Value, ValueExt, VariableResolver};
 use std::fmt::Write;
 
@@ -26,6 +27,10 @@ pub fn eval_expr(expr: &Expr, resolver: &dyn VariableResolver) -> Result<Value,
                     Ok(Value::Number(left.to_number()? + right_num))
                 }
                 Value::Boolean(_) => Err("Cannot use + with boolean on left side".to_string()),
+                Value::Table(_) => Err("Cannot use + with table".to_string()),
+                Value::List(_) | Value::Object(_) => {
+                    Err("Cannot use + with list or object".to_string())
+                }
                 Value::Undefined => Err("Cannot use + with undefined".to_string()),
             }
         }
@@ -113,5 +118,13 @@ pub fn eval_expr(expr: &Expr, resolver: &dyn VariableResolver) -> Result<Value,
             }
             Ok(Value::String(result))
         }
+
+        Expr::Call(function, args) => {
+            let args = args
+                .iter()
+                .map(|arg| eval_expr(arg, resolver))
+                .collect::<Result<Vec<_>, _>>()?;
+            call_function(*function, &args)
+        }
     }
 }
@@ -154,6 +154,10 @@ impl Lexer {
                 self.advance();
                 Token::RightParen
             }
+            ',' => {
+                self.advance();
+                Token::Comma
+            }
             '=' => {
                 self.advance();
                 if self.current() == Some('=') {
@@ -220,7 +224,7 @@ impl Lexer {
                     "AND" => Token::And,
                     "OR" => Token::Or,
                     "NOT" => Token::Not,
-                    _ => return Err(format!("Unknown identifier: {ident}")),
+                    _ => Token::Identifier(ident),
                 }
             }
             '\'' => {
@@ -2,14 +2,18 @@ mod ast;
 mod evaluator;
 mod lexer;
 mod parser;
+mod query;
+mod table;
 mod token;
 mod value;
 mod variable_type;
 
-pub use ast::Expr;
+pub use ast::{Expr, Function};
 pub use evaluator::eval_expr;
 pub use lexer::Lexer;
 pub use parser::parse_expr;
+pub use query::{JsonPath, XPath, validate_paths, xml_element};
+pub use table::Table;
 pub use token::Token;
 pub use value::{Value, VariableResolver};
 pub use variable_type::VariableType;
@@ -702,4 +706,126 @@ mod tests {
             Value::String("Pi is approximately 3.14".to_string())
         );
     }
+
+    fn order() -> Value {
+        let item = |sku: &str, qty: f64| {
+            Value::Object(indexmap::IndexMap::from([
+                ("sku".to_string(), Value::String(sku.to_string())),
+                ("qty".to_string(), Value::Number(qty)),
+            ]))
+        };
+        Value::Object(indexmap::IndexMap::from([
+            ("id".to_string(), Value::Number(7.0)),
+            (
+                "items".to_string(),
+                Value::List(vec![item("A-1", 2.0), item("B-2", 5.0)]),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_json_path_queries() {
+        let value = order();
+        let select = |path: &str| JsonPath::parse(path).unwrap().select(&value);
+
+        assert_eq!(select("$.id"), Value::Number(7.0));
+        assert_eq!(select("$.items[1].sku"), Value::String("B-2".to_string()));
+        assert_eq!(select("$['items'][-1].qty"), Value::Number(5.0));
+        assert_eq!(
+            select("$.items[*].sku"),
+            Value::List(vec![
+                Value::String("A-1".to_string()),
+                Value::String("B-2".to_string())
+            ])
+        );
+        assert_eq!(select("$..qty").to_string(), "[2,5]");
+        assert_eq!(select("$.items[5]"), Value::Undefined);
+        assert_eq!(select("$"), value);
+
+        for invalid in ["id", "$.", "$[1", "$[x]", "$.items[\"sku]", "$x"] {
+            assert!(JsonPath::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_xpath_queries() {
+        let element = |name: &str, attributes: &[(&str, &str)], text: &str, children| {
+            xml_element(
+                name.to_string(),
+                attributes
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), Value::String((*v).to_string())))
+                    .collect(),
+                text.to_string(),
+                children,
+            )
+        };
+        let root = element(
+            "order",
+            &[("id", "7")],
+            "",
+            vec![
+                element("item", &[("sku", "A-1")], "Pen", Vec::new()),
+                element(
+                    "item",
+                    &[("sku", "B-2")],
+                    "Ink",
+                    vec![element("note", &[], "fragile", Vec::new())],
+                ),
+            ],
+        );
+        let select = |path: &str| XPath::parse(path).unwrap().select(&root);
+
+        assert_eq!(select("/order/@id"), Value::String("7".to_string()));
+        assert_eq!(
+            select("order/item[2]/text()"),
+            Value::String("Ink".to_string())
+        );
+        assert_eq!(
+            select("//item[@sku='A-1']/text()"),
+            Value::String("Pen".to_string())
+        );
+        assert_eq!(select("//item[note='fragile']/@sku").to_string(), "B-2");
+        assert_eq!(select("/order/*/@sku").to_string(), "[\"A-1\",\"B-2\"]");
+        assert_eq!(
+            select("//note/text()"),
+            Value::String("fragile".to_string())
+        );
+        assert_eq!(select("/invoice"), Value::Undefined);
+        assert_eq!(select("/"), root);
+
+        for invalid in [
+            "",
+            "/order/",
+            "/order/@id/text()",
+            "//item[0]",
+            "/a/..",
+            "/a[b=c]",
+        ] {
+            assert!(XPath::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_path_functions() {
+        let mut vars = HashMap::new();
+        vars.insert("order".to_string(), order());
+        let resolver = MockResolver { vars };
+
+        let expr = parse_expr("json_path(@order, \"$.items[0].qty\") * 3").unwrap();
+        assert_eq!(eval_expr(&expr, &resolver).unwrap(), Value::Number(6.0));
+
+        assert!(parse_expr("json_path(@order)").is_err());
+        assert!(parse_expr("lookup(@order, \"$\")").is_err());
+        assert!(parse_expr("json_path").is_err());
+
+        let expr = parse_expr("xpath(@order, \"/order\")").unwrap();
+        assert_eq!(eval_expr(&expr, &resolver).unwrap(), Value::Undefined);
+
+        let expr = parse_expr("1 + json_path(@order, \"items\")").unwrap();
+        assert!(validate_paths(&expr).unwrap_err().contains("'items'"));
+        assert!(eval_expr(&expr, &resolver).is_err());
+        let expr = parse_expr("!(xpath(@order, \"//item[@sku='A-1']\") == 1)").unwrap();
+        assert!(validate_paths(&expr).is_ok());
+    }
 }
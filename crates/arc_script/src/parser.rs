@@ -1,4 +1,4 @@
-use crate::ast::{Expr, InterpolationSegment};
+use crate::ast::{Expr, Function, InterpolationSegment};
 use crate::lexer::Lexer;
 use crate::token::Token;
 use crate::value::Value;
@@ -244,6 +244,35 @@ impl Parser {
         Ok(segments)
     }
 
+    fn parse_call(&mut self, name: &str) -> Result<Expr, String> {
+        if self.current() != Some(&Token::LeftParen) {
+            return Err(format!("Unknown identifier: {name}"));
+        }
+        let function =
+            Function::from_name(name).ok_or_else(|| format!("Unknown function: {name}"))?;
+        self.advance();
+
+        let mut args = Vec::new();
+        if self.current() != Some(&Token::RightParen) {
+            args.push(self.parse_or()?);
+            while self.current() == Some(&Token::Comma) {
+                self.advance();
+                args.push(self.parse_or()?);
+            }
+        }
+        self.expect(&Token::RightParen)?;
+
+        if args.len() != function.arity() {
+            return Err(format!(
+                "{}() takes {} arguments, found {}",
+                function.name(),
+                function.arity(),
+                args.len()
+            ));
+        }
+        Ok(Expr::Call(function, args))
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, String> {
         match self.current() {
             Some(Token::Number(n)) => {
@@ -279,6 +308,11 @@ impl Parser {
                 self.advance();
                 Ok(Expr::Load(name_cloned))
             }
+            Some(Token::Identifier(name)) => {
+                let name_cloned = name.clone();
+                self.advance();
+                self.parse_call(&name_cloned)
+            }
             Some(Token::LeftParen) => {
                 self.advance();
                 let value = self.parse_or()?;
@@ -0,0 +1,545 @@
+use crate::ast::{Expr, Function};
+use crate::value::Value;
+use indexmap::IndexMap;
+
+const XML_NAME: &str = "name";
+const XML_ATTRIBUTES: &str = "attributes";
+const XML_TEXT: &str = "text";
+const XML_CHILDREN: &str = "children";
+
+/// Builds the object a parsed XML element is represented by: its tag `name`, its
+/// `attributes`, the `text` directly inside it and its `children` elements.
+#[must_use]
+pub fn xml_element(
+    name: String,
+    attributes: IndexMap<String, Value>,
+    text: String,
+    children: Vec<Value>,
+) -> Value {
+    Value::Object(IndexMap::from([
+        (XML_NAME.to_string(), Value::String(name)),
+        (XML_ATTRIBUTES.to_string(), Value::Object(attributes)),
+        (XML_TEXT.to_string(), Value::String(text)),
+        (XML_CHILDREN.to_string(), Value::List(children)),
+    ]))
+}
+
+/// Checks the syntax of every literal path passed to `json_path` or `xpath` in
+/// `expr`, so a mistyped path is reported before the expression is evaluated.
+///
+/// # Errors
+///
+/// Returns the first invalid path and what is wrong with it.
+pub fn validate_paths(expr: &Expr) -> Result<(), String> {
+    if let Expr::Call(function, args) = expr
+        && let Some(Expr::Const(Value::String(path))) = args.get(1)
+    {
+        match function {
+            Function::JsonPath => {
+                JsonPath::parse(path)?;
+            }
+            Function::XPath => {
+                XPath::parse(path)?;
+            }
+        }
+    }
+    expr.children().into_iter().try_for_each(validate_paths)
+}
+
+/// Evaluates a path function on already evaluated arguments.
+pub fn call_function(function: Function, args: &[Value]) -> Result<Value, String> {
+    let [value, path] = args else {
+        return Err(format!(
+            "{}() takes {} arguments, found {}",
+            function.name(),
+            function.arity(),
+            args.len()
+        ));
+    };
+    let Value::String(path) = path else {
+        return Err(format!(
+            "{}() expects the path as a string",
+            function.name()
+        ));
+    };
+    match function {
+        Function::JsonPath => Ok(JsonPath::parse(path)?.select(value)),
+        Function::XPath => Ok(XPath::parse(path)?.select(value)),
+    }
+}
+
+/// One match is returned as is, several as a list and none as `Undefined`.
+fn collapse(matches: &[&Value]) -> Value {
+    match matches {
+        [] => Value::Undefined,
+        [single] => (*single).clone(),
+        _ => Value::List(matches.iter().map(|&value| value.clone()).collect()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonSelector {
+    Name(String),
+    /// Position in a list, counted from the end when negative.
+    Index(i64),
+    Wildcard,
+}
+
+impl JsonSelector {
+    fn apply<'a>(&self, value: &'a Value, out: &mut Vec<&'a Value>) {
+        match (self, value) {
+            (Self::Name(name), Value::Object(fields)) => out.extend(fields.get(name)),
+            (Self::Index(index), Value::List(items)) => {
+                let position = if *index < 0 {
+                    usize::try_from(index.unsigned_abs())
+                        .ok()
+                        .and_then(|back| items.len().checked_sub(back))
+                } else {
+                    usize::try_from(*index).ok()
+                };
+                out.extend(position.and_then(|p| items.get(p)));
+            }
+            (Self::Wildcard, Value::List(items)) => out.extend(items),
+            (Self::Wildcard, Value::Object(fields)) => out.extend(fields.values()),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct JsonStep {
+    /// Applies the selector to the value and everything nested in it (`..`).
+    recursive: bool,
+    selector: JsonSelector,
+}
+
+/// A JSONPath-like query: `$` followed by `.name` or `['name']`, `[index]`
+/// (negative from the end), `.*` or `[*]`, with `..` for recursive descent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    steps: Vec<JsonStep>,
+}
+
+impl JsonPath {
+    /// # Errors
+    ///
+    /// Returns an error describing the first syntax problem in `path`.
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let invalid = |message: &str| format!("Invalid JSON path '{path}': {message}");
+        let chars: Vec<char> = path.trim().chars().collect();
+        if chars.first() != Some(&'$') {
+            return Err(invalid("it must start with '$'"));
+        }
+
+        let mut steps = Vec::new();
+        let mut i = 1;
+        while i < chars.len() {
+            let (recursive, selector, next) = match chars[i] {
+                '.' => {
+                    let recursive = chars.get(i + 1) == Some(&'.');
+                    let start = if recursive { i + 2 } else { i + 1 };
+                    if recursive && chars.get(start) == Some(&'[') {
+                        let (selector, next) =
+                            parse_json_bracket(&chars, start).map_err(|e| invalid(&e))?;
+                        (true, selector, next)
+                    } else {
+                        let end = (start..chars.len())
+                            .find(|&j| chars[j] == '.' || chars[j] == '[')
+                            .unwrap_or(chars.len());
+                        let name: String = chars[start..end].iter().collect();
+                        let selector = match name.as_str() {
+                            "" => return Err(invalid("expected a name after '.'")),
+                            "*" => JsonSelector::Wildcard,
+                            _ => JsonSelector::Name(name),
+                        };
+                        (recursive, selector, end)
+                    }
+                }
+                '[' => {
+                    let (selector, next) =
+                        parse_json_bracket(&chars, i).map_err(|e| invalid(&e))?;
+                    (false, selector, next)
+                }
+                c => return Err(invalid(&format!("unexpected '{c}'"))),
+            };
+            steps.push(JsonStep {
+                recursive,
+                selector,
+            });
+            i = next;
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// The value at this path in `root`: one match as is, several as a list and
+    /// none as `Undefined`.
+    #[must_use]
+    pub fn select(&self, root: &Value) -> Value {
+        let mut current = vec![root];
+        for step in &self.steps {
+            let mut candidates = Vec::new();
+            if step.recursive {
+                for value in current {
+                    descendants_or_self(value, &mut candidates);
+                }
+            } else {
+                candidates = current;
+            }
+
+            current = Vec::new();
+            for value in candidates {
+                step.selector.apply(value, &mut current);
+            }
+        }
+        collapse(&current)
+    }
+}
+
+/// Parses `[index]`, `[*]` or `['name']` starting at the `[` at `start`, and
+/// returns the selector with the position after the closing `]`.
+fn parse_json_bracket(chars: &[char], start: usize) -> Result<(JsonSelector, usize), String> {
+    let open = start + 1;
+    if let Some(&quote) = chars.get(open).filter(|&&c| c == '\'' || c == '"') {
+        let name_end = (open + 1..chars.len())
+            .find(|&j| chars[j] == quote)
+            .ok_or("unterminated name in brackets")?;
+        if chars.get(name_end + 1) != Some(&']') {
+            return Err("expected ']' after the quoted name".to_string());
+        }
+        let name = chars[open + 1..name_end].iter().collect();
+        return Ok((JsonSelector::Name(name), name_end + 2));
+    }
+
+    let close = (open..chars.len())
+        .find(|&j| chars[j] == ']')
+        .ok_or("missing ']'")?;
+    let content: String = chars[open..close].iter().collect();
+    let selector = match content.trim() {
+        "*" => JsonSelector::Wildcard,
+        index => index
+            .parse()
+            .map(JsonSelector::Index)
+            .map_err(|_| format!("'{index}' in brackets is not an index, '*' or a quoted name"))?,
+    };
+    Ok((selector, close + 1))
+}
+
+fn descendants_or_self<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    match value {
+        Value::List(items) => items.iter().for_each(|item| descendants_or_self(item, out)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| descendants_or_self(field, out)),
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeTest {
+    Element(String),
+    AnyElement,
+    Attribute(String),
+    Text,
+    SelfNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    /// 1-based position among the matches of the step.
+    Position(usize),
+    /// `[@name]` or `[@name='value']`.
+    Attribute(String, Option<String>),
+    /// `[name]` or `[name='value']`, comparing the text of a child element.
+    Child(String, Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct XStep {
+    /// Reached with `//`, so the test applies at any depth.
+    descendant: bool,
+    test: NodeTest,
+    predicates: Vec<Predicate>,
+}
+
+/// A node an `XPath` step starts from: the document, whose only child is the
+/// root element, or a value inside it.
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Document(&'a Value),
+    Value(&'a Value),
+}
+
+impl<'a> Node<'a> {
+    fn child_elements(self) -> &'a [Value] {
+        match self {
+            Self::Document(root) => std::slice::from_ref(root),
+            Self::Value(value) => field(value, XML_CHILDREN)
+                .and_then(Value::as_list)
+                .map_or(&[], Vec::as_slice),
+        }
+    }
+
+    fn descendants_or_self(self, out: &mut Vec<Self>) {
+        out.push(self);
+        for child in self.child_elements() {
+            Self::Value(child).descendants_or_self(out);
+        }
+    }
+}
+
+fn field<'a>(element: &'a Value, name: &str) -> Option<&'a Value> {
+    element.as_object().and_then(|fields| fields.get(name))
+}
+
+fn element_name(element: &Value) -> Option<&str> {
+    field(element, XML_NAME).and_then(Value::as_str)
+}
+
+fn attribute<'a>(element: &'a Value, name: &str) -> Option<&'a Value> {
+    field(element, XML_ATTRIBUTES)
+        .and_then(Value::as_object)
+        .and_then(|attributes| attributes.get(name))
+}
+
+impl NodeTest {
+    fn apply<'a>(&self, node: Node<'a>) -> Vec<&'a Value> {
+        match (self, node) {
+            (Self::Element(name), _) => node
+                .child_elements()
+                .iter()
+                .filter(|child| element_name(child) == Some(name.as_str()))
+                .collect(),
+            (Self::AnyElement, _) => node.child_elements().iter().collect(),
+            (Self::Attribute(name), Node::Value(element)) => {
+                attribute(element, name).into_iter().collect()
+            }
+            (Self::Text, Node::Value(element)) => field(element, XML_TEXT).into_iter().collect(),
+            (Self::SelfNode, Node::Value(value) | Node::Document(value)) => vec![value],
+            (Self::Attribute(_) | Self::Text, Node::Document(_)) => Vec::new(),
+        }
+    }
+}
+
+impl Predicate {
+    fn filter<'a>(&self, matches: Vec<&'a Value>) -> Vec<&'a Value> {
+        let text_matches = |value: &Value, expected: Option<&String>| {
+            expected.is_none_or(|expected| value.as_str() == Some(expected.as_str()))
+        };
+        match self {
+            Self::Position(position) => matches.get(position - 1).copied().into_iter().collect(),
+            Self::Attribute(name, expected) => matches
+                .into_iter()
+                .filter(|element| {
+                    attribute(element, name)
+                        .is_some_and(|value| text_matches(value, expected.as_ref()))
+                })
+                .collect(),
+            Self::Child(name, expected) => matches
+                .into_iter()
+                .filter(|&element| {
+                    Node::Value(element).child_elements().iter().any(|child| {
+                        element_name(child) == Some(name.as_str())
+                            && field(child, XML_TEXT)
+                                .is_some_and(|text| text_matches(text, expected.as_ref()))
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A subset of `XPath` over elements built by [`xml_element`].
+///
+/// Steps are separated by `/` or `//` and are element names, `*`, `.`,
+/// `@attribute` or `text()`, each filtered by `[n]`, `[@attr]`, `[@attr='v']`,
+/// `[child]` or `[child='v']`. Relative paths start at the document, like absolute ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XPath {
+    steps: Vec<XStep>,
+}
+
+impl XPath {
+    /// # Errors
+    ///
+    /// Returns an error describing the first syntax problem in `path`.
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let invalid = |message: &str| format!("Invalid XPath '{path}': {message}");
+        let chars: Vec<char> = path.trim().chars().collect();
+        if chars.is_empty() {
+            return Err(invalid("it is empty"));
+        }
+        if chars == ['/'] {
+            return Ok(Self { steps: Vec::new() });
+        }
+
+        let mut steps = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let mut descendant = false;
+            if chars[i] == '/' {
+                i += 1;
+                if chars.get(i) == Some(&'/') {
+                    descendant = true;
+                    i += 1;
+                }
+            } else if i > 0 {
+                return Err(invalid(&format!("unexpected '{}'", chars[i])));
+            }
+
+            let (test, next) = parse_node_test(&chars, i).map_err(|e| invalid(&e))?;
+            i = next;
+            let mut predicates = Vec::new();
+            while chars.get(i) == Some(&'[') {
+                let (predicate, next) = parse_predicate(&chars, i).map_err(|e| invalid(&e))?;
+                predicates.push(predicate);
+                i = next;
+            }
+            steps.push(XStep {
+                descendant,
+                test,
+                predicates,
+            });
+        }
+
+        let ends_early = steps
+            .iter()
+            .rev()
+            .skip(1)
+            .any(|step| matches!(step.test, NodeTest::Attribute(_) | NodeTest::Text));
+        if ends_early {
+            return Err(invalid("attributes and text() can only be the last step"));
+        }
+        Ok(Self { steps })
+    }
+
+    /// The elements, attribute values or text at this path below `root`, the
+    /// root element: one match as is, several as a list and none as `Undefined`.
+    #[must_use]
+    pub fn select(&self, root: &Value) -> Value {
+        let mut current = vec![Node::Document(root)];
+        let mut matched = vec![root];
+        for step in &self.steps {
+            let mut bases = Vec::new();
+            if step.descendant {
+                for node in current {
+                    node.descendants_or_self(&mut bases);
+                }
+            } else {
+                bases = current;
+            }
+
+            matched = Vec::new();
+            for base in bases {
+                let mut step_matches = step.test.apply(base);
+                for predicate in &step.predicates {
+                    step_matches = predicate.filter(step_matches);
+                }
+                matched.extend(step_matches);
+            }
+            current = matched.iter().map(|&value| Node::Value(value)).collect();
+        }
+        collapse(&matched)
+    }
+}
+
+fn is_name_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | ':')
+}
+
+fn is_name(text: &str) -> bool {
+    text.chars().next().is_some_and(is_name_start) && text.chars().all(is_name_char)
+}
+
+fn read_name(chars: &[char], start: usize) -> (String, usize) {
+    if !chars.get(start).copied().is_some_and(is_name_start) {
+        return (String::new(), start);
+    }
+    let end = (start..chars.len())
+        .find(|&j| !is_name_char(chars[j]))
+        .unwrap_or(chars.len());
+    (chars[start..end].iter().collect(), end)
+}
+
+fn parse_node_test(chars: &[char], start: usize) -> Result<(NodeTest, usize), String> {
+    match chars.get(start) {
+        Some('.') if chars.get(start + 1) == Some(&'.') => {
+            Err("parent steps ('..') are not supported".to_string())
+        }
+        Some('.') => Ok((NodeTest::SelfNode, start + 1)),
+        Some('*') => Ok((NodeTest::AnyElement, start + 1)),
+        Some('@') => match read_name(chars, start + 1) {
+            (name, _) if name.is_empty() => Err("expected an attribute name after '@'".to_string()),
+            (name, end) => Ok((NodeTest::Attribute(name), end)),
+        },
+        _ => {
+            let (name, end) = read_name(chars, start);
+            if name.is_empty() {
+                return Err("expected a step".to_string());
+            }
+            if chars.get(end) == Some(&'(') {
+                return if name == "text" && chars.get(end + 1) == Some(&')') {
+                    Ok((NodeTest::Text, end + 2))
+                } else {
+                    Err(format!("unsupported function '{name}()'"))
+                };
+            }
+            Ok((NodeTest::Element(name), end))
+        }
+    }
+}
+
+/// Parses the predicate starting at the `[` at `start`, and returns it with the
+/// position after the closing `]`.
+fn parse_predicate(chars: &[char], start: usize) -> Result<(Predicate, usize), String> {
+    let mut quote = None;
+    let close = (start + 1..chars.len())
+        .find(|&j| match (quote, chars[j]) {
+            (None, '\'' | '"') => {
+                quote = Some(chars[j]);
+                false
+            }
+            (Some(q), c) if c == q => {
+                quote = None;
+                false
+            }
+            (None, ']') => true,
+            _ => false,
+        })
+        .ok_or("missing ']'")?;
+    let content: String = chars[start + 1..close].iter().collect();
+    let content = content.trim();
+
+    if !content.is_empty() && content.chars().all(|c| c.is_ascii_digit()) {
+        return match content.parse::<usize>() {
+            Ok(position) if position >= 1 => Ok((Predicate::Position(position), close + 1)),
+            _ => Err(format!("position '{content}' must be 1 or more")),
+        };
+    }
+
+    let (target, expected) = match content.split_once('=') {
+        Some((target, literal)) => (target.trim(), Some(parse_literal(literal.trim())?)),
+        None => (content, None),
+    };
+    let predicate = match target.strip_prefix('@') {
+        Some(name) if is_name(name) => Predicate::Attribute(name.to_string(), expected),
+        None if is_name(target) => Predicate::Child(target.to_string(), expected),
+        _ => return Err(format!("unsupported predicate '[{content}]'")),
+    };
+    Ok((predicate, close + 1))
+}
+
+fn parse_literal(literal: &str) -> Result<String, String> {
+    let mut chars = literal.chars();
+    match (chars.next(), chars.next_back()) {
+        (Some(open), Some(close)) if open == close && matches!(open, '\'' | '"') => {
+            Ok(chars.collect())
+        }
+        _ => Err(format!("expected a quoted value, found '{literal}'")),
+    }
+}
@@ -0,0 +1,50 @@
+use crate::value::Value;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Rows of values with named columns, e.g. a range read from a spreadsheet.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    #[must_use]
+    pub const fn new(columns: Vec<String>, rows: Vec<Vec<Value>>) -> Self {
+        Self { columns, rows }
+    }
+
+    #[must_use]
+    pub const fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    #[must_use]
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    #[must_use]
+    pub fn get(&self, row: usize, column: &str) -> Option<&Value> {
+        let col = self.column_index(column)?;
+        self.rows.get(row).and_then(|r| r.get(col))
+    }
+}
+
+/// One line per row, cells separated by tabs, with the column names as the first line.
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.columns.join("\t"))?;
+        for row in &self.rows {
+            writeln!(f)?;
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    write!(f, "\t")?;
+                }
+                write!(f, "{value}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ pub enum Token {
     Boolean(bool),
     String(String),
     Variable(String),
+    Identifier(String),
 
     Plus,
     Minus,
@@ -24,4 +25,5 @@ pub enum Token {
 
     LeftParen,
     RightParen,
+    Comma,
 }
@@ -1,4 +1,6 @@
+use crate::table::Table;
 use crate::variable_type::VariableType;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -7,6 +9,11 @@ pub enum Value {
     Number(f64),
     Boolean(bool),
     String(String),
+    Table(Table),
+    /// Ordered items, e.g. a JSON array.
+    List(Vec<Self>),
+    /// Named fields in document order, e.g. a JSON object or a parsed XML element.
+    Object(IndexMap<String, Self>),
     Undefined,
 }
 
@@ -36,7 +43,10 @@ impl Value {
     #[must_use]
     pub const fn get_type(&self) -> VariableType {
         match self {
-            Self::String(_) => VariableType::String,
+            // Tables and structured values have no declarable type and are shown as text.
+            Self::String(_) | Self::Table(_) | Self::List(_) | Self::Object(_) => {
+                VariableType::String
+            }
             Self::Boolean(_) => VariableType::Boolean,
             Self::Number(_) | Self::Undefined => VariableType::Number,
         }
@@ -91,6 +101,33 @@ impl Value {
         }
     }
 
+    #[must_use]
+    pub const fn as_table(&self) -> Option<&Table> {
+        if let Self::Table(t) = self {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn as_list(&self) -> Option<&Vec<Self>> {
+        if let Self::List(items) = self {
+            Some(items)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn as_object(&self) -> Option<&IndexMap<String, Self>> {
+        if let Self::Object(fields) = self {
+            Some(fields)
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     pub fn as_str(&self) -> Option<&str> {
         match self {
@@ -112,11 +149,61 @@ impl fmt::Display for Value {
                     write!(f, "{n}")
                 }
             }
+            Self::Table(t) => write!(f, "{t}"),
+            Self::List(_) | Self::Object(_) => write_json(f, self),
             Self::Undefined => write!(f, ""),
         }
     }
 }
 
+/// Writes `value` as compact JSON, the way lists and objects are displayed.
+fn write_json(f: &mut fmt::Formatter<'_>, value: &Value) -> fmt::Result {
+    match value {
+        Value::Number(_) | Value::Boolean(_) => write!(f, "{value}"),
+        Value::String(s) => write_json_string(f, s),
+        Value::Table(t) => write_json_string(f, &t.to_string()),
+        Value::List(items) => {
+            write!(f, "[")?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write_json(f, item)?;
+            }
+            write!(f, "]")
+        }
+        Value::Object(fields) => {
+            write!(f, "{{")?;
+            for (i, (name, field)) in fields.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write_json_string(f, name)?;
+                write!(f, ":")?;
+                write_json(f, field)?;
+            }
+            write!(f, "}}")
+        }
+        Value::Undefined => write!(f, "null"),
+    }
+}
+
+fn write_json_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
 pub trait VariableResolver {
     /// Resolves a variable by name.
     ///
@@ -0,0 +1,993 @@
+use super::{CellRange, CellRef, CellValue, MAX_COLUMN, MAX_ROW, column_index, column_name};
+use crate::package::Package;
+use chrono::{NaiveDate, Timelike};
+use quick_xml::Writer;
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::QName;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+
+const WORKBOOK_PART: &str = "xl/workbook.xml";
+const WORKBOOK_RELS_PART: &str = "xl/_rels/workbook.xml.rels";
+const STYLES_PART: &str = "xl/styles.xml";
+const CALC_CHAIN_PART: &str = "xl/calcChain.xml";
+const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+const WORKSHEET_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml";
+const WORKSHEET_REL_TYPE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet";
+
+/// Built-in number formats used for dates written into cells without a date format.
+const DATE_NUM_FMT: u32 = 14;
+const DATETIME_NUM_FMT: u32 = 22;
+
+/// Cell formats for written dates, by style index.
+struct DateStyles {
+    date: u32,
+    datetime: u32,
+    /// Every cell format whose number format shows a date or time.
+    dates: HashSet<u32>,
+}
+
+type CellEdits = BTreeMap<(u32, u32), CellValue>;
+
+/// What Excel writes for a reference that moved off the sheet.
+const REF_ERROR: &str = "#REF!";
+
+/// Edits cell values of an existing .xlsx package in place.
+///
+/// Only the `sheetData` of edited worksheets is rewritten: styles, formulas in
+/// other cells, merged cells, other sheets and every other part of the package
+/// are saved back unchanged. Edited cells keep their style; overwriting a
+/// formula cell replaces the formula with the value. Cells that shared the
+/// overwritten formula get their own copy of it.
+pub struct XlsxEditor {
+    package: Package,
+    /// Sheet names with the package path of their worksheet part, in workbook order.
+    sheets: Vec<(String, String)>,
+    edits: BTreeMap<String, CellEdits>,
+}
+
+impl XlsxEditor {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let mut editor = Self {
+            package: Package::open(path.as_ref(), "Excel")?,
+            sheets: Vec::new(),
+            edits: BTreeMap::new(),
+        };
+        editor.sheets = editor.read_sheets()?;
+        Ok(editor)
+    }
+
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.sheets.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    pub fn set_cell(&mut self, sheet: &str, cell: &str, value: CellValue) -> Result<(), String> {
+        let cell = CellRef::parse(cell)?;
+        self.write_rows(sheet, cell, &[vec![value]])
+    }
+
+    /// Sets the cells of `rows` starting at `start`. `CellValue::Empty` clears a cell.
+    pub fn write_rows(
+        &mut self,
+        sheet: &str,
+        start: CellRef,
+        rows: &[Vec<CellValue>],
+    ) -> Result<(), String> {
+        let part = self.sheet_part(sheet)?.to_string();
+        let edits = self.edits.entry(part).or_default();
+
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let target = CellRef {
+                    row: start.row + r as u32,
+                    col: start.col + c as u32,
+                };
+                if target.row > MAX_ROW || target.col > MAX_COLUMN {
+                    return Err(format!("Cell {} is outside the worksheet", target));
+                }
+                if let CellValue::Number(n) = value
+                    && !n.is_finite()
+                {
+                    return Err(format!("Cannot write {} to cell {}", n, target));
+                }
+                edits.insert((target.row, target.col), value.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends an empty worksheet named `name` after the existing ones.
+    pub fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+        if name.trim().is_empty() || name.chars().count() > 31 {
+            return Err(format!("Invalid sheet name: '{}'", name));
+        }
+        if self
+            .sheets
+            .iter()
+            .any(|(n, _)| n.eq_ignore_ascii_case(name))
+        {
+            return Err(format!("Sheet '{}' already exists", name));
+        }
+
+        let workbook = self.package.part_text(WORKBOOK_PART)?;
+        let rels = self.package.part_text(WORKBOOK_RELS_PART)?;
+        let content_types = self.package.part_text(CONTENT_TYPES_PART)?;
+
+        let sheet_id = (1..)
+            .find(|id| !workbook.contains(&format!("sheetId=\"{}\"", id)))
+            .unwrap_or(1);
+        let rel_id = (1..)
+            .map(|n| format!("rId{}", n))
+            .find(|id| !rels.contains(&format!("Id=\"{}\"", id)))
+            .unwrap_or_default();
+        let file_number = (1..)
+            .find(|n| {
+                self.package
+                    .part(&format!("xl/worksheets/sheet{}.xml", n))
+                    .is_none()
+            })
+            .unwrap_or(1);
+        let part = format!("xl/worksheets/sheet{}.xml", file_number);
+
+        let workbook = insert_before(
+            &workbook,
+            "</sheets>",
+            &format!(
+                r#"<sheet name="{}" sheetId="{}" r:id="{}"/>"#,
+                escape(name),
+                sheet_id,
+                rel_id
+            ),
+        )?;
+        let rels = insert_before(
+            &rels,
+            "</Relationships>",
+            &format!(
+                r#"<Relationship Id="{}" Type="{}" Target="worksheets/sheet{}.xml"/>"#,
+                rel_id, WORKSHEET_REL_TYPE, file_number
+            ),
+        )?;
+        let content_types = insert_before(
+            &content_types,
+            "</Types>",
+            &format!(
+                r#"<Override PartName="/{}" ContentType="{}"/>"#,
+                part, WORKSHEET_CONTENT_TYPE
+            ),
+        )?;
+
+        self.package.set_part(WORKBOOK_PART, workbook.into_bytes());
+        self.package.set_part(WORKBOOK_RELS_PART, rels.into_bytes());
+        self.package
+            .set_part(CONTENT_TYPES_PART, content_types.into_bytes());
+        self.package.set_part(
+            &part,
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+                r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+                r#"<dimension ref="A1"/><sheetData/></worksheet>"#
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+        self.sheets.push((name.to_string(), part));
+        Ok(())
+    }
+
+    /// Applies the pending edits and writes the package to `path`.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        if !self.edits.is_empty() {
+            self.apply_edits()?;
+        }
+
+        self.package.save(path.as_ref())
+    }
+
+    fn apply_edits(&mut self) -> Result<(), String> {
+        let needs_date_styles = self
+            .edits
+            .values()
+            .flat_map(BTreeMap::values)
+            .any(|value| matches!(value, CellValue::DateTime(_)));
+        let date_styles = if needs_date_styles {
+            Some(self.add_date_styles()?)
+        } else {
+            None
+        };
+
+        for (part, edits) in std::mem::take(&mut self.edits) {
+            let xml = self
+                .package
+                .part(&part)
+                .ok_or_else(|| format!("Worksheet part {} is missing", part))?;
+            let xml = rewrite_sheet(xml, &edits, date_styles.as_ref())
+                .map_err(|e| format!("Failed to update {}: {}", part, e))?;
+            self.package.set_part(&part, xml);
+        }
+
+        self.force_recalculation()
+    }
+
+    /// Formulas depending on edited cells are recalculated when the file is opened;
+    /// the calculation chain is dropped because it may list overwritten formulas.
+    fn force_recalculation(&mut self) -> Result<(), String> {
+        let workbook = self.package.part_text(WORKBOOK_PART)?;
+        let mut has_calc_pr = false;
+        for_each_element(&workbook, b"calcPr", |_| {
+            has_calc_pr = true;
+            Ok(())
+        })?;
+        let mut has_defined_names = false;
+        for_each_element(&workbook, b"definedNames", |_| {
+            has_defined_names = true;
+            Ok(())
+        })?;
+        // `calcPr` follows `definedNames`, or `sheets` when there are none.
+        let anchor: &[u8] = if has_defined_names {
+            b"definedNames"
+        } else {
+            b"sheets"
+        };
+        let calc_pr = |sibling: QName| {
+            let mut calc_pr = BytesStart::new(qualified_name(sibling, "calcPr"));
+            calc_pr.push_attribute(("fullCalcOnLoad", "1"));
+            calc_pr
+        };
+
+        let workbook = rewrite_xml(&workbook, |writer, event| {
+            let is_start = matches!(event, Event::Start(_));
+            match event {
+                Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"calcPr" => {
+                    let mut updated = without_attribute(&e, b"fullCalcOnLoad");
+                    updated.push_attribute(("fullCalcOnLoad", "1"));
+                    write_event(
+                        writer,
+                        if is_start {
+                            Event::Start(updated)
+                        } else {
+                            Event::Empty(updated)
+                        },
+                    )
+                }
+                Event::End(e) if !has_calc_pr && e.local_name().as_ref() == anchor => {
+                    let element = calc_pr(e.name());
+                    write_event(writer, Event::End(e))?;
+                    write_event(writer, Event::Empty(element))
+                }
+                Event::Empty(e) if !has_calc_pr && e.local_name().as_ref() == anchor => {
+                    let element = calc_pr(e.name());
+                    write_event(writer, Event::Empty(e))?;
+                    write_event(writer, Event::Empty(element))
+                }
+                event => write_event(writer, event),
+            }
+        })?;
+        self.package.set_part(WORKBOOK_PART, workbook.into_bytes());
+
+        if self.package.part(CALC_CHAIN_PART).is_some() {
+            self.package.remove_part(CALC_CHAIN_PART);
+            let rels = remove_element_containing(
+                &self.package.part_text(WORKBOOK_RELS_PART)?,
+                "calcChain",
+            );
+            let content_types = remove_element_containing(
+                &self.package.part_text(CONTENT_TYPES_PART)?,
+                "calcChain",
+            );
+            self.package.set_part(WORKBOOK_RELS_PART, rels.into_bytes());
+            self.package
+                .set_part(CONTENT_TYPES_PART, content_types.into_bytes());
+        }
+        Ok(())
+    }
+
+    /// Finds the cell formats for dates and date-times, adding the missing ones,
+    /// and notes every cell format that already shows a date.
+    fn add_date_styles(&mut self) -> Result<DateStyles, String> {
+        let styles = self.package.part_text(STYLES_PART)?;
+
+        let mut custom_date_formats = HashSet::new();
+        for_each_element(&styles, b"numFmt", |e| {
+            if let (Some(id), Some(code)) =
+                (attribute(e, b"numFmtId")?, attribute(e, b"formatCode")?)
+                && let Ok(id) = id.parse::<u32>()
+                && is_date_format(&code)
+            {
+                custom_date_formats.insert(id);
+            }
+            Ok(())
+        })?;
+
+        // The number format of each cell format, in style index order.
+        let mut formats = Vec::new();
+        let mut has_cell_xfs = false;
+        let mut in_cell_xfs = false;
+        let mut reader = quick_xml::Reader::from_str(&styles);
+        loop {
+            let event = reader.read_event().map_err(|e| e.to_string())?;
+            let is_start = matches!(event, Event::Start(_));
+            match event {
+                Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"cellXfs" => {
+                    has_cell_xfs = true;
+                    in_cell_xfs = is_start;
+                }
+                Event::End(e) if e.local_name().as_ref() == b"cellXfs" => in_cell_xfs = false,
+                Event::Start(e) | Event::Empty(e)
+                    if in_cell_xfs && e.local_name().as_ref() == b"xf" =>
+                {
+                    let num_fmt = attribute(&e, b"numFmtId")?.and_then(|id| id.parse().ok());
+                    formats.push(num_fmt.unwrap_or(0));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        if !has_cell_xfs {
+            return Err(format!("{} has no cellXfs", STYLES_PART));
+        }
+
+        let mut missing = Vec::new();
+        let mut style = |num_fmt: u32| match formats.iter().position(|id| *id == num_fmt) {
+            Some(index) => index as u32,
+            None => {
+                missing.push(num_fmt);
+                (formats.len() + missing.len() - 1) as u32
+            }
+        };
+        let date = style(DATE_NUM_FMT);
+        let datetime = style(DATETIME_NUM_FMT);
+        let mut dates: HashSet<u32> = (0..)
+            .zip(&formats)
+            .filter(|(_, id)| is_builtin_date_format(**id) || custom_date_formats.contains(id))
+            .map(|(index, _)| index)
+            .collect();
+        dates.extend([date, datetime]);
+
+        if !missing.is_empty() {
+            let count = (formats.len() + missing.len()).to_string();
+            let with_count = |e: &BytesStart| {
+                let mut start = without_attribute(e, b"count");
+                start.push_attribute(("count", count.as_str()));
+                start
+            };
+            let write_missing = |writer: &mut Writer<Vec<u8>>, sibling: QName| {
+                for num_fmt in &missing {
+                    let mut xf = BytesStart::new(qualified_name(sibling, "xf"));
+                    xf.extend_attributes([
+                        ("numFmtId", num_fmt.to_string().as_str()),
+                        ("fontId", "0"),
+                        ("fillId", "0"),
+                        ("borderId", "0"),
+                        ("xfId", "0"),
+                        ("applyNumberFormat", "1"),
+                    ]);
+                    write_event(writer, Event::Empty(xf))?;
+                }
+                Ok::<_, String>(())
+            };
+
+            let styles = rewrite_xml(&styles, |writer, event| match event {
+                Event::Start(e) if e.local_name().as_ref() == b"cellXfs" => {
+                    write_event(writer, Event::Start(with_count(&e)))
+                }
+                Event::Empty(e) if e.local_name().as_ref() == b"cellXfs" => {
+                    let start = with_count(&e);
+                    let end = start.to_end().into_owned();
+                    write_event(writer, Event::Start(start))?;
+                    write_missing(writer, e.name())?;
+                    write_event(writer, Event::End(end))
+                }
+                Event::End(e) if e.local_name().as_ref() == b"cellXfs" => {
+                    write_missing(writer, e.name())?;
+                    write_event(writer, Event::End(e))
+                }
+                event => write_event(writer, event),
+            })?;
+            self.package.set_part(STYLES_PART, styles.into_bytes());
+        }
+
+        Ok(DateStyles {
+            date,
+            datetime,
+            dates,
+        })
+    }
+
+    fn read_sheets(&self) -> Result<Vec<(String, String)>, String> {
+        let rels = self.package.part_text(WORKBOOK_RELS_PART)?;
+        let mut targets = BTreeMap::new();
+        for_each_element(&rels, b"Relationship", |e| {
+            if let (Some(id), Some(target)) = (attribute(e, b"Id")?, attribute(e, b"Target")?) {
+                targets.insert(id, target);
+            }
+            Ok(())
+        })?;
+
+        let workbook = self.package.part_text(WORKBOOK_PART)?;
+        let mut sheets = Vec::new();
+        for_each_element(&workbook, b"sheet", |e| {
+            let name = attribute(e, b"name")?.unwrap_or_default();
+            let target = attribute(e, b"r:id")?
+                .and_then(|id| targets.get(&id))
+                .ok_or_else(|| format!("Sheet '{}' has no worksheet part", name))?;
+            let part = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("xl/{}", target),
+            };
+            sheets.push((name, part));
+            Ok(())
+        })?;
+
+        Ok(sheets)
+    }
+
+    fn sheet_part(&self, sheet: &str) -> Result<&str, String> {
+        self.sheets
+            .iter()
+            .find(|(name, _)| name == sheet)
+            .map(|(_, part)| part.as_str())
+            .ok_or_else(|| format!("Sheet '{}' not found", sheet))
+    }
+}
+
+/// Streams a worksheet, replacing edited cells and inserting new cells and rows
+/// in order. Everything outside `sheetData`, except the dimension, is copied as is.
+fn rewrite_sheet(
+    xml: &[u8],
+    edits: &CellEdits,
+    date_styles: Option<&DateStyles>,
+) -> Result<Vec<u8>, String> {
+    let mut pending: BTreeMap<u32, BTreeMap<u32, &CellValue>> = BTreeMap::new();
+    for (&(row, col), value) in edits {
+        pending.entry(row).or_default().insert(col, value);
+    }
+    let cells = CellWriter { date_styles };
+
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut buf = Vec::new();
+    let mut in_sheet_data = false;
+    // Rows and cells may omit `r`, in which case they follow the previous one.
+    let mut next_row: u32 = 0;
+    let mut row: u32 = 0;
+    let mut next_col: u32 = 0;
+    let mut row_edits: Option<BTreeMap<u32, &CellValue>> = None;
+    let mut skip_cell = false;
+    let mut cell = CellRef { row: 0, col: 0 };
+    // Shared formulas whose master cell was overwritten, by `si`: the formula and
+    // where it was written. Masters always precede the cells sharing them.
+    let mut orphaned: HashMap<String, (String, CellRef)> = HashMap::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| e.to_string())?;
+        let is_start = matches!(event, Event::Start(_));
+
+        match event {
+            Event::Eof => break,
+            Event::Start(e) if skip_cell && e.local_name().as_ref() == b"f" => {
+                if let Some(si) = shared_index(&e)?
+                    && attribute(&e, b"ref")?.is_some()
+                {
+                    let formula = reader.read_text(e.name()).map_err(|e| e.to_string())?;
+                    let formula = unescape(&formula).map_err(|e| e.to_string())?;
+                    orphaned.insert(si, (formula.into_owned(), cell));
+                }
+            }
+            Event::End(e) if skip_cell => skip_cell = e.local_name().as_ref() != b"c",
+            _ if skip_cell => {}
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"dimension" => {
+                let current = attribute(&e, b"ref")?.unwrap_or_default();
+                let mut dimension = BytesStart::new("dimension");
+                dimension.push_attribute(("ref", extend_dimension(&current, edits).as_str()));
+                write_event(&mut writer, Event::Empty(dimension))?;
+                if is_start {
+                    reader
+                        .read_to_end_into(e.name(), &mut Vec::new())
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"sheetData" => {
+                in_sheet_data = true;
+                write_event(&mut writer, Event::Start(e))?;
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"sheetData" => {
+                let end = e.to_end().into_owned();
+                write_event(&mut writer, Event::Start(e))?;
+                for (row, cells_in_row) in std::mem::take(&mut pending) {
+                    cells.new_row(&mut writer, row, &cells_in_row)?;
+                }
+                write_event(&mut writer, Event::End(end))?;
+            }
+            Event::End(e) if in_sheet_data && e.local_name().as_ref() == b"sheetData" => {
+                for (row, cells_in_row) in std::mem::take(&mut pending) {
+                    cells.new_row(&mut writer, row, &cells_in_row)?;
+                }
+                in_sheet_data = false;
+                write_event(&mut writer, Event::End(e))?;
+            }
+            Event::Start(e) | Event::Empty(e)
+                if in_sheet_data && e.local_name().as_ref() == b"row" =>
+            {
+                row = match attribute(&e, b"r")? {
+                    Some(r) => r
+                        .parse::<u32>()
+                        .ok()
+                        .and_then(|r| r.checked_sub(1))
+                        .ok_or_else(|| format!("Invalid row number '{}'", r))?,
+                    None => next_row,
+                };
+                next_row = row + 1;
+                next_col = 0;
+
+                let earlier: Vec<u32> = pending.range(..row).map(|(r, _)| *r).collect();
+                for r in earlier {
+                    if let Some(cells_in_row) = pending.remove(&r) {
+                        cells.new_row(&mut writer, r, &cells_in_row)?;
+                    }
+                }
+
+                match pending.remove(&row) {
+                    // `spans` is only a hint and would be stale after inserting cells.
+                    Some(edits_in_row) => {
+                        let start = without_attribute(&e, b"spans");
+                        if is_start {
+                            row_edits = Some(edits_in_row);
+                            write_event(&mut writer, Event::Start(start))?;
+                        } else {
+                            let end = start.to_end().into_owned();
+                            write_event(&mut writer, Event::Start(start))?;
+                            for (col, value) in edits_in_row {
+                                cells.cell(&mut writer, row, col, value, None)?;
+                            }
+                            write_event(&mut writer, Event::End(end))?;
+                        }
+                    }
+                    None if is_start => write_event(&mut writer, Event::Start(e))?,
+                    None => write_event(&mut writer, Event::Empty(e))?,
+                }
+            }
+            Event::End(e) if in_sheet_data && e.local_name().as_ref() == b"row" => {
+                for (col, value) in row_edits.take().unwrap_or_default() {
+                    cells.cell(&mut writer, row, col, value, None)?;
+                }
+                write_event(&mut writer, Event::End(e))?;
+            }
+            Event::Start(e) | Event::Empty(e)
+                if in_sheet_data && e.local_name().as_ref() == b"c" =>
+            {
+                let col = match attribute(&e, b"r")? {
+                    Some(r) => CellRef::parse(&r)?.col,
+                    None => next_col,
+                };
+                next_col = col + 1;
+                cell = CellRef { row, col };
+
+                let Some(edits_in_row) = row_edits.as_mut() else {
+                    write_event(
+                        &mut writer,
+                        if is_start {
+                            Event::Start(e)
+                        } else {
+                            Event::Empty(e)
+                        },
+                    )?;
+                    buf.clear();
+                    continue;
+                };
+                let earlier: Vec<u32> = edits_in_row.range(..col).map(|(c, _)| *c).collect();
+                for c in earlier {
+                    if let Some(value) = edits_in_row.remove(&c) {
+                        cells.cell(&mut writer, row, c, value, None)?;
+                    }
+                }
+
+                match edits_in_row.remove(&col) {
+                    Some(value) => {
+                        let style = attribute(&e, b"s")?;
+                        cells.cell(&mut writer, row, col, value, style.as_deref())?;
+                        skip_cell = is_start;
+                    }
+                    None if is_start => write_event(&mut writer, Event::Start(e))?,
+                    None => write_event(&mut writer, Event::Empty(e))?,
+                }
+            }
+            Event::Start(e) | Event::Empty(e)
+                if in_sheet_data && e.local_name().as_ref() == b"f" =>
+            {
+                let master = match shared_index(&e)? {
+                    Some(si) if attribute(&e, b"ref")?.is_none() => orphaned.get(&si),
+                    _ => None,
+                };
+                match master {
+                    Some((formula, master)) => {
+                        let formula = shift_formula(
+                            formula,
+                            cell.row.saturating_sub(master.row),
+                            cell.col.saturating_sub(master.col),
+                        );
+                        write!(writer.get_mut(), "<f>{}</f>", escape(&formula))
+                            .map_err(|e| e.to_string())?;
+                        if is_start {
+                            reader
+                                .read_to_end_into(e.name(), &mut Vec::new())
+                                .map_err(|e| e.to_string())?;
+                        }
+                    }
+                    None if is_start => write_event(&mut writer, Event::Start(e))?,
+                    None => write_event(&mut writer, Event::Empty(e))?,
+                }
+            }
+            event => write_event(&mut writer, event)?,
+        }
+        buf.clear();
+    }
+
+    Ok(writer.into_inner())
+}
+
+/// The `si` of a `<f t="shared">` element.
+fn shared_index(e: &BytesStart) -> Result<Option<String>, String> {
+    if attribute(e, b"t")?.as_deref() != Some("shared") {
+        return Ok(None);
+    }
+    attribute(e, b"si")
+}
+
+/// Moves the relative references of `formula` down by `rows` and right by `cols`,
+/// the way Excel derives each cell of a shared formula from its master.
+/// References that would leave the sheet become `#REF!`, as in Excel.
+fn shift_formula(formula: &str, rows: u32, cols: u32) -> String {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || matches!(c, '$' | '_' | '.');
+    let mut shifted = String::with_capacity(formula.len());
+    let mut rest = formula;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '"' || c == '\'' {
+            // Text and quoted sheet names end at an undoubled quote.
+            let mut end = 1;
+            while let Some(i) = rest[end..].find(c) {
+                end += i + 1;
+                if !rest[end..].starts_with(c) {
+                    break;
+                }
+                end += 1;
+            }
+            shifted.push_str(&rest[..end.min(rest.len())]);
+            rest = &rest[end.min(rest.len())..];
+            continue;
+        }
+        if !is_word(c) {
+            shifted.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let end = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(end);
+        rest = after;
+        // Function and sheet names look like references but are followed by `(` or `!`.
+        if after.starts_with(['(', '!']) {
+            shifted.push_str(word);
+            continue;
+        }
+
+        // Whole columns (`A:C`) and rows (`2:5`) come in pairs.
+        let second = after.strip_prefix(':').map(|tail| {
+            let end = tail.find(|c| !is_word(c)).unwrap_or(tail.len());
+            &tail[..end]
+        });
+        let range = second.and_then(|second| {
+            let columns = shift_column(word, cols).zip(shift_column(second, cols));
+            let lines = shift_row(word, rows).zip(shift_row(second, rows));
+            Some((columns.or(lines)?, 1 + second.len()))
+        });
+        if let Some(((first, second), consumed)) = range {
+            match (first, second) {
+                (Some(first), Some(second)) => {
+                    shifted.push_str(&format!("{}:{}", first, second));
+                }
+                _ => shifted.push_str(REF_ERROR),
+            }
+            rest = &rest[consumed..];
+            continue;
+        }
+
+        match shift_cell(word, rows, cols) {
+            Some(Some(reference)) => shifted.push_str(&reference),
+            Some(None) => shifted.push_str(REF_ERROR),
+            None => shifted.push_str(word),
+        }
+    }
+    shifted
+}
+
+/// `None` when `word` is not a column such as `$B`, `Some(None)` when it would
+/// move past the last column.
+fn shift_column(word: &str, cols: u32) -> Option<Option<String>> {
+    let (absolute, letters) = match word.strip_prefix('$') {
+        Some(letters) => (true, letters),
+        None => (false, word),
+    };
+    let col = column_index(letters)?;
+    if absolute {
+        return Some(Some(word.to_string()));
+    }
+    let col = col + cols;
+    Some((col <= MAX_COLUMN).then(|| column_name(col)))
+}
+
+/// Like [`shift_column`] for a one-based row such as `$7`.
+fn shift_row(word: &str, rows: u32) -> Option<Option<String>> {
+    let (absolute, digits) = match word.strip_prefix('$') {
+        Some(digits) => (true, digits),
+        None => (false, word),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let row = digits
+        .parse::<u32>()
+        .ok()
+        .filter(|row| (1..=MAX_ROW + 1).contains(row))?;
+    if absolute {
+        return Some(Some(word.to_string()));
+    }
+    let row = row + rows;
+    Some((row <= MAX_ROW + 1).then(|| row.to_string()))
+}
+
+/// Like [`shift_column`] for a cell such as `B$7`.
+fn shift_cell(word: &str, rows: u32, cols: u32) -> Option<Option<String>> {
+    let split = word
+        .trim_start_matches('$')
+        .find(|c: char| !c.is_ascii_alphabetic())?
+        + usize::from(word.starts_with('$'));
+    let (column, row) = word.split_at(split);
+    match (shift_column(column, cols)?, shift_row(row, rows)?) {
+        (Some(column), Some(row)) => Some(Some(format!("{}{}", column, row))),
+        _ => Some(None),
+    }
+}
+
+fn write_event(writer: &mut Writer<Vec<u8>>, event: Event) -> Result<(), String> {
+    writer.write_event(event).map_err(|e| e.to_string())
+}
+
+struct CellWriter<'a> {
+    date_styles: Option<&'a DateStyles>,
+}
+
+impl CellWriter<'_> {
+    fn new_row(
+        &self,
+        writer: &mut Writer<Vec<u8>>,
+        row: u32,
+        cells: &BTreeMap<u32, &CellValue>,
+    ) -> Result<(), String> {
+        if cells.values().all(|value| **value == CellValue::Empty) {
+            return Ok(());
+        }
+        write!(writer.get_mut(), r#"<row r="{}">"#, row + 1).map_err(|e| e.to_string())?;
+        for (&col, value) in cells {
+            self.cell(writer, row, col, value, None)?;
+        }
+        write!(writer.get_mut(), "</row>").map_err(|e| e.to_string())
+    }
+
+    /// Writes one cell, keeping `style` when the cell already had one. Dates
+    /// replace a style without a date format, which would show the serial number.
+    fn cell(
+        &self,
+        writer: &mut Writer<Vec<u8>>,
+        row: u32,
+        col: u32,
+        value: &CellValue,
+        style: Option<&str>,
+    ) -> Result<(), String> {
+        let reference = CellRef { row, col };
+        let style = match (value, self.date_styles) {
+            (CellValue::DateTime(dt), Some(styles)) => Some(
+                style
+                    .filter(|s| s.parse().is_ok_and(|s| styles.dates.contains(&s)))
+                    .map_or_else(
+                        || {
+                            if dt.num_seconds_from_midnight() == 0 {
+                                styles.date
+                            } else {
+                                styles.datetime
+                            }
+                            .to_string()
+                        },
+                        str::to_string,
+                    ),
+            ),
+            _ => style.map(str::to_string),
+        };
+        let style = style.map_or_else(String::new, |s| format!(r#" s="{}""#, s));
+
+        let xml = match value {
+            CellValue::Empty if style.is_empty() => return Ok(()),
+            CellValue::Empty => format!(r#"<c r="{}"{}/>"#, reference, style),
+            CellValue::Number(n) => format!(r#"<c r="{}"{}><v>{}</v></c>"#, reference, style, n),
+            CellValue::Bool(b) => format!(
+                r#"<c r="{}"{} t="b"><v>{}</v></c>"#,
+                reference,
+                style,
+                u8::from(*b)
+            ),
+            CellValue::Text(text) => format!(
+                r#"<c r="{}"{} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                reference,
+                style,
+                escape(text)
+            ),
+            CellValue::DateTime(dt) => {
+                let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .ok_or("Invalid Excel epoch")?;
+                let serial = (*dt - epoch).num_milliseconds() as f64 / 86_400_000.0;
+                format!(r#"<c r="{}"{}><v>{}</v></c>"#, reference, style, serial)
+            }
+        };
+        writer
+            .get_mut()
+            .write_all(xml.as_bytes())
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn extend_dimension(current: &str, edits: &CellEdits) -> String {
+    let (mut min_row, mut min_col, mut max_row, mut max_col) = match CellRange::parse(current) {
+        Ok(CellRange {
+            start: Some(start),
+            end,
+        }) => {
+            let end = end.unwrap_or(start);
+            (start.row, start.col, end.row, end.col)
+        }
+        _ => (u32::MAX, u32::MAX, 0, 0),
+    };
+
+    for (&(row, col), value) in edits {
+        if *value != CellValue::Empty {
+            min_row = min_row.min(row);
+            min_col = min_col.min(col);
+            max_row = max_row.max(row);
+            max_col = max_col.max(col);
+        }
+    }
+
+    if min_row == u32::MAX {
+        return if current.is_empty() {
+            "A1".to_string()
+        } else {
+            current.to_string()
+        };
+    }
+    let start = CellRef {
+        row: min_row,
+        col: min_col,
+    };
+    let end = CellRef {
+        row: max_row,
+        col: max_col,
+    };
+    if start == end {
+        start.to_string()
+    } else {
+        format!("{}:{}", start, end)
+    }
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Result<Option<String>, String> {
+    e.try_get_attribute(name)
+        .map_err(|e| e.to_string())?
+        .map(|attr| {
+            attr.unescape_value()
+                .map(|v| v.to_string())
+                .map_err(|e| e.to_string())
+        })
+        .transpose()
+}
+
+fn without_attribute(e: &BytesStart, name: &[u8]) -> BytesStart<'static> {
+    let mut copy = BytesStart::new(String::from_utf8_lossy(e.name().as_ref()).to_string());
+    copy.extend_attributes(
+        e.attributes()
+            .flatten()
+            .filter(|attr| attr.key.as_ref() != name),
+    );
+    copy.into_owned()
+}
+
+fn for_each_element(
+    xml: &str,
+    name: &[u8],
+    mut f: impl FnMut(&BytesStart) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == name => f(&e)?,
+            Event::Eof => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// Streams `xml` through `f`, which writes each event, changed or not.
+fn rewrite_xml(
+    xml: &str,
+    mut f: impl FnMut(&mut Writer<Vec<u8>>, Event) -> Result<(), String>,
+) -> Result<String, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Eof => break,
+            event => f(&mut writer, event)?,
+        }
+    }
+    String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+}
+
+/// `local` with the namespace prefix of `sibling`, e.g. `x:xf` next to `x:cellXfs`.
+fn qualified_name(sibling: QName, local: &str) -> String {
+    match sibling.prefix() {
+        Some(prefix) => format!("{}:{}", String::from_utf8_lossy(prefix.as_ref()), local),
+        None => local.to_string(),
+    }
+}
+
+/// Built-in number formats for dates and times, `14` to `22` and `45` to `47`.
+const fn is_builtin_date_format(id: u32) -> bool {
+    matches!(id, 14..=22 | 45..=47)
+}
+
+/// Whether a custom number format such as `dd.mm.yyyy` shows a date or time.
+/// Quoted text, escaped characters and `[...]` sections are not format codes.
+fn is_date_format(code: &str) -> bool {
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                chars.find(|c| *c == '"');
+            }
+            '[' => {
+                chars.find(|c| *c == ']');
+            }
+            '\\' | '_' | '*' => {
+                chars.next();
+            }
+            c if matches!(c.to_ascii_lowercase(), 'y' | 'm' | 'd' | 'h' | 's') => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn insert_before(xml: &str, anchor: &str, content: &str) -> Result<String, String> {
+    let pos = xml
+        .rfind(anchor)
+        .ok_or_else(|| format!("'{}' not found in package part", anchor))?;
+    let mut xml = xml.to_string();
+    xml.insert_str(pos, content);
+    Ok(xml)
+}
+
+/// Removes the self-closing element that mentions `needle`, e.g. a relationship.
+fn remove_element_containing(xml: &str, needle: &str) -> String {
+    let Some(pos) = xml.find(needle) else {
+        return xml.to_string();
+    };
+    let start = xml[..pos].rfind('<').unwrap_or(pos);
+    let end = xml[pos..].find("/>").map_or(pos, |i| pos + i + 2);
+    format!("{}{}", &xml[..start], &xml[end..])
+}
@@ -1,8 +1,184 @@
-use calamine::{Reader, Xlsx};
+mod editor;
+
+pub use editor::XlsxEditor;
+
+use calamine::{DataType, Reader, Xlsx};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use rust_xlsxwriter::Workbook;
+use std::fmt;
 use std::io::BufReader;
 use std::path::Path;
 
+/// Largest column index supported by the xlsx format (`XFD`).
+const MAX_COLUMN: u32 = 16_383;
+/// Largest row index supported by the xlsx format.
+const MAX_ROW: u32 = 1_048_575;
+
+/// A cell value with its Excel type preserved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    DateTime(NaiveDateTime),
+}
+
+impl CellValue {
+    /// Text in one of the formats dates are displayed in becomes a date, anything else stays text.
+    pub fn from_text(text: &str) -> Self {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .map(|date| date.and_time(NaiveTime::MIN))
+            })
+            .map_or_else(|_| Self::Text(text.to_string()), Self::DateTime)
+    }
+
+    fn from_data(cell: &DataType) -> Self {
+        match cell {
+            DataType::Empty => Self::Empty,
+            DataType::String(s) => Self::Text(s.clone()),
+            DataType::Float(f) => Self::Number(*f),
+            DataType::Int(i) => Self::Number(*i as f64),
+            DataType::Bool(b) => Self::Bool(*b),
+            DataType::Error(e) => Self::Text(e.to_string()),
+            DataType::DateTime(_) | DataType::DateTimeIso(_) => cell
+                .as_datetime()
+                .map_or_else(|| Self::Text(cell.to_string()), Self::DateTime),
+            DataType::Duration(days) => Self::Number(*days),
+            DataType::DurationIso(d) => Self::Text(d.clone()),
+        }
+    }
+}
+
+/// Dates without a time part are shown as `YYYY-MM-DD`, others as `YYYY-MM-DD HH:MM:SS`.
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => Ok(()),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Text(s) => write!(f, "{}", s),
+            Self::DateTime(dt) if dt.num_seconds_from_midnight() == 0 => {
+                write!(f, "{}", dt.format("%Y-%m-%d"))
+            }
+            Self::DateTime(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S")),
+        }
+    }
+}
+
+/// Zero-based cell position parsed from A1 notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CellRef {
+    pub row: u32,
+    pub col: u32,
+}
+
+impl CellRef {
+    pub fn parse(reference: &str) -> Result<Self, String> {
+        let reference = reference.trim();
+        let invalid = || format!("Invalid cell reference: '{}'", reference);
+
+        let split = reference
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (letters, digits) = reference.split_at(split);
+        let letters = letters.trim_start_matches('$').trim_end_matches('$');
+        let col = column_index(letters).ok_or_else(invalid)?;
+        let row: u32 = digits.parse().map_err(|_| invalid())?;
+        if row == 0 || row - 1 > MAX_ROW {
+            return Err(invalid());
+        }
+
+        Ok(Self { row: row - 1, col })
+    }
+}
+
+impl fmt::Display for CellRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", column_name(self.col), self.row + 1)
+    }
+}
+
+/// Zero-based index of column letters such as `AB`, if they name a column.
+fn column_index(letters: &str) -> Option<u32> {
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+    let mut col: u32 = 0;
+    for c in letters.chars() {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        col = col * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+    (col - 1 <= MAX_COLUMN).then_some(col - 1)
+}
+
+fn column_name(col: u32) -> String {
+    let mut letters = Vec::new();
+    let mut col = col + 1;
+    while col > 0 {
+        let rem = (col - 1) % 26;
+        letters.push((b'A' + rem as u8) as char);
+        col = (col - 1) / 26;
+    }
+    letters.into_iter().rev().collect()
+}
+
+/// A range in A1 notation such as `A1:C10`.
+///
+/// A single cell (`B2`) means from that cell to the end of the used area and an
+/// empty string means the whole used area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRange {
+    pub start: Option<CellRef>,
+    pub end: Option<CellRef>,
+}
+
+impl CellRange {
+    pub fn parse(range: &str) -> Result<Self, String> {
+        let range = range.trim();
+        if range.is_empty() {
+            return Ok(Self {
+                start: None,
+                end: None,
+            });
+        }
+
+        match range.split_once(':') {
+            Some((start, end)) => {
+                let start = CellRef::parse(start)?;
+                let end = CellRef::parse(end)?;
+                if end.row < start.row || end.col < start.col {
+                    return Err(format!("Invalid range: '{}'", range));
+                }
+                Ok(Self {
+                    start: Some(start),
+                    end: Some(end),
+                })
+            }
+            None => Ok(Self {
+                start: Some(CellRef::parse(range)?),
+                end: None,
+            }),
+        }
+    }
+}
+
+/// Looks up an absolute position. calamine 0.22's `Range::get_value` compares the
+/// column against the range height, so it misses cells in wide, short ranges.
+fn cell_at(data: &calamine::Range<DataType>, row: u32, col: u32) -> Option<&DataType> {
+    let (start, end) = (data.start()?, data.end()?);
+    if (start.0..=end.0).contains(&row) && (start.1..=end.1).contains(&col) {
+        Some(&data[((row - start.0) as usize, (col - start.1) as usize)])
+    } else {
+        None
+    }
+}
+
+/// Cell values of one worksheet keyed by `(row, col)`.
 pub struct Excel;
 
 impl Excel {
@@ -47,6 +223,119 @@ impl Excel {
     }
 }
 
+impl Excel {
+    pub fn list_sheets<P: AsRef<Path>>(path: P) -> Result<Vec<String>, String> {
+        Ok(Self::read(path)?.sheet_names().to_vec())
+    }
+
+    /// Reads `range` of `sheet` as rows of typed values.
+    pub fn read_range<P: AsRef<Path>>(
+        path: P,
+        sheet: &str,
+        range: &str,
+    ) -> Result<Vec<Vec<CellValue>>, String> {
+        let range = CellRange::parse(range)?;
+        let data = Self::sheet_data(&mut Self::read(path)?, sheet)?;
+
+        let (Some(used_start), Some(used_end)) = (data.start(), data.end()) else {
+            return Ok(Vec::new());
+        };
+        let (start_row, start_col) = range
+            .start
+            .map_or(used_start, |start| (start.row, start.col));
+        let (end_row, end_col) = range.end.map_or(used_end, |end| (end.row, end.col));
+
+        Ok((start_row..=end_row)
+            .map(|row| {
+                (start_col..=end_col)
+                    .map(|col| {
+                        cell_at(&data, row, col).map_or(CellValue::Empty, CellValue::from_data)
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    pub fn read_cell<P: AsRef<Path>>(
+        path: P,
+        sheet: &str,
+        cell: &str,
+    ) -> Result<CellValue, String> {
+        let cell = CellRef::parse(cell)?;
+        let data = Self::sheet_data(&mut Self::read(path)?, sheet)?;
+
+        Ok(cell_at(&data, cell.row, cell.col).map_or(CellValue::Empty, CellValue::from_data))
+    }
+
+    /// Writes `rows` starting at `start_cell`, creating the file and the sheet if needed.
+    ///
+    /// Existing workbooks are edited in place with [`XlsxEditor`], so formatting,
+    /// formulas and other sheets are kept.
+    pub fn write_range<P: AsRef<Path>>(
+        path: P,
+        sheet: &str,
+        start_cell: &str,
+        rows: &[Vec<CellValue>],
+    ) -> Result<(), String> {
+        let path = path.as_ref();
+        let start = CellRef::parse(start_cell)?;
+        let mut editor = Self::open_for_edit(path, sheet)?;
+
+        editor.write_rows(sheet, start, rows)?;
+        editor.save(path)
+    }
+
+    /// Writes `rows` below the last used row of `sheet`, aligned with its first used column.
+    pub fn append_rows<P: AsRef<Path>>(
+        path: P,
+        sheet: &str,
+        rows: &[Vec<CellValue>],
+    ) -> Result<(), String> {
+        let path = path.as_ref();
+        let mut editor = Self::open_for_edit(path, sheet)?;
+        let data = Self::sheet_data(&mut Self::read(path)?, sheet)?;
+
+        let start = match (data.start(), data.end()) {
+            (Some((_, start_col)), Some((end_row, _))) => CellRef {
+                row: end_row + 1,
+                col: start_col,
+            },
+            _ => CellRef { row: 0, col: 0 },
+        };
+        editor.write_rows(sheet, start, rows)?;
+        editor.save(path)
+    }
+
+    fn sheet_data(
+        workbook: &mut Xlsx<BufReader<std::fs::File>>,
+        sheet: &str,
+    ) -> Result<calamine::Range<DataType>, String> {
+        workbook
+            .worksheet_range(sheet)
+            .ok_or_else(|| format!("Sheet '{}' not found", sheet))?
+            .map_err(|e| format!("Failed to read sheet '{}': {}", sheet, e))
+    }
+
+    /// Opens `path` for editing, creating the workbook or adding `sheet` when missing.
+    fn open_for_edit(path: &Path, sheet: &str) -> Result<XlsxEditor, String> {
+        if !path.exists() {
+            let mut workbook = Workbook::new();
+            workbook
+                .add_worksheet()
+                .set_name(sheet)
+                .map_err(|e| format!("Failed to write Excel file: {}", e))?;
+            Self::write(&mut workbook, path)?;
+        }
+
+        let mut editor = XlsxEditor::open(path)?;
+        if !editor.sheet_names().iter().any(|name| name == sheet) {
+            editor.add_sheet(sheet)?;
+            editor.save(path)?;
+        }
+        Ok(editor)
+    }
+}
+
 impl Default for Excel {
     fn default() -> Self {
         Excel
@@ -1,7 +1,12 @@
 pub mod excel;
+pub mod mail;
 pub mod outlook;
+mod package;
 pub mod word;
 
-pub use excel::Excel;
-pub use outlook::{EmailMessage, Outlook};
-pub use word::Word;
+pub use excel::{CellRange, CellRef, CellValue, Excel, XlsxEditor};
+pub use mail::{
+    Attachment, EmailMessage, Imap, ImapQuery, MailSecurity, MailServer, Mime, MimeMessage, Smtp,
+};
+pub use outlook::Outlook;
+pub use word::{TemplateRow, TemplateValue, Word};
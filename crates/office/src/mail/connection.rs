@@ -0,0 +1,205 @@
+use super::{MailSecurity, MailServer};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Where Linux distributions keep the system CA bundle. `SSL_CERT_FILE` takes
+/// precedence, as it does for OpenSSL.
+const SYSTEM_CA_BUNDLES: [&str; 4] = [
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+/// Line-oriented connection to a mail server.
+///
+/// TLS runs in-process over the same socket, so the connect, read and write
+/// timeouts of the server apply to the handshake as well. The server
+/// certificate is checked against the host name and the roots of [`root_store`].
+pub(crate) struct Connection {
+    reader: BufReader<Stream>,
+}
+
+enum Stream {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Plain(stream) => stream.read(buf),
+            Stream::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Plain(stream) => stream.write(buf),
+            Stream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Plain(stream) => stream.flush(),
+            Stream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+impl Connection {
+    /// Connects to `server`, with TLS from the start for [`MailSecurity::Tls`].
+    /// For [`MailSecurity::StartTls`] the caller negotiates the upgrade and
+    /// then calls [`Connection::start_tls`].
+    pub fn open(server: &MailServer) -> Result<Self, String> {
+        if server.host.trim().is_empty() {
+            return Err("Mail server is empty".to_string());
+        }
+
+        let address = (server.host.as_str(), server.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Failed to resolve {}: {}", server.host, e))?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {}", server.host))?;
+        let stream = TcpStream::connect_timeout(&address, server.timeout)
+            .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+        stream
+            .set_read_timeout(Some(server.timeout))
+            .and_then(|_| stream.set_write_timeout(Some(server.timeout)))
+            .map_err(|e| e.to_string())?;
+
+        let stream = match server.security {
+            MailSecurity::Tls => handshake(stream, server)?,
+            MailSecurity::None | MailSecurity::StartTls => Stream::Plain(stream),
+        };
+        Ok(Self {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Upgrades a plain connection to TLS after the server accepted STARTTLS.
+    pub fn start_tls(self, server: &MailServer) -> Result<Self, String> {
+        // Anything the server sent after accepting STARTTLS would be read as
+        // if it had come over TLS, so refuse it instead.
+        if !self.reader.buffer().is_empty() {
+            return Err("Mail server sent unexpected data before the TLS handshake".to_string());
+        }
+        let Stream::Plain(stream) = self.reader.into_inner() else {
+            return Err("Connection already uses TLS".to_string());
+        };
+        Ok(Self {
+            reader: BufReader::new(handshake(stream, server)?),
+        })
+    }
+
+    /// Reads one line without its line ending.
+    pub fn read_line(&mut self) -> Result<String, String> {
+        let mut line = Vec::new();
+        let read = self
+            .reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("Failed to read from mail server: {}", e))?;
+        if read == 0 {
+            return Err("Connection closed by mail server".to_string());
+        }
+        while line.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+            line.pop();
+        }
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    }
+
+    pub fn read_exact(&mut self, length: usize) -> Result<Vec<u8>, String> {
+        let mut data = vec![0; length];
+        self.reader
+            .read_exact(&mut data)
+            .map_err(|e| format!("Failed to read from mail server: {}", e))?;
+        Ok(data)
+    }
+
+    pub fn write_line(&mut self, line: &str) -> Result<(), String> {
+        self.write_all(format!("{}\r\n", line).as_bytes())
+    }
+
+    pub fn write_all(&mut self, data: &[u8]) -> Result<(), String> {
+        let writer = self.reader.get_mut();
+        writer
+            .write_all(data)
+            .and_then(|_| writer.flush())
+            .map_err(|e| format!("Failed to write to mail server: {}", e))
+    }
+}
+
+/// Runs the TLS handshake over `stream` so certificate errors surface here
+/// rather than on the first read.
+fn handshake(stream: TcpStream, server: &MailServer) -> Result<Stream, String> {
+    let host = &server.host;
+    let name = ServerName::try_from(host.trim().to_string())
+        .map_err(|e| format!("Invalid mail server name '{}': {}", host, e))?;
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Failed to set up TLS: {}", e))?
+            .with_root_certificates(root_store(server)?)
+            .with_no_client_auth();
+    let connection = ClientConnection::new(Arc::new(config), name)
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+
+    let mut stream = StreamOwned::new(connection, stream);
+    while stream.conn.is_handshaking() {
+        match stream.conn.complete_io(&mut stream.sock) {
+            Ok((0, 0)) => return Err("TLS connection failed: connection closed".to_string()),
+            Ok(_) => {}
+            Err(e) => return Err(format!("TLS connection failed: {}", e)),
+        }
+    }
+    Ok(Stream::Tls(Box::new(stream)))
+}
+
+/// The Mozilla roots plus the system CA bundle and the server's own bundle, so
+/// company CAs and TLS-inspecting proxies are trusted like in other programs.
+fn root_store(server: &MailServer) -> Result<RootCertStore, String> {
+    let mut roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    let system_bundle = std::env::var_os("SSL_CERT_FILE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            SYSTEM_CA_BUNDLES
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.is_file())
+        });
+    // The system bundle only adds roots, so one that cannot be read is skipped.
+    if let Some(certificates) = system_bundle.and_then(|path| read_certificates(&path).ok()) {
+        roots.add_parsable_certificates(certificates);
+    }
+
+    if let Some(path) = &server.ca_bundle {
+        let (added, _) = roots.add_parsable_certificates(read_certificates(path)?);
+        if added == 0 {
+            return Err(format!("No CA certificates found in {}", path.display()));
+        }
+    }
+    Ok(roots)
+}
+
+fn read_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    CertificateDer::pem_file_iter(path)
+        .and_then(Iterator::collect)
+        .map_err(|e| {
+            format!(
+                "Failed to read CA certificates from {}: {}",
+                path.display(),
+                e
+            )
+        })
+}
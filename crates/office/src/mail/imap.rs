@@ -0,0 +1,271 @@
+use super::connection::Connection;
+use super::{EmailMessage, MailSecurity, MailServer, Mime};
+use std::path::PathBuf;
+
+/// Which messages [`Imap::fetch`] reads and what it does with them.
+#[derive(Debug, Clone)]
+pub struct ImapQuery {
+    pub folder: String,
+    /// IMAP SEARCH criteria, e.g. `UNSEEN` or `FROM "bank" SINCE 1-Mar-2025`.
+    pub criteria: String,
+    /// Mark the fetched messages as read. Otherwise the folder is opened read-only.
+    pub mark_read: bool,
+    /// The most recent messages to fetch, or 0 for all matching messages.
+    pub limit: usize,
+    /// Directory to save attachments to. When set, `attachments` holds the saved paths.
+    pub attachments_dir: Option<PathBuf>,
+}
+
+impl Default for ImapQuery {
+    fn default() -> Self {
+        Self {
+            folder: "INBOX".to_string(),
+            criteria: "UNSEEN".to_string(),
+            mark_read: false,
+            limit: 0,
+            attachments_dir: None,
+        }
+    }
+}
+
+pub struct Imap;
+
+impl Imap {
+    /// Fetches the messages in `query.folder` that match `query.criteria`, oldest first.
+    pub fn fetch(server: &MailServer, query: &ImapQuery) -> Result<Vec<EmailMessage>, String> {
+        let criteria = if query.criteria.trim().is_empty() {
+            "ALL"
+        } else {
+            query.criteria.trim()
+        };
+        if criteria.contains(['\r', '\n']) || query.folder.contains(['\r', '\n']) {
+            return Err("IMAP folder and search criteria must be a single line".to_string());
+        }
+
+        if !server.username.is_empty() {
+            server.check_login()?;
+        }
+
+        let mut session = Session {
+            connection: Connection::open(server)?,
+            next_tag: 1,
+        };
+        let greeting = session.connection.read_line()?;
+        if greeting.starts_with("* BYE") {
+            return Err(format!("IMAP server refused the connection: {}", greeting));
+        }
+        let preauth = greeting.starts_with("* PREAUTH");
+        if server.security == MailSecurity::StartTls {
+            // STARTTLS is only allowed before login, so a pre-authenticated
+            // session would stay unencrypted.
+            if preauth {
+                return Err("IMAP server logged in before STARTTLS".to_string());
+            }
+            session
+                .command("STARTTLS")
+                .map_err(|e| format!("IMAP server refused STARTTLS: {}", e))?;
+            session.connection = session.connection.start_tls(server)?;
+        }
+        if !preauth && !server.username.is_empty() {
+            session
+                .command(&format!(
+                    "LOGIN {} {}",
+                    quote(&server.username)?,
+                    quote(&server.password)?
+                ))
+                .map_err(|e| format!("IMAP login failed: {}", e))?;
+        }
+
+        let folder = quote(&encode_folder(&query.folder))?;
+        let select = if query.mark_read { "SELECT" } else { "EXAMINE" };
+        session
+            .command(&format!("{} {}", select, folder))
+            .map_err(|e| format!("Failed to open folder '{}': {}", query.folder, e))?;
+
+        let charset = if criteria.is_ascii() {
+            ""
+        } else {
+            "CHARSET UTF-8 "
+        };
+        let mut uids: Vec<u64> = session
+            .command(&format!("UID SEARCH {}{}", charset, criteria))
+            .map_err(|e| format!("IMAP search failed: {}", e))?
+            .iter()
+            .filter_map(|response| response.text.strip_prefix("* SEARCH"))
+            .flat_map(|ids| ids.split_whitespace().filter_map(|id| id.parse().ok()))
+            .collect();
+        uids.sort_unstable();
+        if query.limit > 0 && uids.len() > query.limit {
+            uids.drain(..uids.len() - query.limit);
+        }
+        if uids.is_empty() {
+            session.logout();
+            return Ok(Vec::new());
+        }
+
+        let set = uids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let responses = session
+            .command(&format!("UID FETCH {} (UID BODY.PEEK[])", set))
+            .map_err(|e| format!("IMAP fetch failed: {}", e))?;
+
+        let mut messages = Vec::new();
+        for response in responses {
+            if !response.text.contains(" FETCH ") {
+                continue;
+            }
+            let Some(raw) = response.literals.first() else {
+                continue;
+            };
+            let parsed = Mime::parse(raw);
+            let mut message = parsed.message;
+            if let Some(dir) = &query.attachments_dir {
+                message.attachments = Mime::save_attachments(dir, &parsed.attachments, false)?;
+            }
+            messages.push(message);
+        }
+
+        if query.mark_read {
+            session
+                .command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", set))
+                .map_err(|e| format!("Failed to mark messages as read: {}", e))?;
+        }
+        session.logout();
+        Ok(messages)
+    }
+}
+
+/// An IMAP quoted string.
+fn quote(text: &str) -> Result<String, String> {
+    if text.contains(['\r', '\n', '\0']) {
+        return Err("IMAP strings cannot contain line breaks".to_string());
+    }
+    Ok(format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+/// Folder name in IMAP's modified UTF-7 (RFC 3501, section 5.1.3).
+fn encode_folder(name: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+,";
+    let mut out = String::new();
+    let mut pending: Vec<u16> = Vec::new();
+
+    let flush = |pending: &mut Vec<u16>, out: &mut String| {
+        if pending.is_empty() {
+            return;
+        }
+        let bytes: Vec<u8> = pending.iter().flat_map(|unit| unit.to_be_bytes()).collect();
+        out.push('&');
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            }
+        }
+        out.push('-');
+        pending.clear();
+    };
+
+    for c in name.chars() {
+        if (' '..='~').contains(&c) {
+            flush(&mut pending, &mut out);
+            if c == '&' {
+                out.push_str("&-");
+            } else {
+                out.push(c);
+            }
+        } else {
+            pending.extend(c.encode_utf16(&mut [0; 2]).iter());
+        }
+    }
+    flush(&mut pending, &mut out);
+    out
+}
+
+/// One untagged or tagged response line, with the literals it carried.
+struct Response {
+    text: String,
+    literals: Vec<Vec<u8>>,
+}
+
+struct Session {
+    connection: Connection,
+    next_tag: u32,
+}
+
+impl Session {
+    /// Sends a command and returns its untagged responses, or the server's
+    /// message if it did not answer OK.
+    fn command(&mut self, command: &str) -> Result<Vec<Response>, String> {
+        let tag = format!("A{}", self.next_tag);
+        self.next_tag += 1;
+        self.connection
+            .write_line(&format!("{} {}", tag, command))?;
+
+        let mut responses = Vec::new();
+        loop {
+            let response = self.response()?;
+            if let Some(status) = response.text.strip_prefix(&format!("{} ", tag)) {
+                return if status.starts_with("OK") {
+                    Ok(responses)
+                } else {
+                    Err(status.to_string())
+                };
+            }
+            responses.push(response);
+        }
+    }
+
+    /// Reads a response line, following `{n}` literals to the end of the response.
+    fn response(&mut self) -> Result<Response, String> {
+        let mut text = self.connection.read_line()?;
+        let mut literals = Vec::new();
+        while let Some(length) = literal_length(&text) {
+            literals.push(self.connection.read_exact(length)?);
+            text.push_str(&self.connection.read_line()?);
+        }
+        Ok(Response { text, literals })
+    }
+
+    fn logout(&mut self) {
+        let _ = self.command("LOGOUT");
+    }
+}
+
+fn literal_length(line: &str) -> Option<usize> {
+    let start = line.strip_suffix('}')?.rfind('{')?;
+    line[start + 1..line.len() - 1]
+        .trim_end_matches('+')
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_names_use_modified_utf7() {
+        assert_eq!(encode_folder("INBOX"), "INBOX");
+        assert_eq!(encode_folder("Tom & Jerry"), "Tom &- Jerry");
+        assert_eq!(encode_folder("Входящие"), "&BBIERQQ+BDQETwRJBDgENQ-");
+        assert_eq!(
+            encode_folder("~peter/mail/台北/日本語"),
+            "~peter/mail/&U,BTFw-/&ZeVnLIqe-"
+        );
+    }
+
+    #[test]
+    fn literal_lengths() {
+        assert_eq!(literal_length("* 1 FETCH (UID 5 BODY[] {120}"), Some(120));
+        assert_eq!(literal_length("* 1 FETCH (FLAGS ())"), None);
+    }
+}
@@ -0,0 +1,961 @@
+use super::{Attachment, EmailMessage};
+use base64::engine::DecodePaddingMode;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD};
+use base64::{Engine, alphabet};
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+const LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+/// A message read from its MIME source, with the attachment contents.
+///
+/// `message.attachments` names the attached files; inline images referenced
+/// from the HTML body are only in `attachments`, with their content id.
+#[derive(Debug, Clone, Default)]
+pub struct MimeMessage {
+    pub message: EmailMessage,
+    pub attachments: Vec<Attachment>,
+}
+
+pub struct Mime;
+
+impl Mime {
+    /// Parses the MIME source of a message. Malformed input is read as far as possible.
+    pub fn parse(raw: &[u8]) -> MimeMessage {
+        parse(raw)
+    }
+
+    /// Reads an `.eml` file.
+    pub fn read(path: &Path) -> Result<MimeMessage, String> {
+        let raw =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(parse(&raw))
+    }
+
+    /// Builds the MIME source of `message` with `attachments`; the paths in
+    /// `message.attachments` are not read, see [`Mime::load_attachments`].
+    pub fn build(message: &EmailMessage, attachments: &[Attachment]) -> Vec<u8> {
+        build(message, attachments, false)
+    }
+
+    /// Saves `message` as an `.eml` file that mail clients open as an unsent draft.
+    pub fn save_draft(
+        path: &Path,
+        message: &EmailMessage,
+        attachments: &[Attachment],
+    ) -> Result<(), String> {
+        std::fs::write(path, build(message, attachments, true))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Reads the files in `paths` as attachments.
+    pub fn load_attachments(paths: &[String]) -> Result<Vec<Attachment>, String> {
+        load_attachments(paths)
+    }
+
+    /// Saves attachments to `dir` without overwriting existing files and
+    /// returns their paths. Inline images are skipped unless `include_inline` is set.
+    pub fn save_attachments(
+        dir: &Path,
+        attachments: &[Attachment],
+        include_inline: bool,
+    ) -> Result<Vec<String>, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+
+        let mut paths = Vec::new();
+        for attachment in attachments
+            .iter()
+            .filter(|a| include_inline || a.content_id.is_none())
+        {
+            let path = unique_path(dir, &safe_filename(&attachment.filename));
+            std::fs::write(&path, &attachment.data)
+                .map_err(|e| format!("Failed to save attachment {}: {}", path.display(), e))?;
+            paths.push(path.to_string_lossy().into_owned());
+        }
+        Ok(paths)
+    }
+}
+
+/// A file name with path separators and characters Windows rejects replaced.
+fn safe_filename(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name.trim().trim_matches('.');
+    if name.is_empty() {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `dir/name`, or `dir/stem (n).ext` when that file already exists.
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name, String::new()),
+    };
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|path| !path.exists())
+        .unwrap_or(path)
+}
+
+fn load_attachments(paths: &[String]) -> Result<Vec<Attachment>, String> {
+    paths
+        .iter()
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| {
+            let path = Path::new(path.trim());
+            let data = std::fs::read(path)
+                .map_err(|e| format!("Failed to read attachment {}: {}", path.display(), e))?;
+            let filename = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "attachment".to_string());
+            Ok(Attachment {
+                content_type: content_type_for(&filename).to_string(),
+                filename,
+                content_id: None,
+                data,
+            })
+        })
+        .collect()
+}
+
+/// Content type guessed from the file extension.
+fn content_type_for(filename: &str) -> &'static str {
+    let extension = Path::new(filename)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "htm" | "html" => "text/html",
+        "xml" => "application/xml",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "eml" => "message/rfc822",
+        "doc" => "application/msword",
+        "xls" => "application/vnd.ms-excel",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A new `Message-ID` whose domain is taken from the sender address.
+fn generate_message_id(from: &str) -> String {
+    let domain = super::addresses(from)
+        .first()
+        .and_then(|address| address.rsplit_once('@').map(|(_, d)| d.to_string()))
+        .filter(|domain| !domain.is_empty())
+        .unwrap_or_else(|| "localhost".to_string());
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "<{}.{}.{}@{}>",
+        nanos,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        domain
+    )
+}
+
+/// Builds the MIME source of `message`. Attachments with a content id are
+/// placed next to the HTML body so it can reference them as `cid:`. An HTML
+/// message without a plain body gets one made from the HTML.
+fn build(message: &EmailMessage, attachments: &[Attachment], draft: bool) -> Vec<u8> {
+    let mut out = String::new();
+    let date = chrono::DateTime::parse_from_rfc3339(&message.timestamp)
+        .map(|date| date.to_rfc2822())
+        .unwrap_or_else(|_| chrono::Local::now().to_rfc2822());
+    let message_id = if message.message_id.is_empty() {
+        generate_message_id(&message.from)
+    } else {
+        message.message_id.clone()
+    };
+
+    header(
+        &mut out,
+        "From",
+        &encode_address_list(&single_line(&message.from)),
+    );
+    header(
+        &mut out,
+        "To",
+        &encode_address_list(&single_line(&message.to)),
+    );
+    if !message.cc.trim().is_empty() {
+        header(
+            &mut out,
+            "Cc",
+            &encode_address_list(&single_line(&message.cc)),
+        );
+    }
+    header(
+        &mut out,
+        "Subject",
+        &encode_words(&single_line(&message.subject)),
+    );
+    header(&mut out, "Date", &date);
+    header(&mut out, "Message-ID", &single_line(&message_id));
+    header(&mut out, "MIME-Version", "1.0");
+    if draft {
+        header(&mut out, "X-Unsent", "1");
+    }
+
+    let (inline, files): (Vec<&Attachment>, Vec<&Attachment>) = attachments
+        .iter()
+        .partition(|a| a.content_id.is_some() && message.html_body.is_some());
+
+    let plain = match &message.html_body {
+        Some(html) if message.body.is_empty() => html_to_text(html),
+        _ => message.body.clone(),
+    };
+    let mut body = text_part("plain", &plain);
+    if let Some(html) = &message.html_body {
+        body = multipart("alternative", &[body, text_part("html", html)]);
+        if !inline.is_empty() {
+            let mut parts = vec![body];
+            parts.extend(inline.iter().map(|a| attachment_part(a, true)));
+            body = multipart("related", &parts);
+        }
+    }
+    if !files.is_empty() {
+        let mut parts = vec![body];
+        parts.extend(files.iter().map(|a| attachment_part(a, false)));
+        body = multipart("mixed", &parts);
+    }
+
+    out.push_str(&body);
+    out.into_bytes()
+}
+
+/// Header text with line breaks replaced, so values cannot start new headers.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+fn header(out: &mut String, name: &str, value: &str) {
+    out.push_str(name);
+    out.push_str(": ");
+    out.push_str(value);
+    out.push_str("\r\n");
+}
+
+/// Headers and body of one MIME part, ready to be written.
+fn text_part(subtype: &str, text: &str) -> String {
+    let text = normalize_newlines(text);
+    let plain = text.is_ascii() && text.lines().all(|line| line.len() <= 998);
+    if plain {
+        format!(
+            "Content-Type: text/{}; charset=utf-8\r\nContent-Transfer-Encoding: 7bit\r\n\r\n{}",
+            subtype, text
+        )
+    } else {
+        format!(
+            "Content-Type: text/{}; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+            subtype,
+            base64_lines(text.as_bytes())
+        )
+    }
+}
+
+fn attachment_part(attachment: &Attachment, inline: bool) -> String {
+    let mut part = format!(
+        "Content-Type: {}; name={}\r\nContent-Transfer-Encoding: base64\r\n",
+        attachment.content_type,
+        quote_parameter(&encode_words(&single_line(&attachment.filename)))
+    );
+    match attachment.content_id.as_ref().filter(|_| inline) {
+        Some(id) => {
+            part.push_str(&format!(
+                "Content-ID: <{}>\r\n",
+                single_line(id.trim_matches(['<', '>']))
+            ));
+            part.push_str("Content-Disposition: inline");
+        }
+        None => part.push_str("Content-Disposition: attachment"),
+    }
+    part.push_str(&filename_parameter(&single_line(&attachment.filename)));
+    part.push_str("\r\n\r\n");
+    part.push_str(&base64_lines(&attachment.data));
+    part
+}
+
+fn multipart(subtype: &str, parts: &[String]) -> String {
+    let boundary = format!(
+        "----=_Part_{}_{}",
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default()
+    );
+    let mut out = format!(
+        "Content-Type: multipart/{}; boundary=\"{}\"\r\n\r\n",
+        subtype, boundary
+    );
+    for part in parts {
+        out.push_str(&format!("--{}\r\n{}\r\n", boundary, part));
+    }
+    out.push_str(&format!("--{}--\r\n", boundary));
+    out
+}
+
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 38);
+    for chunk in encoded.as_bytes().chunks(76) {
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn quote_parameter(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `; filename=...`, using the RFC 2231 form for names that are not plain ASCII.
+fn filename_parameter(filename: &str) -> String {
+    if filename.is_ascii() {
+        return format!("; filename={}", quote_parameter(filename));
+    }
+    let encoded: String = filename
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+    format!("; filename*=utf-8''{}", encoded)
+}
+
+/// RFC 2047 encoded words for text that is not plain ASCII.
+fn encode_words(text: &str) -> String {
+    if text.is_ascii() && !text.contains("=?") {
+        return text.to_string();
+    }
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > 45 {
+            words.push(format!("=?UTF-8?B?{}?=", STANDARD.encode(&chunk)));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() {
+        words.push(format!("=?UTF-8?B?{}?=", STANDARD.encode(&chunk)));
+    }
+    words.join("\r\n ")
+}
+
+/// Encodes the display names in an address list, leaving the addresses as they are.
+fn encode_address_list(list: &str) -> String {
+    split_address_list(list)
+        .iter()
+        .map(|entry| match entry.rfind('<') {
+            Some(start) => {
+                let name = entry[..start].trim().trim_matches('"');
+                let address = entry[start..].trim();
+                if name.is_empty() {
+                    address.to_string()
+                } else if name.is_ascii() {
+                    format!("{} {}", quote_parameter(name), address)
+                } else {
+                    format!("{} {}", encode_words(name), address)
+                }
+            }
+            None => entry.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn split_address_list(list: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut angle = false;
+    for c in list.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => angle = true,
+            '>' if !quoted => angle = false,
+            ',' | ';' if !quoted && !angle => {
+                if !current.trim().is_empty() {
+                    entries.push(current.trim().to_string());
+                }
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        entries.push(current.trim().to_string());
+    }
+    entries
+}
+
+fn parse(raw: &[u8]) -> MimeMessage {
+    let part = Part::parse(raw);
+    let mut parsed = MimeMessage {
+        message: EmailMessage {
+            from: part.header("from"),
+            to: part.header("to"),
+            cc: part.header("cc"),
+            subject: part.header("subject"),
+            message_id: part.header("message-id"),
+            timestamp: {
+                let date = part.header("date");
+                chrono::DateTime::parse_from_rfc2822(&date)
+                    .map(|date| date.to_rfc3339())
+                    .unwrap_or(date)
+            },
+            ..Default::default()
+        },
+        attachments: Vec::new(),
+    };
+
+    collect(&part, &mut parsed);
+    if parsed.message.body.is_empty()
+        && let Some(html) = &parsed.message.html_body
+    {
+        parsed.message.body = html_to_text(html);
+    }
+    parsed.message.attachments = parsed
+        .attachments
+        .iter()
+        .filter(|a| a.content_id.is_none())
+        .map(|a| a.filename.clone())
+        .collect();
+    parsed
+}
+
+fn collect(part: &Part, parsed: &mut MimeMessage) {
+    let (mime_type, params) = part.content_type();
+    let disposition = part.raw_header("content-disposition");
+    let (disposition_kind, disposition_params) = parse_parameters(&disposition);
+    let filename = parameter(&disposition_params, "filename")
+        .or_else(|| parameter(&params, "name"))
+        .map(|name| decode_words(&name));
+
+    if let Some(boundary) = mime_type
+        .starts_with("multipart/")
+        .then(|| parameter(&params, "boundary"))
+        .flatten()
+    {
+        for child in split_multipart(part.body, &boundary) {
+            collect(&Part::parse(child), parsed);
+        }
+        return;
+    }
+
+    let data = part.decoded_body();
+    let is_attachment = disposition_kind.eq_ignore_ascii_case("attachment") || filename.is_some();
+    if !is_attachment && mime_type == "text/plain" && parsed.message.body.is_empty() {
+        parsed.message.body = decode_text(&data, parameter(&params, "charset").as_deref());
+    } else if !is_attachment && mime_type == "text/html" && parsed.message.html_body.is_none() {
+        parsed.message.html_body =
+            Some(decode_text(&data, parameter(&params, "charset").as_deref()));
+    } else {
+        let content_id = Some(part.header("content-id"))
+            .filter(|id| !id.is_empty() && !disposition_kind.eq_ignore_ascii_case("attachment"))
+            .map(|id| id.trim_matches(['<', '>', ' ']).to_string());
+        let filename = filename.unwrap_or_else(|| {
+            let extension = match mime_type.as_str() {
+                "message/rfc822" => ".eml",
+                "text/plain" => ".txt",
+                "text/html" => ".html",
+                "image/png" => ".png",
+                "image/jpeg" => ".jpg",
+                "image/gif" => ".gif",
+                _ => ".bin",
+            };
+            format!("attachment{}{}", parsed.attachments.len() + 1, extension)
+        });
+        parsed.attachments.push(Attachment {
+            filename,
+            content_type: mime_type,
+            content_id,
+            data,
+        });
+    }
+}
+
+/// Headers and raw body of one MIME entity.
+struct Part<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    fn parse(raw: &'a [u8]) -> Self {
+        let split = find(raw, b"\r\n\r\n")
+            .map(|i| (i, i + 4))
+            .into_iter()
+            .chain(find(raw, b"\n\n").map(|i| (i, i + 2)))
+            .min_by_key(|(end, _)| *end);
+        let (head, body) = match split {
+            Some((end, start)) => (&raw[..end], &raw[start..]),
+            None if raw.starts_with(b"\r\n") => (&raw[..0], &raw[2..]),
+            None if raw.starts_with(b"\n") => (&raw[..0], &raw[1..]),
+            None => (raw, &raw[raw.len()..]),
+        };
+
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in decode_head(head).lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+            }
+        }
+        Self { headers, body }
+    }
+
+    fn raw_header(&self, name: &str) -> String {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    }
+
+    /// Header value with encoded words decoded.
+    fn header(&self, name: &str) -> String {
+        decode_words(&self.raw_header(name))
+    }
+
+    fn content_type(&self) -> (String, Vec<(String, String)>) {
+        let (value, params) = parse_parameters(&self.raw_header("content-type"));
+        let value = value.to_ascii_lowercase();
+        if value.is_empty() {
+            ("text/plain".to_string(), params)
+        } else {
+            (value, params)
+        }
+    }
+
+    fn decoded_body(&self) -> Vec<u8> {
+        let encoding = self.raw_header("content-transfer-encoding");
+        match encoding.trim().to_ascii_lowercase().as_str() {
+            "base64" => decode_base64(self.body),
+            "quoted-printable" => decode_quoted_printable(self.body),
+            _ => self.body.to_vec(),
+        }
+    }
+}
+
+/// Header text. Headers should be ASCII with encoded words, but gateways also
+/// write raw 8-bit text, usually in the charset of the body.
+fn decode_head(head: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(head) {
+        return text.to_string();
+    }
+    let latin = WINDOWS_1252.decode_without_bom_handling(head).0;
+    let charset = latin
+        .lines()
+        .find(|line| line.to_ascii_lowercase().starts_with("content-type:"))
+        .and_then(|line| parameter(&parse_parameters(&line[13..]).1, "charset"));
+    decode_text(head, charset.as_deref())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// The bodies between `--boundary` lines, without the line breaks around them.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut offset = 0;
+
+    while offset < body.len() {
+        let end = body[offset..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map(|i| offset + i + 1)
+            .unwrap_or(body.len());
+        let line = trim_line_end(&body[offset..end]);
+        if line.starts_with(delimiter.as_bytes()) {
+            if let Some(start) = start {
+                parts.push(trim_line_end(&body[start..offset]));
+            }
+            if line[delimiter.len()..].starts_with(b"--") {
+                return parts;
+            }
+            start = Some(end);
+        }
+        offset = end;
+    }
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Splits `type; key=value; ...` into the value and its parameters,
+/// joining RFC 2231 continuations and decoding RFC 2231 charsets.
+fn parse_parameters(header: &str) -> (String, Vec<(String, String)>) {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in header.chars() {
+        if escaped {
+            current.push(c);
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => pieces.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    pieces.push(current);
+
+    let value = pieces.remove(0).trim().to_string();
+    let mut params: Vec<(String, String)> = Vec::new();
+    let mut extended: Vec<(String, usize, bool, String)> = Vec::new();
+    for piece in pieces {
+        let Some((key, val)) = piece.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let val = val.trim().to_string();
+        let (key, encoded) = match key.strip_suffix('*') {
+            Some(key) => (key.to_string(), true),
+            None => (key, false),
+        };
+        match key.split_once('*') {
+            Some((name, index)) if index.parse::<usize>().is_ok() => {
+                extended.push((name.to_string(), index.parse().unwrap_or(0), encoded, val));
+            }
+            _ if encoded => extended.push((key, 0, true, val)),
+            _ => params.push((key, val)),
+        }
+    }
+
+    extended.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+    let mut names: Vec<&String> = extended.iter().map(|(name, ..)| name).collect();
+    names.dedup();
+    for name in names {
+        let mut charset = None;
+        let mut bytes = Vec::new();
+        for (_, index, encoded, val) in extended.iter().filter(|(n, ..)| n == name) {
+            if *encoded {
+                let mut val = val.as_str();
+                if *index == 0
+                    && let Some((cs, rest)) = val.split_once('\'')
+                {
+                    charset = Some(cs.to_string());
+                    val = rest.split_once('\'').map(|(_, v)| v).unwrap_or(rest);
+                }
+                bytes.extend(percent_decode(val));
+            } else {
+                bytes.extend(val.as_bytes());
+            }
+        }
+        params.retain(|(key, _)| key != name);
+        params.push((name.clone(), decode_text(&bytes, charset.as_deref())));
+    }
+
+    (value, params)
+}
+
+fn parameter(params: &[(String, String)], name: &str) -> Option<String> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+        .filter(|value| !value.is_empty())
+}
+
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decodes base64 leniently: line breaks, padding and stray characters are ignored.
+fn decode_base64(data: &[u8]) -> Vec<u8> {
+    let mut clean: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/'))
+        .collect();
+    if clean.len() % 4 == 1 {
+        clean.pop();
+    }
+    LENIENT.decode(&clean).unwrap_or_default()
+}
+
+fn decode_quoted_printable(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        if data[i] != b'=' {
+            out.push(data[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &data[i + 1..];
+        if rest.starts_with(b"\r\n") {
+            i += 3;
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+        } else if let Some(byte) = rest
+            .get(..2)
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(b'=');
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decodes text in `charset`. Without a known charset, UTF-8 is tried first
+/// and Windows-1252 is used for anything else.
+fn decode_text(data: &[u8], charset: Option<&str>) -> String {
+    let encoding = charset
+        .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+        .unwrap_or(if std::str::from_utf8(data).is_ok() {
+            UTF_8
+        } else {
+            WINDOWS_1252
+        });
+    encoding.decode_without_bom_handling(data).0.into_owned()
+}
+
+/// Decodes RFC 2047 encoded words. Whitespace between adjacent encoded words is dropped.
+fn decode_words(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    let mut pending_space = String::new();
+    let mut last_was_word = false;
+
+    while !rest.is_empty() {
+        let word = rest
+            .strip_prefix("=?")
+            .and_then(|inner| decode_word(inner).map(|(decoded, len)| (decoded, len + 2)));
+        if let Some((decoded, len)) = word {
+            if !last_was_word {
+                out.push_str(&pending_space);
+            }
+            pending_space.clear();
+            out.push_str(&decoded);
+            rest = &rest[len..];
+            last_was_word = true;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or(' ');
+        rest = &rest[c.len_utf8()..];
+        if c.is_whitespace() {
+            pending_space.push(c);
+        } else {
+            out.push_str(&pending_space);
+            pending_space.clear();
+            out.push(c);
+            last_was_word = false;
+        }
+    }
+    out.push_str(&pending_space);
+    out
+}
+
+/// Decodes `charset?B|Q?text?=` and returns the text and the consumed length.
+fn decode_word(inner: &str) -> Option<(String, usize)> {
+    let (charset, rest) = inner.split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let end = rest.find("?=")?;
+    let text = &rest[..end];
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+    let consumed = charset.len() + encoding.len() + end + 4;
+    let bytes = match encoding {
+        "B" | "b" => decode_base64(text.as_bytes()),
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    // RFC 2231 allows a language after the charset, as in `utf-8*en`.
+    let charset = charset.split('*').next().unwrap_or(charset);
+    Some((decode_text(&bytes, Some(charset)), consumed))
+}
+
+/// Plain text from an HTML body, for messages that have no text alternative.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut tag = String::new();
+    let mut in_tag = false;
+    let mut skip = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                if name == "style" || name == "script" {
+                    skip = !tag.starts_with('/');
+                }
+                if matches!(name.as_str(), "br" | "p" | "div" | "tr" | "li") {
+                    text.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ if !skip => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_messages_parse_back() {
+        let message = EmailMessage {
+            from: "Әлия <aliya@example.kz>".to_string(),
+            to: "bob@example.com, \"Doe, Jane\" <jane@example.com>".to_string(),
+            subject: "Отчёт за март".to_string(),
+            body: "Привет\n.leading dot".to_string(),
+            html_body: Some("<p>Привет</p><img src=\"cid:logo\">".to_string()),
+            ..Default::default()
+        };
+        let attachments = vec![
+            Attachment {
+                filename: "отчёт.csv".to_string(),
+                content_type: "text/csv".to_string(),
+                content_id: None,
+                data: b"a;b\n1;2".to_vec(),
+            },
+            Attachment {
+                filename: "logo.png".to_string(),
+                content_type: "image/png".to_string(),
+                content_id: Some("logo".to_string()),
+                data: vec![0x89, b'P', b'N', b'G'],
+            },
+        ];
+
+        let parsed = parse(&build(&message, &attachments, false));
+
+        assert_eq!(parsed.message.from, "Әлия <aliya@example.kz>");
+        assert_eq!(
+            parsed.message.to,
+            "bob@example.com, \"Doe, Jane\" <jane@example.com>"
+        );
+        assert_eq!(parsed.message.subject, message.subject);
+        assert_eq!(parsed.message.body, "Привет\r\n.leading dot");
+        assert_eq!(parsed.message.html_body, message.html_body);
+        assert!(parsed.message.message_id.ends_with("@example.kz>"));
+        assert_eq!(parsed.message.attachments, vec!["отчёт.csv".to_string()]);
+        assert_eq!(parsed.attachments.len(), 2);
+        let logo = &parsed.attachments[0];
+        assert_eq!(logo.content_id.as_deref(), Some("logo"));
+        assert_eq!(logo.data, attachments[1].data);
+        assert_eq!(parsed.attachments[1].data, attachments[0].data);
+    }
+
+    #[test]
+    fn encoded_words_and_parameters_decode() {
+        assert_eq!(
+            decode_words("=?utf-8?B?0J/RgNC40LI=?= =?utf-8?Q?=D0=B5=D1=82?= there"),
+            "Привет there"
+        );
+        assert_eq!(decode_words("plain =?bad"), "plain =?bad");
+        let (value, params) =
+            parse_parameters("attachment; filename*0*=utf-8''%D1%84; filename*1=.txt");
+        assert_eq!(value, "attachment");
+        assert_eq!(parameter(&params, "filename").as_deref(), Some("ф.txt"));
+    }
+}
@@ -0,0 +1,160 @@
+mod connection;
+mod imap;
+mod mime;
+mod smtp;
+
+pub use imap::{Imap, ImapQuery};
+pub use mime::{Mime, MimeMessage};
+pub use smtp::Smtp;
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmailMessage {
+    pub from: String,
+    /// Recipients separated by commas, as in the `To` header.
+    pub to: String,
+    pub cc: String,
+    pub subject: String,
+    /// Plain text body.
+    pub body: String,
+    pub html_body: Option<String>,
+    pub timestamp: String,
+    pub message_id: String,
+    /// Files to attach when sending; names (or saved paths) of attachments when reading.
+    pub attachments: Vec<String>,
+}
+
+/// A file attached to a received message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    /// Set for inline parts referenced from the HTML body as `cid:...`.
+    pub content_id: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// How the connection to a mail server is protected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MailSecurity {
+    /// Plain text, for local relays and test servers. Logging in needs
+    /// [`MailServer::with_insecure_login`].
+    None,
+    /// Plain connection upgraded with STARTTLS, usually on port 587 or 143.
+    #[default]
+    StartTls,
+    /// TLS from the start, usually on port 465 or 993.
+    Tls,
+}
+
+impl MailSecurity {
+    pub fn all() -> [MailSecurity; 3] {
+        [
+            MailSecurity::None,
+            MailSecurity::StartTls,
+            MailSecurity::Tls,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MailSecurity::None => "None",
+            MailSecurity::StartTls => "STARTTLS",
+            MailSecurity::Tls => "TLS",
+        }
+    }
+}
+
+/// Address and credentials of an SMTP or IMAP server. An empty username skips authentication.
+#[derive(Debug, Clone)]
+pub struct MailServer {
+    pub host: String,
+    pub port: u16,
+    pub security: MailSecurity,
+    pub username: String,
+    pub password: String,
+    /// Send the password even though `security` is [`MailSecurity::None`].
+    pub allow_insecure_login: bool,
+    /// PEM file with extra trusted root certificates, e.g. a company CA.
+    pub ca_bundle: Option<PathBuf>,
+    pub timeout: Duration,
+}
+
+impl MailServer {
+    pub fn new(host: &str, port: u16, security: MailSecurity) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+            security,
+            username: String::new(),
+            password: String::new(),
+            allow_insecure_login: false,
+            ca_bundle: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.username = username.to_string();
+        self.password = password.to_string();
+        self
+    }
+
+    pub fn with_insecure_login(mut self, allow: bool) -> Self {
+        self.allow_insecure_login = allow;
+        self
+    }
+
+    pub fn with_ca_bundle(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_bundle = Some(path.into());
+        self
+    }
+
+    /// Refuses to log in over an unencrypted connection unless that was allowed explicitly.
+    pub(crate) fn check_login(&self) -> Result<(), String> {
+        if self.security == MailSecurity::None && !self.allow_insecure_login {
+            return Err(
+                "Refusing to send the password over an unencrypted connection: \
+                 use STARTTLS or TLS, or allow insecure login"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The bare addresses in a list like `Ann <ann@example.com>, bob@example.com`.
+pub(crate) fn addresses(list: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut angle = false;
+
+    for c in list.chars().chain([',']) {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => angle = true,
+            '>' if !quoted => angle = false,
+            ',' | ';' if !quoted && !angle => {
+                let entry = current.trim();
+                let address = match (entry.rfind('<'), entry.rfind('>')) {
+                    (Some(start), Some(end)) if start < end => &entry[start + 1..end],
+                    _ => entry,
+                };
+                if !address.trim().is_empty() {
+                    result.push(address.trim().to_string());
+                }
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    result
+}
@@ -0,0 +1,176 @@
+use super::connection::Connection;
+use super::{EmailMessage, MailSecurity, MailServer, Mime, addresses};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+pub struct Smtp;
+
+impl Smtp {
+    /// Sends `message` through `server`. The files listed in
+    /// `message.attachments` are attached, and `html_body`, when set, is sent
+    /// as an alternative to the plain text body.
+    pub fn send(server: &MailServer, message: &EmailMessage) -> Result<(), String> {
+        let sender = addresses(&message.from)
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Sender address is empty".to_string())?;
+        let recipients: Vec<String> = addresses(&message.to)
+            .into_iter()
+            .chain(addresses(&message.cc))
+            .collect();
+        if recipients.is_empty() {
+            return Err("No recipients".to_string());
+        }
+        if let Some(address) = recipients
+            .iter()
+            .chain([&sender])
+            .find(|address| address.contains(char::is_control))
+        {
+            return Err(format!("Invalid email address: {:?}", address));
+        }
+        let attachments = Mime::load_attachments(&message.attachments)?;
+        let data = Mime::build(message, &attachments);
+
+        if !server.username.is_empty() {
+            server.check_login()?;
+        }
+
+        let mut session = Session {
+            connection: Connection::open(server)?,
+        };
+        session.expect("greeting", &[220])?;
+        let mut capabilities = session.hello()?;
+        if server.security == MailSecurity::StartTls {
+            session.expect_command("STARTTLS", &[220])?;
+            session.connection = session.connection.start_tls(server)?;
+            capabilities = session.hello()?;
+        }
+        if !server.username.is_empty() {
+            session.authenticate(server, &capabilities)?;
+        }
+
+        session.expect_command(&format!("MAIL FROM:<{}>", sender), &[250])?;
+        for recipient in &recipients {
+            session.expect_command(&format!("RCPT TO:<{}>", recipient), &[250, 251])?;
+        }
+        session.expect_command("DATA", &[354])?;
+        session.connection.write_all(&dot_stuff(&data))?;
+        session.expect("message", &[250])?;
+        let _ = session.command("QUIT");
+        Ok(())
+    }
+}
+
+struct Session {
+    connection: Connection,
+}
+
+impl Session {
+    /// Reads a possibly multi-line reply and returns its code and text lines.
+    fn reply(&mut self) -> Result<(u16, Vec<String>), String> {
+        let mut lines = Vec::new();
+        loop {
+            let line = self.connection.read_line()?;
+            let code = line
+                .get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .ok_or_else(|| format!("Unexpected SMTP reply: {}", line))?;
+            let more = line.as_bytes().get(3) == Some(&b'-');
+            lines.push(line.get(4..).unwrap_or_default().to_string());
+            if !more {
+                return Ok((code, lines));
+            }
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<(u16, Vec<String>), String> {
+        self.connection.write_line(command)?;
+        self.reply()
+    }
+
+    fn expect(&mut self, what: &str, codes: &[u16]) -> Result<Vec<String>, String> {
+        let (code, lines) = self.reply()?;
+        if codes.contains(&code) {
+            Ok(lines)
+        } else {
+            Err(format!(
+                "SMTP server rejected {}: {} {}",
+                what,
+                code,
+                lines.join(" ")
+            ))
+        }
+    }
+
+    fn expect_command(&mut self, command: &str, codes: &[u16]) -> Result<Vec<String>, String> {
+        self.connection.write_line(command)?;
+        let what = command.split(':').next().unwrap_or(command);
+        self.expect(what, codes)
+    }
+
+    /// Greets the server and returns its EHLO capabilities, or none if it only knows HELO.
+    fn hello(&mut self) -> Result<Vec<String>, String> {
+        match self.command("EHLO localhost")? {
+            (250, lines) => Ok(lines),
+            _ => {
+                self.expect_command("HELO localhost", &[250])?;
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn authenticate(&mut self, server: &MailServer, capabilities: &[String]) -> Result<(), String> {
+        let mechanisms: Vec<String> = capabilities
+            .iter()
+            .filter_map(|line| {
+                let upper = line.to_ascii_uppercase();
+                upper
+                    .strip_prefix("AUTH")
+                    .filter(|rest| rest.starts_with([' ', '=']))
+                    .map(|rest| rest[1..].to_string())
+            })
+            .flat_map(|rest| {
+                rest.split_whitespace()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        if mechanisms.iter().any(|m| m == "PLAIN") {
+            let credentials = format!("\0{}\0{}", server.username, server.password);
+            self.connection
+                .write_line(&format!("AUTH PLAIN {}", STANDARD.encode(credentials)))?;
+            self.expect("credentials", &[235])?;
+        } else if mechanisms.iter().any(|m| m == "LOGIN") {
+            self.expect_command("AUTH LOGIN", &[334])?;
+            self.connection
+                .write_line(&STANDARD.encode(&server.username))?;
+            self.expect("username", &[334])?;
+            self.connection
+                .write_line(&STANDARD.encode(&server.password))?;
+            self.expect("credentials", &[235])?;
+        } else {
+            return Err("SMTP server does not offer PLAIN or LOGIN authentication".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Message data for the DATA command: lines starting with a dot get another
+/// one, and the data ends with the terminating `.` line.
+fn dot_stuff(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 8);
+    let mut line_start = true;
+    for &byte in data {
+        if line_start && byte == b'.' {
+            out.push(b'.');
+        }
+        out.push(byte);
+        line_start = byte == b'\n';
+    }
+    if !out.ends_with(b"\r\n") {
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b".\r\n");
+    out
+}
@@ -1,11 +1,4 @@
-#[derive(Debug, Clone)]
-pub struct EmailMessage {
-    pub to: String,
-    pub subject: String,
-    pub body: String,
-    pub timestamp: String,
-    pub attachments: Vec<String>,
-}
+pub use crate::mail::EmailMessage;
 
 pub struct Outlook;
 
@@ -452,6 +445,7 @@ fn get_emails_impl(folder: &str) -> Result<Vec<EmailMessage>, String> {
                         body,
                         timestamp: received_time,
                         attachments: attachment_names,
+                        ..Default::default()
                     });
                 }
             }
@@ -515,6 +509,7 @@ mod tests {
             body: "Test body".to_string(),
             timestamp: "2025-01-01T00:00:00Z".to_string(),
             attachments: vec!["file.pdf".to_string()],
+            ..Default::default()
         };
         assert_eq!(email.to, "test@example.com");
         assert_eq!(email.attachments.len(), 1);
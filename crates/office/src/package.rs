@@ -0,0 +1,85 @@
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// The parts of an Office Open XML package (.xlsx, .docx), kept in archive
+/// order so that a saved package differs from the original only in edited parts.
+pub(crate) struct Package {
+    /// Used in error messages, e.g. "Excel" or "Word".
+    kind: &'static str,
+    parts: Vec<(String, Vec<u8>)>,
+}
+
+impl Package {
+    pub fn open(path: &Path, kind: &'static str) -> Result<Self, String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+        let mut archive =
+            ZipArchive::new(file).map_err(|e| format!("Failed to read {} file: {}", kind, e))?;
+
+        let mut parts = Vec::with_capacity(archive.len());
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|e| format!("Failed to read {} file: {}", kind, e))?;
+            let mut data = Vec::new();
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {}: {}", entry.name(), e))?;
+            parts.push((entry.name().to_string(), data));
+        }
+
+        Ok(Self { kind, parts })
+    }
+
+    pub fn part_names(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn part(&self, name: &str) -> Option<&[u8]> {
+        self.parts
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, data)| data.as_slice())
+    }
+
+    pub fn part_text(&self, name: &str) -> Result<String, String> {
+        let data = self
+            .part(name)
+            .ok_or_else(|| format!("{} file has no {}", self.kind, name))?;
+        String::from_utf8(data.to_vec()).map_err(|e| format!("Invalid {}: {}", name, e))
+    }
+
+    pub fn set_part(&mut self, name: &str, data: Vec<u8>) {
+        match self.parts.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = data,
+            None => self.parts.push((name.to_string(), data)),
+        }
+    }
+
+    pub fn remove_part(&mut self, name: &str) {
+        self.parts.retain(|(n, _)| n != name);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let write_error =
+            |e: &dyn std::fmt::Display| format!("Failed to write {} file: {}", self.kind, e);
+
+        for (name, data) in &self.parts {
+            if name.ends_with('/') {
+                zip.add_directory(name.as_str(), options)
+                    .map_err(|e| write_error(&e))?;
+                continue;
+            }
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| write_error(&e))?;
+            zip.write_all(data).map_err(|e| write_error(&e))?;
+        }
+        zip.finish().map_err(|e| write_error(&e))?;
+
+        std::fs::write(path, buffer.into_inner()).map_err(|e| write_error(&e))
+    }
+}
@@ -1,7 +1,15 @@
-use docx_rs::{BuildXML, Document, Paragraph, Run};
-use std::io::Write;
+use docx_rs::{
+    Document, DocumentChild, Docx, FooterChild, HeaderChild, InsertChild, Paragraph,
+    ParagraphChild, Run, RunChild, StructuredDataTag, StructuredDataTagChild, Table,
+    TableCellContent, TableChild, TableRowChild,
+};
+use std::collections::BTreeMap;
 use std::path::Path;
 
+mod template;
+
+pub use template::{TemplateRow, TemplateValue};
+
 pub struct Word;
 
 impl Word {
@@ -14,27 +22,166 @@ impl Word {
     }
 
     pub fn write<P: AsRef<Path>>(document: &Document, path: P) -> Result<(), String> {
-        let path = path.as_ref();
-        let mut file =
-            std::fs::File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
-
-        let xml = document.build();
+        Self::write_docx(Docx::new().document(document.clone()), path)
+    }
 
-        file.write_all(&xml)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
+    /// Packs `docx` into a .docx file, including its headers, footers and styles.
+    pub fn write_docx<P: AsRef<Path>>(docx: Docx, path: P) -> Result<(), String> {
+        let file = std::fs::File::create(path.as_ref())
+            .map_err(|e| format!("Failed to create file: {}", e))?;
 
-        Ok(())
+        docx.build()
+            .pack(file)
+            .map_err(|e| format!("Failed to write file: {}", e))
     }
 
+    /// Reads the text of a .docx file, see [`Word::extract_text`].
     pub fn read<P: AsRef<Path>>(path: P) -> Result<String, String> {
-        let path = path.as_ref();
-        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let bytes =
+            std::fs::read(path.as_ref()).map_err(|e| format!("Failed to read file: {}", e))?;
+        let docx =
+            docx_rs::read_docx(&bytes).map_err(|e| format!("Failed to read DOCX file: {}", e))?;
 
-        Ok(format!("Read {} bytes from DOCX file", bytes.len()))
+        Ok(Self::extract_text(&docx.document))
     }
 
+    /// Fills the `{field}`s of the .docx at `template` with `values` and saves
+    /// the result to `output`; see [`TemplateValue`] for lists and conditions.
+    pub fn fill_template<P: AsRef<Path>, Q: AsRef<Path>>(
+        template: P,
+        output: Q,
+        values: &BTreeMap<String, TemplateValue>,
+    ) -> Result<(), String> {
+        template::fill(template.as_ref(), output.as_ref(), values)
+    }
+
+    /// One line per paragraph: headers first, then the body, then footers.
+    /// Table rows become lines with their cells separated by tabs; deleted
+    /// tracked changes are skipped.
     pub fn extract_text(document: &Document) -> String {
-        format!("Document with {} paragraphs", document.children.len())
+        let section = &document.section_property;
+        let mut lines = Vec::new();
+
+        for header in [&section.header, &section.first_header, &section.even_header]
+            .into_iter()
+            .flatten()
+        {
+            for child in &header.children {
+                match child {
+                    HeaderChild::Paragraph(p) => lines.push(paragraph_text(p)),
+                    HeaderChild::Table(t) => table_lines(t, &mut lines),
+                    HeaderChild::StructuredDataTag(t) => tag_lines(t, &mut lines),
+                }
+            }
+        }
+
+        for child in &document.children {
+            match child {
+                DocumentChild::Paragraph(p) => lines.push(paragraph_text(p)),
+                DocumentChild::Table(t) => table_lines(t, &mut lines),
+                DocumentChild::StructuredDataTag(t) => tag_lines(t, &mut lines),
+                _ => {}
+            }
+        }
+
+        for footer in [&section.footer, &section.first_footer, &section.even_footer]
+            .into_iter()
+            .flatten()
+        {
+            for child in &footer.children {
+                match child {
+                    FooterChild::Paragraph(p) => lines.push(paragraph_text(p)),
+                    FooterChild::Table(t) => table_lines(t, &mut lines),
+                    FooterChild::StructuredDataTag(t) => tag_lines(t, &mut lines),
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+fn paragraph_text(paragraph: &Paragraph) -> String {
+    let mut text = String::new();
+    for child in &paragraph.children {
+        paragraph_child_text(child, &mut text);
+    }
+    text
+}
+
+fn paragraph_child_text(child: &ParagraphChild, text: &mut String) {
+    match child {
+        ParagraphChild::Run(run) => run_text(run, text),
+        ParagraphChild::Insert(insert) => {
+            for child in &insert.children {
+                if let InsertChild::Run(run) = child {
+                    run_text(run, text);
+                }
+            }
+        }
+        ParagraphChild::Hyperlink(link) => {
+            for child in &link.children {
+                paragraph_child_text(child, text);
+            }
+        }
+        ParagraphChild::StructuredDataTag(tag) => {
+            for child in &tag.children {
+                if let StructuredDataTagChild::Run(run) = child {
+                    run_text(run, text);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn run_text(run: &Run, text: &mut String) {
+    for child in &run.children {
+        match child {
+            RunChild::Text(t) => text.push_str(&t.text),
+            RunChild::Tab(_) | RunChild::PTab(_) => text.push('\t'),
+            RunChild::Break(_) => text.push('\n'),
+            _ => {}
+        }
+    }
+}
+
+fn table_lines(table: &Table, lines: &mut Vec<String>) {
+    for TableChild::TableRow(row) in &table.rows {
+        let cells: Vec<String> = row
+            .cells
+            .iter()
+            .map(|TableRowChild::TableCell(cell)| {
+                let mut cell_lines = Vec::new();
+                for content in &cell.children {
+                    match content {
+                        TableCellContent::Paragraph(p) => cell_lines.push(paragraph_text(p)),
+                        TableCellContent::Table(t) => table_lines(t, &mut cell_lines),
+                        TableCellContent::StructuredDataTag(t) => tag_lines(t, &mut cell_lines),
+                        TableCellContent::TableOfContents(_) => {}
+                    }
+                }
+                // Keep one line per row so cells stay aligned with their columns.
+                cell_lines.join(" ").replace(['\t', '\n'], " ")
+            })
+            .collect();
+        lines.push(cells.join("\t"));
+    }
+}
+
+fn tag_lines(tag: &StructuredDataTag, lines: &mut Vec<String>) {
+    let mut runs = String::new();
+    for child in &tag.children {
+        match child {
+            StructuredDataTagChild::Run(run) => run_text(run, &mut runs),
+            StructuredDataTagChild::Paragraph(p) => lines.push(paragraph_text(p)),
+            StructuredDataTagChild::Table(t) => table_lines(t, lines),
+            StructuredDataTagChild::StructuredDataTag(t) => tag_lines(t, lines),
+            _ => {}
+        }
+    }
+    if !runs.is_empty() {
+        lines.push(runs);
     }
 }
 
@@ -90,12 +237,7 @@ mod tests {
 
         assert!(Word::write(&doc, &temp_file).is_ok());
 
-        let result = Word::read(&temp_file);
-        assert!(result.is_ok());
-
-        if let Ok(content) = result {
-            assert!(!content.is_empty());
-        }
+        assert_eq!(Word::read(&temp_file).unwrap(), "Test Content");
 
         let _ = std::fs::remove_file(&temp_file);
     }
@@ -0,0 +1,460 @@
+use crate::package::Package;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const DOCUMENT_PART: &str = "word/document.xml";
+
+/// A value a template field is filled with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    Text(String),
+    Bool(bool),
+    /// Items of a list, each mapping column names to text. A table row using
+    /// `{name.column}` fields is repeated once per item.
+    Rows(Vec<TemplateRow>),
+}
+
+/// Column names mapped to the text of one list item.
+pub type TemplateRow = BTreeMap<String, String>;
+
+impl TemplateValue {
+    /// Whether a `{#if name}` section is kept: non-empty text, `true` or a non-empty list.
+    fn is_set(&self) -> bool {
+        match self {
+            Self::Text(text) => !text.is_empty(),
+            Self::Bool(b) => *b,
+            Self::Rows(rows) => !rows.is_empty(),
+        }
+    }
+}
+
+/// Fills the body, headers and footers of the .docx at `template` and saves it to `output`.
+///
+/// - `{name}` is replaced with the value of `name`, even when Word has split
+///   the field across several runs; the first run's formatting is kept.
+/// - A table row containing `{list.column}` fields is repeated for each item of `list`.
+/// - Paragraphs between `{#if name}` and `{/if}` (each on its own line) are
+///   removed unless `name` is set; `{#if !name}` keeps them only when it is not.
+pub(super) fn fill(
+    template: &Path,
+    output: &Path,
+    values: &BTreeMap<String, TemplateValue>,
+) -> Result<(), String> {
+    let mut package = Package::open(template, "Word")?;
+    let parts: Vec<String> = package
+        .part_names()
+        .filter(|name| {
+            *name == DOCUMENT_PART
+                || ((name.starts_with("word/header") || name.starts_with("word/footer"))
+                    && name.ends_with(".xml"))
+        })
+        .map(str::to_string)
+        .collect();
+    if !parts.iter().any(|part| part == DOCUMENT_PART) {
+        return Err(format!("Word file has no {}", DOCUMENT_PART));
+    }
+
+    let lookup = |name: &str| values.get(name);
+    for part in parts {
+        let xml = package.part(&part).unwrap_or_default();
+        let mut nodes = parse(xml).map_err(|e| format!("Failed to read {}: {}", part, e))?;
+        fill_nodes(&mut nodes, &lookup)?;
+        package.set_part(&part, write(&nodes)?);
+    }
+
+    package.save(output)
+}
+
+enum Node {
+    Element(Element),
+    /// Unescaped character data.
+    Text(String),
+    Other(Event<'static>),
+}
+
+struct Element {
+    start: BytesStart<'static>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn is(&self, name: &[u8]) -> bool {
+        self.start.name().as_ref() == name
+    }
+
+    /// Text of all `w:t` elements inside, in document order.
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for child in &self.children {
+            match child {
+                Node::Element(e) if e.is(b"w:t") => text.push_str(&e.own_text()),
+                Node::Element(e) => text.push_str(&e.text()),
+                _ => {}
+            }
+        }
+        text
+    }
+
+    fn own_text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn set_text(&mut self, text: String) {
+        if !self
+            .start
+            .attributes()
+            .flatten()
+            .any(|a| a.key.as_ref() == b"xml:space")
+        {
+            self.start.push_attribute(("xml:space", "preserve"));
+        }
+        self.children = vec![Node::Text(text)];
+    }
+}
+
+fn parse(xml: &[u8]) -> Result<Vec<Node>, String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = Vec::new();
+
+    fn push(stack: &mut [Element], root: &mut Vec<Node>, node: Node) {
+        let children = match stack.last_mut() {
+            Some(parent) => &mut parent.children,
+            None => root,
+        };
+        match (children.last_mut(), node) {
+            (Some(Node::Text(last)), Node::Text(text)) => last.push_str(&text),
+            (_, node) => children.push(node),
+        }
+    }
+
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| e.to_string())?
+        {
+            Event::Eof => break,
+            Event::Start(e) => stack.push(Element {
+                start: e.into_owned(),
+                children: Vec::new(),
+            }),
+            Event::Empty(e) => {
+                let element = Element {
+                    start: e.into_owned(),
+                    children: Vec::new(),
+                };
+                push(&mut stack, &mut root, Node::Element(element));
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| "Unexpected closing tag".to_string())?;
+                push(&mut stack, &mut root, Node::Element(element));
+            }
+            Event::Text(e) => {
+                let text = e.decode().map_err(|e| e.to_string())?.into_owned();
+                push(&mut stack, &mut root, Node::Text(text));
+            }
+            Event::GeneralRef(e) => {
+                let text = match e.resolve_char_ref().map_err(|e| e.to_string())? {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let name = e.decode().map_err(|e| e.to_string())?;
+                        resolve_predefined_entity(&name)
+                            .ok_or_else(|| format!("Unknown entity &{};", name))?
+                            .to_string()
+                    }
+                };
+                push(&mut stack, &mut root, Node::Text(text));
+            }
+            event => push(&mut stack, &mut root, Node::Other(event.into_owned())),
+        }
+        buf.clear();
+    }
+
+    if stack.is_empty() {
+        Ok(root)
+    } else {
+        Err("Unexpected end of file".to_string())
+    }
+}
+
+fn write(nodes: &[Node]) -> Result<Vec<u8>, String> {
+    fn write_nodes(writer: &mut Writer<Vec<u8>>, nodes: &[Node]) -> std::io::Result<()> {
+        for node in nodes {
+            match node {
+                Node::Element(e) if e.children.is_empty() => {
+                    writer.write_event(Event::Empty(e.start.borrow()))?;
+                }
+                Node::Element(e) => {
+                    writer.write_event(Event::Start(e.start.borrow()))?;
+                    write_nodes(writer, &e.children)?;
+                    writer.write_event(Event::End(e.start.to_end()))?;
+                }
+                Node::Text(text) => writer.write_event(Event::Text(BytesText::new(text)))?,
+                Node::Other(event) => writer.write_event(event.borrow())?,
+            }
+        }
+        Ok(())
+    }
+
+    let mut writer = Writer::new(Vec::new());
+    write_nodes(&mut writer, nodes).map_err(|e| e.to_string())?;
+    Ok(writer.into_inner())
+}
+
+type Lookup<'v> = dyn Fn(&str) -> Option<&'v TemplateValue> + 'v;
+
+/// Applies sections, then repeated rows, then fields to `nodes` and everything below them.
+fn fill_nodes<'a>(nodes: &mut Vec<Node>, lookup: &Lookup<'a>) -> Result<(), String> {
+    apply_sections(nodes, lookup)?;
+
+    let mut index = 0;
+    while index < nodes.len() {
+        let Node::Element(element) = &mut nodes[index] else {
+            index += 1;
+            continue;
+        };
+
+        if element.is(b"w:tr")
+            && let Some((list, items)) = repeated_list(element, lookup)?
+        {
+            let Node::Element(row) = nodes.remove(index) else {
+                unreachable!()
+            };
+            for item in items {
+                let item_values: BTreeMap<String, TemplateValue> = item
+                    .iter()
+                    .map(|(column, value)| {
+                        (
+                            format!("{}.{}", list, column),
+                            TemplateValue::Text(value.clone()),
+                        )
+                    })
+                    .collect();
+                let item_lookup = |name: &str| item_values.get(name).or_else(|| lookup(name));
+                let mut copy = clone_element(&row);
+                fill_nodes(&mut copy.children, &item_lookup)?;
+                nodes.insert(index, Node::Element(copy));
+                index += 1;
+            }
+            continue;
+        }
+
+        if element.is(b"w:p") {
+            replace_fields(element, lookup)?;
+        }
+        fill_nodes(&mut element.children, lookup)?;
+        index += 1;
+    }
+    Ok(())
+}
+
+/// The list a table row repeats over: the one its `{list.column}` fields name.
+fn repeated_list<'a>(
+    row: &Element,
+    lookup: &Lookup<'a>,
+) -> Result<Option<(String, &'a [TemplateRow])>, String> {
+    let mut found: Option<(String, &'a [TemplateRow])> = None;
+    for (_, field) in fields(&row.text()) {
+        let Some((list, _)) = field.split_once('.') else {
+            continue;
+        };
+        let Some(TemplateValue::Rows(items)) = lookup(list) else {
+            continue;
+        };
+        match &found {
+            Some((name, _)) if name != list => {
+                return Err(format!(
+                    "A table row can repeat only one list, found '{}' and '{}'",
+                    name, list
+                ));
+            }
+            Some(_) => {}
+            None => found = Some((list.to_string(), items.as_slice())),
+        }
+    }
+    Ok(found)
+}
+
+/// Removes `{#if name}` / `{/if}` marker paragraphs, and the nodes between
+/// them when the condition does not hold.
+fn apply_sections<'a>(nodes: &mut Vec<Node>, lookup: &Lookup<'a>) -> Result<(), String> {
+    let has_markers = nodes.iter().any(|node| section_marker(node).is_some());
+    if !has_markers {
+        return Ok(());
+    }
+
+    let mut keep_stack: Vec<bool> = Vec::new();
+    let mut kept = Vec::with_capacity(nodes.len());
+    for node in nodes.drain(..) {
+        match section_marker(&node) {
+            Some(Marker::If(condition)) => {
+                let (name, negated) = match condition.strip_prefix('!') {
+                    Some(name) => (name.trim(), true),
+                    None => (condition.as_str(), false),
+                };
+                let is_set = lookup(name).is_some_and(TemplateValue::is_set);
+                keep_stack.push(is_set != negated);
+            }
+            Some(Marker::EndIf) => {
+                keep_stack
+                    .pop()
+                    .ok_or_else(|| "Template has {/if} without a matching {#if}".to_string())?;
+            }
+            None if keep_stack.iter().all(|keep| *keep) => kept.push(node),
+            None => {}
+        }
+    }
+    if !keep_stack.is_empty() {
+        return Err("Template has {#if} without a matching {/if}".to_string());
+    }
+
+    *nodes = kept;
+    Ok(())
+}
+
+enum Marker {
+    If(String),
+    EndIf,
+}
+
+fn section_marker(node: &Node) -> Option<Marker> {
+    let Node::Element(element) = node else {
+        return None;
+    };
+    if !element.is(b"w:p") {
+        return None;
+    }
+    let text = element.text();
+    let inner = text.trim().strip_prefix('{')?.strip_suffix('}')?.trim();
+    if inner == "/if" {
+        return Some(Marker::EndIf);
+    }
+    let condition = inner.strip_prefix("#if ")?.trim();
+    Some(Marker::If(condition.to_string()))
+}
+
+/// Replaces the `{field}`s of a paragraph. A field may span several `w:t`
+/// elements: its value goes where it starts and the rest of it is cut out.
+fn replace_fields<'a>(paragraph: &mut Element, lookup: &Lookup<'a>) -> Result<(), String> {
+    let mut texts: Vec<&mut Element> = Vec::new();
+    collect_texts(&mut paragraph.children, &mut texts);
+
+    let segments: Vec<String> = texts.iter().map(|t| t.own_text()).collect();
+    let full: String = segments.concat();
+    let found = fields(&full);
+    if found.is_empty() {
+        return Ok(());
+    }
+
+    let mut starts = Vec::with_capacity(segments.len());
+    let mut offset = 0;
+    for segment in &segments {
+        starts.push(offset);
+        offset += segment.len();
+    }
+
+    let mut new_segments = segments.clone();
+    for (range, field) in found.into_iter().rev() {
+        let value = match lookup(&field) {
+            Some(TemplateValue::Text(text)) => text.clone(),
+            Some(TemplateValue::Bool(b)) => b.to_string(),
+            Some(TemplateValue::Rows(_)) => {
+                return Err(format!(
+                    "Template field '{}' is a list; use {{{}.column}} in a table row",
+                    field, field
+                ));
+            }
+            None => return Err(format!("Template field '{}' has no value", field)),
+        };
+
+        for (index, segment) in new_segments.iter_mut().enumerate().rev() {
+            let seg_start = starts[index];
+            let seg_end = seg_start + segments[index].len();
+            if seg_end <= range.start || seg_start >= range.end {
+                continue;
+            }
+            let from = range.start.max(seg_start) - seg_start;
+            let to = range.end.min(seg_end) - seg_start;
+            let replacement = if range.start >= seg_start {
+                value.as_str()
+            } else {
+                ""
+            };
+            segment.replace_range(from..to, replacement);
+        }
+    }
+
+    for ((element, old), new) in texts.iter_mut().zip(&segments).zip(new_segments) {
+        if *old != new {
+            element.set_text(new);
+        }
+    }
+    Ok(())
+}
+
+/// `w:t` elements of a paragraph, not descending into paragraphs nested in text boxes.
+fn collect_texts<'e>(nodes: &'e mut [Node], texts: &mut Vec<&'e mut Element>) {
+    for node in nodes {
+        if let Node::Element(element) = node {
+            if element.is(b"w:t") {
+                texts.push(element);
+            } else if !element.is(b"w:p") {
+                collect_texts(&mut element.children, texts);
+            }
+        }
+    }
+}
+
+/// Byte ranges and names of the `{field}`s in `text`. Names are letters,
+/// digits, `_` and `.`; other braces are left as text.
+fn fields(text: &str) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+    while let Some(open) = text[rest..].find('{').map(|i| rest + i) {
+        let Some(close) = text[open..].find('}').map(|i| open + i) else {
+            break;
+        };
+        let name = text[open + 1..close].trim();
+        let valid = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        if valid {
+            found.push((open..close + 1, name.to_string()));
+            rest = close + 1;
+        } else {
+            rest = open + 1;
+        }
+    }
+    found
+}
+
+fn clone_element(element: &Element) -> Element {
+    Element {
+        start: element.start.clone(),
+        children: element
+            .children
+            .iter()
+            .map(|child| match child {
+                Node::Element(e) => Node::Element(clone_element(e)),
+                Node::Text(text) => Node::Text(text.clone()),
+                Node::Other(event) => Node::Other(event.clone()),
+            })
+            .collect(),
+    }
+}
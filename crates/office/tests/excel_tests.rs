@@ -1,4 +1,8 @@
-use office::Excel;
+use chrono::NaiveDate;
+use office::{CellRange, CellRef, CellValue, Excel, XlsxEditor};
+use rust_xlsxwriter::{Format, Formula, Workbook};
+use std::io::Read;
+use std::path::Path;
 
 #[test]
 fn test_excel_create_workbook() {
@@ -29,3 +33,453 @@ fn test_excel_roundtrip() {
 
     let _ = std::fs::remove_file(&temp_file);
 }
+
+fn sample_rows() -> Vec<Vec<CellValue>> {
+    let date = NaiveDate::from_ymd_opt(2024, 3, 15)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    vec![
+        vec![
+            CellValue::Text("Name".to_string()),
+            CellValue::Text("Amount".to_string()),
+            CellValue::Text("Paid".to_string()),
+            CellValue::Text("Date".to_string()),
+        ],
+        vec![
+            CellValue::Text("Alice".to_string()),
+            CellValue::Number(120.5),
+            CellValue::Bool(true),
+            CellValue::DateTime(date),
+        ],
+        vec![
+            CellValue::Text("Bob".to_string()),
+            CellValue::Number(42.0),
+            CellValue::Bool(false),
+            CellValue::Empty,
+        ],
+    ]
+}
+
+#[test]
+fn test_cell_ref_parsing() {
+    assert_eq!(CellRef::parse("A1").unwrap(), CellRef { row: 0, col: 0 });
+    assert_eq!(
+        CellRef::parse("$AB$12").unwrap(),
+        CellRef { row: 11, col: 27 }
+    );
+    assert_eq!(
+        CellRef::parse("xfd1048576").unwrap().to_string(),
+        "XFD1048576"
+    );
+    assert!(CellRef::parse("A0").is_err());
+    assert!(CellRef::parse("12").is_err());
+    assert!(CellRef::parse("XFE1").is_err());
+
+    let range = CellRange::parse("B2:D4").unwrap();
+    assert_eq!(range.start, Some(CellRef { row: 1, col: 1 }));
+    assert_eq!(range.end, Some(CellRef { row: 3, col: 3 }));
+    assert!(CellRange::parse("D4:B2").is_err());
+    assert_eq!(CellRange::parse("").unwrap().start, None);
+}
+
+#[test]
+fn test_excel_write_and_read_range() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.xlsx");
+
+    Excel::write_range(&path, "Data", "B2", &sample_rows()).unwrap();
+    assert_eq!(Excel::list_sheets(&path).unwrap(), ["Data"]);
+
+    let rows = Excel::read_range(&path, "Data", "").unwrap();
+    assert_eq!(rows, sample_rows());
+
+    let rows = Excel::read_range(&path, "Data", "C3:D3").unwrap();
+    assert_eq!(
+        rows,
+        vec![vec![CellValue::Number(120.5), CellValue::Bool(true)]]
+    );
+
+    let date = Excel::read_cell(&path, "Data", "E3").unwrap();
+    assert_eq!(date.to_string(), "2024-03-15");
+    assert_eq!(
+        Excel::read_cell(&path, "Data", "Z99").unwrap(),
+        CellValue::Empty
+    );
+}
+
+#[test]
+fn test_excel_append_rows_keeps_other_sheets() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("log.xlsx");
+
+    Excel::write_range(&path, "Summary", "A1", &[vec![CellValue::Number(1.0)]]).unwrap();
+    Excel::write_range(&path, "Log", "A1", &sample_rows()[..2]).unwrap();
+    Excel::append_rows(&path, "Log", &sample_rows()[2..]).unwrap();
+
+    assert_eq!(Excel::list_sheets(&path).unwrap(), ["Summary", "Log"]);
+    assert_eq!(Excel::read_range(&path, "Log", "").unwrap(), sample_rows());
+    assert_eq!(
+        Excel::read_cell(&path, "Summary", "A1").unwrap(),
+        CellValue::Number(1.0)
+    );
+}
+
+#[test]
+fn test_excel_missing_sheet_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.xlsx");
+    Excel::write_range(&path, "Sheet1", "A1", &[vec![CellValue::Bool(true)]]).unwrap();
+
+    let err = Excel::read_range(&path, "Missing", "A1:B2").unwrap_err();
+    assert!(err.contains("Missing"));
+    assert!(Excel::read_cell(dir.path().join("nope.xlsx"), "Sheet1", "A1").is_err());
+}
+
+#[test]
+fn test_cell_value_from_text() {
+    let date = CellValue::from_text("2024-03-15");
+    assert!(matches!(date, CellValue::DateTime(_)));
+    assert_eq!(date.to_string(), "2024-03-15");
+    assert_eq!(
+        CellValue::from_text("2024-03-15 08:30:00").to_string(),
+        "2024-03-15 08:30:00"
+    );
+    assert_eq!(
+        CellValue::from_text("15.03.2024"),
+        CellValue::Text("15.03.2024".to_string())
+    );
+}
+
+/// A workbook with a styled header, a formula, a merged range and a second sheet.
+fn formatted_fixture(path: &Path) {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let money = Format::new().set_num_format("#,##0.00");
+
+    let sheet = workbook.add_worksheet().set_name("Report").unwrap();
+    sheet.write_string_with_format(0, 0, "Item", &bold).unwrap();
+    sheet
+        .write_string_with_format(0, 1, "Price", &bold)
+        .unwrap();
+    sheet.write_string(1, 0, "Pen").unwrap();
+    sheet.write_number_with_format(1, 1, 1.5, &money).unwrap();
+    sheet.write_string(2, 0, "Book").unwrap();
+    sheet.write_number_with_format(2, 1, 12.0, &money).unwrap();
+    sheet
+        .write_formula(3, 1, Formula::new("=SUM(B2:B3)").set_result("13.5"))
+        .unwrap();
+    sheet.merge_range(5, 0, 5, 2, "Notes", &bold).unwrap();
+
+    let other = workbook.add_worksheet().set_name("Other").unwrap();
+    other.write_string(0, 0, "untouched").unwrap();
+
+    workbook.save(path).unwrap();
+}
+
+fn package_part(path: &Path, name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut part = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut part)
+        .unwrap();
+    part
+}
+
+#[test]
+fn test_xlsx_editor_preserves_formatting() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("formatted.xlsx");
+    formatted_fixture(&path);
+    let styles = package_part(&path, "xl/styles.xml");
+    let other = package_part(&path, "xl/worksheets/sheet2.xml");
+
+    let mut editor = XlsxEditor::open(&path).unwrap();
+    assert_eq!(editor.sheet_names(), ["Report", "Other"]);
+    editor
+        .set_cell("Report", "B2", CellValue::Number(2.25))
+        .unwrap();
+    editor
+        .write_rows(
+            "Report",
+            CellRef::parse("A5").unwrap(),
+            &[vec![
+                CellValue::Text("Total <net>".to_string()),
+                CellValue::Empty,
+                CellValue::from_text("2024-03-15"),
+            ]],
+        )
+        .unwrap();
+    editor
+        .set_cell("Report", "D8", CellValue::Bool(true))
+        .unwrap();
+    editor.save(&path).unwrap();
+
+    assert_eq!(
+        Excel::read_range(&path, "Report", "A1:D8").unwrap(),
+        vec![
+            vec![
+                CellValue::Text("Item".to_string()),
+                CellValue::Text("Price".to_string()),
+                CellValue::Empty,
+                CellValue::Empty,
+            ],
+            vec![
+                CellValue::Text("Pen".to_string()),
+                CellValue::Number(2.25),
+                CellValue::Empty,
+                CellValue::Empty,
+            ],
+            vec![
+                CellValue::Text("Book".to_string()),
+                CellValue::Number(12.0),
+                CellValue::Empty,
+                CellValue::Empty,
+            ],
+            vec![
+                CellValue::Empty,
+                CellValue::Number(13.5),
+                CellValue::Empty,
+                CellValue::Empty,
+            ],
+            vec![
+                CellValue::Text("Total <net>".to_string()),
+                CellValue::Empty,
+                CellValue::from_text("2024-03-15"),
+                CellValue::Empty,
+            ],
+            vec![
+                CellValue::Text("Notes".to_string()),
+                CellValue::Empty,
+                CellValue::Empty,
+                CellValue::Empty,
+            ],
+            vec![CellValue::Empty; 4],
+            vec![
+                CellValue::Empty,
+                CellValue::Empty,
+                CellValue::Empty,
+                CellValue::Bool(true),
+            ],
+        ]
+    );
+
+    let report = package_part(&path, "xl/worksheets/sheet1.xml");
+    assert!(report.contains("<f>SUM(B2:B3)</f>"));
+    assert!(report.contains(r#"<mergeCell ref="A6:C6"/>"#));
+    assert!(report.contains(r#"<dimension ref="A1:D8"/>"#));
+    let price = report.split(r#"<c r="B2""#).nth(1).unwrap();
+    assert!(price.starts_with(r#" s="2""#));
+    assert_eq!(package_part(&path, "xl/worksheets/sheet2.xml"), other);
+    assert!(
+        package_part(&path, "xl/styles.xml")
+            .starts_with(&styles[..styles.find("<cellXfs").unwrap()])
+    );
+    assert!(package_part(&path, "xl/workbook.xml").contains(r#"fullCalcOnLoad="1""#));
+}
+
+#[test]
+fn test_xlsx_editor_adds_sheets_and_rejects_bad_cells() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("formatted.xlsx");
+    formatted_fixture(&path);
+
+    let mut editor = XlsxEditor::open(&path).unwrap();
+    assert!(editor.add_sheet("report").is_err());
+    editor.add_sheet("New & Empty").unwrap();
+    assert!(editor.set_cell("Missing", "A1", CellValue::Empty).is_err());
+    assert!(
+        editor
+            .set_cell("Report", "A1", CellValue::Number(f64::NAN))
+            .is_err()
+    );
+    editor
+        .set_cell("New & Empty", "C2", CellValue::Number(7.0))
+        .unwrap();
+    editor.save(&path).unwrap();
+
+    assert_eq!(
+        Excel::list_sheets(&path).unwrap(),
+        ["Report", "Other", "New & Empty"]
+    );
+    assert_eq!(
+        Excel::read_cell(&path, "New & Empty", "C2").unwrap(),
+        CellValue::Number(7.0)
+    );
+    assert_eq!(
+        Excel::read_cell(&path, "Report", "A1").unwrap(),
+        CellValue::Text("Item".to_string())
+    );
+}
+
+/// A workbook whose formulas are stored the way Excel saves filled-down formulas:
+/// the first cell holds `<f t="shared" ref=.. si=..>`, the others only `si`.
+fn shared_formula_fixture(path: &Path) {
+    let parts = [
+        (
+            "[Content_Types].xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#,
+        ),
+        (
+            "_rels/.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+        ),
+        (
+            "xl/workbook.xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+        ),
+        (
+            "xl/worksheets/sheet1.xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><dimension ref="A1:D4"/><sheetData><row r="1"><c r="A1"><v>1</v></c><c r="B1"><f t="shared" ref="B1:B3" si="0">A1*2+$A$1</f><v>3</v></c></row><row r="2"><c r="A2"><v>2</v></c><c r="B2"><f t="shared" si="0"/><v>5</v></c></row><row r="3"><c r="A3"><v>3</v></c><c r="B3"><f t="shared" si="0"/><v>7</v></c></row><row r="4"><c r="C4" t="str"><f t="shared" ref="C4:D4" si="1">SUM(A1:A3)&amp;"A1"&amp;COUNT(A:A)</f><v>63</v></c><c r="D4" t="str"><f t="shared" si="1"/><v>153</v></c></row></sheetData></worksheet>"#,
+        ),
+    ];
+    write_package(path, &parts);
+}
+
+fn write_package(path: &Path, parts: &[(&str, &str)]) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    for (name, contents) in parts {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut zip, contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn test_xlsx_editor_expands_overwritten_shared_formulas() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shared.xlsx");
+    shared_formula_fixture(&path);
+
+    let mut editor = XlsxEditor::open(&path).unwrap();
+    editor
+        .set_cell("Data", "B1", CellValue::Number(10.0))
+        .unwrap();
+    editor
+        .set_cell("Data", "C4", CellValue::Text("total".to_string()))
+        .unwrap();
+    editor.save(&path).unwrap();
+
+    let sheet = package_part(&path, "xl/worksheets/sheet1.xml");
+    assert!(!sheet.contains("si="), "{}", sheet);
+    assert!(sheet.contains(r#"<c r="B2"><f>A2*2+$A$1</f><v>5</v></c>"#));
+    assert!(sheet.contains(r#"<c r="B3"><f>A3*2+$A$1</f><v>7</v></c>"#));
+    assert!(sheet.contains("<f>SUM(B1:B3)&amp;&quot;A1&quot;&amp;COUNT(B:B)</f>"));
+    assert!(
+        package_part(&path, "xl/workbook.xml")
+            .contains(r#"</sheets><calcPr fullCalcOnLoad="1"/></workbook>"#)
+    );
+    assert_eq!(
+        Excel::read_cell(&path, "Data", "B1").unwrap(),
+        CellValue::Number(10.0)
+    );
+}
+
+#[test]
+fn test_xlsx_editor_writes_dates_into_styled_cells() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("formatted.xlsx");
+    formatted_fixture(&path);
+    let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+    for (cell, time) in [("B2", (0, 0, 0)), ("B3", (9, 30, 0)), ("B2", (0, 0, 0))] {
+        let mut editor = XlsxEditor::open(&path).unwrap();
+        let value = date.and_hms_opt(time.0, time.1, time.2).unwrap();
+        editor
+            .set_cell("Report", cell, CellValue::DateTime(value))
+            .unwrap();
+        editor.save(&path).unwrap();
+    }
+
+    assert_eq!(
+        Excel::read_cell(&path, "Report", "B2").unwrap(),
+        CellValue::DateTime(date.and_hms_opt(0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        Excel::read_cell(&path, "Report", "B3").unwrap(),
+        CellValue::DateTime(date.and_hms_opt(9, 30, 0).unwrap())
+    );
+    let styles = package_part(&path, "xl/styles.xml");
+    assert_eq!(styles.matches(r#"<xf numFmtId="14""#).count(), 1);
+    assert_eq!(styles.matches(r#"<xf numFmtId="22""#).count(), 1);
+}
+
+#[test]
+fn test_xlsx_editor_updates_prefixed_styles_and_calc_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("prefixed.xlsx");
+    write_package(
+        &path,
+        &[
+            (
+                "xl/workbook.xml",
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets><calcPr calcId="191029" fullCalcOnLoad="0"/></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/styles.xml",
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<x:styleSheet xmlns:x="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><x:cellStyleXfs count="1"><x:xf numFmtId="0"/></x:cellStyleXfs><x:cellXfs/></x:styleSheet>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#,
+            ),
+        ],
+    );
+
+    let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+    for _ in 0..2 {
+        let mut editor = XlsxEditor::open(&path).unwrap();
+        editor
+            .set_cell(
+                "Data",
+                "A1",
+                CellValue::DateTime(date.and_hms_opt(0, 0, 0).unwrap()),
+            )
+            .unwrap();
+        editor.save(&path).unwrap();
+    }
+
+    let styles = package_part(&path, "xl/styles.xml");
+    assert!(
+        styles.contains(concat!(
+            r#"<x:cellXfs count="2">"#,
+            r#"<x:xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+            r#"<x:xf numFmtId="22" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+            "</x:cellXfs>"
+        )),
+        "{}",
+        styles
+    );
+    let sheet = package_part(&path, "xl/worksheets/sheet1.xml");
+    assert!(
+        sheet.contains(r#"<c r="A1" s="0"><v>45366</v></c>"#),
+        "{}",
+        sheet
+    );
+    let workbook = package_part(&path, "xl/workbook.xml");
+    assert!(
+        workbook.contains(r#"<calcPr calcId="191029" fullCalcOnLoad="1"/>"#),
+        "{}",
+        workbook
+    );
+}
@@ -0,0 +1,496 @@
+use office::{Attachment, EmailMessage, Imap, ImapQuery, MailSecurity, MailServer, Mime, Smtp};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::JoinHandle;
+
+/// Runs `handler` on the first connection to a local port and returns the port.
+fn serve<T: Send + 'static>(
+    handler: impl FnOnce(BufReader<TcpStream>, TcpStream) -> T + Send + 'static,
+) -> (u16, JoinHandle<T>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        handler(reader, stream)
+    });
+    (port, handle)
+}
+
+fn read_line(reader: &mut BufReader<TcpStream>) -> String {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    line.trim_end().to_string()
+}
+
+/// A stand-in SMTP server. Returns the commands it received and the message data.
+fn smtp_server(reject_recipient: bool) -> (u16, JoinHandle<(Vec<String>, String)>) {
+    serve(move |mut reader, mut stream| {
+        let mut commands = Vec::new();
+        let mut data = String::new();
+        stream.write_all(b"220 test ESMTP\r\n").unwrap();
+        loop {
+            let line = read_line(&mut reader);
+            if line.is_empty() {
+                break;
+            }
+            commands.push(line.clone());
+            let reply = match line.split([' ', ':']).next().unwrap() {
+                "EHLO" => "250-test\r\n250-SIZE 1000000\r\n250 AUTH LOGIN PLAIN",
+                "AUTH" => "235 Authenticated",
+                "RCPT" if reject_recipient => "550 No such user",
+                "DATA" => {
+                    stream.write_all(b"354 Go ahead\r\n").unwrap();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&line);
+                    }
+                    "250 Queued"
+                }
+                "QUIT" => {
+                    stream.write_all(b"221 Bye\r\n").unwrap();
+                    break;
+                }
+                _ => "250 OK",
+            };
+            stream
+                .write_all(format!("{}\r\n", reply).as_bytes())
+                .unwrap();
+        }
+        (commands, data)
+    })
+}
+
+fn raw_message(subject: &str, body: &str, attachment: Option<(&str, &str)>) -> String {
+    let mut message = format!(
+        "From: Bank <noreply@bank.kz>\r\nTo: ops@example.com\r\nSubject: {}\r\n\
+         Date: Mon, 3 Mar 2025 10:15:00 +0500\r\nMessage-ID: <{}@bank.kz>\r\n",
+        subject,
+        subject.to_lowercase().replace(' ', "-")
+    );
+    match attachment {
+        Some((name, base64)) => message.push_str(&format!(
+            "Content-Type: multipart/mixed; boundary=\"b1\"\r\n\r\n\
+             --b1\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n\
+             --b1\r\nContent-Type: application/pdf\r\nContent-Transfer-Encoding: base64\r\n\
+             Content-Disposition: attachment; filename=\"{}\"\r\n\r\n{}\r\n--b1--\r\n",
+            body, name, base64
+        )),
+        None => message.push_str(&format!("\r\n{}\r\n", body)),
+    }
+    message
+}
+
+/// A stand-in IMAP server holding messages by UID. Returns the commands it received.
+fn imap_server(messages: Vec<(u64, String)>) -> (u16, JoinHandle<Vec<String>>) {
+    serve(move |mut reader, mut stream| {
+        let mut commands = Vec::new();
+        stream.write_all(b"* OK IMAP4rev1 ready\r\n").unwrap();
+        loop {
+            let line = read_line(&mut reader);
+            if line.is_empty() {
+                break;
+            }
+            commands.push(line.clone());
+            let (tag, command) = line.split_once(' ').unwrap();
+            let mut reply = String::new();
+            if command.starts_with("LOGIN") && !command.ends_with("\"secret\"") {
+                reply.push_str(&format!(
+                    "{} NO [AUTHENTICATIONFAILED] Invalid credentials\r\n",
+                    tag
+                ));
+            } else if command.starts_with("SELECT") || command.starts_with("EXAMINE") {
+                reply.push_str(&format!("* {} EXISTS\r\n", messages.len()));
+            } else if command.starts_with("UID SEARCH") {
+                let uids: Vec<String> = messages.iter().map(|(uid, _)| uid.to_string()).collect();
+                reply.push_str(&format!("* SEARCH {}\r\n", uids.join(" ")));
+            } else if let Some(set) = command.strip_prefix("UID FETCH ") {
+                let set = set.split(' ').next().unwrap();
+                for (n, (uid, raw)) in messages.iter().enumerate() {
+                    if set.split(',').any(|id| id == uid.to_string()) {
+                        reply.push_str(&format!(
+                            "* {} FETCH (UID {} BODY[] {{{}}}\r\n{})\r\n",
+                            n + 1,
+                            uid,
+                            raw.len(),
+                            raw
+                        ));
+                    }
+                }
+            } else if command == "LOGOUT" {
+                reply.push_str("* BYE Logging out\r\n");
+            }
+            if !reply.contains(&format!("{} NO", tag)) {
+                reply.push_str(&format!("{} OK Done\r\n", tag));
+            }
+            stream.write_all(reply.as_bytes()).unwrap();
+            if command == "LOGOUT" {
+                break;
+            }
+        }
+        commands
+    })
+}
+
+fn local_server(port: u16) -> MailServer {
+    MailServer::new("127.0.0.1", port, MailSecurity::None)
+        .with_credentials("robot", "secret")
+        .with_insecure_login(true)
+}
+
+#[test]
+fn test_smtp_sends_html_message_with_attachments() {
+    let dir = tempfile::tempdir().unwrap();
+    let attachment = dir.path().join("report.csv");
+    std::fs::write(&attachment, "id;amount\n1;1500\n").unwrap();
+
+    let (port, server) = smtp_server(false);
+    let message = EmailMessage {
+        from: "Robot <robot@example.com>".to_string(),
+        to: "ops@example.com; Dana <dana@example.com>".to_string(),
+        cc: "audit@example.com".to_string(),
+        subject: "Daily report".to_string(),
+        body: "Report attached.\n.hidden line".to_string(),
+        html_body: Some("<p>Report attached.</p>".to_string()),
+        attachments: vec![attachment.display().to_string()],
+        ..Default::default()
+    };
+    Smtp::send(&local_server(port), &message).unwrap();
+    let (commands, data) = server.join().unwrap();
+
+    assert_eq!(commands[0], "EHLO localhost");
+    assert_eq!(commands[1], "AUTH PLAIN AHJvYm90AHNlY3JldA==");
+    assert_eq!(commands[2], "MAIL FROM:<robot@example.com>");
+    assert_eq!(
+        &commands[3..6],
+        [
+            "RCPT TO:<ops@example.com>",
+            "RCPT TO:<dana@example.com>",
+            "RCPT TO:<audit@example.com>"
+        ]
+    );
+    assert_eq!(commands.last().unwrap(), "QUIT");
+
+    assert!(data.contains("From: \"Robot\" <robot@example.com>\r\n"));
+    assert!(data.contains("Cc: audit@example.com\r\n"));
+    assert!(data.contains("Subject: Daily report\r\n"));
+    assert!(data.contains("Message-ID: <"));
+    assert!(data.contains("multipart/mixed"));
+    assert!(data.contains("multipart/alternative"));
+    assert!(data.contains("<p>Report attached.</p>"));
+    assert!(data.contains("\r\n..hidden line"));
+    assert!(data.contains("filename=\"report.csv\""));
+    assert!(data.contains("aWQ7YW1vdW50CjE7MTUwMAo="));
+}
+
+#[test]
+fn test_smtp_reports_rejected_recipients() {
+    let (port, server) = smtp_server(true);
+    let message = EmailMessage {
+        from: "robot@example.com".to_string(),
+        to: "nobody@example.com".to_string(),
+        subject: "Hello".to_string(),
+        ..Default::default()
+    };
+    let error = Smtp::send(&local_server(port), &message).unwrap_err();
+    drop(server);
+
+    assert!(error.contains("RCPT TO"), "{}", error);
+    assert!(error.contains("550 No such user"), "{}", error);
+}
+
+#[test]
+fn test_smtp_validates_message_before_connecting() {
+    let server = MailServer::new("127.0.0.1", 1, MailSecurity::None);
+    let mut message = EmailMessage {
+        from: "robot@example.com".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(Smtp::send(&server, &message).unwrap_err(), "No recipients");
+
+    message.to = "ops@example.com".to_string();
+    message.attachments = vec!["/nonexistent/file.pdf".to_string()];
+    assert!(
+        Smtp::send(&server, &message)
+            .unwrap_err()
+            .contains("Failed to read attachment")
+    );
+}
+
+#[test]
+fn test_mail_refuses_plain_text_login_unless_allowed() {
+    let message = EmailMessage {
+        from: "robot@example.com".to_string(),
+        to: "ops@example.com".to_string(),
+        ..Default::default()
+    };
+    // Nothing listens on port 1: the password is refused before connecting.
+    let server = local_server(1).with_insecure_login(false);
+    let error = Smtp::send(&server, &message).unwrap_err();
+    assert!(error.contains("unencrypted connection"), "{}", error);
+    let error = Imap::fetch(&server, &ImapQuery::default()).unwrap_err();
+    assert!(error.contains("unencrypted connection"), "{}", error);
+
+    let server = MailServer::new("127.0.0.1", 1, MailSecurity::None);
+    let error = Smtp::send(&server, &message).unwrap_err();
+    assert!(error.starts_with("Failed to connect"), "{}", error);
+}
+
+#[test]
+fn test_starttls_upgrades_the_same_connection() {
+    // Answers STARTTLS and returns the first byte sent after it, which starts
+    // a TLS handshake record (0x16) when the client upgraded in place.
+    let (port, server) = serve(|mut reader, mut stream| {
+        stream.write_all(b"220 test ESMTP\r\n").unwrap();
+        assert_eq!(read_line(&mut reader), "EHLO localhost");
+        stream.write_all(b"250-test\r\n250 STARTTLS\r\n").unwrap();
+        assert_eq!(read_line(&mut reader), "STARTTLS");
+        stream.write_all(b"220 Ready to start TLS\r\n").unwrap();
+        let mut first = [0u8];
+        reader.read_exact(&mut first).unwrap();
+        first[0]
+    });
+    let message = EmailMessage {
+        from: "robot@example.com".to_string(),
+        to: "ops@example.com".to_string(),
+        ..Default::default()
+    };
+    let mail_server = MailServer::new("127.0.0.1", port, MailSecurity::StartTls);
+    let error = Smtp::send(&mail_server, &message).unwrap_err();
+
+    assert_eq!(server.join().unwrap(), 0x16);
+    assert!(error.starts_with("TLS connection failed"), "{}", error);
+}
+
+#[test]
+fn test_tls_reports_handshake_failure() {
+    let (port, server) = serve(|_, mut stream| {
+        stream.write_all(b"* OK IMAP4rev1 ready\r\n").unwrap();
+    });
+    let mail_server = MailServer::new("127.0.0.1", port, MailSecurity::Tls);
+    let error = Imap::fetch(&mail_server, &ImapQuery::default()).unwrap_err();
+    server.join().unwrap();
+
+    assert!(error.starts_with("TLS connection failed"), "{}", error);
+}
+
+#[test]
+fn test_tls_reports_unusable_ca_bundle() {
+    let dir = tempfile::tempdir().unwrap();
+    let empty = dir.path().join("empty.pem");
+    std::fs::write(&empty, "not a certificate\n").unwrap();
+
+    for (bundle, expected) in [
+        (
+            dir.path().join("missing.pem"),
+            "Failed to read CA certificates",
+        ),
+        (empty, "No CA certificates found"),
+    ] {
+        let (port, server) = serve(|_, _| {});
+        let mail_server =
+            MailServer::new("127.0.0.1", port, MailSecurity::Tls).with_ca_bundle(bundle);
+        let error = Imap::fetch(&mail_server, &ImapQuery::default()).unwrap_err();
+        server.join().unwrap();
+
+        assert!(error.starts_with(expected), "{}", error);
+    }
+}
+
+#[test]
+fn test_imap_fetches_messages_and_saves_attachments() {
+    let dir = tempfile::tempdir().unwrap();
+    let (port, server) = imap_server(vec![
+        (
+            7,
+            raw_message(
+                "Statement",
+                "Your statement is ready.",
+                Some(("statement.pdf", "JVBERi0xLjQ=")),
+            ),
+        ),
+        (9, raw_message("Reminder", "Payment due tomorrow.", None)),
+    ]);
+    let query = ImapQuery {
+        folder: "Входящие".to_string(),
+        mark_read: true,
+        attachments_dir: Some(dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let messages = Imap::fetch(&local_server(port), &query).unwrap();
+    let commands = server.join().unwrap();
+
+    assert_eq!(
+        commands,
+        [
+            "A1 LOGIN \"robot\" \"secret\"",
+            "A2 SELECT \"&BBIERQQ+BDQETwRJBDgENQ-\"",
+            "A3 UID SEARCH UNSEEN",
+            "A4 UID FETCH 7,9 (UID BODY.PEEK[])",
+            "A5 UID STORE 7,9 +FLAGS.SILENT (\\Seen)",
+            "A6 LOGOUT",
+        ]
+    );
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].from, "Bank <noreply@bank.kz>");
+    assert_eq!(messages[0].to, "ops@example.com");
+    assert_eq!(messages[0].subject, "Statement");
+    assert_eq!(messages[0].body, "Your statement is ready.");
+    assert_eq!(messages[0].timestamp, "2025-03-03T10:15:00+05:00");
+    assert_eq!(messages[0].message_id, "<statement@bank.kz>");
+    let saved = dir.path().join("statement.pdf");
+    assert_eq!(messages[0].attachments, [saved.display().to_string()]);
+    assert_eq!(std::fs::read(&saved).unwrap(), b"%PDF-1.4");
+    assert_eq!(messages[1].body, "Payment due tomorrow.\r\n");
+    assert!(messages[1].attachments.is_empty());
+}
+
+#[test]
+fn test_imap_limit_keeps_newest_and_examines_read_only() {
+    let (port, server) = imap_server(vec![
+        (3, raw_message("Old", "old", None)),
+        (4, raw_message("New", "new", None)),
+    ]);
+    let query = ImapQuery {
+        criteria: "FROM \"bank\"".to_string(),
+        limit: 1,
+        ..Default::default()
+    };
+    let messages = Imap::fetch(&local_server(port), &query).unwrap();
+    let commands = server.join().unwrap();
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].subject, "New");
+    assert_eq!(commands[1], "A2 EXAMINE \"INBOX\"");
+    assert_eq!(commands[2], "A3 UID SEARCH FROM \"bank\"");
+    assert_eq!(commands[3], "A4 UID FETCH 4 (UID BODY.PEEK[])");
+    assert!(!commands.iter().any(|c| c.contains("STORE")));
+}
+
+#[test]
+fn test_imap_reports_login_failure() {
+    let (port, server) = imap_server(Vec::new());
+    let mail_server = MailServer::new("127.0.0.1", port, MailSecurity::None)
+        .with_credentials("robot", "wrong")
+        .with_insecure_login(true);
+    let error = Imap::fetch(&mail_server, &ImapQuery::default()).unwrap_err();
+    drop(server);
+
+    assert!(error.starts_with("IMAP login failed"), "{}", error);
+    assert!(error.contains("Invalid credentials"), "{}", error);
+}
+
+#[test]
+fn test_mime_decodes_cyrillic_charsets() {
+    let koi8 = b"From: =?koi8-r?B?4s/O1SDCwc7L?= <noreply@bank.ru>\r\n\
+Subject: =?windows-1251?Q?=C2=FB=EF=E8=F1=EA=E0?=\r\n\
+Content-Type: text/plain; charset=koi8-r\r\n\
+Content-Transfer-Encoding: 8bit\r\n\r\n\xf7\xd9\xd0\xc9\xd3\xcb\xc1 \xc7\xcf\xd4\xcf\xd7\xc1\r\n";
+    let message = Mime::parse(koi8).message;
+    assert_eq!(message.from, "Бону банк <noreply@bank.ru>");
+    assert_eq!(message.subject, "Выписка");
+    assert_eq!(message.body, "Выписка готова\r\n");
+
+    // Some gateways write 8-bit headers in the charset of the body.
+    let raw_headers = b"From: \xc1\xf3\xf5\xe3\xe0\xeb\xf2\xe5\xf0\xe8\xff <acc@example.kz>\r\n\
+Subject: \xce\xf2\xf7\xe5\xf2\r\n\
+Content-Type: text/plain; charset=windows-1251\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\r\n=CE=F2=F7=E5=F2 =E3=EE=F2=EE=E2\r\n";
+    let message = Mime::parse(raw_headers).message;
+    assert_eq!(message.from, "Бухгалтерия <acc@example.kz>");
+    assert_eq!(message.subject, "Отчет");
+    assert_eq!(message.body, "Отчет готов\r\n");
+}
+
+#[test]
+fn test_mime_separates_inline_images_from_attachments() {
+    let raw = "From: shop@example.com\r\nSubject: Invoice\r\n\
+        Content-Type: multipart/mixed; boundary=outer\r\n\r\n\
+        --outer\r\nContent-Type: multipart/related; boundary=inner\r\n\r\n\
+        --inner\r\nContent-Type: text/html; charset=utf-8\r\n\r\n\
+        <p>Thanks!</p><img src=\"cid:logo@shop\">\r\n\
+        --inner\r\nContent-Type: image/png\r\nContent-ID: <logo@shop>\r\n\
+        Content-Transfer-Encoding: base64\r\n\r\niVBORw0KGgo=\r\n--inner--\r\n\
+        --outer\r\nContent-Type: application/pdf; name=\"=?utf-8?B?0YHRh9C10YIucGRm?=\"\r\n\
+        Content-Disposition: attachment\r\nContent-Transfer-Encoding: base64\r\n\r\n\
+        JVBERi0xLjQ=\r\n--outer--\r\n";
+    let parsed = Mime::parse(raw.as_bytes());
+
+    assert_eq!(
+        parsed.message.html_body.as_deref(),
+        Some("<p>Thanks!</p><img src=\"cid:logo@shop\">")
+    );
+    assert_eq!(parsed.message.body, "Thanks!");
+    assert_eq!(parsed.message.attachments, ["счет.pdf"]);
+    assert_eq!(parsed.attachments.len(), 2);
+    assert_eq!(
+        parsed.attachments[0].content_id.as_deref(),
+        Some("logo@shop")
+    );
+    assert_eq!(parsed.attachments[0].data, b"\x89PNG\r\n\x1a\n");
+    assert_eq!(parsed.attachments[1].data, b"%PDF-1.4");
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("счет.pdf"), "older").unwrap();
+    let saved = Mime::save_attachments(dir.path(), &parsed.attachments, false).unwrap();
+    assert_eq!(
+        saved,
+        [dir.path().join("счет (1).pdf").display().to_string()]
+    );
+    let saved = Mime::save_attachments(dir.path(), &parsed.attachments, true).unwrap();
+    assert_eq!(
+        saved,
+        [
+            dir.path().join("attachment1.png").display().to_string(),
+            dir.path().join("счет (2).pdf").display().to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_mime_drafts_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("draft.eml");
+    let message = EmailMessage {
+        from: "Робот <robot@example.kz>".to_string(),
+        to: "ops@example.kz".to_string(),
+        subject: "Отчёт за март".to_string(),
+        body: "Сводка во вложении.".to_string(),
+        html_body: Some("<p>Сводка во вложении.</p><img src=\"cid:chart\">".to_string()),
+        ..Default::default()
+    };
+    let attachments = [
+        Attachment {
+            filename: "chart.png".to_string(),
+            content_type: "image/png".to_string(),
+            content_id: Some("chart".to_string()),
+            data: b"\x89PNG".to_vec(),
+        },
+        Attachment {
+            filename: "отчёт.csv".to_string(),
+            content_type: "text/csv".to_string(),
+            content_id: None,
+            data: "id;сумма\n1;1500\n".as_bytes().to_vec(),
+        },
+    ];
+    Mime::save_draft(&path, &message, &attachments).unwrap();
+
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert!(raw.is_ascii());
+    assert!(raw.contains("X-Unsent: 1\r\n"));
+    assert!(raw.contains("multipart/related"));
+
+    let parsed = Mime::read(&path).unwrap();
+    assert_eq!(parsed.message.from, "Робот <robot@example.kz>");
+    assert_eq!(parsed.message.subject, message.subject);
+    assert_eq!(parsed.message.body, message.body);
+    assert_eq!(parsed.message.html_body, message.html_body);
+    assert_eq!(parsed.message.attachments, ["отчёт.csv"]);
+    assert_eq!(parsed.attachments, attachments);
+    assert!(Mime::read(&dir.path().join("missing.eml")).is_err());
+}
@@ -72,6 +72,7 @@ fn test_email_message_with_attachments() {
         body: "Test body".to_string(),
         timestamp: "2025-01-01T00:00:00Z".to_string(),
         attachments: vec!["file.pdf".to_string(), "document.docx".to_string()],
+        ..Default::default()
     };
     assert_eq!(email.to, "test@example.com");
     assert_eq!(email.attachments.len(), 2);
@@ -1,4 +1,6 @@
-use office::Word;
+use docx_rs::{Docx, Footer, Header, Paragraph, Run, Table, TableCell, TableRow};
+use office::{TemplateValue, Word};
+use std::collections::BTreeMap;
 
 #[test]
 fn test_word_create_document() {
@@ -44,12 +46,181 @@ fn test_word_roundtrip() {
 
     assert!(Word::write(&doc, &temp_file).is_ok());
 
-    let read_doc = Word::read(&temp_file);
-    assert!(read_doc.is_ok(), "Failed to read Word document");
+    let content = Word::read(&temp_file).expect("Failed to read Word document");
+    assert_eq!(content, "Test Content\nSecond Line");
 
-    if let Ok(content) = read_doc {
-        assert!(!content.is_empty(), "Read content is empty");
+    let _ = std::fs::remove_file(&temp_file);
+}
+
+fn text_paragraph(text: &str) -> Paragraph {
+    Paragraph::new().add_run(Run::new().add_text(text))
+}
+
+fn text_cell(text: &str) -> TableCell {
+    TableCell::new().add_paragraph(text_paragraph(text))
+}
+
+#[test]
+fn test_word_writes_valid_package() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("package.docx");
+    let doc = Word::add_paragraph(Word::create_document(), "Packed");
+    Word::write(&doc, &path).unwrap();
+
+    let archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let names: Vec<&str> = archive.file_names().collect();
+    for part in [
+        "[Content_Types].xml",
+        "_rels/.rels",
+        "word/document.xml",
+        "word/styles.xml",
+    ] {
+        assert!(names.contains(&part), "missing {}", part);
     }
+}
 
-    let _ = std::fs::remove_file(&temp_file);
+#[test]
+fn test_word_extracts_headers_tables_and_footers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("contract.docx");
+
+    let docx = Docx::new()
+        .header(Header::new().add_paragraph(text_paragraph("ACME Ltd")))
+        .footer(Footer::new().add_paragraph(text_paragraph("Page footer")))
+        .add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new().add_text("Contract No. "))
+                .add_run(Run::new().add_text("AB-2024/117").bold()),
+        )
+        .add_table(Table::new(vec![
+            TableRow::new(vec![text_cell("Item"), text_cell("Amount")]),
+            TableRow::new(vec![
+                text_cell("Services"),
+                TableCell::new()
+                    .add_paragraph(text_paragraph("1 500"))
+                    .add_paragraph(text_paragraph("KZT")),
+            ]),
+        ]))
+        .add_paragraph(text_paragraph("Signed"));
+    Word::write_docx(docx, &path).unwrap();
+
+    assert_eq!(
+        Word::read(&path).unwrap(),
+        "ACME Ltd\nContract No. AB-2024/117\nItem\tAmount\nServices\t1 500 KZT\nSigned\nPage footer"
+    );
+}
+
+#[test]
+fn test_word_read_rejects_non_docx() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plain.docx");
+    std::fs::write(&path, "not a zip").unwrap();
+
+    assert!(Word::read(&path).is_err());
+    assert!(Word::read(dir.path().join("missing.docx")).is_err());
+}
+
+fn template_fixture(path: &std::path::Path) {
+    let docx = Docx::new()
+        .header(Header::new().add_paragraph(text_paragraph("{company} & partners")))
+        .add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new().add_text("Dear {cli"))
+                .add_run(Run::new().add_text("ent").bold())
+                .add_run(Run::new().add_text("}, contract {number} is {state}.")),
+        )
+        .add_paragraph(text_paragraph("{#if vip}"))
+        .add_paragraph(text_paragraph("Priority support included."))
+        .add_paragraph(text_paragraph("{/if}"))
+        .add_paragraph(text_paragraph("{#if !vip}"))
+        .add_paragraph(text_paragraph("Standard support."))
+        .add_paragraph(text_paragraph("{/if}"))
+        .add_table(Table::new(vec![
+            TableRow::new(vec![text_cell("Item"), text_cell("Qty")]),
+            TableRow::new(vec![text_cell("{items.Name}"), text_cell("{items.Qty}")]),
+            TableRow::new(vec![text_cell("Total"), text_cell("{total}")]),
+        ]))
+        .add_paragraph(text_paragraph("Braces {like this} stay."));
+    Word::write_docx(docx, path).unwrap();
+}
+
+fn template_values(vip: bool) -> BTreeMap<String, TemplateValue> {
+    let item = |name: &str, qty: &str| {
+        BTreeMap::from([
+            ("Name".to_string(), name.to_string()),
+            ("Qty".to_string(), qty.to_string()),
+        ])
+    };
+    BTreeMap::from([
+        (
+            "company".to_string(),
+            TemplateValue::Text("ACME <Ltd>".to_string()),
+        ),
+        (
+            "client".to_string(),
+            TemplateValue::Text("Aigerim".to_string()),
+        ),
+        (
+            "number".to_string(),
+            TemplateValue::Text("AB-117".to_string()),
+        ),
+        ("state".to_string(), TemplateValue::Bool(true)),
+        ("vip".to_string(), TemplateValue::Bool(vip)),
+        ("total".to_string(), TemplateValue::Text("5".to_string())),
+        (
+            "items".to_string(),
+            TemplateValue::Rows(vec![item("Pen", "2"), item("Book", "3")]),
+        ),
+    ])
+}
+
+#[test]
+fn test_word_fill_template() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = dir.path().join("template.docx");
+    let output = dir.path().join("filled.docx");
+    template_fixture(&template);
+
+    Word::fill_template(&template, &output, &template_values(true)).unwrap();
+    assert_eq!(
+        Word::read(&output).unwrap(),
+        "ACME <Ltd> & partners\n\
+         Dear Aigerim, contract AB-117 is true.\n\
+         Priority support included.\n\
+         Item\tQty\nPen\t2\nBook\t3\nTotal\t5\n\
+         Braces {like this} stay."
+    );
+
+    let mut values = template_values(false);
+    values.insert("items".to_string(), TemplateValue::Rows(Vec::new()));
+    Word::fill_template(&template, &output, &values).unwrap();
+    let text = Word::read(&output).unwrap();
+    assert!(text.contains("Standard support."));
+    assert!(!text.contains("Priority"));
+    assert!(text.contains("Item\tQty\nTotal\t5"));
+}
+
+#[test]
+fn test_word_fill_template_reports_missing_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = dir.path().join("template.docx");
+    let output = dir.path().join("filled.docx");
+    template_fixture(&template);
+
+    let mut values = template_values(true);
+    values.remove("number");
+    let err = Word::fill_template(&template, &output, &values).unwrap_err();
+    assert!(err.contains("'number'"), "{}", err);
+    assert!(!output.exists());
+
+    let mut values = template_values(true);
+    values.insert(
+        "items".to_string(),
+        TemplateValue::Rows(vec![BTreeMap::from([(
+            "Name".to_string(),
+            "Pen".to_string(),
+        )])]),
+    );
+    let err = Word::fill_template(&template, &output, &values).unwrap_err();
+    assert!(err.contains("'items.Qty'"), "{}", err);
 }
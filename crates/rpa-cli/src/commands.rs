@@ -0,0 +1,153 @@
+use crate::load_project;
+use clap::Subcommand;
+use rpa_core::diff::{diff_projects, merge_projects};
+use rpa_core::secrets::SecretVault;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand)]
+pub enum Command {
+    #[command(about = "Show structural differences between two projects")]
+    Diff {
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+    },
+    #[command(about = "Three-way merge of two projects edited from a common base")]
+    Merge {
+        #[arg(value_name = "BASE")]
+        base: PathBuf,
+        #[arg(value_name = "OURS")]
+        ours: PathBuf,
+        #[arg(value_name = "THEIRS")]
+        theirs: PathBuf,
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Where to write the merged project (defaults to OURS)"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Manage secret variable values in the local encrypted vault")]
+    Secrets {
+        #[command(subcommand)]
+        action: SecretsAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SecretsAction {
+    #[command(about = "Store a secret; the value is read from stdin when omitted")]
+    Set {
+        #[arg(value_name = "NAME")]
+        name: String,
+        #[arg(value_name = "VALUE")]
+        value: Option<String>,
+    },
+    #[command(about = "List stored secret names")]
+    List,
+    #[command(about = "Remove a stored secret")]
+    Rm {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+/// Runs a subcommand and returns the process exit code.
+pub fn run(command: Command) -> i32 {
+    let result = match command {
+        Command::Diff { old, new } => diff(&old, &new),
+        Command::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+        } => {
+            let output = output.unwrap_or_else(|| ours.clone());
+            merge(&base, &ours, &theirs, &output)
+        }
+        Command::Secrets { action } => secrets(action),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        2
+    })
+}
+
+fn diff(old: &Path, new: &Path) -> Result<i32, String> {
+    let old = load_project(old)?;
+    let new = load_project(new)?;
+
+    let diff = diff_projects(&old, &new);
+    if diff.is_empty() {
+        return Ok(0);
+    }
+
+    print!("{}", diff);
+    Ok(1)
+}
+
+fn merge(base: &Path, ours: &Path, theirs: &Path, output: &Path) -> Result<i32, String> {
+    let base = load_project(base)?;
+    let ours = load_project(ours)?;
+    let theirs = load_project(theirs)?;
+
+    let result = merge_projects(&base, &ours, &theirs);
+    rpa_core::save_project(&result.project, output)?;
+
+    if result.conflicts.is_empty() {
+        println!("Merged into {}", output.display());
+        return Ok(0);
+    }
+
+    eprintln!(
+        "Merged into {} with {} conflicts, our side was kept:",
+        output.display(),
+        result.conflicts.len()
+    );
+    for conflict in &result.conflicts {
+        eprintln!("  CONFLICT: {}", conflict);
+    }
+    Ok(1)
+}
+
+fn secrets(action: SecretsAction) -> Result<i32, String> {
+    let mut vault = SecretVault::open_default()?;
+
+    match action {
+        SecretsAction::Set { name, value } => {
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    eprint!("Value for '{}': ", name);
+                    let mut line = String::new();
+                    std::io::stdin()
+                        .lock()
+                        .read_line(&mut line)
+                        .map_err(|e| format!("Failed to read value: {}", e))?;
+                    line.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            vault.set(&name, &value);
+            vault.save()?;
+            println!("Stored secret '{}' in {}", name, vault.path().display());
+        }
+        SecretsAction::List => {
+            for name in vault.names() {
+                println!("{}", name);
+            }
+        }
+        SecretsAction::Rm { name } => {
+            if !vault.remove(&name) {
+                return Err(format!("Secret '{}' not found", name));
+            }
+            vault.save()?;
+            println!("Removed secret '{}'", name);
+        }
+    }
+
+    Ok(0)
+}
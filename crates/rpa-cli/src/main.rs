@@ -1,17 +1,30 @@
+mod commands;
+
 use arc_script::Value;
 use clap::Parser;
+use commands::Command;
 use rpa_core::execution::{ExecutionContext, IrExecutor, LogOutput, ScopeFrame};
 use rpa_core::log::{LogEntry, LogLevel};
-use rpa_core::{IrBuilder, Project, ProjectFile, ScenarioValidator, StopControl};
-use std::path::PathBuf;
+use rpa_core::project_format::{PROJECT_EXTENSION, project_dir};
+use rpa_core::secrets::{SecretVault, resolve_project_secrets, secret_env_var};
+use rpa_core::{IrBuilder, Project, ScenarioValidator, StopControl};
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 #[derive(Parser)]
 #[command(name = "rpa-cli")]
 #[command(about = "QazCode RPA CLI - Execute RPA projects from command line", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    #[arg(value_name = "FILE", help = "Path to the .rpa project file")]
-    project_file: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(
+        value_name = "FILE",
+        required = true,
+        help = "Path to the .rpa project file or project directory"
+    )]
+    project_file: Option<PathBuf>,
 
     #[arg(short, long, help = "Print verbose output")]
     verbose: bool,
@@ -50,12 +63,20 @@ impl LogOutput for CliLogOutput {
 fn main() {
     let cli = Cli::parse();
 
-    if !cli.project_file.exists() {
-        eprintln!("Error: File not found: {}", cli.project_file.display());
+    if let Some(command) = cli.command {
+        std::process::exit(commands::run(command));
+    }
+
+    let project_file = cli
+        .project_file
+        .expect("clap requires FILE when no subcommand is given");
+
+    if !project_file.exists() {
+        eprintln!("Error: File not found: {}", project_file.display());
         std::process::exit(1);
     }
 
-    let project = match load_project(&cli.project_file) {
+    let mut project = match load_project(&project_file) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("Error loading project: {}", e);
@@ -63,6 +84,8 @@ fn main() {
         }
     };
 
+    resolve_secrets(&mut project);
+
     let mut variables = project.variables.clone();
 
     let _cli_vars = parse_variables(&cli.var);
@@ -202,23 +225,51 @@ fn main() {
     }
 }
 
-fn load_project(path: &PathBuf) -> Result<Project, String> {
-    if path.extension().and_then(|s| s.to_str()) != Some("rpa") {
+fn load_project(path: &Path) -> Result<Project, String> {
+    if project_dir(path).is_none()
+        && path.extension().and_then(|s| s.to_str()) != Some(PROJECT_EXTENSION)
+    {
         return Err(format!(
-            "Invalid file extension: expected .rpa, got {:?}",
+            "Invalid file extension: expected .rpa, project.toml or a project directory, got {:?}",
             path
         ));
     }
 
-    let contents =
-        std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let loaded = rpa_core::load_project(path)?;
+    for warning in &loaded.warnings {
+        eprintln!("Warning: {}", warning);
+    }
 
-    let project = serde_json::from_str::<ProjectFile>(&contents)
-        .map(|pf| pf.project)
-        .or_else(|_| serde_json::from_str::<Project>(&contents))
-        .map_err(|e| format!("Failed to parse project: {}", e))?;
+    Ok(loaded.project)
+}
 
-    Ok(project)
+/// Fills secret variables from the environment or the local vault before a run.
+fn resolve_secrets(project: &mut Project) {
+    let has_secrets = project
+        .all_scenarios()
+        .map(|s| &s.variables)
+        .chain(std::iter::once(&project.variables))
+        .any(|vars| vars.secret_names().next().is_some());
+    if !has_secrets {
+        return;
+    }
+
+    let vault = match SecretVault::open_default() {
+        Ok(vault) => Some(vault),
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            None
+        }
+    };
+
+    for name in resolve_project_secrets(project, vault.as_ref()) {
+        eprintln!(
+            "Warning: Secret '{}' is not set, use `rpa-cli secrets set {}` or {}",
+            name,
+            name,
+            secret_env_var(&name)
+        );
+    }
 }
 
 fn parse_variables(var_args: &[String]) -> indexmap::IndexMap<String, String> {
@@ -1,6 +1,11 @@
 use crate::Activity;
 use crate::constants::ActivityDefaults;
+use crate::csv::{CsvDelimiter, CsvQuote};
+use crate::desktop::ClickKind;
+use crate::files::TextEncoding;
+use crate::http::{HttpBodyKind, HttpMethod};
 use crate::log::LogLevel;
+use crate::mail::MailSecurity;
 use arc_script::VariableType;
 use shared::NanoId;
 use std::sync::LazyLock;
@@ -12,6 +17,15 @@ pub enum ActivityCategory {
     ControlFlow,
     Scenarios,
     Scripting,
+    Files,
+    Excel,
+    Word,
+    Mail,
+    Csv,
+    Data,
+    Web,
+    Database,
+    UiAutomation,
 }
 
 #[non_exhaustive]
@@ -24,6 +38,15 @@ pub enum ColorCategory {
     ControlFlow,
     Execution,
     Note,
+    Files,
+    Excel,
+    Word,
+    Mail,
+    Csv,
+    Data,
+    Web,
+    Database,
+    UiAutomation,
 }
 
 #[non_exhaustive]
@@ -73,6 +96,7 @@ pub enum PropertyType {
     ScenarioSelector,
     CodeEditor,
     Combobox,
+    Checkbox,
 }
 
 #[non_exhaustive]
@@ -181,6 +205,378 @@ static ACTIVITY_DEFINITIONS: LazyLock<Vec<(&'static ActivityMetadata, Activity)>
                     height: 64.0,
                 },
             ),
+            (
+                &READ_TEXT_FILE_METADATA,
+                Activity::ReadTextFile {
+                    path: String::new(),
+                    encoding: TextEncoding::Utf8,
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &WRITE_TEXT_FILE_METADATA,
+                Activity::WriteTextFile {
+                    path: String::new(),
+                    content: String::new(),
+                    encoding: TextEncoding::Utf8,
+                    append: false,
+                },
+            ),
+            (
+                &COPY_FILE_METADATA,
+                Activity::CopyFile {
+                    source: String::new(),
+                    destination: String::new(),
+                    overwrite: false,
+                },
+            ),
+            (
+                &MOVE_FILE_METADATA,
+                Activity::MoveFile {
+                    source: String::new(),
+                    destination: String::new(),
+                    overwrite: false,
+                },
+            ),
+            (
+                &DELETE_FILE_METADATA,
+                Activity::DeleteFile {
+                    path: String::new(),
+                },
+            ),
+            (
+                &CREATE_DIRECTORY_METADATA,
+                Activity::CreateDirectory {
+                    path: String::new(),
+                },
+            ),
+            (
+                &LIST_FILES_METADATA,
+                Activity::ListFiles {
+                    directory: String::new(),
+                    pattern: String::from(ActivityDefaults::FILE_PATTERN),
+                    recursive: false,
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &FILE_EXISTS_METADATA,
+                Activity::FileExists {
+                    path: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &WAIT_FOR_FILE_METADATA,
+                Activity::WaitForFile {
+                    path: String::new(),
+                    timeout_ms: ActivityDefaults::WAIT_FOR_FILE_TIMEOUT_MS,
+                },
+            ),
+            (
+                &EXCEL_READ_RANGE_METADATA,
+                Activity::ExcelReadRange {
+                    path: String::new(),
+                    sheet: String::from(ActivityDefaults::EXCEL_SHEET),
+                    range: String::new(),
+                    has_headers: true,
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &EXCEL_READ_CELL_METADATA,
+                Activity::ExcelReadCell {
+                    path: String::new(),
+                    sheet: String::from(ActivityDefaults::EXCEL_SHEET),
+                    cell: String::from(ActivityDefaults::EXCEL_CELL),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &EXCEL_WRITE_CELL_METADATA,
+                Activity::ExcelWriteCell {
+                    path: String::new(),
+                    sheet: String::from(ActivityDefaults::EXCEL_SHEET),
+                    cell: String::from(ActivityDefaults::EXCEL_CELL),
+                    value: String::new(),
+                },
+            ),
+            (
+                &EXCEL_WRITE_RANGE_METADATA,
+                Activity::ExcelWriteRange {
+                    path: String::new(),
+                    sheet: String::from(ActivityDefaults::EXCEL_SHEET),
+                    cell: String::from(ActivityDefaults::EXCEL_CELL),
+                    value: String::new(),
+                    write_headers: true,
+                },
+            ),
+            (
+                &EXCEL_APPEND_ROWS_METADATA,
+                Activity::ExcelAppendRows {
+                    path: String::new(),
+                    sheet: String::from(ActivityDefaults::EXCEL_SHEET),
+                    value: String::new(),
+                },
+            ),
+            (
+                &EXCEL_LIST_SHEETS_METADATA,
+                Activity::ExcelListSheets {
+                    path: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &FILL_WORD_TEMPLATE_METADATA,
+                Activity::FillWordTemplate {
+                    template_path: String::new(),
+                    output_path: String::new(),
+                    values: String::new(),
+                },
+            ),
+            (
+                &SEND_EMAIL_METADATA,
+                Activity::SendEmail {
+                    server: String::new(),
+                    port: ActivityDefaults::SMTP_PORT,
+                    security: MailSecurity::StartTls,
+                    username: String::new(),
+                    password: String::new(),
+                    allow_insecure_login: false,
+                    ca_bundle: String::new(),
+                    from: String::new(),
+                    to: String::new(),
+                    cc: String::new(),
+                    subject: String::new(),
+                    body: String::new(),
+                    html: false,
+                    attachments: String::new(),
+                },
+            ),
+            (
+                &READ_EMAILS_METADATA,
+                Activity::ReadEmails {
+                    server: String::new(),
+                    port: ActivityDefaults::IMAP_PORT,
+                    security: MailSecurity::Tls,
+                    username: String::new(),
+                    password: String::new(),
+                    allow_insecure_login: false,
+                    ca_bundle: String::new(),
+                    folder: String::from(ActivityDefaults::MAIL_FOLDER),
+                    criteria: String::from(ActivityDefaults::MAIL_CRITERIA),
+                    limit: ActivityDefaults::MAIL_LIMIT,
+                    mark_read: false,
+                    attachments_dir: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &PARSE_EMAIL_METADATA,
+                Activity::ParseEmail {
+                    path: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &SAVE_ATTACHMENTS_METADATA,
+                Activity::SaveAttachments {
+                    path: String::new(),
+                    directory: String::new(),
+                    include_inline: false,
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &READ_CSV_METADATA,
+                Activity::ReadCsv {
+                    path: String::new(),
+                    delimiter: CsvDelimiter::Auto,
+                    quote: CsvQuote::Double,
+                    encoding: TextEncoding::Utf8,
+                    has_headers: true,
+                    skip_rows: 0,
+                    max_rows: ActivityDefaults::CSV_BATCH_ROWS,
+                    output_var: String::new(),
+                    row_count_var: String::new(),
+                },
+            ),
+            (
+                &WRITE_CSV_METADATA,
+                Activity::WriteCsv {
+                    path: String::new(),
+                    value: String::new(),
+                    delimiter: CsvDelimiter::Auto,
+                    quote: CsvQuote::Double,
+                    encoding: TextEncoding::Utf8,
+                    write_headers: true,
+                },
+            ),
+            (
+                &APPEND_CSV_ROW_METADATA,
+                Activity::AppendCsvRow {
+                    path: String::new(),
+                    values: String::new(),
+                    delimiter: CsvDelimiter::Auto,
+                    quote: CsvQuote::Double,
+                    encoding: TextEncoding::Utf8,
+                },
+            ),
+            (
+                &PARSE_JSON_METADATA,
+                Activity::ParseJson {
+                    text: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &TO_JSON_METADATA,
+                Activity::ToJson {
+                    value: String::new(),
+                    pretty: false,
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &PARSE_XML_METADATA,
+                Activity::ParseXml {
+                    text: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &HTTP_REQUEST_METADATA,
+                Activity::HttpRequest {
+                    method: HttpMethod::Get,
+                    url: String::from(ActivityDefaults::HTTP_URL),
+                    query: String::new(),
+                    headers: String::new(),
+                    body_kind: HttpBodyKind::None,
+                    body: String::new(),
+                    timeout_ms: ActivityDefaults::HTTP_TIMEOUT_MS,
+                    retries: 0,
+                    status_var: String::new(),
+                    headers_var: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &EXECUTE_QUERY_METADATA,
+                Activity::ExecuteQuery {
+                    connection: String::new(),
+                    sql: String::new(),
+                    parameters: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &EXECUTE_NON_QUERY_METADATA,
+                Activity::ExecuteNonQuery {
+                    connection: String::new(),
+                    sql: String::new(),
+                    parameters: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &BEGIN_TRANSACTION_METADATA,
+                Activity::BeginTransaction {
+                    connection: String::new(),
+                },
+            ),
+            (
+                &COMMIT_TRANSACTION_METADATA,
+                Activity::CommitTransaction {
+                    connection: String::new(),
+                },
+            ),
+            (
+                &ROLLBACK_TRANSACTION_METADATA,
+                Activity::RollbackTransaction {
+                    connection: String::new(),
+                },
+            ),
+            (
+                &CLICK_METADATA,
+                Activity::Click {
+                    selector: String::from(ActivityDefaults::UI_SELECTOR),
+                    click: ClickKind::Single,
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &TYPE_INTO_METADATA,
+                Activity::TypeInto {
+                    selector: String::from(ActivityDefaults::UI_SELECTOR),
+                    text: String::new(),
+                    simulate_typing: false,
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &GET_TEXT_METADATA,
+                Activity::GetText {
+                    selector: String::from(ActivityDefaults::UI_SELECTOR),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &WAIT_FOR_ELEMENT_METADATA,
+                Activity::WaitForElement {
+                    selector: String::from(ActivityDefaults::UI_SELECTOR),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &SEND_KEYS_METADATA,
+                Activity::SendKeys {
+                    selector: String::new(),
+                    keys: String::new(),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &LAUNCH_APPLICATION_METADATA,
+                Activity::LaunchApplication {
+                    path: String::new(),
+                    arguments: String::new(),
+                    output_var: String::new(),
+                },
+            ),
+            (
+                &CLOSE_WINDOW_METADATA,
+                Activity::CloseWindow {
+                    selector: String::from(ActivityDefaults::UI_SELECTOR),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &CLICK_IMAGE_METADATA,
+                Activity::ClickImage {
+                    image_path: String::new(),
+                    confidence: ActivityDefaults::IMAGE_CONFIDENCE,
+                    click: ClickKind::Single,
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &WAIT_FOR_IMAGE_METADATA,
+                Activity::WaitForImage {
+                    image_path: String::new(),
+                    confidence: ActivityDefaults::IMAGE_CONFIDENCE,
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
+            (
+                &TAKE_SCREENSHOT_METADATA,
+                Activity::TakeScreenshot {
+                    selector: String::new(),
+                    path: String::new(),
+                    output_var: String::new(),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ),
         ]
     });
 
@@ -202,6 +598,48 @@ impl ActivityMetadata {
             Activity::RunPowershell { .. } => &RUN_POWERSHELL_METADATA,
             Activity::Note { .. } => &NOTE_METADATA,
             Activity::TryCatch => &TRY_CATCH_METADATA,
+            Activity::ReadTextFile { .. } => &READ_TEXT_FILE_METADATA,
+            Activity::WriteTextFile { .. } => &WRITE_TEXT_FILE_METADATA,
+            Activity::CopyFile { .. } => &COPY_FILE_METADATA,
+            Activity::MoveFile { .. } => &MOVE_FILE_METADATA,
+            Activity::DeleteFile { .. } => &DELETE_FILE_METADATA,
+            Activity::CreateDirectory { .. } => &CREATE_DIRECTORY_METADATA,
+            Activity::ListFiles { .. } => &LIST_FILES_METADATA,
+            Activity::FileExists { .. } => &FILE_EXISTS_METADATA,
+            Activity::WaitForFile { .. } => &WAIT_FOR_FILE_METADATA,
+            Activity::ExcelReadRange { .. } => &EXCEL_READ_RANGE_METADATA,
+            Activity::ExcelReadCell { .. } => &EXCEL_READ_CELL_METADATA,
+            Activity::ExcelWriteCell { .. } => &EXCEL_WRITE_CELL_METADATA,
+            Activity::ExcelWriteRange { .. } => &EXCEL_WRITE_RANGE_METADATA,
+            Activity::ExcelAppendRows { .. } => &EXCEL_APPEND_ROWS_METADATA,
+            Activity::ExcelListSheets { .. } => &EXCEL_LIST_SHEETS_METADATA,
+            Activity::FillWordTemplate { .. } => &FILL_WORD_TEMPLATE_METADATA,
+            Activity::SendEmail { .. } => &SEND_EMAIL_METADATA,
+            Activity::ReadEmails { .. } => &READ_EMAILS_METADATA,
+            Activity::ParseEmail { .. } => &PARSE_EMAIL_METADATA,
+            Activity::SaveAttachments { .. } => &SAVE_ATTACHMENTS_METADATA,
+            Activity::ReadCsv { .. } => &READ_CSV_METADATA,
+            Activity::WriteCsv { .. } => &WRITE_CSV_METADATA,
+            Activity::AppendCsvRow { .. } => &APPEND_CSV_ROW_METADATA,
+            Activity::ParseJson { .. } => &PARSE_JSON_METADATA,
+            Activity::ToJson { .. } => &TO_JSON_METADATA,
+            Activity::ParseXml { .. } => &PARSE_XML_METADATA,
+            Activity::HttpRequest { .. } => &HTTP_REQUEST_METADATA,
+            Activity::ExecuteQuery { .. } => &EXECUTE_QUERY_METADATA,
+            Activity::ExecuteNonQuery { .. } => &EXECUTE_NON_QUERY_METADATA,
+            Activity::BeginTransaction { .. } => &BEGIN_TRANSACTION_METADATA,
+            Activity::CommitTransaction { .. } => &COMMIT_TRANSACTION_METADATA,
+            Activity::RollbackTransaction { .. } => &ROLLBACK_TRANSACTION_METADATA,
+            Activity::Click { .. } => &CLICK_METADATA,
+            Activity::TypeInto { .. } => &TYPE_INTO_METADATA,
+            Activity::GetText { .. } => &GET_TEXT_METADATA,
+            Activity::WaitForElement { .. } => &WAIT_FOR_ELEMENT_METADATA,
+            Activity::SendKeys { .. } => &SEND_KEYS_METADATA,
+            Activity::LaunchApplication { .. } => &LAUNCH_APPLICATION_METADATA,
+            Activity::CloseWindow { .. } => &CLOSE_WINDOW_METADATA,
+            Activity::ClickImage { .. } => &CLICK_IMAGE_METADATA,
+            Activity::WaitForImage { .. } => &WAIT_FOR_IMAGE_METADATA,
+            Activity::TakeScreenshot { .. } => &TAKE_SCREENSHOT_METADATA,
         }
     }
 
@@ -250,6 +688,78 @@ impl ActivityMetadata {
                     .collect(),
                 false,
             ),
+            (
+                ActivityCategory::Files,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Files)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Excel,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Excel)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Word,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Word)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Mail,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Mail)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Csv,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Csv)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Data,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Data)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Web,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Web)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::Database,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::Database)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
+            (
+                ActivityCategory::UiAutomation,
+                all.iter()
+                    .filter(|(m, _)| m.category == ActivityCategory::UiAutomation)
+                    .cloned()
+                    .collect(),
+                false,
+            ),
         ]
     }
 }
@@ -261,6 +771,15 @@ impl ActivityCategory {
             Self::BasicActivities => "activity_groups.basic",
             Self::ControlFlow => "activity_groups.control_flow",
             Self::Scripting => "activity_groups.scripting",
+            Self::Files => "activity_groups.files",
+            Self::Excel => "activity_groups.excel",
+            Self::Word => "activity_groups.word",
+            Self::Mail => "activity_groups.mail",
+            Self::Csv => "activity_groups.csv",
+            Self::Data => "activity_groups.data",
+            Self::Web => "activity_groups.web",
+            Self::Database => "activity_groups.database",
+            Self::UiAutomation => "activity_groups.ui_automation",
         }
     }
 }
@@ -515,3 +1034,1156 @@ static RUN_POWERSHELL_METADATA: ActivityMetadata = ActivityMetadata {
         property_type: PropertyType::CodeEditor,
     }],
 };
+
+const FILE_PATH_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.file_path",
+    tooltip_key: Some("tooltips.file_path_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const ENCODING_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.encoding",
+    tooltip_key: None,
+    property_type: PropertyType::Combobox,
+};
+
+const OUTPUT_VARIABLE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.output_variable",
+    tooltip_key: Some("tooltips.output_variable_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const SOURCE_PATH_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.source_path",
+    tooltip_key: Some("tooltips.file_path_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const DESTINATION_PATH_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.destination_path",
+    tooltip_key: Some("tooltips.file_path_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const OVERWRITE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.overwrite",
+    tooltip_key: None,
+    property_type: PropertyType::Checkbox,
+};
+
+static READ_TEXT_FILE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.read_text_file",
+    button_key: "activity_buttons.read_text_file",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        FILE_PATH_PROPERTY,
+        ENCODING_PROPERTY,
+        OUTPUT_VARIABLE_PROPERTY,
+    ],
+};
+
+static WRITE_TEXT_FILE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.write_text_file",
+    button_key: "activity_buttons.write_text_file",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        FILE_PATH_PROPERTY,
+        PropertyDef {
+            label_key: "properties.file_content",
+            tooltip_key: Some("tooltips.message_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        ENCODING_PROPERTY,
+        PropertyDef {
+            label_key: "properties.append",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+    ],
+};
+
+static COPY_FILE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.copy_file",
+    button_key: "activity_buttons.copy_file",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        SOURCE_PATH_PROPERTY,
+        DESTINATION_PATH_PROPERTY,
+        OVERWRITE_PROPERTY,
+    ],
+};
+
+static MOVE_FILE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.move_file",
+    button_key: "activity_buttons.move_file",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        SOURCE_PATH_PROPERTY,
+        DESTINATION_PATH_PROPERTY,
+        OVERWRITE_PROPERTY,
+    ],
+};
+
+static DELETE_FILE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.delete_file",
+    button_key: "activity_buttons.delete_file",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[FILE_PATH_PROPERTY],
+};
+
+static CREATE_DIRECTORY_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.create_directory",
+    button_key: "activity_buttons.create_directory",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[PropertyDef {
+        label_key: "properties.directory",
+        tooltip_key: Some("tooltips.file_path_help"),
+        property_type: PropertyType::TextSingleLine,
+    }],
+};
+
+static LIST_FILES_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.list_files",
+    button_key: "activity_buttons.list_files",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.directory",
+            tooltip_key: Some("tooltips.file_path_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.file_pattern",
+            tooltip_key: Some("tooltips.file_pattern_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.recursive",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.list_files_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static FILE_EXISTS_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.file_exists",
+    button_key: "activity_buttons.file_exists",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[FILE_PATH_PROPERTY, OUTPUT_VARIABLE_PROPERTY],
+};
+
+static WAIT_FOR_FILE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.wait_for_file",
+    button_key: "activity_buttons.wait_for_file",
+    category: ActivityCategory::Files,
+    color_category: ColorCategory::Files,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        FILE_PATH_PROPERTY,
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: None,
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+const EXCEL_FILE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.file_path",
+    tooltip_key: Some("tooltips.excel_file_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const EXCEL_SHEET_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.sheet",
+    tooltip_key: Some("tooltips.excel_sheet_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const EXCEL_CELL_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.cell",
+    tooltip_key: Some("tooltips.excel_cell_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const EXCEL_VALUE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.value",
+    tooltip_key: Some("tooltips.excel_value_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+static EXCEL_READ_RANGE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.excel_read_range",
+    button_key: "activity_buttons.excel_read_range",
+    category: ActivityCategory::Excel,
+    color_category: ColorCategory::Excel,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EXCEL_FILE_PROPERTY,
+        EXCEL_SHEET_PROPERTY,
+        PropertyDef {
+            label_key: "properties.range",
+            tooltip_key: Some("tooltips.excel_range_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.has_headers",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.excel_table_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static EXCEL_READ_CELL_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.excel_read_cell",
+    button_key: "activity_buttons.excel_read_cell",
+    category: ActivityCategory::Excel,
+    color_category: ColorCategory::Excel,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EXCEL_FILE_PROPERTY,
+        EXCEL_SHEET_PROPERTY,
+        EXCEL_CELL_PROPERTY,
+        OUTPUT_VARIABLE_PROPERTY,
+    ],
+};
+
+static EXCEL_WRITE_CELL_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.excel_write_cell",
+    button_key: "activity_buttons.excel_write_cell",
+    category: ActivityCategory::Excel,
+    color_category: ColorCategory::Excel,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EXCEL_FILE_PROPERTY,
+        EXCEL_SHEET_PROPERTY,
+        EXCEL_CELL_PROPERTY,
+        EXCEL_VALUE_PROPERTY,
+    ],
+};
+
+static EXCEL_WRITE_RANGE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.excel_write_range",
+    button_key: "activity_buttons.excel_write_range",
+    category: ActivityCategory::Excel,
+    color_category: ColorCategory::Excel,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EXCEL_FILE_PROPERTY,
+        EXCEL_SHEET_PROPERTY,
+        PropertyDef {
+            label_key: "properties.start_cell",
+            tooltip_key: Some("tooltips.excel_cell_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.table",
+            tooltip_key: Some("tooltips.excel_table_input_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.write_headers",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+    ],
+};
+
+static EXCEL_APPEND_ROWS_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.excel_append_rows",
+    button_key: "activity_buttons.excel_append_rows",
+    category: ActivityCategory::Excel,
+    color_category: ColorCategory::Excel,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EXCEL_FILE_PROPERTY,
+        EXCEL_SHEET_PROPERTY,
+        PropertyDef {
+            label_key: "properties.table",
+            tooltip_key: Some("tooltips.excel_table_input_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static EXCEL_LIST_SHEETS_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.excel_list_sheets",
+    button_key: "activity_buttons.excel_list_sheets",
+    category: ActivityCategory::Excel,
+    color_category: ColorCategory::Excel,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EXCEL_FILE_PROPERTY,
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.list_sheets_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static FILL_WORD_TEMPLATE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.fill_word_template",
+    button_key: "activity_buttons.fill_word_template",
+    category: ActivityCategory::Word,
+    color_category: ColorCategory::Word,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.template",
+            tooltip_key: Some("tooltips.word_template_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_file",
+            tooltip_key: Some("tooltips.word_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.values",
+            tooltip_key: Some("tooltips.word_values_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+const MAIL_SERVER_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.server",
+    tooltip_key: Some("tooltips.mail_server_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const MAIL_PORT_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.port",
+    tooltip_key: None,
+    property_type: PropertyType::DragInt,
+};
+
+const MAIL_SECURITY_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.security",
+    tooltip_key: Some("tooltips.mail_security_help"),
+    property_type: PropertyType::Combobox,
+};
+
+const MAIL_USERNAME_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.username",
+    tooltip_key: None,
+    property_type: PropertyType::TextSingleLine,
+};
+
+const MAIL_PASSWORD_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.password",
+    tooltip_key: Some("tooltips.mail_password_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+/// Last, so the indices of the text properties before it stay put.
+const MAIL_INSECURE_LOGIN_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.allow_insecure_login",
+    tooltip_key: Some("tooltips.mail_insecure_login_help"),
+    property_type: PropertyType::Checkbox,
+};
+
+const MAIL_CA_BUNDLE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.ca_bundle",
+    tooltip_key: Some("tooltips.mail_ca_bundle_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+static SEND_EMAIL_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.send_email",
+    button_key: "activity_buttons.send_email",
+    category: ActivityCategory::Mail,
+    color_category: ColorCategory::Mail,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        MAIL_SERVER_PROPERTY,
+        MAIL_PORT_PROPERTY,
+        MAIL_SECURITY_PROPERTY,
+        MAIL_USERNAME_PROPERTY,
+        MAIL_PASSWORD_PROPERTY,
+        PropertyDef {
+            label_key: "properties.from",
+            tooltip_key: None,
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.to",
+            tooltip_key: Some("tooltips.mail_recipients_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.cc",
+            tooltip_key: Some("tooltips.mail_recipients_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.subject",
+            tooltip_key: None,
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.body",
+            tooltip_key: None,
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.html_body",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.attachments",
+            tooltip_key: Some("tooltips.mail_attachments_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        MAIL_INSECURE_LOGIN_PROPERTY,
+        MAIL_CA_BUNDLE_PROPERTY,
+    ],
+};
+
+static READ_EMAILS_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.read_emails",
+    button_key: "activity_buttons.read_emails",
+    category: ActivityCategory::Mail,
+    color_category: ColorCategory::Mail,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        MAIL_SERVER_PROPERTY,
+        MAIL_PORT_PROPERTY,
+        MAIL_SECURITY_PROPERTY,
+        MAIL_USERNAME_PROPERTY,
+        MAIL_PASSWORD_PROPERTY,
+        PropertyDef {
+            label_key: "properties.folder",
+            tooltip_key: None,
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.search_criteria",
+            tooltip_key: Some("tooltips.mail_criteria_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.limit",
+            tooltip_key: Some("tooltips.mail_limit_help"),
+            property_type: PropertyType::DragInt,
+        },
+        PropertyDef {
+            label_key: "properties.mark_read",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.attachments_folder",
+            tooltip_key: Some("tooltips.mail_attachments_folder_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.read_emails_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        MAIL_INSECURE_LOGIN_PROPERTY,
+        MAIL_CA_BUNDLE_PROPERTY,
+    ],
+};
+
+const EML_FILE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.file_path",
+    tooltip_key: Some("tooltips.eml_file_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+static PARSE_EMAIL_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.parse_email",
+    button_key: "activity_buttons.parse_email",
+    category: ActivityCategory::Mail,
+    color_category: ColorCategory::Mail,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EML_FILE_PROPERTY,
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.parse_email_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static SAVE_ATTACHMENTS_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.save_attachments",
+    button_key: "activity_buttons.save_attachments",
+    category: ActivityCategory::Mail,
+    color_category: ColorCategory::Mail,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        EML_FILE_PROPERTY,
+        PropertyDef {
+            label_key: "properties.attachments_folder",
+            tooltip_key: None,
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.include_inline",
+            tooltip_key: Some("tooltips.include_inline_help"),
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.save_attachments_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+const CSV_FILE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.file_path",
+    tooltip_key: Some("tooltips.csv_file_help"),
+    property_type: PropertyType::TextSingleLine,
+};
+
+const CSV_DELIMITER_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.delimiter",
+    tooltip_key: Some("tooltips.csv_delimiter_help"),
+    property_type: PropertyType::Combobox,
+};
+
+const CSV_QUOTE_PROPERTY: PropertyDef = PropertyDef {
+    label_key: "properties.quote",
+    tooltip_key: None,
+    property_type: PropertyType::Combobox,
+};
+
+static READ_CSV_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.read_csv",
+    button_key: "activity_buttons.read_csv",
+    category: ActivityCategory::Csv,
+    color_category: ColorCategory::Csv,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        CSV_FILE_PROPERTY,
+        CSV_DELIMITER_PROPERTY,
+        CSV_QUOTE_PROPERTY,
+        ENCODING_PROPERTY,
+        PropertyDef {
+            label_key: "properties.has_headers",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.skip_rows",
+            tooltip_key: Some("tooltips.csv_skip_rows_help"),
+            property_type: PropertyType::DragInt,
+        },
+        PropertyDef {
+            label_key: "properties.max_rows",
+            tooltip_key: Some("tooltips.csv_max_rows_help"),
+            property_type: PropertyType::DragInt,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.csv_table_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.row_count_variable",
+            tooltip_key: Some("tooltips.csv_row_count_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static WRITE_CSV_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.write_csv",
+    button_key: "activity_buttons.write_csv",
+    category: ActivityCategory::Csv,
+    color_category: ColorCategory::Csv,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        CSV_FILE_PROPERTY,
+        PropertyDef {
+            label_key: "properties.table",
+            tooltip_key: Some("tooltips.csv_write_value_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        CSV_DELIMITER_PROPERTY,
+        CSV_QUOTE_PROPERTY,
+        ENCODING_PROPERTY,
+        PropertyDef {
+            label_key: "properties.write_headers",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+    ],
+};
+
+static APPEND_CSV_ROW_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.append_csv_row",
+    button_key: "activity_buttons.append_csv_row",
+    category: ActivityCategory::Csv,
+    color_category: ColorCategory::Csv,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        CSV_FILE_PROPERTY,
+        PropertyDef {
+            label_key: "properties.row_values",
+            tooltip_key: Some("tooltips.csv_row_values_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        CSV_DELIMITER_PROPERTY,
+        CSV_QUOTE_PROPERTY,
+        ENCODING_PROPERTY,
+    ],
+};
+
+static PARSE_JSON_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.parse_json",
+    button_key: "activity_buttons.parse_json",
+    category: ActivityCategory::Data,
+    color_category: ColorCategory::Data,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.json_text",
+            tooltip_key: Some("tooltips.json_text_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.json_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static TO_JSON_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.to_json",
+    button_key: "activity_buttons.to_json",
+    category: ActivityCategory::Data,
+    color_category: ColorCategory::Data,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.value",
+            tooltip_key: Some("tooltips.to_json_value_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.pretty",
+            tooltip_key: None,
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.to_json_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static PARSE_XML_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.parse_xml",
+    button_key: "activity_buttons.parse_xml",
+    category: ActivityCategory::Data,
+    color_category: ColorCategory::Data,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.xml_text",
+            tooltip_key: Some("tooltips.xml_text_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.xml_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+static HTTP_REQUEST_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.http_request",
+    button_key: "activity_buttons.http_request",
+    category: ActivityCategory::Web,
+    color_category: ColorCategory::Web,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.method",
+            tooltip_key: None,
+            property_type: PropertyType::Combobox,
+        },
+        PropertyDef {
+            label_key: "properties.url",
+            tooltip_key: Some("tooltips.http_url_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.query_params",
+            tooltip_key: Some("tooltips.http_query_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.headers",
+            tooltip_key: Some("tooltips.http_headers_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.body_kind",
+            tooltip_key: Some("tooltips.http_body_kind_help"),
+            property_type: PropertyType::Combobox,
+        },
+        PropertyDef {
+            label_key: "properties.request_body",
+            tooltip_key: Some("tooltips.http_body_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: None,
+            property_type: PropertyType::DragInt,
+        },
+        PropertyDef {
+            label_key: "properties.retries",
+            tooltip_key: Some("tooltips.http_retries_help"),
+            property_type: PropertyType::DragInt,
+        },
+        PropertyDef {
+            label_key: "properties.status_variable",
+            tooltip_key: Some("tooltips.http_status_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.headers_variable",
+            tooltip_key: Some("tooltips.http_headers_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.http_body_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static EXECUTE_QUERY_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.execute_query",
+    button_key: "activity_buttons.execute_query",
+    category: ActivityCategory::Database,
+    color_category: ColorCategory::Database,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.connection",
+            tooltip_key: Some("tooltips.db_connection_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.sql",
+            tooltip_key: Some("tooltips.db_sql_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.query_parameters",
+            tooltip_key: Some("tooltips.db_parameters_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.db_query_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static EXECUTE_NON_QUERY_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.execute_non_query",
+    button_key: "activity_buttons.execute_non_query",
+    category: ActivityCategory::Database,
+    color_category: ColorCategory::Database,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.connection",
+            tooltip_key: Some("tooltips.db_connection_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.sql",
+            tooltip_key: Some("tooltips.db_sql_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.query_parameters",
+            tooltip_key: Some("tooltips.db_parameters_help"),
+            property_type: PropertyType::TextMultiLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.db_rows_affected_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static BEGIN_TRANSACTION_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.begin_transaction",
+    button_key: "activity_buttons.begin_transaction",
+    category: ActivityCategory::Database,
+    color_category: ColorCategory::Database,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[PropertyDef {
+        label_key: "properties.connection",
+        tooltip_key: Some("tooltips.db_transaction_help"),
+        property_type: PropertyType::TextSingleLine,
+    }],
+};
+
+static COMMIT_TRANSACTION_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.commit_transaction",
+    button_key: "activity_buttons.commit_transaction",
+    category: ActivityCategory::Database,
+    color_category: ColorCategory::Database,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[PropertyDef {
+        label_key: "properties.connection",
+        tooltip_key: Some("tooltips.db_connection_help"),
+        property_type: PropertyType::TextSingleLine,
+    }],
+};
+
+static ROLLBACK_TRANSACTION_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.rollback_transaction",
+    button_key: "activity_buttons.rollback_transaction",
+    category: ActivityCategory::Database,
+    color_category: ColorCategory::Database,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[PropertyDef {
+        label_key: "properties.connection",
+        tooltip_key: Some("tooltips.db_connection_help"),
+        property_type: PropertyType::TextSingleLine,
+    }],
+};
+
+static CLICK_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.click",
+    button_key: "activity_buttons.click",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.click_kind",
+            tooltip_key: None,
+            property_type: PropertyType::Combobox,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static TYPE_INTO_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.type_into",
+    button_key: "activity_buttons.type_into",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.text",
+            tooltip_key: Some("tooltips.ui_text_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.simulate_typing",
+            tooltip_key: Some("tooltips.ui_simulate_typing_help"),
+            property_type: PropertyType::Checkbox,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static GET_TEXT_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.get_text",
+    button_key: "activity_buttons.get_text",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.ui_get_text_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static WAIT_FOR_ELEMENT_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.wait_for_element",
+    button_key: "activity_buttons.wait_for_element",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static SEND_KEYS_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.send_keys",
+    button_key: "activity_buttons.send_keys",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_send_keys_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.keys",
+            tooltip_key: Some("tooltips.ui_keys_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static LAUNCH_APPLICATION_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.launch_application",
+    button_key: "activity_buttons.launch_application",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.application_path",
+            tooltip_key: Some("tooltips.ui_launch_path_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.arguments",
+            tooltip_key: Some("tooltips.ui_arguments_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.ui_process_id_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+    ],
+};
+
+static CLOSE_WINDOW_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.close_window",
+    button_key: "activity_buttons.close_window",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_close_window_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static CLICK_IMAGE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.click_image",
+    button_key: "activity_buttons.click_image",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.image_path",
+            tooltip_key: Some("tooltips.ui_image_path_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.confidence",
+            tooltip_key: Some("tooltips.ui_confidence_help"),
+            property_type: PropertyType::Slider,
+        },
+        PropertyDef {
+            label_key: "properties.click_kind",
+            tooltip_key: None,
+            property_type: PropertyType::Combobox,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static WAIT_FOR_IMAGE_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.wait_for_image",
+    button_key: "activity_buttons.wait_for_image",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.image_path",
+            tooltip_key: Some("tooltips.ui_image_path_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.confidence",
+            tooltip_key: Some("tooltips.ui_confidence_help"),
+            property_type: PropertyType::Slider,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
+
+static TAKE_SCREENSHOT_METADATA: ActivityMetadata = ActivityMetadata {
+    name_key: "activity_names.take_screenshot",
+    button_key: "activity_buttons.take_screenshot",
+    category: ActivityCategory::UiAutomation,
+    color_category: ColorCategory::UiAutomation,
+    pin_config: PIN_SUCCESS_ERROR,
+    can_have_error_output: true,
+    properties: &[
+        PropertyDef {
+            label_key: "properties.selector",
+            tooltip_key: Some("tooltips.ui_screenshot_selector_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.file_path",
+            tooltip_key: Some("tooltips.ui_screenshot_path_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.output_variable",
+            tooltip_key: Some("tooltips.ui_screenshot_output_help"),
+            property_type: PropertyType::TextSingleLine,
+        },
+        PropertyDef {
+            label_key: "properties.timeout_ms",
+            tooltip_key: Some("tooltips.ui_timeout_help"),
+            property_type: PropertyType::DragInt,
+        },
+    ],
+};
@@ -18,6 +18,11 @@ impl CoreConstants {
     pub const MAX_RECURSION_DEPTH: usize = 100;
 
     pub const ERROR_VARIABLE_NAME: &'static str = "last_error";
+    pub const FILE_POLL_INTERVAL_MS: u64 = 250;
+    pub const ELEMENT_POLL_INTERVAL_MS: u64 = 200;
+    /// Folder under the system temp folder that holds one folder per run for
+    /// screenshots and other evidence
+    pub const RUN_FOLDER_NAME: &'static str = "rpa-runs";
     pub const TIMESTAMP_FORMAT_MINUTES: u64 = 60;
 }
 
@@ -45,6 +50,21 @@ impl ActivityDefaults {
     pub const LOOP_INDEX: &'static str = "i";
     pub const POWERSHELL_CODE: &'static str = "";
     pub const NOTE_TEXT: &'static str = "default_values.note_text";
+    pub const FILE_PATTERN: &'static str = "\"*\"";
+    pub const WAIT_FOR_FILE_TIMEOUT_MS: u64 = 30_000;
+    pub const EXCEL_SHEET: &'static str = "\"Sheet1\"";
+    pub const EXCEL_CELL: &'static str = "\"A1\"";
+    pub const SMTP_PORT: u16 = 587;
+    pub const IMAP_PORT: u16 = 993;
+    pub const MAIL_FOLDER: &'static str = "\"INBOX\"";
+    pub const MAIL_CRITERIA: &'static str = "\"UNSEEN\"";
+    pub const MAIL_LIMIT: u64 = 50;
+    pub const CSV_BATCH_ROWS: u64 = 0;
+    pub const HTTP_URL: &'static str = "\"https://\"";
+    pub const HTTP_TIMEOUT_MS: u64 = 30_000;
+    pub const UI_SELECTOR: &'static str = "Window>title~";
+    pub const UI_TIMEOUT_MS: u64 = 10_000;
+    pub const IMAGE_CONFIDENCE: f32 = ui_automation::DEFAULT_CONFIDENCE;
 }
 
 pub struct ValidationConstants;
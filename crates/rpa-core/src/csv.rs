@@ -0,0 +1,864 @@
+use crate::files::{self, TextEncoding};
+use ::csv::{
+    QuoteStyle, ReaderBuilder, StringRecord, StringRecordsIntoIter, Terminator, WriterBuilder,
+};
+use arc_script::{Table, Value};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use serde::{Deserialize, Serialize};
+use shared::NanoId;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Bytes at the start of a file looked at to detect its delimiter.
+const SNIFF_SAMPLE_BYTES: u64 = 64 * 1024;
+/// Records of the sample compared when detecting the delimiter.
+const SNIFF_RECORDS: usize = 50;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CsvDelimiter {
+    /// Detected from the start of the file, or a comma for a new file.
+    #[default]
+    Auto,
+    Comma,
+    Semicolon,
+    Tab,
+    Pipe,
+}
+
+impl CsvDelimiter {
+    const CANDIDATES: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+    pub fn all() -> [CsvDelimiter; 5] {
+        [
+            CsvDelimiter::Auto,
+            CsvDelimiter::Comma,
+            CsvDelimiter::Semicolon,
+            CsvDelimiter::Tab,
+            CsvDelimiter::Pipe,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CsvDelimiter::Auto => "Auto",
+            CsvDelimiter::Comma => ", (comma)",
+            CsvDelimiter::Semicolon => "; (semicolon)",
+            CsvDelimiter::Tab => "Tab",
+            CsvDelimiter::Pipe => "| (pipe)",
+        }
+    }
+
+    fn byte(&self) -> Option<u8> {
+        match self {
+            CsvDelimiter::Auto => None,
+            CsvDelimiter::Comma => Some(b','),
+            CsvDelimiter::Semicolon => Some(b';'),
+            CsvDelimiter::Tab => Some(b'\t'),
+            CsvDelimiter::Pipe => Some(b'|'),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CsvQuote {
+    #[default]
+    Double,
+    Single,
+    /// Quotes are ordinary characters and fields are never quoted.
+    None,
+}
+
+impl CsvQuote {
+    pub fn all() -> [CsvQuote; 3] {
+        [CsvQuote::Double, CsvQuote::Single, CsvQuote::None]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CsvQuote::Double => "\" (double)",
+            CsvQuote::Single => "' (single)",
+            CsvQuote::None => "None",
+        }
+    }
+
+    fn byte(&self) -> Option<u8> {
+        match self {
+            CsvQuote::Double => Some(b'"'),
+            CsvQuote::Single => Some(b'\''),
+            CsvQuote::None => None,
+        }
+    }
+}
+
+/// How a CSV file is laid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CsvFormat {
+    pub delimiter: CsvDelimiter,
+    pub quote: CsvQuote,
+    pub encoding: TextEncoding,
+}
+
+/// Rows of a CSV file to read: `skip_rows` data rows are passed over and at
+/// most `max_rows` (0 for all) are returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CsvRange {
+    pub has_headers: bool,
+    pub skip_rows: usize,
+    pub max_rows: usize,
+}
+
+/// Reads a CSV file into a table of strings. Records are streamed from the
+/// file, so only the rows in `range` are held in memory. With `has_headers`
+/// the first record names the columns, otherwise (and for blank header
+/// fields) they are `Column1`, `Column2`, ...
+pub fn read_table(path: &Path, format: &CsvFormat, range: &CsvRange) -> Result<Table, String> {
+    CsvReader::open(path, format, range)?.read(range.max_rows)
+}
+
+/// An open CSV file that hands out its rows a batch at a time.
+pub struct CsvReader {
+    path: PathBuf,
+    format: CsvFormat,
+    range: CsvRange,
+    header: Option<StringRecord>,
+    records: StringRecordsIntoIter<Box<dyn Read + Send>>,
+}
+
+impl CsvReader {
+    /// Opens `path`, reads the header row if there is one and passes over
+    /// `range.skip_rows` data rows.
+    pub fn open(path: &Path, format: &CsvFormat, range: &CsvRange) -> Result<Self, String> {
+        let mut input = decoded_reader(path, format.encoding)?;
+        let mut sample = Vec::new();
+        (&mut input)
+            .take(SNIFF_SAMPLE_BYTES)
+            .read_to_end(&mut sample)
+            .map_err(|e| read_error(path, &e))?;
+        let delimiter = format
+            .delimiter
+            .byte()
+            .unwrap_or_else(|| sniff_delimiter(&sample, format.quote));
+
+        let input: Box<dyn Read + Send> = Box::new(Cursor::new(sample).chain(input));
+        let mut records = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .quote(format.quote.byte().unwrap_or(b'"'))
+            .quoting(format.quote.byte().is_some())
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(input)
+            .into_records();
+
+        let header = match range.has_headers {
+            true => records
+                .next()
+                .transpose()
+                .map_err(|e| read_error(path, &e))?,
+            false => None,
+        };
+        for record in records.by_ref().take(range.skip_rows) {
+            record.map_err(|e| read_error(path, &e))?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            format: *format,
+            range: *range,
+            header,
+            records,
+        })
+    }
+
+    /// The next `max_rows` rows, or all that are left for 0. The table is
+    /// empty once the end of the file has been reached.
+    pub fn read(&mut self, max_rows: usize) -> Result<Table, String> {
+        let limit = match max_rows {
+            0 => usize::MAX,
+            n => n,
+        };
+        let rows = self
+            .records
+            .by_ref()
+            .take(limit)
+            .map(|record| record.map(|record| record.iter().map(String::from).collect()))
+            .collect::<Result<Vec<Vec<String>>, _>>()
+            .map_err(|e| read_error(&self.path, &e))?;
+
+        Ok(rows_to_table(self.header.clone(), rows))
+    }
+}
+
+/// Readers kept open between runs of Read CSV, so that reading a file in
+/// batches goes through it once instead of re-reading it for every batch.
+/// Each Read CSV node has its own cursor, even when two nodes read the same file.
+#[derive(Default)]
+pub struct CsvCursors {
+    readers: HashMap<(NanoId, PathBuf), CsvReader>,
+}
+
+impl CsvCursors {
+    /// The next batch of `range.max_rows` rows of `path`, continuing where the
+    /// previous batch read by `node` stopped. A batch that comes back empty
+    /// closes the file, so reading it again starts from the top. With
+    /// `max_rows` 0 the whole file is read at once.
+    pub fn read(
+        &mut self,
+        node: &NanoId,
+        path: &Path,
+        format: &CsvFormat,
+        range: &CsvRange,
+    ) -> Result<Table, String> {
+        let key = (node.clone(), path.to_path_buf());
+        if range.max_rows == 0 {
+            self.readers.remove(&key);
+            return read_table(path, format, range);
+        }
+
+        let reader = match self.readers.entry(key.clone()) {
+            Entry::Occupied(entry)
+                if entry.get().format == *format && entry.get().range == *range =>
+            {
+                entry.into_mut()
+            }
+            Entry::Occupied(mut entry) => {
+                entry.insert(CsvReader::open(path, format, range)?);
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => entry.insert(CsvReader::open(path, format, range)?),
+        };
+        let batch = reader.read(range.max_rows);
+        if batch.as_ref().map_or(true, |table| table.rows.is_empty()) {
+            self.readers.remove(&key);
+        }
+        batch
+    }
+
+    /// Closes the files read by `nodes`, so their next batch starts from the top.
+    pub fn close(&mut self, nodes: &HashSet<NanoId>) {
+        self.readers.retain(|(node, _), _| !nodes.contains(node));
+    }
+}
+
+fn read_error(path: &Path, e: &dyn std::fmt::Display) -> String {
+    format!("Failed to read {}: {}", path.display(), e)
+}
+
+fn rows_to_table(header: Option<StringRecord>, rows: Vec<Vec<String>>) -> Table {
+    let header = header.unwrap_or_default();
+    let width = rows
+        .iter()
+        .map(Vec::len)
+        .chain([header.len()])
+        .max()
+        .unwrap_or(0);
+
+    let columns = (0..width)
+        .map(|i| match header.get(i).map(str::trim) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => format!("Column{}", i + 1),
+        })
+        .collect();
+    let rows = rows
+        .into_iter()
+        .map(|mut row| {
+            row.resize(width, String::new());
+            row.into_iter().map(Value::String).collect()
+        })
+        .collect();
+
+    Table::new(columns, rows)
+}
+
+/// Writes `value` to a new CSV file, replacing an existing one: the rows of a
+/// table, preceded by its column names when `write_headers` is set, or a
+/// single field for any other value.
+pub fn write_table(
+    path: &Path,
+    value: &Value,
+    format: &CsvFormat,
+    write_headers: bool,
+) -> Result<usize, String> {
+    let delimiter = delimiter_for_writing(path, format)?;
+    let rows = value_to_rows(value);
+    let header = match value {
+        Value::Table(table) if write_headers => Some(table.columns.clone()),
+        _ => None,
+    };
+    let text = to_csv(
+        header.into_iter().chain(rows.iter().cloned()),
+        delimiter,
+        format.quote,
+    )?;
+    files::write_text(path, &text, format.encoding, false)?;
+    Ok(rows.len())
+}
+
+/// Appends rows to a CSV file in the delimiter it already uses when the
+/// delimiter is `Auto`. A new or empty file gets `header` first.
+pub fn append_rows(
+    path: &Path,
+    rows: &[Vec<String>],
+    header: Option<&[String]>,
+    format: &CsvFormat,
+) -> Result<(), String> {
+    let delimiter = delimiter_for_writing(path, format)?;
+    let is_empty = std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+    let header = header.filter(|_| is_empty).map(<[String]>::to_vec);
+    let mut text = to_csv(
+        header.into_iter().chain(rows.iter().cloned()),
+        delimiter,
+        format.quote,
+    )?;
+    if !is_empty && !ends_with_newline(path, format.encoding)? {
+        text.insert_str(0, "\r\n");
+    }
+    files::write_text(path, &text, format.encoding, true)
+}
+
+/// Fields to write for `value`: the rows of a table, or a single field for any other value.
+pub fn value_to_rows(value: &Value) -> Vec<Vec<String>> {
+    match value {
+        Value::Table(table) => table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(ToString::to_string).collect())
+            .collect(),
+        other => vec![vec![other.to_string()]],
+    }
+}
+
+/// The delimiter that occurs the same number of times in most records of the
+/// sample, outside quotes. Ties go to the delimiter found more often per
+/// record, then to the comma. A sample without any candidate gives a comma.
+pub fn sniff_delimiter(sample: &[u8], quote: CsvQuote) -> u8 {
+    let quote = quote.byte();
+    let mut records: Vec<[usize; 4]> = vec![[0; 4]];
+    let mut quoted = false;
+    for &byte in sample {
+        if Some(byte) == quote {
+            quoted = !quoted;
+        } else if quoted {
+            continue;
+        } else if byte == b'\n' {
+            if records.len() > SNIFF_RECORDS {
+                break;
+            }
+            records.push([0; 4]);
+        } else if let Some(i) = CsvDelimiter::CANDIDATES.iter().position(|c| *c == byte) {
+            records.last_mut().unwrap()[i] += 1;
+        }
+    }
+    // The last record may be cut short by the end of the sample.
+    if records.len() > 1 {
+        records.pop();
+    }
+
+    let mut best = (0, 0, b',');
+    for (i, delimiter) in CsvDelimiter::CANDIDATES.into_iter().enumerate() {
+        let mut frequencies: Vec<(usize, usize)> = Vec::new();
+        for count in records.iter().map(|counts| counts[i]).filter(|n| *n > 0) {
+            match frequencies.iter_mut().find(|(n, _)| *n == count) {
+                Some((_, records)) => *records += 1,
+                None => frequencies.push((count, 1)),
+            }
+        }
+        if let Some((count, consistent)) = frequencies
+            .into_iter()
+            .max_by_key(|(count, records)| (*records, *count))
+            && (consistent, count) > (best.0, best.1)
+        {
+            best = (consistent, count, delimiter);
+        }
+    }
+    best.2
+}
+
+fn decoded_reader(path: &Path, encoding: TextEncoding) -> Result<impl Read + Send + use<>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let encoding = match encoding {
+        // Invalid UTF-8 is passed through so that reading reports it.
+        TextEncoding::Utf8 => None,
+        TextEncoding::Utf16 => Some(encoding_rs::UTF_16LE),
+        TextEncoding::Cp1251 => Some(encoding_rs::WINDOWS_1251),
+    };
+    Ok(DecodeReaderBytesBuilder::new()
+        .encoding(encoding)
+        .bom_override(true)
+        .utf8_passthru(true)
+        .build(file))
+}
+
+fn delimiter_for_writing(path: &Path, format: &CsvFormat) -> Result<u8, String> {
+    if let Some(delimiter) = format.delimiter.byte() {
+        return Ok(delimiter);
+    }
+    if std::fs::metadata(path).map_or(true, |m| m.len() == 0) {
+        return Ok(b',');
+    }
+    let mut sample = Vec::new();
+    decoded_reader(path, format.encoding)?
+        .take(SNIFF_SAMPLE_BYTES)
+        .read_to_end(&mut sample)
+        .map_err(|e| read_error(path, &e))?;
+    Ok(sniff_delimiter(&sample, format.quote))
+}
+
+fn ends_with_newline(path: &Path, encoding: TextEncoding) -> Result<bool, String> {
+    let error = |e: std::io::Error| format!("Failed to read {}: {}", path.display(), e);
+    let mut file = File::open(path).map_err(error)?;
+    let newline: &[u8] = match encoding {
+        TextEncoding::Utf16 => b"\n\0",
+        TextEncoding::Utf8 | TextEncoding::Cp1251 => b"\n",
+    };
+    let length = file.metadata().map_err(error)?.len();
+    if length < newline.len() as u64 {
+        return Ok(false);
+    }
+    let mut end = vec![0; newline.len()];
+    file.seek(SeekFrom::End(-(newline.len() as i64)))
+        .and_then(|_| file.read_exact(&mut end))
+        .map_err(error)?;
+    Ok(end == newline)
+}
+
+fn to_csv(
+    rows: impl Iterator<Item = Vec<String>>,
+    delimiter: u8,
+    quote: CsvQuote,
+) -> Result<String, String> {
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .quote(quote.byte().unwrap_or(b'"'))
+        .quote_style(match quote {
+            CsvQuote::None => QuoteStyle::Never,
+            CsvQuote::Double | CsvQuote::Single => QuoteStyle::Necessary,
+        })
+        .terminator(Terminator::CRLF)
+        .flexible(true)
+        .from_writer(Vec::new());
+    for row in rows {
+        writer
+            .write_record(&row)
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::CoreConstants;
+
+    use crate::execution::{ExecutionContext, IrExecutor, ScopeFrame};
+    use crate::log::LogEntry;
+    use crate::node_graph::BranchType;
+    use crate::test_support::{add_nodes, handle_errors, linear_project, run_project};
+    use crate::variables::Variables;
+    use crate::{Activity, IrBuilder, ScenarioValidator, StopControl};
+
+    fn strings(row: &[Value]) -> Vec<String> {
+        row.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn delimiters_are_detected() {
+        let statement = "Дата;Сумма;Назначение\n01.03.2025;1500,00;Оплата по счету 1, 2\n\
+                         02.03.2025;200,50;Комиссия\n";
+        assert_eq!(
+            sniff_delimiter(statement.as_bytes(), CsvQuote::Double),
+            b';'
+        );
+        assert_eq!(
+            sniff_delimiter(
+                b"id,name\n1,\"Smith; John\"\n2,\"Doe; Jane\"\n",
+                CsvQuote::Double
+            ),
+            b','
+        );
+        assert_eq!(
+            sniff_delimiter(b"a\tb|c\nd\te|f\n", CsvQuote::Double),
+            b'\t'
+        );
+        assert_eq!(
+            sniff_delimiter(b"single column\nvalue\n", CsvQuote::Double),
+            b','
+        );
+    }
+
+    #[test]
+    fn cp1251_statements_are_read_in_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("statement.csv");
+        let text = "Дата;Сумма;Назначение\r\n01.03.2025;1500,00;\"Оплата; счет 1\"\r\n\
+                    02.03.2025;200,50;Комиссия\r\n03.03.2025;10\r\n";
+        std::fs::write(&path, encoding_rs::WINDOWS_1251.encode(text).0).unwrap();
+        let format = CsvFormat {
+            encoding: TextEncoding::Cp1251,
+            ..Default::default()
+        };
+
+        let all = CsvRange {
+            has_headers: true,
+            ..Default::default()
+        };
+        let table = read_table(&path, &format, &all).unwrap();
+        assert_eq!(table.columns, ["Дата", "Сумма", "Назначение"]);
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(
+            strings(&table.rows[0]),
+            ["01.03.2025", "1500,00", "Оплата; счет 1"]
+        );
+        assert_eq!(strings(&table.rows[2]), ["03.03.2025", "10", ""]);
+
+        let page = CsvRange {
+            has_headers: true,
+            skip_rows: 1,
+            max_rows: 1,
+        };
+        let table = read_table(&path, &format, &page).unwrap();
+        assert_eq!(table.columns, ["Дата", "Сумма", "Назначение"]);
+        assert_eq!(
+            strings(&table.rows[0]),
+            ["02.03.2025", "200,50", "Комиссия"]
+        );
+
+        let past_end = CsvRange {
+            skip_rows: 10,
+            ..page
+        };
+        assert!(
+            read_table(&path, &format, &past_end)
+                .unwrap()
+                .rows
+                .is_empty()
+        );
+
+        let utf8 = CsvFormat::default();
+        let error = read_table(&path, &utf8, &all).unwrap_err();
+        assert!(
+            error.contains("UTF-8") || error.contains("utf-8"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn cursors_read_batches_without_starting_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "n\n1\n2\n3\n4\n5\n6\n").unwrap();
+        let format = CsvFormat::default();
+        let batch = CsvRange {
+            has_headers: true,
+            skip_rows: 1,
+            max_rows: 2,
+        };
+        let (node, other) = (NanoId::default(), NanoId::default());
+        let mut cursors = CsvCursors::default();
+        let mut read = |cursors: &mut CsvCursors, node: &NanoId, range: &CsvRange| {
+            let table = cursors.read(node, &path, &format, range).unwrap();
+            assert_eq!(table.columns, ["n"]);
+            table
+                .rows
+                .iter()
+                .map(|row| row[0].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(read(&mut cursors, &node, &batch), ["2", "3"]);
+        assert_eq!(read(&mut cursors, &node, &batch), ["4", "5"]);
+        // Another node reading the same file has a cursor of its own.
+        assert_eq!(read(&mut cursors, &other, &batch), ["2", "3"]);
+        // Closing a node only starts that node over.
+        cursors.close(&HashSet::from([node.clone()]));
+        assert_eq!(read(&mut cursors, &node, &batch), ["2", "3"]);
+        assert_eq!(read(&mut cursors, &other, &batch), ["4", "5"]);
+        assert_eq!(read(&mut cursors, &node, &batch), ["4", "5"]);
+        assert_eq!(read(&mut cursors, &node, &batch), ["6"]);
+        assert!(read(&mut cursors, &node, &batch).is_empty());
+        // The empty batch closed the file, so the next read starts over.
+        std::fs::write(&path, "n\n1\n2\n3\n").unwrap();
+        assert_eq!(read(&mut cursors, &node, &batch), ["2", "3"]);
+        // Other settings open the file again.
+        let first = CsvRange {
+            skip_rows: 0,
+            ..batch
+        };
+        assert_eq!(read(&mut cursors, &node, &first), ["1", "2"]);
+    }
+
+    #[test]
+    fn headerless_files_get_numbered_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "\u{feff}'a|b'|c\nd\n").unwrap();
+        let format = CsvFormat {
+            quote: CsvQuote::Single,
+            ..Default::default()
+        };
+
+        let table = read_table(&path, &format, &CsvRange::default()).unwrap();
+        assert_eq!(table.columns, ["Column1", "Column2"]);
+        assert_eq!(strings(&table.rows[0]), ["a|b", "c"]);
+        assert_eq!(strings(&table.rows[1]), ["d", ""]);
+    }
+
+    #[test]
+    fn tables_are_written_and_rows_appended() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.csv");
+        let table = Value::Table(Table::new(
+            vec!["Name".to_string(), "Amount".to_string()],
+            vec![vec![
+                Value::String("Doe; Jane".to_string()),
+                Value::Number(1500.0),
+            ]],
+        ));
+        let format = CsvFormat {
+            delimiter: CsvDelimiter::Semicolon,
+            ..Default::default()
+        };
+
+        assert_eq!(write_table(&path, &table, &format, true).unwrap(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Name;Amount\r\n\"Doe; Jane\";1500\r\n"
+        );
+
+        // Auto keeps the delimiter of the file, and a missing final newline is added.
+        std::fs::write(&path, "Name;Amount\r\nSmith;10").unwrap();
+        let auto = CsvFormat::default();
+        let rows = vec![vec!["Ivanov".to_string(), "20,5".to_string()]];
+        append_rows(&path, &rows, None, &auto).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Name;Amount\r\nSmith;10\r\nIvanov;20,5\r\n"
+        );
+
+        let new_path = dir.path().join("new.csv");
+        let header = ["Name".to_string(), "Amount".to_string()];
+        append_rows(&new_path, &rows, Some(&header), &auto).unwrap();
+        append_rows(&new_path, &rows, Some(&header), &auto).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&new_path).unwrap(),
+            "Name,Amount\r\nIvanov,\"20,5\"\r\nIvanov,\"20,5\"\r\n"
+        );
+    }
+
+    #[test]
+    fn utf16_files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("utf16.csv");
+        let format = CsvFormat {
+            delimiter: CsvDelimiter::Tab,
+            encoding: TextEncoding::Utf16,
+            ..Default::default()
+        };
+        let value = Value::Table(Table::new(
+            vec!["Город".to_string()],
+            vec![vec![Value::String("Алматы".to_string())]],
+        ));
+        write_table(&path, &value, &format, true).unwrap();
+        append_rows(&path, &[vec!["Астана".to_string()]], None, &format).unwrap();
+
+        let range = CsvRange {
+            has_headers: true,
+            ..Default::default()
+        };
+        let table = read_table(&path, &format, &range).unwrap();
+        assert_eq!(table.columns, ["Город"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1][0], Value::String("Астана".to_string()));
+    }
+
+    fn read_batch(path: &Path, name: &str) -> Activity {
+        Activity::ReadCsv {
+            path: format!("\"{}\"", path.display()),
+            delimiter: CsvDelimiter::Auto,
+            quote: CsvQuote::Double,
+            encoding: TextEncoding::Utf8,
+            has_headers: true,
+            skip_rows: 0,
+            max_rows: 2,
+            output_var: name.to_string(),
+            row_count_var: format!("{}_count", name),
+        }
+    }
+
+    fn batch_sizes(log: &[LogEntry]) -> Vec<&str> {
+        log.iter()
+            .filter_map(|entry| entry.message.strip_prefix("Read "))
+            .filter_map(|message| message.split(' ').next())
+            .collect()
+    }
+
+    #[test]
+    fn executor_reads_batches_and_counts_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "n\n1\n2\n3\n").unwrap();
+
+        let mut project = linear_project(vec![
+            read_batch(&path, "first"),
+            Activity::Loop {
+                start: 0,
+                end: 3,
+                step: 1,
+                index: "i".to_string(),
+            },
+        ]);
+        let scenario = &mut project.main_scenario;
+        let loop_node = scenario.nodes[2].id.clone();
+        let body = add_nodes(scenario, [read_batch(&path, "second")]).remove(0);
+        scenario.add_connection_with_branch(loop_node, body, BranchType::LoopBody);
+
+        let (context, log) = run_project(&project);
+        let variables = &context.scope_stack.last().unwrap().variables;
+        let count = |name: &str| variables.get(&format!("{}_count", name)).cloned();
+
+        // The node in the loop continues its own batches, not those of the first.
+        assert_eq!(batch_sizes(&log), ["2", "2", "1", "0"]);
+        assert_eq!(count("first"), Some(Value::Number(2.0)));
+        assert_eq!(count("second"), Some(Value::Number(0.0)));
+        let Some(Value::Table(first)) = variables.get("first") else {
+            panic!("first batch is not a table");
+        };
+        assert_eq!(
+            first.rows,
+            [
+                [Value::String("1".to_string())],
+                [Value::String("2".to_string())]
+            ]
+        );
+    }
+
+    #[test]
+    fn entering_a_loop_again_starts_its_batches_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "n\n1\n2\n3\n").unwrap();
+        let each = |index: &str| Activity::Loop {
+            start: 0,
+            end: 2,
+            step: 1,
+            index: index.to_string(),
+        };
+
+        let mut project = linear_project(vec![each("i")]);
+        let scenario = &mut project.main_scenario;
+        let outer = scenario.nodes[1].id.clone();
+        let ids = add_nodes(scenario, [each("j"), read_batch(&path, "rows")]);
+        scenario.add_connection_with_branch(outer, ids[0].clone(), BranchType::LoopBody);
+        scenario.add_connection_with_branch(ids[0].clone(), ids[1].clone(), BranchType::LoopBody);
+
+        let (_, log) = run_project(&project);
+        assert_eq!(batch_sizes(&log), ["2", "1", "2", "1"]);
+    }
+
+    #[test]
+    fn each_run_starts_batches_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "n\n1\n2\n3\n").unwrap();
+        let project = linear_project(vec![read_batch(&path, "rows")]);
+        let mut variables = Variables::new();
+        let reachable = ScenarioValidator::new(&project.main_scenario, &project)
+            .validate()
+            .reachable_nodes;
+        let program = IrBuilder::new(&project.main_scenario, &project, &reachable, &mut variables)
+            .build()
+            .unwrap();
+        let context = ExecutionContext::new_without_sender(
+            std::time::SystemTime::now(),
+            vec![ScopeFrame {
+                scenario_id: project.main_scenario.id.clone(),
+                variables: Variables::new(),
+            }],
+            variables,
+            StopControl::new(),
+        );
+
+        let mut log: Vec<LogEntry> = Vec::new();
+        let mut executor = IrExecutor::new(&program, &project, context, &mut log);
+        executor.execute().unwrap();
+        executor.execute().unwrap();
+        drop(executor);
+        assert_eq!(batch_sizes(&log), ["2", "2"]);
+    }
+
+    #[test]
+    fn executor_appends_reads_and_routes_csv_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let orders = dir.path().join("orders.csv");
+        let copy = dir.path().join("copy.csv");
+        let quoted = |path: &Path| format!("\"{}\"", path.display());
+
+        let mut project = linear_project(vec![
+            Activity::AppendCsvRow {
+                path: quoted(&orders),
+                values: "\"Pen\"\n3".to_string(),
+                delimiter: CsvDelimiter::Semicolon,
+                quote: CsvQuote::Double,
+                encoding: TextEncoding::Cp1251,
+            },
+            Activity::AppendCsvRow {
+                path: quoted(&orders),
+                values: "\"Ink\"\n\n2 * 5".to_string(),
+                delimiter: CsvDelimiter::Auto,
+                quote: CsvQuote::Double,
+                encoding: TextEncoding::Cp1251,
+            },
+            Activity::ReadCsv {
+                path: quoted(&orders),
+                delimiter: CsvDelimiter::Auto,
+                quote: CsvQuote::Double,
+                encoding: TextEncoding::Cp1251,
+                has_headers: false,
+                skip_rows: 0,
+                max_rows: 0,
+                output_var: "rows".to_string(),
+                row_count_var: String::new(),
+            },
+            Activity::WriteCsv {
+                path: quoted(&copy),
+                value: "@rows".to_string(),
+                delimiter: CsvDelimiter::Comma,
+                quote: CsvQuote::Double,
+                encoding: TextEncoding::Utf8,
+                write_headers: true,
+            },
+            Activity::ReadCsv {
+                path: quoted(&dir.path().join("missing.csv")),
+                delimiter: CsvDelimiter::Auto,
+                quote: CsvQuote::Double,
+                encoding: TextEncoding::Utf8,
+                has_headers: true,
+                skip_rows: 0,
+                max_rows: 0,
+                output_var: "missing".to_string(),
+                row_count_var: String::new(),
+            },
+        ]);
+        let failing = project.main_scenario.nodes[5].id.clone();
+        handle_errors(&mut project.main_scenario, &[failing]);
+
+        let (context, log) = run_project(&project);
+        let last_error = context
+            .global_variables
+            .get(CoreConstants::ERROR_VARIABLE_NAME)
+            .cloned();
+
+        assert_eq!(
+            std::fs::read_to_string(&copy).unwrap(),
+            "Column1,Column2\r\nPen,3\r\nInk,10\r\n"
+        );
+        assert!(log.iter().any(|e| e.message == "handled"));
+        assert!(last_error.is_some_and(|e| e.to_string().contains("missing.csv")));
+    }
+}
@@ -0,0 +1,596 @@
+use crate::structured;
+use arc_script::{Table, Value};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+/// How long SQLite waits for a lock held by another process.
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DatabaseKind {
+    #[default]
+    Sqlite,
+    Postgres,
+    SqlServer,
+}
+
+impl DatabaseKind {
+    pub fn all() -> [DatabaseKind; 3] {
+        [
+            DatabaseKind::Sqlite,
+            DatabaseKind::Postgres,
+            DatabaseKind::SqlServer,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseKind::Sqlite => "SQLite",
+            DatabaseKind::Postgres => "PostgreSQL",
+            DatabaseKind::SqlServer => "SQL Server",
+        }
+    }
+}
+
+/// A named database connection stored in the project. Activities refer to it
+/// by name; it is opened on first use and stays open until the run ends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseConnection {
+    pub name: String,
+    #[serde(default)]
+    pub kind: DatabaseKind,
+    /// Expression with the file path for SQLite, or the connection string.
+    pub target: String,
+    #[serde(default)]
+    pub username: String,
+    /// Secret variable with the password, such as `@db_password`; validation rejects
+    /// literals and plain variables.
+    #[serde(default)]
+    pub password: String,
+}
+
+impl DatabaseConnection {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: DatabaseKind::Sqlite,
+            target: String::new(),
+            username: String::new(),
+            password: String::new(),
+        }
+    }
+}
+
+/// Connection settings with their expressions already evaluated.
+pub struct ConnectOptions {
+    pub target: String,
+    pub username: String,
+    pub password: String,
+}
+
+/// Opens sessions for one kind of database. SQLite is built in; other kinds
+/// become available once a driver is registered with [`register_driver`].
+pub trait DatabaseDriver: Send + Sync {
+    fn connect(&self, options: &ConnectOptions) -> Result<Box<dyn DatabaseSession>, String>;
+}
+
+/// An open connection. Parameters are bound by position (`?1`, `?2`, ...).
+pub trait DatabaseSession {
+    /// Runs a statement that returns rows, one table row per result row.
+    fn query(&mut self, sql: &str, params: &[Value]) -> Result<Table, String>;
+    /// Runs a statement that returns no rows and reports how many rows it changed.
+    fn execute(&mut self, sql: &str, params: &[Value]) -> Result<u64, String>;
+    fn begin(&mut self) -> Result<(), String>;
+    fn commit(&mut self) -> Result<(), String>;
+    fn rollback(&mut self) -> Result<(), String>;
+}
+
+static DRIVERS: LazyLock<RwLock<HashMap<DatabaseKind, Arc<dyn DatabaseDriver>>>> =
+    LazyLock::new(|| {
+        let mut drivers: HashMap<DatabaseKind, Arc<dyn DatabaseDriver>> = HashMap::new();
+        drivers.insert(DatabaseKind::Sqlite, Arc::new(SqliteDriver));
+        RwLock::new(drivers)
+    });
+
+/// Makes `kind` usable by database activities, replacing any earlier driver.
+pub fn register_driver(kind: DatabaseKind, driver: Arc<dyn DatabaseDriver>) {
+    if let Ok(mut drivers) = DRIVERS.write() {
+        drivers.insert(kind, driver);
+    }
+}
+
+pub fn connect(
+    kind: DatabaseKind,
+    options: &ConnectOptions,
+) -> Result<Box<dyn DatabaseSession>, String> {
+    let driver = DRIVERS
+        .read()
+        .ok()
+        .and_then(|drivers| drivers.get(&kind).cloned())
+        .ok_or_else(|| format!("No {} driver is available", kind.as_str()))?;
+    driver.connect(options)
+}
+
+struct OpenSession {
+    session: Box<dyn DatabaseSession>,
+    /// Try-block depth when the current transaction was begun.
+    transaction: Option<usize>,
+}
+
+/// Sessions opened during a run, by connection name.
+#[derive(Default)]
+pub struct Databases {
+    sessions: HashMap<String, OpenSession>,
+}
+
+impl Databases {
+    pub fn is_open(&self, name: &str) -> bool {
+        self.sessions.contains_key(name)
+    }
+
+    pub fn insert(&mut self, name: &str, session: Box<dyn DatabaseSession>) {
+        self.sessions.insert(
+            name.to_string(),
+            OpenSession {
+                session,
+                transaction: None,
+            },
+        );
+    }
+
+    pub fn in_transaction(&self, name: &str) -> bool {
+        self.sessions
+            .get(name)
+            .is_some_and(|open| open.transaction.is_some())
+    }
+
+    pub fn session(&mut self, name: &str) -> Result<&mut dyn DatabaseSession, String> {
+        Ok(self.open_session(name)?.session.as_mut())
+    }
+
+    /// Begins a transaction inside `depth` nested try blocks.
+    pub fn begin(&mut self, name: &str, depth: usize) -> Result<(), String> {
+        let open = self.open_session(name)?;
+        if open.transaction.is_some() {
+            return Err(format!("A transaction is already open on '{}'", name));
+        }
+        open.session.begin()?;
+        open.transaction = Some(depth);
+        Ok(())
+    }
+
+    pub fn commit(&mut self, name: &str) -> Result<(), String> {
+        let open = self.open_session(name)?;
+        if open.transaction.is_none() {
+            return Err(format!("No transaction is open on '{}'", name));
+        }
+        open.session.commit()?;
+        open.transaction = None;
+        Ok(())
+    }
+
+    pub fn rollback(&mut self, name: &str) -> Result<(), String> {
+        let open = self.open_session(name)?;
+        if open.transaction.is_none() {
+            return Err(format!("No transaction is open on '{}'", name));
+        }
+        open.transaction = None;
+        open.session.rollback()
+    }
+
+    /// Rolls back the transactions begun inside more than `depth` try blocks,
+    /// i.e. those of a try block whose error is being caught. Returns the
+    /// connection names with the outcome of each rollback.
+    pub fn rollback_deeper_than(&mut self, depth: usize) -> Vec<(String, Result<(), String>)> {
+        let mut names: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, open)| open.transaction.is_some_and(|begun| begun > depth))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| {
+                let result = self.rollback(&name);
+                (name, result)
+            })
+            .collect()
+    }
+
+    fn open_session(&mut self, name: &str) -> Result<&mut OpenSession, String> {
+        self.sessions
+            .get_mut(name)
+            .ok_or_else(|| format!("Database connection '{}' is not open", name))
+    }
+}
+
+struct SqliteDriver;
+
+impl DatabaseDriver for SqliteDriver {
+    fn connect(&self, options: &ConnectOptions) -> Result<Box<dyn DatabaseSession>, String> {
+        let path = options.target.trim();
+        if path.is_empty() {
+            return Err("Database path is empty".to_string());
+        }
+        let connection = rusqlite::Connection::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        connection
+            .busy_timeout(SQLITE_BUSY_TIMEOUT)
+            .map_err(|e| e.to_string())?;
+        Ok(Box::new(SqliteSession { connection }))
+    }
+}
+
+struct SqliteSession {
+    connection: rusqlite::Connection,
+}
+
+impl DatabaseSession for SqliteSession {
+    fn query(&mut self, sql: &str, params: &[Value]) -> Result<Table, String> {
+        let mut statement = self.connection.prepare(sql).map_err(sql_error)?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut rows = statement
+            .query(rusqlite::params_from_iter(sql_params(params)?))
+            .map_err(sql_error)?;
+
+        let mut table_rows = Vec::new();
+        while let Some(row) = rows.next().map_err(sql_error)? {
+            let values = (0..columns.len())
+                .map(|i| row.get_ref(i).map(sql_to_value))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(sql_error)?;
+            table_rows.push(values);
+        }
+
+        Ok(Table::new(columns, table_rows))
+    }
+
+    fn execute(&mut self, sql: &str, params: &[Value]) -> Result<u64, String> {
+        let changed = self
+            .connection
+            .execute(sql, rusqlite::params_from_iter(sql_params(params)?))
+            .map_err(sql_error)?;
+        Ok(changed as u64)
+    }
+
+    fn begin(&mut self) -> Result<(), String> {
+        self.connection.execute_batch("BEGIN").map_err(sql_error)
+    }
+
+    fn commit(&mut self) -> Result<(), String> {
+        self.connection.execute_batch("COMMIT").map_err(sql_error)
+    }
+
+    fn rollback(&mut self) -> Result<(), String> {
+        self.connection.execute_batch("ROLLBACK").map_err(sql_error)
+    }
+}
+
+fn sql_error(error: rusqlite::Error) -> String {
+    format!("SQL error: {}", error)
+}
+
+/// Whole numbers are bound as integers; lists, objects and tables as JSON text.
+fn sql_params(params: &[Value]) -> Result<Vec<SqlValue>, String> {
+    params
+        .iter()
+        .map(|value| {
+            Ok(match value {
+                Value::Undefined => SqlValue::Null,
+                Value::Boolean(b) => SqlValue::Integer(i64::from(*b)),
+                Value::Number(n) if n.fract() == 0.0 && n.abs() < 9.0e15 => {
+                    SqlValue::Integer(*n as i64)
+                }
+                Value::Number(n) => SqlValue::Real(*n),
+                Value::String(s) => SqlValue::Text(s.clone()),
+                Value::List(_) | Value::Object(_) | Value::Table(_) => {
+                    SqlValue::Text(structured::to_json(value, false)?)
+                }
+            })
+        })
+        .collect()
+}
+
+fn sql_to_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Undefined,
+        ValueRef::Integer(i) => Value::Number(i as f64),
+        ValueRef::Real(f) => Value::Number(f),
+        ValueRef::Text(text) | ValueRef::Blob(text) => {
+            Value::String(String::from_utf8_lossy(text).into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::node_graph::BranchType;
+
+    use crate::test_support::{add_nodes, linear_project, run_project};
+    use crate::validation::ErrorCode;
+    use crate::variables::{VariableScope, Variables};
+    use crate::{Activity, Project, ScenarioValidator};
+
+    fn sqlite(path: &std::path::Path) -> Box<dyn DatabaseSession> {
+        let options = ConnectOptions {
+            target: path.display().to_string(),
+            username: String::new(),
+            password: String::new(),
+        };
+        connect(DatabaseKind::Sqlite, &options).unwrap()
+    }
+
+    fn statement(sql: &str, parameters: &str) -> Activity {
+        Activity::ExecuteNonQuery {
+            connection: "erp".to_string(),
+            sql: sql.to_string(),
+            parameters: parameters.to_string(),
+            output_var: "changed".to_string(),
+        }
+    }
+
+    #[test]
+    fn sqlite_binds_parameters_and_returns_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = sqlite(&dir.path().join("erp.db"));
+
+        session
+            .execute(
+                "CREATE TABLE orders (id INTEGER, item TEXT, price REAL, paid INTEGER, note TEXT)",
+                &[],
+            )
+            .unwrap();
+        let insert = "INSERT INTO orders VALUES (?1, ?2, ?3, ?4, ?5)";
+        let rows = [
+            (1.0, "Pen", 2.5),
+            (2.0, "Ink'; DROP TABLE orders; --", 10.0),
+        ];
+        for (id, item, price) in rows {
+            let params = [
+                Value::Number(id),
+                Value::String(item.to_string()),
+                Value::Number(price),
+                Value::Boolean(id == 1.0),
+                Value::Undefined,
+            ];
+            assert_eq!(session.execute(insert, &params).unwrap(), 1);
+        }
+
+        let table = session
+            .query(
+                "SELECT id, item, price, paid, note FROM orders WHERE price > ?1 ORDER BY id",
+                &[Value::Number(1.0)],
+            )
+            .unwrap();
+        assert_eq!(table.columns, ["id", "item", "price", "paid", "note"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(
+            table.rows[0],
+            [
+                Value::Number(1.0),
+                Value::String("Pen".to_string()),
+                Value::Number(2.5),
+                Value::Number(1.0),
+                Value::Undefined,
+            ]
+        );
+        assert_eq!(
+            table.rows[1][1],
+            Value::String("Ink'; DROP TABLE orders; --".to_string())
+        );
+
+        assert_eq!(
+            session.execute("UPDATE orders SET paid = 1", &[]).unwrap(),
+            2
+        );
+        assert!(
+            session
+                .query("SELECT * FROM missing", &[])
+                .unwrap_err()
+                .starts_with("SQL error")
+        );
+    }
+
+    #[test]
+    fn missing_drivers_and_paths_are_reported() {
+        let options = ConnectOptions {
+            target: "  ".to_string(),
+            username: String::new(),
+            password: String::new(),
+        };
+        assert_eq!(
+            connect(DatabaseKind::Sqlite, &options).err().unwrap(),
+            "Database path is empty"
+        );
+        assert_eq!(
+            connect(DatabaseKind::SqlServer, &options).err().unwrap(),
+            "No SQL Server driver is available"
+        );
+    }
+
+    #[test]
+    fn caught_errors_roll_back_open_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("erp.db");
+
+        let mut project = Project::new("Test", Variables::new());
+        let mut connection = DatabaseConnection::new("erp");
+        connection.target = format!("\"{}\"", path.display());
+        project.databases.push(connection);
+
+        let scenario_id = project.main_scenario.id.clone();
+        let activities = [
+            Activity::Start {
+                scenario_id: scenario_id.clone(),
+            },
+            statement("CREATE TABLE orders (id INTEGER, item TEXT)", ""),
+            Activity::BeginTransaction {
+                connection: "erp".to_string(),
+            },
+            statement("INSERT INTO orders VALUES (?1, ?2)", "1\n\n\"Pen\""),
+            Activity::CommitTransaction {
+                connection: "erp".to_string(),
+            },
+            Activity::TryCatch,
+            Activity::BeginTransaction {
+                connection: "erp".to_string(),
+            },
+            statement("INSERT INTO orders VALUES (?1, ?2)", "2\n\"Ink\""),
+            statement("INSERT INTO missing VALUES (1)", ""),
+            Activity::ExecuteQuery {
+                connection: "erp".to_string(),
+                sql: "SELECT id, item FROM orders ORDER BY id".to_string(),
+                parameters: String::new(),
+                output_var: "orders".to_string(),
+            },
+            Activity::End {
+                scenario_id: scenario_id.clone(),
+            },
+        ];
+        let ids = add_nodes(&mut project.main_scenario, activities);
+        let connections = [
+            (0, 1, BranchType::Default),
+            (1, 2, BranchType::Default),
+            (2, 3, BranchType::Default),
+            (3, 4, BranchType::Default),
+            (4, 5, BranchType::Default),
+            (5, 6, BranchType::TryBranch),
+            (6, 7, BranchType::Default),
+            (7, 8, BranchType::Default),
+            (8, 10, BranchType::Default),
+            (5, 9, BranchType::CatchBranch),
+            (9, 10, BranchType::Default),
+        ];
+        for (from, to, branch) in connections {
+            project.main_scenario.add_connection_with_branch(
+                ids[from].clone(),
+                ids[to].clone(),
+                branch,
+            );
+        }
+
+        let (context, log) = run_project(&project);
+        let scenario = context.scope_stack.last().unwrap().variables.clone();
+
+        let Some(Value::Table(orders)) = scenario.get("orders") else {
+            panic!("orders is not a table: {:?}", scenario.get("orders"));
+        };
+        assert_eq!(
+            orders.rows,
+            [[Value::Number(1.0), Value::String("Pen".to_string())]]
+        );
+        assert!(
+            log.iter()
+                .any(|e| e.message == "Rolled back transaction on 'erp'")
+        );
+    }
+
+    #[test]
+    fn error_output_rolls_back_the_failed_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("erp.db");
+        let select = |output_var: &str| Activity::ExecuteQuery {
+            connection: "erp".to_string(),
+            sql: "SELECT id, item FROM orders".to_string(),
+            parameters: String::new(),
+            output_var: output_var.to_string(),
+        };
+
+        let mut project = linear_project(vec![
+            statement("CREATE TABLE orders (id INTEGER, item TEXT)", ""),
+            Activity::BeginTransaction {
+                connection: "erp".to_string(),
+            },
+            statement("INSERT INTO orders VALUES (?1, ?2)", "1\n\"Pen\""),
+            statement("INSERT INTO missing VALUES (1)", ""),
+            select("committed"),
+        ]);
+        let mut connection = DatabaseConnection::new("erp");
+        connection.target = format!("\"{}\"", path.display());
+        project.databases.push(connection);
+
+        let scenario = &mut project.main_scenario;
+        let (failing, end) = (scenario.nodes[4].id.clone(), scenario.nodes[6].id.clone());
+        let handler = add_nodes(scenario, [select("orders")]).remove(0);
+        scenario.add_connection_with_branch(failing, handler.clone(), BranchType::ErrorBranch);
+        scenario.add_connection_with_branch(handler, end, BranchType::Default);
+
+        let (context, log) = run_project(&project);
+        let scenario = context.scope_stack.last().unwrap().variables.clone();
+
+        let Some(Value::Table(orders)) = scenario.get("orders") else {
+            panic!("orders is not a table: {:?}", scenario.get("orders"));
+        };
+        assert!(orders.rows.is_empty(), "{:?}", orders.rows);
+        assert!(
+            log.iter()
+                .any(|e| e.message == "Rolled back transaction on 'erp'")
+        );
+    }
+
+    #[test]
+    fn validator_requires_secret_passwords() {
+        let codes = |password: &str| {
+            let mut project = linear_project(vec![Activity::BeginTransaction {
+                connection: "erp".to_string(),
+            }]);
+            let mut connection = DatabaseConnection::new("erp");
+            connection.password = password.to_string();
+            project.databases.push(connection);
+            project.variables.set(
+                "plain",
+                Value::String("hunter2".to_string()),
+                VariableScope::Global,
+            );
+            project
+                .variables
+                .create_secret("db_password", VariableScope::Global);
+
+            ScenarioValidator::new(&project.main_scenario, &project)
+                .validate()
+                .errors
+                .into_iter()
+                .map(|issue| issue.code)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(codes("\"hunter2\""), [ErrorCode::E113]);
+        assert_eq!(codes("@plain"), [ErrorCode::E113]);
+        assert_eq!(codes("@db_password + \"!\""), [ErrorCode::E113]);
+        assert!(codes("@db_password").is_empty());
+        assert!(codes("").is_empty());
+    }
+
+    #[test]
+    fn validator_reports_unknown_connections_and_empty_sql() {
+        let mut project = linear_project(vec![
+            Activity::ExecuteQuery {
+                connection: "crm".to_string(),
+                sql: "SELECT 1".to_string(),
+                parameters: String::new(),
+                output_var: "rows".to_string(),
+            },
+            statement(" ", ""),
+        ]);
+        project.databases.push(DatabaseConnection::new("erp"));
+
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        let codes: Vec<ErrorCode> = result
+            .errors
+            .iter()
+            .map(|issue| issue.code.clone())
+            .collect();
+        assert_eq!(codes, [ErrorCode::E108, ErrorCode::E109]);
+        assert!(result.errors[0].message.contains("'crm'"));
+    }
+}
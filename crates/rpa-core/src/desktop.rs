@@ -0,0 +1,347 @@
+use crate::constants::CoreConstants;
+use crate::stop_control::StopControl;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use ui_automation::{AutomationError, Element, ImageMatch, Rect, Selector};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClickKind {
+    #[default]
+    Single,
+    Double,
+    Right,
+}
+
+impl ClickKind {
+    pub fn all() -> [ClickKind; 3] {
+        [ClickKind::Single, ClickKind::Double, ClickKind::Right]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClickKind::Single => "Single",
+            ClickKind::Double => "Double",
+            ClickKind::Right => "Right",
+        }
+    }
+}
+
+fn automation_error(error: AutomationError) -> String {
+    error.to_string()
+}
+
+/// Checks that `selector` is a well-formed selector DSL string such as
+/// `Window>title~Invoice>Control>class~Edit`.
+pub fn check_selector(selector: &str) -> Result<(), String> {
+    Selector::parse(selector)
+        .map(|_| ())
+        .map_err(automation_error)
+}
+
+/// Finds the element matched by `selector`, polling until it appears or
+/// `timeout` runs out. A zero timeout looks exactly once.
+pub fn find_element(
+    selector: &str,
+    timeout: Duration,
+    stop_control: &StopControl,
+) -> Result<Element, String> {
+    check_selector(selector)?;
+    let started = Instant::now();
+
+    loop {
+        let error = match ui_automation::find_element_by_selector(selector) {
+            Ok(element) => return Ok(element),
+            Err(e) => automation_error(e),
+        };
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(if timeout.is_zero() {
+                error
+            } else {
+                format!(
+                    "Element not found after {} ms: {}",
+                    timeout.as_millis(),
+                    error
+                )
+            });
+        }
+
+        let poll = (timeout - elapsed).min(Duration::from_millis(
+            CoreConstants::ELEMENT_POLL_INTERVAL_MS,
+        ));
+        if !stop_control.sleep_interruptible(poll.as_millis() as u64) {
+            return Err("Execution stopped by user".to_string());
+        }
+    }
+}
+
+pub fn click(element: &Element, kind: ClickKind) -> Result<(), String> {
+    match kind {
+        ClickKind::Single => element.click(),
+        ClickKind::Double => element.double_click(),
+        ClickKind::Right => element.right_click(),
+    }
+    .map_err(automation_error)
+}
+
+/// Finds the image stored at `path` on the screen, polling until it appears
+/// or `timeout` runs out. A zero timeout looks exactly once.
+pub fn find_image(
+    path: &Path,
+    confidence: f32,
+    timeout: Duration,
+    stop_control: &StopControl,
+) -> Result<ImageMatch, String> {
+    let template = ui_automation::load_image(path).map_err(automation_error)?;
+    let started = Instant::now();
+
+    loop {
+        if let Some(found) =
+            ui_automation::find_image_on_screen(&template, confidence).map_err(automation_error)?
+        {
+            return Ok(found);
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(if timeout.is_zero() {
+                format!("Image '{}' not found on screen", path.display())
+            } else {
+                format!(
+                    "Image '{}' not found on screen after {} ms",
+                    path.display(),
+                    timeout.as_millis()
+                )
+            });
+        }
+
+        let poll = (timeout - elapsed).min(Duration::from_millis(
+            CoreConstants::ELEMENT_POLL_INTERVAL_MS,
+        ));
+        if !stop_control.sleep_interruptible(poll.as_millis() as u64) {
+            return Err("Execution stopped by user".to_string());
+        }
+    }
+}
+
+/// Clicks the centre of a screen area, such as a found image.
+pub fn click_at(rect: Rect, kind: ClickKind) -> Result<(), String> {
+    let (x, y) = (rect.left + rect.width / 2, rect.top + rect.height / 2);
+    match kind {
+        ClickKind::Single => ui_automation::click(x, y),
+        ClickKind::Double => ui_automation::double_click(x, y),
+        ClickKind::Right => ui_automation::right_click(x, y),
+    }
+    .map_err(automation_error)
+}
+
+/// Saves a PNG of `element`, or of the whole screen, creating the folder
+/// it goes into.
+pub fn save_screenshot(element: Option<&Element>, path: &Path) -> Result<(), String> {
+    let image = match element {
+        Some(element) => ui_automation::capture_element(element),
+        None => ui_automation::capture_screen(),
+    }
+    .map_err(automation_error)?;
+    if let Some(folder) = path
+        .parent()
+        .filter(|folder| !folder.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(folder)
+            .map_err(|e| format!("Failed to create folder '{}': {}", folder.display(), e))?;
+    }
+    ui_automation::save_png(&image, path).map_err(automation_error)
+}
+
+/// Replaces the text of `element`, or focuses it and types the text key by key
+/// for fields that ignore direct updates.
+pub fn type_into(element: &Element, text: &str, simulate_typing: bool) -> Result<(), String> {
+    if simulate_typing {
+        focus(element)?;
+        ui_automation::type_text(text).map_err(automation_error)
+    } else {
+        element.set_text(text).map_err(automation_error)
+    }
+}
+
+pub fn focus(element: &Element) -> Result<(), String> {
+    element.focus().map_err(automation_error)
+}
+
+pub fn get_text(element: &mut Element) -> Result<String, String> {
+    element.get_text_live().map_err(automation_error)
+}
+
+pub fn close_window(element: &Element) -> Result<(), String> {
+    element.close().map_err(automation_error)
+}
+
+/// Splits `keys` into key combinations separated by whitespace, e.g.
+/// `ctrl+a delete ctrl+shift+s` presses three combinations in turn.
+pub fn key_combinations(keys: &str) -> Vec<&str> {
+    keys.split_whitespace().collect()
+}
+
+pub fn send_keys(keys: &str) -> Result<(), String> {
+    let combinations = key_combinations(keys);
+    if combinations.is_empty() {
+        return Err("No keys to send".to_string());
+    }
+    for combination in combinations {
+        ui_automation::key_sequence(combination).map_err(automation_error)?;
+    }
+    Ok(())
+}
+
+/// Starts `path` with `arguments` and returns the process id.
+pub fn launch(path: &str, arguments: &str) -> Result<u32, String> {
+    ui_automation::launch_application(path, arguments)
+        .map(|application| application.pid())
+        .map_err(|e| format!("Failed to launch {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ActivityDefaults;
+
+    use crate::validation::ErrorCode;
+
+    use crate::test_support::{handle_errors, linear_project, run_project};
+    use crate::{Activity, Project, ScenarioValidator};
+    use shared::NanoId;
+
+    /// Start → activities → End, with every activity's Error pin logging
+    /// `"handled"` before joining End.
+    fn project(activities: Vec<Activity>) -> Project {
+        let count = activities.len();
+        let mut project = linear_project(activities);
+        let ids: Vec<NanoId> = project.main_scenario.nodes[1..=count]
+            .iter()
+            .map(|node| node.id.clone())
+            .collect();
+        handle_errors(&mut project.main_scenario, &ids);
+        project
+    }
+
+    #[test]
+    fn selectors_and_keys_are_checked() {
+        assert!(check_selector("Window>title~Invoice>Control>class~Edit;index~2").is_ok());
+        assert!(check_selector("Window>title~").is_err());
+        assert!(check_selector("Control").is_err());
+        assert!(check_selector("Window>colour~red").is_err());
+
+        assert_eq!(
+            key_combinations(" ctrl+a  delete\nctrl+shift+s "),
+            ["ctrl+a", "delete", "ctrl+shift+s"]
+        );
+        assert_eq!(send_keys("  ").unwrap_err(), "No keys to send");
+    }
+
+    #[test]
+    fn validator_reports_bad_selectors_and_empty_keys() {
+        let project = project(vec![
+            Activity::Click {
+                selector: "Window>title~".to_string(),
+                click: ClickKind::Double,
+                timeout_ms: 0,
+            },
+            Activity::SendKeys {
+                selector: String::new(),
+                keys: " ".to_string(),
+                timeout_ms: 0,
+            },
+            Activity::GetText {
+                selector: "Window>title~Notepad>Control>class~Edit".to_string(),
+                timeout_ms: 0,
+                output_var: String::new(),
+            },
+        ]);
+
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        let codes: Vec<ErrorCode> = result
+            .errors
+            .iter()
+            .map(|issue| issue.code.clone())
+            .collect();
+        assert_eq!(codes.len(), 3, "{:?}", result.errors);
+        assert!(codes.contains(&ErrorCode::E110));
+        assert!(codes.contains(&ErrorCode::E111));
+        assert!(codes.contains(&ErrorCode::E201));
+    }
+
+    #[test]
+    fn missing_elements_take_the_error_branch() {
+        let project = project(vec![Activity::WaitForElement {
+            selector: "Window>title~=No window has this title 7f3a9c".to_string(),
+            timeout_ms: 0,
+        }]);
+
+        let (context, log) = run_project(&project);
+
+        assert!(
+            context
+                .global_variables
+                .get(CoreConstants::ERROR_VARIABLE_NAME)
+                .is_some()
+        );
+        assert!(log.iter().any(|e| e.message == "handled"));
+    }
+
+    #[test]
+    fn validator_checks_image_confidence() {
+        let project = project(vec![
+            Activity::ClickImage {
+                image_path: "\"ok.png\"".to_string(),
+                confidence: 1.5,
+                click: ClickKind::Single,
+                timeout_ms: 0,
+            },
+            Activity::WaitForImage {
+                image_path: "\"ok.png\"".to_string(),
+                confidence: 0.8,
+                timeout_ms: 0,
+            },
+            Activity::TakeScreenshot {
+                selector: String::new(),
+                path: String::new(),
+                output_var: String::new(),
+                timeout_ms: 0,
+            },
+        ]);
+
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        let codes: Vec<ErrorCode> = result
+            .errors
+            .iter()
+            .map(|issue| issue.code.clone())
+            .collect();
+        assert_eq!(codes, [ErrorCode::E112], "{:?}", result.errors);
+    }
+
+    #[test]
+    fn missing_reference_images_take_the_error_branch() {
+        let project = project(vec![Activity::WaitForImage {
+            image_path: "\"no-such-image-7f3a9c.png\"".to_string(),
+            confidence: ActivityDefaults::IMAGE_CONFIDENCE,
+            timeout_ms: 0,
+        }]);
+
+        let (context, log) = run_project(&project);
+
+        let error = context
+            .global_variables
+            .get(CoreConstants::ERROR_VARIABLE_NAME)
+            .unwrap()
+            .to_string();
+        assert!(error.contains("no-such-image-7f3a9c.png"), "{error}");
+        // Whether or not a display is available, the failure asks for evidence
+        assert!(
+            log.iter()
+                .any(|e| e.message.contains("screenshot of the error"))
+        );
+        assert!(log.iter().any(|e| e.message == "handled"));
+    }
+}
@@ -0,0 +1,1104 @@
+use crate::database::DatabaseConnection;
+use crate::library::LibraryImport;
+use crate::node_graph::{
+    Activity, BranchType, Connection, Node, Project, Scenario, ScenarioParameter,
+};
+use crate::variables::{VariableScope, Variables};
+use arc_script::Value;
+use indexmap::IndexMap;
+use shared::NanoId;
+use std::fmt::Display;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Renamed {
+        from: String,
+        to: String,
+    },
+    VariableAdded {
+        name: String,
+        value: Value,
+    },
+    VariableRemoved {
+        name: String,
+    },
+    VariableChanged {
+        name: String,
+        from: Value,
+        to: Value,
+    },
+    VariableSecretChanged {
+        name: String,
+        secret: bool,
+    },
+    ImportAdded {
+        path: String,
+        version: String,
+    },
+    ImportRemoved {
+        path: String,
+    },
+    ImportChanged {
+        path: String,
+        from: String,
+        to: String,
+    },
+    DatabaseAdded {
+        name: String,
+    },
+    DatabaseRemoved {
+        name: String,
+    },
+    DatabaseChanged {
+        name: String,
+    },
+    ParametersChanged,
+    NodeAdded {
+        node_id: NanoId,
+        activity: String,
+    },
+    NodeRemoved {
+        node_id: NanoId,
+        activity: String,
+    },
+    NodeMoved {
+        node_id: NanoId,
+        activity: String,
+        from: (f32, f32),
+        to: (f32, f32),
+    },
+    NodeResized {
+        node_id: NanoId,
+        activity: String,
+        from: (f32, f32),
+        to: (f32, f32),
+    },
+    ActivityChanged {
+        node_id: NanoId,
+        activity: String,
+        field: String,
+        from: String,
+        to: String,
+    },
+    ConnectionAdded {
+        from_node: NanoId,
+        to_node: NanoId,
+        branch_type: BranchType,
+    },
+    ConnectionRemoved {
+        from_node: NanoId,
+        to_node: NanoId,
+        branch_type: BranchType,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioStatus {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioDiff {
+    pub scenario_id: NanoId,
+    pub name: String,
+    pub status: ScenarioStatus,
+    pub changes: Vec<Change>,
+}
+
+/// Differences between two projects, matched by `NanoId` rather than by position in the file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProjectDiff {
+    pub changes: Vec<Change>,
+    pub scenarios: Vec<ScenarioDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub location: String,
+    pub message: String,
+}
+
+pub struct MergeResult {
+    /// Merged project. Conflicting values are taken from "ours".
+    pub project: Project,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl ProjectDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.scenarios.is_empty()
+    }
+}
+
+impl Conflict {
+    fn new(location: &str, message: &str) -> Self {
+        Self {
+            location: location.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+pub fn diff_projects(old: &Project, new: &Project) -> ProjectDiff {
+    let mut changes = Vec::new();
+
+    if old.name != new.name {
+        changes.push(Change::Renamed {
+            from: old.name.clone(),
+            to: new.name.clone(),
+        });
+    }
+    diff_variables(&old.variables, &new.variables, &mut changes);
+    diff_imports(&old.imports, &new.imports, &mut changes);
+    diff_databases(&old.databases, &new.databases, &mut changes);
+
+    let mut scenarios = Vec::new();
+    let old_scenarios = scenario_map(old);
+    let new_scenarios = scenario_map(new);
+    let empty = Scenario::new("");
+
+    for (id, new_scenario) in &new_scenarios {
+        let (status, old_scenario) = match old_scenarios.get(id) {
+            Some(old_scenario) => (ScenarioStatus::Modified, *old_scenario),
+            None => (ScenarioStatus::Added, &empty),
+        };
+        let changes = diff_scenarios(old_scenario, new_scenario);
+        if status == ScenarioStatus::Added || !changes.is_empty() {
+            scenarios.push(ScenarioDiff {
+                scenario_id: (*id).clone(),
+                name: new_scenario.name.clone(),
+                status,
+                changes,
+            });
+        }
+    }
+
+    for (id, old_scenario) in &old_scenarios {
+        if !new_scenarios.contains_key(id) {
+            scenarios.push(ScenarioDiff {
+                scenario_id: (*id).clone(),
+                name: old_scenario.name.clone(),
+                status: ScenarioStatus::Removed,
+                changes: Vec::new(),
+            });
+        }
+    }
+
+    ProjectDiff { changes, scenarios }
+}
+
+fn diff_scenarios(old: &Scenario, new: &Scenario) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    if !old.name.is_empty() && old.name != new.name {
+        changes.push(Change::Renamed {
+            from: old.name.clone(),
+            to: new.name.clone(),
+        });
+    }
+    if old.parameters != new.parameters {
+        changes.push(Change::ParametersChanged);
+    }
+    diff_variables(&old.variables, &new.variables, &mut changes);
+
+    let old_nodes = keyed(&old.nodes, |n| &n.id);
+    let new_nodes = keyed(&new.nodes, |n| &n.id);
+
+    for (id, node) in &new_nodes {
+        let activity = activity_name(&node.activity);
+        let Some(old_node) = old_nodes.get(id) else {
+            changes.push(Change::NodeAdded {
+                node_id: (*id).clone(),
+                activity,
+            });
+            continue;
+        };
+
+        if (old_node.x, old_node.y) != (node.x, node.y) {
+            changes.push(Change::NodeMoved {
+                node_id: (*id).clone(),
+                activity: activity.clone(),
+                from: (old_node.x, old_node.y),
+                to: (node.x, node.y),
+            });
+        }
+        if (old_node.width, old_node.height) != (node.width, node.height) {
+            changes.push(Change::NodeResized {
+                node_id: (*id).clone(),
+                activity: activity.clone(),
+                from: (old_node.width, old_node.height),
+                to: (node.width, node.height),
+            });
+        }
+        diff_activities(id, &old_node.activity, &node.activity, &mut changes);
+    }
+
+    for (id, node) in &old_nodes {
+        if !new_nodes.contains_key(id) {
+            changes.push(Change::NodeRemoved {
+                node_id: (*id).clone(),
+                activity: activity_name(&node.activity),
+            });
+        }
+    }
+
+    let old_connections = keyed(&old.connections, |c| &c.id);
+    let new_connections = keyed(&new.connections, |c| &c.id);
+
+    for (id, old_connection) in &old_connections {
+        if new_connections.get(id) != Some(old_connection) {
+            changes.push(Change::ConnectionRemoved {
+                from_node: old_connection.from_node.clone(),
+                to_node: old_connection.to_node.clone(),
+                branch_type: old_connection.branch_type.clone(),
+            });
+        }
+    }
+    for (id, new_connection) in &new_connections {
+        if old_connections.get(id) != Some(new_connection) {
+            changes.push(Change::ConnectionAdded {
+                from_node: new_connection.from_node.clone(),
+                to_node: new_connection.to_node.clone(),
+                branch_type: new_connection.branch_type.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Reports changed activity fields one by one; a different activity kind is a single change.
+fn diff_activities(node_id: &NanoId, old: &Activity, new: &Activity, changes: &mut Vec<Change>) {
+    if old == new {
+        return;
+    }
+
+    let activity = activity_name(new);
+    let old_name = activity_name(old);
+    let old_value = serde_json::to_value(old).unwrap_or_default();
+    let new_value = serde_json::to_value(new).unwrap_or_default();
+
+    let fields = match (
+        old_value.get(&old_name).and_then(|v| v.as_object()),
+        new_value.get(&activity).and_then(|v| v.as_object()),
+    ) {
+        (Some(old_fields), Some(new_fields)) if old_name == activity => (old_fields, new_fields),
+        _ => {
+            changes.push(Change::ActivityChanged {
+                node_id: node_id.clone(),
+                activity: activity.clone(),
+                field: "activity".to_string(),
+                from: old_name,
+                to: activity,
+            });
+            return;
+        }
+    };
+
+    let (old_fields, new_fields) = fields;
+    let null = serde_json::Value::Null;
+    let field_names = new_fields
+        .keys()
+        .chain(old_fields.keys().filter(|k| !new_fields.contains_key(*k)));
+
+    for field in field_names {
+        let from = old_fields.get(field).unwrap_or(&null);
+        let to = new_fields.get(field).unwrap_or(&null);
+        if from != to {
+            changes.push(Change::ActivityChanged {
+                node_id: node_id.clone(),
+                activity: activity.clone(),
+                field: field.clone(),
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+    }
+}
+
+fn diff_variables(old: &Variables, new: &Variables, changes: &mut Vec<Change>) {
+    let old_vars = variable_map(old);
+    let new_vars = variable_map(new);
+
+    for (name, (value, scope, secret)) in &new_vars {
+        let Some((old_value, old_scope, old_secret)) = old_vars.get(name) else {
+            changes.push(Change::VariableAdded {
+                name: name.clone(),
+                value: value.clone(),
+            });
+            continue;
+        };
+
+        if old_value != value || old_scope != scope {
+            changes.push(Change::VariableChanged {
+                name: name.clone(),
+                from: old_value.clone(),
+                to: value.clone(),
+            });
+        }
+        if old_secret != secret {
+            changes.push(Change::VariableSecretChanged {
+                name: name.clone(),
+                secret: *secret,
+            });
+        }
+    }
+
+    for name in old_vars.keys() {
+        if !new_vars.contains_key(name) {
+            changes.push(Change::VariableRemoved { name: name.clone() });
+        }
+    }
+}
+
+fn diff_imports(old: &[LibraryImport], new: &[LibraryImport], changes: &mut Vec<Change>) {
+    let old_imports = keyed(old, |i| &i.path);
+    let new_imports = keyed(new, |i| &i.path);
+
+    for (path, import) in &new_imports {
+        match old_imports.get(path) {
+            None => changes.push(Change::ImportAdded {
+                path: (*path).clone(),
+                version: import.version.clone(),
+            }),
+            Some(old_import) if old_import.version != import.version => {
+                changes.push(Change::ImportChanged {
+                    path: (*path).clone(),
+                    from: old_import.version.clone(),
+                    to: import.version.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for path in old_imports.keys() {
+        if !new_imports.contains_key(path) {
+            changes.push(Change::ImportRemoved {
+                path: (*path).clone(),
+            });
+        }
+    }
+}
+
+fn diff_databases(
+    old: &[DatabaseConnection],
+    new: &[DatabaseConnection],
+    changes: &mut Vec<Change>,
+) {
+    let old_databases = keyed(old, |d| &d.name);
+    let new_databases = keyed(new, |d| &d.name);
+
+    for (name, database) in &new_databases {
+        match old_databases.get(name) {
+            None => changes.push(Change::DatabaseAdded {
+                name: (*name).clone(),
+            }),
+            Some(old_database) if old_database != database => {
+                changes.push(Change::DatabaseChanged {
+                    name: (*name).clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for name in old_databases.keys() {
+        if !new_databases.contains_key(name) {
+            changes.push(Change::DatabaseRemoved {
+                name: (*name).clone(),
+            });
+        }
+    }
+}
+
+/// Three-way merge of two projects derived from `base`.
+///
+/// Nodes, connections, variables and scenarios are matched by id. A node's layout and its
+/// activity are merged separately, so moving a node on one side and editing it on the other
+/// does not conflict. Connections left pointing at removed nodes are dropped and reported.
+pub fn merge_projects(base: &Project, ours: &Project, theirs: &Project) -> MergeResult {
+    let mut conflicts = Vec::new();
+    let mut project = ours.clone();
+
+    project.name = merge_value(
+        &base.name,
+        &ours.name,
+        &theirs.name,
+        "project name",
+        &mut conflicts,
+    );
+    project.variables = merge_variables(
+        &base.variables,
+        &ours.variables,
+        &theirs.variables,
+        "project variables",
+        &mut conflicts,
+    );
+
+    let imports = merge_map(
+        &owned_keyed(&base.imports, |i| i.path.clone()),
+        &owned_keyed(&ours.imports, |i| i.path.clone()),
+        &owned_keyed(&theirs.imports, |i| i.path.clone()),
+        |path, b, o, t| {
+            let location = format!("import '{}'", path);
+            merge_entry(b, o, t, &location, &mut conflicts, |b, o, t, c| {
+                merge_value_opt(b, o, t, &location, c)
+            })
+        },
+    );
+    project.imports = imports.into_values().collect();
+
+    let databases = merge_map(
+        &owned_keyed(&base.databases, |d| d.name.clone()),
+        &owned_keyed(&ours.databases, |d| d.name.clone()),
+        &owned_keyed(&theirs.databases, |d| d.name.clone()),
+        |name, b, o, t| {
+            let location = format!("database '{}'", name);
+            merge_entry(b, o, t, &location, &mut conflicts, |b, o, t, c| {
+                merge_value_opt(b, o, t, &location, c)
+            })
+        },
+    );
+    project.databases = databases.into_values().collect();
+
+    project.main_scenario = merge_scenario(
+        Some(&base.main_scenario),
+        &ours.main_scenario,
+        &theirs.main_scenario,
+        &mut conflicts,
+    );
+
+    let scenarios = merge_map(
+        &owned_keyed(&base.scenarios, |s| s.id.clone()),
+        &owned_keyed(&ours.scenarios, |s| s.id.clone()),
+        &owned_keyed(&theirs.scenarios, |s| s.id.clone()),
+        |_, b, o, t| {
+            let name = o.or(t).map(|s| s.name.clone()).unwrap_or_default();
+            let location = format!("scenario '{}'", name);
+            merge_entry(b, o, t, &location, &mut conflicts, |b, o, t, c| {
+                merge_scenario(b, o, t, c)
+            })
+        },
+    );
+    project.scenarios = scenarios.into_values().collect();
+
+    MergeResult { project, conflicts }
+}
+
+fn merge_scenario(
+    base: Option<&Scenario>,
+    ours: &Scenario,
+    theirs: &Scenario,
+    conflicts: &mut Vec<Conflict>,
+) -> Scenario {
+    let location = format!("scenario '{}'", ours.name);
+    let empty = Scenario::new("");
+    let base = base.unwrap_or(&empty);
+
+    let name = merge_value(
+        &base.name,
+        &ours.name,
+        &theirs.name,
+        &format!("{} name", location),
+        conflicts,
+    );
+    let parameters: Vec<ScenarioParameter> = merge_value(
+        &base.parameters,
+        &ours.parameters,
+        &theirs.parameters,
+        &format!("{} parameters", location),
+        conflicts,
+    );
+    let variables = merge_variables(
+        &base.variables,
+        &ours.variables,
+        &theirs.variables,
+        &format!("{} variables", location),
+        conflicts,
+    );
+
+    let nodes = merge_map(
+        &owned_keyed(&base.nodes, |n| n.id.clone()),
+        &owned_keyed(&ours.nodes, |n| n.id.clone()),
+        &owned_keyed(&theirs.nodes, |n| n.id.clone()),
+        |id, b, o, t| {
+            let node_location = format!("{}, node {}", location, id.as_str());
+            merge_entry(b, o, t, &node_location, conflicts, |b, o, t, c| {
+                merge_node(b, o, t, &node_location, c)
+            })
+        },
+    );
+
+    let connections = merge_map(
+        &owned_keyed(&base.connections, |c| c.id.clone()),
+        &owned_keyed(&ours.connections, |c| c.id.clone()),
+        &owned_keyed(&theirs.connections, |c| c.id.clone()),
+        |id, b, o, t| {
+            let connection_location = format!("{}, connection {}", location, id.as_str());
+            merge_entry(b, o, t, &connection_location, conflicts, |b, o, t, c| {
+                merge_value_opt(b, o, t, &connection_location, c)
+            })
+        },
+    );
+
+    let connections = connections
+        .into_values()
+        .filter(|connection: &Connection| {
+            let connected = nodes.contains_key(&connection.from_node)
+                && nodes.contains_key(&connection.to_node);
+            if !connected {
+                conflicts.push(Conflict::new(
+                    &format!("{}, connection {}", location, connection.id.as_str()),
+                    "connects a node that was removed, dropped",
+                ));
+            }
+            connected
+        })
+        .collect();
+
+    Scenario {
+        id: ours.id.clone(),
+        name,
+        nodes: nodes.into_values().collect(),
+        connections,
+        parameters,
+        variables,
+    }
+}
+
+fn merge_node(
+    base: Option<&Node>,
+    ours: &Node,
+    theirs: &Node,
+    location: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> Node {
+    let layout = |n: &Node| (n.x, n.y, n.width, n.height);
+
+    let activity = merge_value_opt(
+        base.map(|b| &b.activity),
+        &ours.activity,
+        &theirs.activity,
+        &format!("{} activity", location),
+        conflicts,
+    );
+    let (x, y, width, height) = merge_value_opt(
+        base.map(layout).as_ref(),
+        &layout(ours),
+        &layout(theirs),
+        &format!("{} position", location),
+        conflicts,
+    );
+
+    Node {
+        id: ours.id.clone(),
+        activity,
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+fn merge_variables(
+    base: &Variables,
+    ours: &Variables,
+    theirs: &Variables,
+    location: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> Variables {
+    let merged = merge_map(
+        &variable_map(base),
+        &variable_map(ours),
+        &variable_map(theirs),
+        |name, b, o, t| {
+            let location = format!("{} '{}'", location, name);
+            merge_entry(b, o, t, &location, conflicts, |b, o, t, c| {
+                merge_value_opt(b, o, t, &location, c)
+            })
+        },
+    );
+
+    let mut variables = Variables::new();
+    for (name, (value, scope, secret)) in merged {
+        if secret {
+            variables.create_secret(&name, scope);
+        } else {
+            variables.set(&name, value, scope);
+        }
+    }
+    variables
+}
+
+fn merge_value<T: Clone + PartialEq>(
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    location: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> T {
+    merge_value_opt(Some(base), ours, theirs, location, conflicts)
+}
+
+/// Takes whichever side changed `base`; if both changed it differently, reports a conflict and keeps ours.
+fn merge_value_opt<T: Clone + PartialEq>(
+    base: Option<&T>,
+    ours: &T,
+    theirs: &T,
+    location: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> T {
+    if ours == theirs || base == Some(theirs) {
+        ours.clone()
+    } else if base == Some(ours) {
+        theirs.clone()
+    } else {
+        conflicts.push(Conflict::new(location, "changed differently on both sides"));
+        ours.clone()
+    }
+}
+
+/// Merges one keyed entry that may be missing on any side. `merge_both` runs when both sides kept it.
+fn merge_entry<T: Clone + PartialEq>(
+    base: Option<&T>,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+    location: &str,
+    conflicts: &mut Vec<Conflict>,
+    merge_both: impl FnOnce(Option<&T>, &T, &T, &mut Vec<Conflict>) -> T,
+) -> Option<T> {
+    match (ours, theirs) {
+        (Some(o), Some(t)) if o == t => Some(o.clone()),
+        (Some(o), Some(t)) => Some(merge_both(base, o, t, conflicts)),
+        (Some(kept), None) | (None, Some(kept)) => match base {
+            None => Some(kept.clone()),
+            Some(b) if b == kept => None,
+            Some(_) => {
+                conflicts.push(Conflict::new(
+                    location,
+                    "changed on one side and removed on the other",
+                ));
+                Some(kept.clone())
+            }
+        },
+        (None, None) => None,
+    }
+}
+
+/// Merges keyed collections, keeping our order and appending entries only they added.
+fn merge_map<K: Clone + Eq + Hash, V>(
+    base: &IndexMap<K, V>,
+    ours: &IndexMap<K, V>,
+    theirs: &IndexMap<K, V>,
+    mut merge: impl FnMut(&K, Option<&V>, Option<&V>, Option<&V>) -> Option<V>,
+) -> IndexMap<K, V> {
+    let keys = ours
+        .keys()
+        .chain(theirs.keys().filter(|k| !ours.contains_key(*k)));
+
+    let mut merged = IndexMap::new();
+    for key in keys {
+        if let Some(value) = merge(key, base.get(key), ours.get(key), theirs.get(key)) {
+            merged.insert(key.clone(), value);
+        }
+    }
+    merged
+}
+
+fn scenario_map(project: &Project) -> IndexMap<&NanoId, &Scenario> {
+    keyed(
+        std::iter::once(&project.main_scenario).chain(&project.scenarios),
+        |s| &s.id,
+    )
+}
+
+fn keyed<'a, K: Eq + Hash + 'a, V: 'a>(
+    items: impl IntoIterator<Item = &'a V>,
+    key: impl Fn(&'a V) -> &'a K,
+) -> IndexMap<&'a K, &'a V> {
+    items.into_iter().map(|item| (key(item), item)).collect()
+}
+
+fn owned_keyed<K: Eq + Hash, V: Clone>(items: &[V], key: impl Fn(&V) -> K) -> IndexMap<K, V> {
+    items.iter().map(|item| (key(item), item.clone())).collect()
+}
+
+/// Name -> (value, scope, secret). Secret values are never part of the project, so
+/// they show up as `Undefined` here just like in the saved file.
+fn variable_map(variables: &Variables) -> IndexMap<String, (Value, VariableScope, bool)> {
+    let mut entries: Vec<_> = variables
+        .iter()
+        .map(|(name, value, scope)| {
+            let secret = variables.is_secret(name);
+            let value = if secret {
+                Value::Undefined
+            } else {
+                value.clone()
+            };
+            (name.to_string(), (value, scope.clone(), secret))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.into_iter().collect()
+}
+
+fn activity_name(activity: &Activity) -> String {
+    match serde_json::to_value(activity) {
+        Ok(serde_json::Value::String(name)) => name,
+        Ok(serde_json::Value::Object(map)) => map.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Renamed { from, to } => write!(f, "~ renamed '{}' -> '{}'", from, to),
+            Change::VariableAdded { name, value } => write!(f, "+ variable {} = {}", name, value),
+            Change::VariableRemoved { name } => write!(f, "- variable {}", name),
+            Change::VariableChanged { name, from, to } => {
+                write!(f, "~ variable {}: {} -> {}", name, from, to)
+            }
+            Change::VariableSecretChanged { name, secret: true } => {
+                write!(f, "~ variable {}: now secret", name)
+            }
+            Change::VariableSecretChanged {
+                name,
+                secret: false,
+            } => write!(f, "~ variable {}: no longer secret", name),
+            Change::ImportAdded { path, version } => write!(f, "+ import {} {}", path, version),
+            Change::ImportRemoved { path } => write!(f, "- import {}", path),
+            Change::ImportChanged { path, from, to } => {
+                write!(f, "~ import {}: {} -> {}", path, from, to)
+            }
+            Change::DatabaseAdded { name } => write!(f, "+ database {}", name),
+            Change::DatabaseRemoved { name } => write!(f, "- database {}", name),
+            Change::DatabaseChanged { name } => write!(f, "~ database {}", name),
+            Change::ParametersChanged => write!(f, "~ parameters changed"),
+            Change::NodeAdded { node_id, activity } => {
+                write!(f, "+ node {} {}", node_id.as_str(), activity)
+            }
+            Change::NodeRemoved { node_id, activity } => {
+                write!(f, "- node {} {}", node_id.as_str(), activity)
+            }
+            Change::NodeMoved {
+                node_id,
+                activity,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ node {} {} moved ({}, {}) -> ({}, {})",
+                node_id.as_str(),
+                activity,
+                from.0,
+                from.1,
+                to.0,
+                to.1
+            ),
+            Change::NodeResized {
+                node_id,
+                activity,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ node {} {} resized {}x{} -> {}x{}",
+                node_id.as_str(),
+                activity,
+                from.0,
+                from.1,
+                to.0,
+                to.1
+            ),
+            Change::ActivityChanged {
+                node_id,
+                activity,
+                field,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ node {} {}.{}: {} -> {}",
+                node_id.as_str(),
+                activity,
+                field,
+                from,
+                to
+            ),
+            Change::ConnectionAdded {
+                from_node,
+                to_node,
+                branch_type,
+            } => write!(
+                f,
+                "+ connection {} -> {} ({:?})",
+                from_node.as_str(),
+                to_node.as_str(),
+                branch_type
+            ),
+            Change::ConnectionRemoved {
+                from_node,
+                to_node,
+                branch_type,
+            } => write!(
+                f,
+                "- connection {} -> {} ({:?})",
+                from_node.as_str(),
+                to_node.as_str(),
+                branch_type
+            ),
+        }
+    }
+}
+
+impl Display for ProjectDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+
+        for scenario in &self.scenarios {
+            let status = match scenario.status {
+                ScenarioStatus::Added => "added",
+                ScenarioStatus::Removed => "removed",
+                ScenarioStatus::Modified => "modified",
+            };
+            writeln!(
+                f,
+                "scenario '{}' ({}) {}",
+                scenario.name,
+                scenario.scenario_id.as_str(),
+                status
+            )?;
+            for change in &scenario.changes {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::LogLevel;
+
+    fn node(activity: Activity, x: f32) -> Node {
+        Node {
+            id: NanoId::default(),
+            activity,
+            x,
+            y: 0.0,
+            width: 128.0,
+            height: 64.0,
+        }
+    }
+
+    fn log(message: &str) -> Activity {
+        Activity::Log {
+            level: LogLevel::Info,
+            message: message.to_string(),
+        }
+    }
+
+    /// Main scenario: Start -> Log("hello") -> End.
+    fn base_project() -> Project {
+        let mut project = Project::new("Bot", Variables::new());
+        let scenario_id = project.main_scenario.id.clone();
+        let nodes = vec![
+            node(
+                Activity::Start {
+                    scenario_id: scenario_id.clone(),
+                },
+                0.0,
+            ),
+            node(log("hello"), 200.0),
+            node(Activity::End { scenario_id }, 400.0),
+        ];
+        for pair in nodes.windows(2) {
+            project.main_scenario.add_connection_with_branch(
+                pair[0].id.clone(),
+                pair[1].id.clone(),
+                BranchType::Default,
+            );
+        }
+        project.main_scenario.nodes = nodes;
+        project
+    }
+
+    #[test]
+    fn diff_reports_moves_and_field_changes() {
+        let old = base_project();
+        let mut new = old.clone();
+        new.main_scenario.nodes[1].x = 250.0;
+        new.main_scenario.nodes[1].activity = log("bye");
+        new.main_scenario.connections.pop();
+        new.variables
+            .set("count", Value::Number(1.0), VariableScope::Global);
+
+        let diff = diff_projects(&old, &new);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.scenarios.len(), 1);
+
+        let changes = &diff.scenarios[0].changes;
+        assert!(matches!(&changes[0], Change::NodeMoved { to, .. } if *to == (250.0, 0.0)));
+        assert!(matches!(
+            &changes[1],
+            Change::ActivityChanged { field, from, to, .. }
+                if field == "message" && from == "\"hello\"" && to == "\"bye\""
+        ));
+        assert!(matches!(&changes[2], Change::ConnectionRemoved { .. }));
+
+        let text = diff.to_string();
+        assert!(text.contains("+ variable count = 1"));
+        assert!(text.contains("Log.message: \"hello\" -> \"bye\""));
+    }
+
+    #[test]
+    fn identical_projects_have_empty_diff() {
+        let project = base_project();
+        assert!(diff_projects(&project, &project.clone()).is_empty());
+    }
+
+    #[test]
+    fn merge_combines_independent_edits() {
+        let base = base_project();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+
+        ours.main_scenario.nodes[1].x = 300.0;
+        theirs.main_scenario.nodes[1].activity = log("changed");
+        let mut added = Scenario::new("Cleanup");
+        added.nodes.push(node(Activity::Continue, 0.0));
+        theirs.scenarios.push(added);
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert!(result.conflicts.is_empty(), "{:?}", result.conflicts);
+
+        let merged = &result.project.main_scenario.nodes[1];
+        assert_eq!(merged.x, 300.0);
+        assert_eq!(merged.activity, log("changed"));
+        assert_eq!(result.project.scenarios.len(), 1);
+    }
+
+    #[test]
+    fn merge_reports_conflicting_edits() {
+        let base = base_project();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+
+        ours.main_scenario.nodes[1].activity = log("ours");
+        theirs.main_scenario.nodes[1].activity = log("theirs");
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(result.conflicts[0].location.ends_with("activity"));
+        assert_eq!(result.project.main_scenario.nodes[1].activity, log("ours"));
+    }
+
+    #[test]
+    fn merge_handles_removed_nodes() {
+        let base = base_project();
+        let removed_id = base.main_scenario.nodes[1].id.clone();
+
+        let mut ours = base.clone();
+        ours.main_scenario.nodes.retain(|n| n.id != removed_id);
+        ours.main_scenario
+            .connections
+            .retain(|c| c.from_node != removed_id && c.to_node != removed_id);
+
+        let mut theirs = base.clone();
+        let end_id = theirs.main_scenario.nodes[2].id.clone();
+        theirs.main_scenario.add_connection_with_branch(
+            removed_id.clone(),
+            end_id,
+            BranchType::ErrorBranch,
+        );
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert_eq!(result.project.main_scenario.nodes.len(), 2);
+        assert!(result.project.main_scenario.connections.is_empty());
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(result.conflicts[0].message.contains("removed"));
+
+        theirs.main_scenario.nodes[1].activity = log("edited");
+        let result = merge_projects(&base, &ours, &theirs);
+        assert!(
+            result
+                .conflicts
+                .iter()
+                .any(|c| c.message == "changed on one side and removed on the other")
+        );
+        assert_eq!(result.project.main_scenario.nodes.len(), 3);
+    }
+
+    #[test]
+    fn diff_reports_secret_flag_changes() {
+        let old = base_project();
+        let mut new = old.clone();
+        new.variables
+            .create_secret("password", VariableScope::Global);
+        new.variables.set(
+            "password",
+            Value::String("hunter2".into()),
+            VariableScope::Global,
+        );
+
+        let diff = diff_projects(&old, &new);
+        let text = diff.to_string();
+        assert!(text.contains("+ variable password"));
+        assert!(!text.contains("hunter2"));
+
+        let mut newer = new.clone();
+        newer.variables.set(
+            "password",
+            Value::String("changed".into()),
+            VariableScope::Global,
+        );
+        assert!(diff_projects(&new, &newer).is_empty());
+
+        let mut plain = Variables::new();
+        plain.set("password", Value::Undefined, VariableScope::Global);
+        newer.variables = plain;
+        let diff = diff_projects(&new, &newer);
+        assert_eq!(
+            diff.changes,
+            [Change::VariableSecretChanged {
+                name: "password".to_string(),
+                secret: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn merge_keeps_secret_variables_secret() {
+        let mut base = base_project();
+        base.variables.create_secret("token", VariableScope::Global);
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+
+        ours.variables
+            .set("count", Value::Number(1.0), VariableScope::Global);
+        theirs
+            .variables
+            .create_secret("api_key", VariableScope::Scenario);
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert!(result.conflicts.is_empty(), "{:?}", result.conflicts);
+        assert!(result.project.variables.is_secret("token"));
+        assert!(result.project.variables.is_secret("api_key"));
+        assert!(!result.project.variables.is_secret("count"));
+    }
+}
@@ -0,0 +1,204 @@
+use arc_script::{Table, Value};
+use office::CellValue;
+
+/// Numbers and booleans keep their type; dates become `YYYY-MM-DD` or
+/// `YYYY-MM-DD HH:MM:SS` strings and empty cells become empty strings.
+pub fn cell_to_value(cell: &CellValue) -> Value {
+    match cell {
+        CellValue::Number(n) => Value::Number(*n),
+        CellValue::Bool(b) => Value::Boolean(*b),
+        CellValue::Empty | CellValue::Text(_) | CellValue::DateTime(_) => {
+            Value::String(cell.to_string())
+        }
+    }
+}
+
+/// Inverse of [`cell_to_value`]: strings in the date formats it produces are written as dates.
+pub fn value_to_cell(value: &Value) -> CellValue {
+    match value {
+        Value::Number(n) => CellValue::Number(*n),
+        Value::Boolean(b) => CellValue::Bool(*b),
+        Value::String(s) if s.is_empty() => CellValue::Empty,
+        Value::String(s) => CellValue::from_text(s),
+        Value::Table(_) | Value::List(_) | Value::Object(_) => CellValue::Text(value.to_string()),
+        Value::Undefined => CellValue::Empty,
+    }
+}
+
+/// Builds a table from spreadsheet rows. With `has_headers` the first row names
+/// the columns, otherwise (and for blank header cells) they are `Column1`, `Column2`, ...
+pub fn rows_to_table(mut rows: Vec<Vec<CellValue>>, has_headers: bool) -> Table {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let header = if has_headers && !rows.is_empty() {
+        rows.remove(0)
+    } else {
+        Vec::new()
+    };
+
+    let columns = (0..width)
+        .map(|i| match header.get(i).map(ToString::to_string) {
+            Some(name) if !name.is_empty() => name,
+            _ => format!("Column{}", i + 1),
+        })
+        .collect();
+    let rows = rows
+        .iter()
+        .map(|row| row.iter().map(cell_to_value).collect())
+        .collect();
+
+    Table::new(columns, rows)
+}
+
+/// Rows to write for `value`: the rows of a table, preceded by its column names
+/// when `with_headers` is set, or a single cell for any other value.
+pub fn value_to_rows(value: &Value, with_headers: bool) -> Vec<Vec<CellValue>> {
+    let Value::Table(table) = value else {
+        return vec![vec![value_to_cell(value)]];
+    };
+
+    let header = with_headers.then(|| {
+        table
+            .columns
+            .iter()
+            .map(|name| CellValue::Text(name.clone()))
+            .collect()
+    });
+    header
+        .into_iter()
+        .chain(
+            table
+                .rows
+                .iter()
+                .map(|row| row.iter().map(value_to_cell).collect()),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::CoreConstants;
+
+    use crate::log::LogLevel;
+
+    use crate::Activity;
+    use crate::test_support::{handle_errors, linear_project, run_project};
+
+    use std::path::Path;
+
+    #[test]
+    fn rows_become_typed_table() {
+        let rows = vec![
+            vec![
+                CellValue::Text("Name".to_string()),
+                CellValue::Empty,
+                CellValue::Text("Paid".to_string()),
+            ],
+            vec![
+                CellValue::Text("Alice".to_string()),
+                CellValue::Number(10.5),
+                CellValue::Bool(true),
+            ],
+            vec![
+                CellValue::from_text("2024-01-31"),
+                CellValue::Empty,
+                CellValue::Bool(false),
+            ],
+        ];
+
+        let table = rows_to_table(rows.clone(), true);
+        assert_eq!(table.columns, ["Name", "Column2", "Paid"]);
+        assert_eq!(table.row_count(), 2);
+        assert_eq!(table.get(0, "Column2"), Some(&Value::Number(10.5)));
+        assert_eq!(table.get(0, "Paid"), Some(&Value::Boolean(true)));
+        assert_eq!(
+            table.get(1, "Name"),
+            Some(&Value::String("2024-01-31".to_string()))
+        );
+
+        let value = Value::Table(table);
+        let written = value_to_rows(&value, true);
+        assert_eq!(written[0][1], CellValue::Text("Column2".to_string()));
+        assert_eq!(written[1..], rows[1..]);
+        assert_eq!(value_to_rows(&value, false), rows[1..]);
+        assert_eq!(
+            value_to_rows(&Value::Number(3.0), true),
+            [[CellValue::Number(3.0)]]
+        );
+    }
+
+    #[test]
+    fn executor_reads_writes_and_routes_excel_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let book = dir.path().join("book.xlsx");
+        let copy = dir.path().join("copy.xlsx");
+        let quoted = |path: &Path| format!("\"{}\"", path.display());
+        office::Excel::write_range(
+            &book,
+            "Data",
+            "A1",
+            &[
+                vec![
+                    CellValue::Text("Item".to_string()),
+                    CellValue::Text("Qty".to_string()),
+                ],
+                vec![CellValue::Text("Pen".to_string()), CellValue::Number(3.0)],
+            ],
+        )
+        .unwrap();
+
+        let mut project = linear_project(vec![
+            Activity::ExcelReadRange {
+                path: quoted(&book),
+                sheet: "\"Data\"".to_string(),
+                range: "\"A1:B2\"".to_string(),
+                has_headers: true,
+                output_var: "items".to_string(),
+            },
+            Activity::ExcelWriteRange {
+                path: quoted(&copy),
+                sheet: "\"Copy\"".to_string(),
+                cell: "\"A1\"".to_string(),
+                value: "@items".to_string(),
+                write_headers: true,
+            },
+            Activity::ExcelAppendRows {
+                path: quoted(&copy),
+                sheet: "\"Copy\"".to_string(),
+                value: "@items".to_string(),
+            },
+            Activity::ExcelReadCell {
+                path: quoted(&copy),
+                sheet: "\"Copy\"".to_string(),
+                cell: "\"B3\"".to_string(),
+                output_var: "qty".to_string(),
+            },
+            Activity::Log {
+                level: LogLevel::Info,
+                message: "\"qty: {@qty * 2}\"".to_string(),
+            },
+            Activity::ExcelReadCell {
+                path: quoted(&book),
+                sheet: "\"Missing\"".to_string(),
+                cell: "\"A1\"".to_string(),
+                output_var: "missing".to_string(),
+            },
+        ]);
+        let failing = project.main_scenario.nodes[6].id.clone();
+        handle_errors(&mut project.main_scenario, &[failing]);
+
+        let (context, log) = run_project(&project);
+        let last_error = context
+            .global_variables
+            .get(CoreConstants::ERROR_VARIABLE_NAME)
+            .cloned();
+
+        assert_eq!(
+            office::Excel::read_range(&copy, "Copy", "").unwrap().len(),
+            3
+        );
+        assert!(log.iter().any(|e| e.message == "qty: 6"));
+        assert!(log.iter().any(|e| e.message == "handled"));
+        assert!(last_error.is_some_and(|e| e.to_string().contains("Missing")));
+    }
+}
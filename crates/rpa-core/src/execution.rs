@@ -1,15 +1,28 @@
 use crate::constants::CoreConstants;
+use crate::csv::{self, CsvCursors};
+use crate::database::{self, ConnectOptions, DatabaseSession, Databases};
+use crate::desktop;
 use crate::events::{ExecutionCommand, ExecutionEvent, ExecutionSnapshot};
-use crate::ir::{Instruction, IrProgram};
+use crate::excel;
+use crate::files;
+use crate::http::{self, HttpRequest, RequestBody};
+use crate::ir::{Instruction, IrProgram, MailAccount};
 use crate::log::{LogActivity, LogEntry, LogLevel};
+use crate::mail;
 use crate::node_graph::{Project, VariableDirection};
+use crate::secrets::{SECRET_MASK, mask_secrets};
 use crate::stop_control::StopControl;
+use crate::structured;
 use crate::variables::{VariableScope, Variables};
-use arc_script::{Value, eval_expr, parse_expr};
+use crate::word;
+use arc_script::{Expr, Value, eval_expr, parse_expr};
+use office::{EmailMessage, Excel, Imap, ImapQuery, MailServer, Mime, Smtp, Word};
 use shared::NanoId;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{Receiver, Sender, SyncSender};
 use std::time::{Duration, Instant, SystemTime};
+use ui_automation::{Element, ImageMatch};
 
 #[derive(Debug, Clone)]
 pub struct ScopeFrame {
@@ -28,6 +41,8 @@ pub struct ExecutionContext {
     pub global_variables: Variables,
     pub scope_stack: Vec<ScopeFrame>,
     pub stop_control: StopControl,
+    /// Where the run saves screenshots; created when the first one is taken
+    pub run_folder: PathBuf,
 }
 
 pub struct IrExecutor<'a, L: LogOutput> {
@@ -43,6 +58,8 @@ pub struct IrExecutor<'a, L: LogOutput> {
     call_stack: Vec<CallFrame>,
     current_scenario_id: NanoId,
     current_node_id: Option<NanoId>,
+    databases: Databases,
+    csv_cursors: CsvCursors,
 }
 
 pub trait LogOutput {
@@ -61,6 +78,14 @@ impl LogOutput for Sender<LogEntry> {
     }
 }
 
+fn masked_value(variables: &Variables, name: &str, value: &Value, secrets: &[String]) -> Value {
+    match value {
+        _ if variables.is_secret(name) => Value::String(SECRET_MASK.to_string()),
+        Value::String(text) if !secrets.is_empty() => Value::String(mask_secrets(text, secrets)),
+        _ => value.clone(),
+    }
+}
+
 pub fn get_timestamp(start_time: SystemTime) -> String {
     let elapsed = start_time.elapsed().unwrap_or_default();
     format!(
@@ -71,6 +96,44 @@ pub fn get_timestamp(start_time: SystemTime) -> String {
     )
 }
 
+/// A folder per run under the temp folder, named after the start time.
+fn run_folder(start_time: SystemTime) -> PathBuf {
+    let started = start_time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    std::env::temp_dir()
+        .join(CoreConstants::RUN_FOLDER_NAME)
+        .join(started.to_string())
+}
+
+/// Instructions that work with the screen, whose failures are worth a
+/// screenshot.
+fn is_ui_instruction(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::Click { .. }
+            | Instruction::TypeInto { .. }
+            | Instruction::GetText { .. }
+            | Instruction::WaitForElement { .. }
+            | Instruction::SendKeys { .. }
+            | Instruction::CloseWindow { .. }
+            | Instruction::ClickImage { .. }
+            | Instruction::WaitForImage { .. }
+            | Instruction::TakeScreenshot { .. }
+    )
+}
+
+/// The connection a database statement or commit runs on.
+fn database_connection(instruction: &Instruction) -> Option<&String> {
+    match instruction {
+        Instruction::ExecuteQuery { connection, .. }
+        | Instruction::ExecuteNonQuery { connection, .. }
+        | Instruction::CommitTransaction { connection } => Some(connection),
+        _ => None,
+    }
+}
+
 impl ExecutionContext {
     fn new(
         start_time: SystemTime,
@@ -83,6 +146,7 @@ impl ExecutionContext {
             global_variables,
             scope_stack,
             stop_control,
+            run_folder: run_folder(start_time),
         }
     }
 
@@ -97,6 +161,7 @@ impl ExecutionContext {
             global_variables,
             scope_stack,
             stop_control,
+            run_folder: run_folder(start_time),
         }
     }
 
@@ -166,6 +231,8 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             call_stack: Vec::new(),
             current_scenario_id: project.main_scenario.id.clone(),
             current_node_id: None,
+            databases: Databases::default(),
+            csv_cursors: CsvCursors::default(),
         }
     }
 
@@ -204,11 +271,16 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
 
         let timestamp = get_timestamp(self.context.start_time);
 
+        let secrets = self.secret_values();
+
         let global_vars: HashMap<String, Value> = self
             .context
             .global_variables
             .iter()
-            .map(|(name, value, _)| (name.to_string(), value.clone()))
+            .map(|(name, value, _)| {
+                let value = masked_value(&self.context.global_variables, name, value, &secrets);
+                (name.to_string(), value)
+            })
             .collect();
 
         let scenario_vars: HashMap<NanoId, HashMap<String, Value>> = self
@@ -219,7 +291,10 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 let vars = frame
                     .variables
                     .iter()
-                    .map(|(name, value, _)| (name.to_string(), value.clone()))
+                    .map(|(name, value, _)| {
+                        let value = masked_value(&frame.variables, name, value, &secrets);
+                        (name.to_string(), value)
+                    })
                     .collect();
                 (frame.scenario_id.clone(), vars)
             })
@@ -240,6 +315,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
 
     pub fn execute(&mut self) -> Result<(), String> {
         let mut pc = self.program.entry_point;
+        self.csv_cursors = CsvCursors::default();
 
         while pc < self.program.instructions.len() {
             self.check_commands()?;
@@ -261,6 +337,12 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     pc = next_pc;
                 }
                 Err(e) => {
+                    let e = self.mask_secrets(&e);
+                    self.save_error_screenshot(pc, &e);
+                    if let Some(target) = self.error_output_target(pc, &e) {
+                        pc = target;
+                        continue;
+                    }
                     if let Some(ref tx) = self.event_tx {
                         let _ = tx.try_send(ExecutionEvent::Error(e.clone()));
                     }
@@ -276,6 +358,218 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
         Ok(())
     }
 
+    /// Current values of all secret variables in scope.
+    fn secret_values(&self) -> Vec<String> {
+        let mut secrets: Vec<String> = self.context.global_variables.secret_values().collect();
+        for frame in &self.context.scope_stack {
+            secrets.extend(frame.variables.secret_values());
+        }
+        secrets
+    }
+
+    fn mask_secrets(&self, text: &str) -> String {
+        let secrets = self.secret_values();
+        if secrets.is_empty() {
+            return text.to_string();
+        }
+        mask_secrets(text, &secrets)
+    }
+
+    /// All log output goes through here so secret values never reach a `LogEntry`.
+    fn emit_log(&mut self, mut entry: LogEntry) {
+        entry.message = self.mask_secrets(&entry.message);
+        self.log.log(entry);
+    }
+
+    /// Where execution continues when the node that raised `error` has a connected Error pin.
+    fn error_output_target(&mut self, pc: usize, error: &str) -> Option<usize> {
+        if error == "Execution stopped by user" {
+            return None;
+        }
+        let target = *self.program.error_targets.get(&pc)?;
+
+        self.context.global_variables.set(
+            CoreConstants::ERROR_VARIABLE_NAME,
+            Value::String(error.to_string()),
+            VariableScope::Global,
+        );
+        self.emit_log(LogEntry {
+            timestamp: get_timestamp(self.context.start_time),
+            node_id: self.current_node_id.clone(),
+            level: LogLevel::Warning,
+            activity: LogActivity::Execution,
+            message: format!("Error routed to Error output: {error}"),
+        });
+
+        // A failed statement leaves its transaction unusable on most databases,
+        // so the transaction ends with the error instead of staying open.
+        if let Some(connection) = database_connection(&self.program.instructions[pc])
+            && self.databases.in_transaction(connection)
+        {
+            let result = self.databases.rollback(connection);
+            self.log_rollbacks(vec![(connection.clone(), result)]);
+        }
+
+        Some(target)
+    }
+
+    fn log_rollbacks(&mut self, rollbacks: Vec<(String, Result<(), String>)>) {
+        for (connection, result) in rollbacks {
+            let message = match result {
+                Ok(()) => format!("Rolled back transaction on '{}'", connection),
+                Err(e) => format!("Failed to roll back transaction on '{}': {}", connection, e),
+            };
+            self.emit_log(LogEntry {
+                timestamp: get_timestamp(self.context.start_time),
+                node_id: self.current_node_id.clone(),
+                level: LogLevel::Warning,
+                activity: LogActivity::Database,
+                message,
+            });
+        }
+    }
+
+    /// Saves the screen into the run folder when a UI instruction fails, so
+    /// the log can point at what was shown at that moment.
+    fn save_error_screenshot(&mut self, pc: usize, error: &str) {
+        if error == "Execution stopped by user"
+            || !is_ui_instruction(&self.program.instructions[pc])
+        {
+            return;
+        }
+
+        let node = self
+            .current_node_id
+            .as_ref()
+            .map_or_else(|| "unknown".to_string(), ToString::to_string);
+        let elapsed = self.context.start_time.elapsed().unwrap_or_default();
+        let path =
+            self.context
+                .run_folder
+                .join(format!("error-{}-{}.png", node, elapsed.as_millis()));
+
+        let (level, message) = match desktop::save_screenshot(None, &path) {
+            Ok(()) => (
+                LogLevel::Info,
+                format!("Saved screenshot of the error to {}", path.display()),
+            ),
+            Err(e) => (
+                LogLevel::Warning,
+                format!("Failed to save screenshot of the error: {}", e),
+            ),
+        };
+        self.emit_log(LogEntry {
+            timestamp: get_timestamp(self.context.start_time),
+            node_id: self.current_node_id.clone(),
+            level,
+            activity: LogActivity::UiAutomation,
+            message,
+        });
+    }
+
+    fn eval_path(&self, expr: &Expr) -> Result<PathBuf, String> {
+        let path = eval_expr(expr, &self.get_combined_variables())?.to_string();
+        if path.trim().is_empty() {
+            return Err("Path is empty".to_string());
+        }
+        Ok(PathBuf::from(path))
+    }
+
+    fn eval_string(&self, expr: &Expr) -> Result<String, String> {
+        Ok(eval_expr(expr, &self.get_combined_variables())?.to_string())
+    }
+
+    fn find_element(&self, selector: &str, timeout_ms: u64) -> Result<Element, String> {
+        desktop::find_element(
+            selector,
+            Duration::from_millis(timeout_ms),
+            &self.context.stop_control,
+        )
+    }
+
+    fn find_image(
+        &self,
+        path: &Path,
+        confidence: f32,
+        timeout_ms: u64,
+    ) -> Result<ImageMatch, String> {
+        desktop::find_image(
+            path,
+            confidence,
+            Duration::from_millis(timeout_ms),
+            &self.context.stop_control,
+        )
+    }
+
+    fn eval_all(&self, exprs: &[Expr]) -> Result<Vec<Value>, String> {
+        let variables = self.get_combined_variables();
+        exprs
+            .iter()
+            .map(|expr| eval_expr(expr, &variables))
+            .collect()
+    }
+
+    fn mail_server(&self, account: &MailAccount) -> Result<MailServer, String> {
+        let server = self.eval_string(&account.server)?;
+        let username = match &account.username {
+            Some(username) => self.eval_string(username)?,
+            None => String::new(),
+        };
+        let password = match &account.password {
+            Some(password) => self.eval_string(password)?,
+            None => String::new(),
+        };
+        let server = MailServer::new(server.trim(), account.port, account.security)
+            .with_credentials(&username, &password)
+            .with_insecure_login(account.allow_insecure_login);
+        Ok(match &account.ca_bundle {
+            Some(ca_bundle) => server.with_ca_bundle(self.eval_path(ca_bundle)?),
+            None => server,
+        })
+    }
+
+    /// Opens the project's database connection `name` on first use.
+    fn database(&mut self, name: &str) -> Result<&mut dyn DatabaseSession, String> {
+        if !self.databases.is_open(name) {
+            let definition = self
+                .project
+                .databases
+                .iter()
+                .find(|d| d.name == name)
+                .ok_or_else(|| format!("Database connection '{}' is not defined", name))?;
+            let setting = |text: &str, property: &str| -> Result<String, String> {
+                if text.trim().is_empty() {
+                    return Ok(String::new());
+                }
+                let expr = parse_expr(text)
+                    .map_err(|e| format!("Invalid {} of '{}': {}", property, name, e))?;
+                self.eval_string(&expr)
+            };
+            let options = ConnectOptions {
+                target: setting(&definition.target, "database")?,
+                username: setting(&definition.username, "username")?,
+                password: setting(&definition.password, "password")?,
+            };
+            let session = database::connect(definition.kind, &options)?;
+            self.databases.insert(name, session);
+            self.log_info(
+                LogActivity::Database,
+                format!("Connected to '{}' ({})", name, definition.kind.as_str()),
+            );
+        }
+        self.databases.session(name)
+    }
+
+    fn log_info(&mut self, activity: LogActivity, message: String) {
+        self.emit_log(LogEntry {
+            timestamp: get_timestamp(self.context.start_time),
+            node_id: self.current_node_id.clone(),
+            level: LogLevel::Info,
+            activity,
+            message,
+        });
+    }
+
     fn get_combined_variables(&self) -> Variables {
         let mut combined = self.context.global_variables.clone();
         if let Some(vars) = self.context.get_scenario_variables() {
@@ -291,22 +585,11 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
 
         match instruction {
             Instruction::Start { scenario_id } => {
-                let scenario = self
-                    .project
-                    .scenarios
-                    .iter()
-                    .find(|s| s.id == *scenario_id)
-                    .or_else(|| {
-                        if self.project.main_scenario.id == *scenario_id {
-                            Some(&self.project.main_scenario)
-                        } else {
-                            None
-                        }
-                    });
+                let scenario = self.project.find_scenario(scenario_id);
 
                 let timestamp = get_timestamp(self.context.start_time);
                 if let Some(scenario) = scenario {
-                    self.log.log(LogEntry {
+                    self.emit_log(LogEntry {
                         timestamp,
                         node_id: self.current_node_id.clone(),
                         level: LogLevel::Info,
@@ -316,7 +599,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     Ok(pc + 1)
                 } else {
                     let error_msg = format!("Scenario with ID {scenario_id} not found");
-                    self.log.log(LogEntry {
+                    self.emit_log(LogEntry {
                         timestamp,
                         node_id: self.current_node_id.clone(),
                         level: LogLevel::Error,
@@ -327,22 +610,11 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 }
             }
             Instruction::End { scenario_id } => {
-                let scenario = self
-                    .project
-                    .scenarios
-                    .iter()
-                    .find(|s| s.id == *scenario_id)
-                    .or_else(|| {
-                        if self.project.main_scenario.id == *scenario_id {
-                            Some(&self.project.main_scenario)
-                        } else {
-                            None
-                        }
-                    });
+                let scenario = self.project.find_scenario(scenario_id);
 
                 let timestamp = get_timestamp(self.context.start_time);
                 if let Some(scenario) = scenario {
-                    self.log.log(LogEntry {
+                    self.emit_log(LogEntry {
                         timestamp,
                         node_id: self.current_node_id.clone(),
                         level: LogLevel::Info,
@@ -377,7 +649,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     }
                 } else {
                     let error_msg = format!("Scenario with ID {scenario_id} not found");
-                    self.log.log(LogEntry {
+                    self.emit_log(LogEntry {
                         timestamp,
                         node_id: self.current_node_id.clone(),
                         level: LogLevel::Error,
@@ -394,7 +666,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 match parse_expr(message) {
                     Ok(expr) => match eval_expr(&expr, &combined_vars) {
                         Ok(value) => {
-                            self.log.log(LogEntry {
+                            self.emit_log(LogEntry {
                                 timestamp,
                                 node_id: self.current_node_id.clone(),
                                 level: level.clone(),
@@ -410,7 +682,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             }
             Instruction::Delay { milliseconds } => {
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -438,7 +710,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     }
                 }
 
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -454,7 +726,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 let result = match eval_expr(expr, &combined_vars) {
                     Ok(value) => value,
                     Err(err) => {
-                        self.log.log(LogEntry {
+                        self.emit_log(LogEntry {
                             timestamp,
                             node_id: self.current_node_id.clone(),
                             level: LogLevel::Error,
@@ -465,7 +737,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     }
                 };
 
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -503,7 +775,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     ),
                 };
 
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level,
@@ -540,7 +812,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     ),
                 };
 
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level,
@@ -565,7 +837,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 end,
                 step,
             } => {
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp: get_timestamp(self.context.start_time),
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -583,7 +855,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 end_target,
             } => {
                 if *step == 0 {
-                    self.log.log(LogEntry {
+                    self.emit_log(LogEntry {
                         timestamp: get_timestamp(self.context.start_time),
                         node_id: self.current_node_id.clone(),
                         level: LogLevel::Warning,
@@ -650,8 +922,9 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     Ok(Value::Boolean(true)) => {
                         let iter_count = self.iteration_counts.entry(pc).or_insert(0);
                         *iter_count += 1;
+                        let iter_count = *iter_count;
 
-                        self.log.log(LogEntry {
+                        self.emit_log(LogEntry {
                             timestamp,
                             node_id: self.current_node_id.clone(),
                             level: LogLevel::Info,
@@ -662,7 +935,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     }
                     Ok(Value::Boolean(false)) => {
                         let iter_count = self.iteration_counts.get(&pc).copied().unwrap_or(0);
-                        self.log.log(LogEntry {
+                        self.emit_log(LogEntry {
                             timestamp,
                             node_id: self.current_node_id.clone(),
                             level: LogLevel::Info,
@@ -678,7 +951,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             }
             Instruction::LoopContinue { check_target } => {
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -689,7 +962,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             }
             Instruction::LoopBreak { end_target } => {
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -701,7 +974,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             Instruction::PushErrorHandler { catch_target } => {
                 self.error_handlers.push(*catch_target);
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -713,7 +986,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             Instruction::PopErrorHandler => {
                 self.error_handlers.pop();
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Info,
@@ -733,22 +1006,11 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                     ));
                 }
 
-                let scenario = self
-                    .project
-                    .scenarios
-                    .iter()
-                    .find(|s| s.id == *scenario_id)
-                    .or_else(|| {
-                        if self.project.main_scenario.id == *scenario_id {
-                            Some(&self.project.main_scenario)
-                        } else {
-                            None
-                        }
-                    });
+                let scenario = self.project.find_scenario(scenario_id);
 
                 if let Some(_scenario) = scenario {
                     let timestamp = get_timestamp(self.context.start_time);
-                    self.log.log(LogEntry {
+                    self.emit_log(LogEntry {
                         timestamp,
                         node_id: self.current_node_id.clone(),
                         level: LogLevel::Info,
@@ -817,7 +1079,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
             }
             Instruction::RunPowershell { code: _ } => {
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: self.current_node_id.clone(),
                     level: LogLevel::Warning,
@@ -826,14 +1088,848 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 });
                 Ok(pc + 1)
             }
+            Instruction::ReadTextFile {
+                path,
+                encoding,
+                output_var,
+            } => {
+                let path = self.eval_path(path)?;
+                let text = files::read_text(&path, *encoding)?;
+                self.log_info(
+                    LogActivity::ReadTextFile,
+                    format!(
+                        "Read {} characters from {} ({})",
+                        text.chars().count(),
+                        path.display(),
+                        encoding.as_str()
+                    ),
+                );
+                self.context
+                    .set_variable(output_var, Value::String(text), VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::WriteTextFile {
+                path,
+                content,
+                encoding,
+                append,
+            } => {
+                let path = self.eval_path(path)?;
+                let content = eval_expr(content, &self.get_combined_variables())?.to_string();
+                files::write_text(&path, &content, *encoding, *append)?;
+                let action = if *append { "Appended" } else { "Wrote" };
+                self.log_info(
+                    LogActivity::WriteTextFile,
+                    format!(
+                        "{} {} characters to {} ({})",
+                        action,
+                        content.chars().count(),
+                        path.display(),
+                        encoding.as_str()
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::CopyFile {
+                source,
+                destination,
+                overwrite,
+                remove_source,
+            } => {
+                let source = self.eval_path(source)?;
+                let destination = self.eval_path(destination)?;
+                if *remove_source {
+                    files::move_file(&source, &destination, *overwrite)?;
+                    self.log_info(
+                        LogActivity::MoveFile,
+                        format!("Moved {} to {}", source.display(), destination.display()),
+                    );
+                } else {
+                    files::copy_file(&source, &destination, *overwrite)?;
+                    self.log_info(
+                        LogActivity::CopyFile,
+                        format!("Copied {} to {}", source.display(), destination.display()),
+                    );
+                }
+                Ok(pc + 1)
+            }
+            Instruction::DeleteFile { path } => {
+                let path = self.eval_path(path)?;
+                files::delete_path(&path)?;
+                self.log_info(
+                    LogActivity::DeleteFile,
+                    format!("Deleted {}", path.display()),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::CreateDirectory { path } => {
+                let path = self.eval_path(path)?;
+                files::create_directory(&path)?;
+                self.log_info(
+                    LogActivity::CreateDirectory,
+                    format!("Created directory {}", path.display()),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ListFiles {
+                directory,
+                pattern,
+                recursive,
+                output_var,
+            } => {
+                let directory = self.eval_path(directory)?;
+                let pattern = eval_expr(pattern, &self.get_combined_variables())?.to_string();
+                let found = files::list_files(&directory, &pattern, *recursive)?;
+                self.log_info(
+                    LogActivity::ListFiles,
+                    format!(
+                        "Found {} files matching '{}' in {}",
+                        found.len(),
+                        pattern,
+                        directory.display()
+                    ),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::String(found.join("\n")),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::FileExists { path, output_var } => {
+                let path = self.eval_path(path)?;
+                let exists = path.exists();
+                self.log_info(
+                    LogActivity::FileExists,
+                    format!("{} exists: {}", path.display(), exists),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::Boolean(exists),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::WaitForFile { path, timeout_ms } => {
+                let path = self.eval_path(path)?;
+                self.log_info(
+                    LogActivity::WaitForFile,
+                    format!("Waiting up to {} ms for {}", timeout_ms, path.display()),
+                );
+                files::wait_for_file(
+                    &path,
+                    Duration::from_millis(*timeout_ms),
+                    &self.context.stop_control,
+                )?;
+                Ok(pc + 1)
+            }
+            Instruction::ExcelReadRange {
+                path,
+                sheet,
+                range,
+                has_headers,
+                output_var,
+            } => {
+                let path = self.eval_path(path)?;
+                let sheet = self.eval_string(sheet)?;
+                let range = match range {
+                    Some(range) => self.eval_string(range)?,
+                    None => String::new(),
+                };
+                let rows = Excel::read_range(&path, &sheet, &range)?;
+                let table = excel::rows_to_table(rows, *has_headers);
+                self.log_info(
+                    LogActivity::ExcelRead,
+                    format!(
+                        "Read {} rows x {} columns from {} [{}]",
+                        table.row_count(),
+                        table.columns.len(),
+                        path.display(),
+                        sheet
+                    ),
+                );
+                self.context
+                    .set_variable(output_var, Value::Table(table), VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::ExcelReadCell {
+                path,
+                sheet,
+                cell,
+                output_var,
+            } => {
+                let path = self.eval_path(path)?;
+                let sheet = self.eval_string(sheet)?;
+                let cell = self.eval_string(cell)?;
+                let value = excel::cell_to_value(&Excel::read_cell(&path, &sheet, &cell)?);
+                self.log_info(
+                    LogActivity::ExcelRead,
+                    format!("Read {}!{} from {}", sheet, cell, path.display()),
+                );
+                self.context
+                    .set_variable(output_var, value, VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::ExcelWrite {
+                path,
+                sheet,
+                cell,
+                value,
+                write_headers,
+            } => {
+                let path = self.eval_path(path)?;
+                let sheet = self.eval_string(sheet)?;
+                let cell = self.eval_string(cell)?;
+                let value = eval_expr(value, &self.get_combined_variables())?;
+                let rows = excel::value_to_rows(&value, *write_headers);
+                Excel::write_range(&path, &sheet, &cell, &rows)?;
+                self.log_info(
+                    LogActivity::ExcelWrite,
+                    format!(
+                        "Wrote {} rows at {}!{} in {}",
+                        rows.len(),
+                        sheet,
+                        cell,
+                        path.display()
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ExcelAppendRows { path, sheet, value } => {
+                let path = self.eval_path(path)?;
+                let sheet = self.eval_string(sheet)?;
+                let value = eval_expr(value, &self.get_combined_variables())?;
+                let rows = excel::value_to_rows(&value, false);
+                Excel::append_rows(&path, &sheet, &rows)?;
+                self.log_info(
+                    LogActivity::ExcelWrite,
+                    format!(
+                        "Appended {} rows to {} [{}]",
+                        rows.len(),
+                        path.display(),
+                        sheet
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ExcelListSheets { path, output_var } => {
+                let path = self.eval_path(path)?;
+                let sheets = Excel::list_sheets(&path)?;
+                self.log_info(
+                    LogActivity::ExcelListSheets,
+                    format!("Found {} sheets in {}", sheets.len(), path.display()),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::String(sheets.join("\n")),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::FillWordTemplate {
+                template_path,
+                output_path,
+                values,
+            } => {
+                let template_path = self.eval_path(template_path)?;
+                let output_path = self.eval_path(output_path)?;
+                let variables = self.get_combined_variables();
+                let record = match values {
+                    Some(values) => Some(eval_expr(values, &variables)?),
+                    None => None,
+                };
+                let values = word::template_values(&variables, record.as_ref())?;
+                Word::fill_template(&template_path, &output_path, &values)?;
+                self.log_info(
+                    LogActivity::FillWordTemplate,
+                    format!(
+                        "Filled {} into {}",
+                        template_path.display(),
+                        output_path.display()
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::SendEmail {
+                account,
+                from,
+                to,
+                cc,
+                subject,
+                body,
+                html,
+                attachments,
+            } => {
+                let server = self.mail_server(account)?;
+                let body = self.eval_string(body)?;
+                let attachments = match attachments {
+                    Some(attachments) => mail::attachment_paths(&eval_expr(
+                        attachments,
+                        &self.get_combined_variables(),
+                    )?),
+                    None => Vec::new(),
+                };
+                let message = EmailMessage {
+                    from: self.eval_string(from)?,
+                    to: self.eval_string(to)?,
+                    cc: match cc {
+                        Some(cc) => self.eval_string(cc)?,
+                        None => String::new(),
+                    },
+                    subject: self.eval_string(subject)?,
+                    body: if *html { String::new() } else { body.clone() },
+                    html_body: html.then_some(body),
+                    attachments,
+                    ..Default::default()
+                };
+                Smtp::send(&server, &message)?;
+                self.log_info(
+                    LogActivity::SendEmail,
+                    format!(
+                        "Sent '{}' to {} via {}",
+                        message.subject, message.to, server.host
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ReadEmails {
+                account,
+                folder,
+                criteria,
+                limit,
+                mark_read,
+                attachments_dir,
+                output_var,
+            } => {
+                let server = self.mail_server(account)?;
+                let query = ImapQuery {
+                    folder: self.eval_string(folder)?,
+                    criteria: self.eval_string(criteria)?,
+                    mark_read: *mark_read,
+                    limit: *limit as usize,
+                    attachments_dir: match attachments_dir {
+                        Some(dir) => Some(self.eval_path(dir)?),
+                        None => None,
+                    },
+                };
+                let messages = Imap::fetch(&server, &query)?;
+                self.log_info(
+                    LogActivity::ReadEmails,
+                    format!(
+                        "Read {} messages from {} on {}",
+                        messages.len(),
+                        query.folder,
+                        server.host
+                    ),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::Table(mail::messages_to_table(&messages)),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ParseEmail { path, output_var } => {
+                let path = self.eval_path(path)?;
+                let parsed = Mime::read(&path)?;
+                self.log_info(
+                    LogActivity::ParseEmail,
+                    format!(
+                        "Parsed {} with {} attachments",
+                        path.display(),
+                        parsed.message.attachments.len()
+                    ),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::Table(mail::messages_to_table(&[parsed.message])),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::SaveAttachments {
+                path,
+                directory,
+                include_inline,
+                output_var,
+            } => {
+                let path = self.eval_path(path)?;
+                let directory = self.eval_path(directory)?;
+                let parsed = Mime::read(&path)?;
+                let saved =
+                    Mime::save_attachments(&directory, &parsed.attachments, *include_inline)?;
+                self.log_info(
+                    LogActivity::SaveAttachments,
+                    format!(
+                        "Saved {} attachments from {} to {}",
+                        saved.len(),
+                        path.display(),
+                        directory.display()
+                    ),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::String(saved.join("\n")),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ReadCsv {
+                path,
+                format,
+                range,
+                output_var,
+                row_count_var,
+            } => {
+                let path = self.eval_path(path)?;
+                let node = self
+                    .current_node_id
+                    .clone()
+                    .ok_or_else(|| "Read CSV is not part of a node".to_string())?;
+                let table = self.csv_cursors.read(&node, &path, format, range)?;
+                let rows = table.row_count();
+                self.log_info(
+                    LogActivity::ReadCsv,
+                    format!("Read {} rows from {}", rows, path.display()),
+                );
+                self.context
+                    .set_variable(output_var, Value::Table(table), VariableScope::Scenario);
+                if let Some(row_count_var) = row_count_var {
+                    self.context.set_variable(
+                        row_count_var,
+                        Value::Number(rows as f64),
+                        VariableScope::Scenario,
+                    );
+                }
+                Ok(pc + 1)
+            }
+            Instruction::WriteCsv {
+                path,
+                value,
+                format,
+                write_headers,
+            } => {
+                let path = self.eval_path(path)?;
+                let value = eval_expr(value, &self.get_combined_variables())?;
+                let rows = csv::write_table(&path, &value, format, *write_headers)?;
+                self.log_info(
+                    LogActivity::WriteCsv,
+                    format!("Wrote {} rows to {}", rows, path.display()),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::AppendCsvRow {
+                path,
+                values,
+                format,
+            } => {
+                let path = self.eval_path(path)?;
+                let variables = self.get_combined_variables();
+                let values = values
+                    .iter()
+                    .map(|value| eval_expr(value, &variables))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (rows, header) = match values.as_slice() {
+                    [Value::Table(table)] => (
+                        csv::value_to_rows(&values[0]),
+                        Some(table.columns.as_slice()),
+                    ),
+                    _ => (vec![values.iter().map(ToString::to_string).collect()], None),
+                };
+                csv::append_rows(&path, &rows, header, format)?;
+                self.log_info(
+                    LogActivity::WriteCsv,
+                    format!("Appended {} rows to {}", rows.len(), path.display()),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::ParseJson { text, output_var } => {
+                let text = eval_expr(text, &self.get_combined_variables())?.to_string();
+                let value = structured::parse_json(&text)?;
+                self.log_info(
+                    LogActivity::ParseJson,
+                    format!("Parsed {} characters of JSON", text.chars().count()),
+                );
+                self.context
+                    .set_variable(output_var, value, VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::ToJson {
+                value,
+                pretty,
+                output_var,
+            } => {
+                let value = eval_expr(value, &self.get_combined_variables())?;
+                let json = structured::to_json(&value, *pretty)?;
+                self.log_info(
+                    LogActivity::ToJson,
+                    format!("Wrote {} characters of JSON", json.chars().count()),
+                );
+                self.context
+                    .set_variable(output_var, Value::String(json), VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::ParseXml { text, output_var } => {
+                let text = eval_expr(text, &self.get_combined_variables())?.to_string();
+                let value = structured::parse_xml(&text)?;
+                self.log_info(
+                    LogActivity::ParseXml,
+                    format!("Parsed {} characters of XML", text.chars().count()),
+                );
+                self.context
+                    .set_variable(output_var, value, VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::HttpRequest {
+                method,
+                url,
+                query,
+                headers,
+                body_kind,
+                body,
+                timeout_ms,
+                retries,
+                status_var,
+                headers_var,
+                output_var,
+            } => {
+                let variables = self.get_combined_variables();
+                let pairs = |expr: &Option<Expr>, separator| match expr {
+                    Some(expr) => http::pairs(&eval_expr(expr, &variables)?, separator),
+                    None => Ok(Vec::new()),
+                };
+                let request = HttpRequest {
+                    method: *method,
+                    url: eval_expr(url, &variables)?.to_string(),
+                    headers: pairs(headers, ':')?,
+                    query: pairs(query, '=')?,
+                    body: match body {
+                        Some(body) => {
+                            RequestBody::from_value(*body_kind, &eval_expr(body, &variables)?)?
+                        }
+                        None => RequestBody::Empty,
+                    },
+                    timeout: Duration::from_millis(*timeout_ms),
+                    retries: *retries,
+                };
+                let response = request.send(&self.context.stop_control)?;
+                let success = response.is_success();
+                self.log_info(
+                    LogActivity::HttpRequest,
+                    format!(
+                        "{} {} returned {} {} ({} bytes)",
+                        method.as_str(),
+                        request.url.trim(),
+                        response.status,
+                        response.status_text,
+                        response.body.len()
+                    ),
+                );
+
+                self.context.set_variable(
+                    status_var,
+                    Value::Number(f64::from(response.status)),
+                    VariableScope::Scenario,
+                );
+                if let Some(headers_var) = headers_var {
+                    self.context.set_variable(
+                        headers_var,
+                        Value::Object(response.headers),
+                        VariableScope::Scenario,
+                    );
+                }
+                self.context.set_variable(
+                    output_var,
+                    Value::String(response.body),
+                    VariableScope::Scenario,
+                );
+
+                if !success {
+                    return Err(format!(
+                        "{} {} returned {} {}",
+                        method.as_str(),
+                        request.url.trim(),
+                        response.status,
+                        response.status_text
+                    ));
+                }
+                Ok(pc + 1)
+            }
+            Instruction::ExecuteQuery {
+                connection,
+                sql,
+                parameters,
+                output_var,
+            } => {
+                let params = self.eval_all(parameters)?;
+                let table = self.database(connection)?.query(sql, &params)?;
+                self.log_info(
+                    LogActivity::Database,
+                    format!(
+                        "Query on '{}' returned {} rows",
+                        connection,
+                        table.row_count()
+                    ),
+                );
+                self.context
+                    .set_variable(output_var, Value::Table(table), VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::ExecuteNonQuery {
+                connection,
+                sql,
+                parameters,
+                output_var,
+            } => {
+                let params = self.eval_all(parameters)?;
+                let changed = self.database(connection)?.execute(sql, &params)?;
+                self.log_info(
+                    LogActivity::Database,
+                    format!("Statement on '{}' changed {} rows", connection, changed),
+                );
+                self.context.set_variable(
+                    output_var,
+                    Value::Number(changed as f64),
+                    VariableScope::Scenario,
+                );
+                Ok(pc + 1)
+            }
+            Instruction::BeginTransaction { connection } => {
+                self.database(connection)?;
+                self.databases
+                    .begin(connection, self.error_handlers.len())?;
+                self.log_info(
+                    LogActivity::Database,
+                    format!("Began transaction on '{}'", connection),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::CommitTransaction { connection } => {
+                self.databases.commit(connection)?;
+                self.log_info(
+                    LogActivity::Database,
+                    format!("Committed transaction on '{}'", connection),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::RollbackTransaction { connection } => {
+                self.databases.rollback(connection)?;
+                self.log_info(
+                    LogActivity::Database,
+                    format!("Rolled back transaction on '{}'", connection),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::Click {
+                selector,
+                click,
+                timeout_ms,
+            } => {
+                let element = self.find_element(selector, *timeout_ms)?;
+                desktop::click(&element, *click)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!("{} click on {}", click.as_str(), selector),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::TypeInto {
+                selector,
+                text,
+                simulate_typing,
+                timeout_ms,
+            } => {
+                let text = self.eval_string(text)?;
+                let element = self.find_element(selector, *timeout_ms)?;
+                desktop::type_into(&element, &text, *simulate_typing)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!(
+                        "Typed {} characters into {}",
+                        text.chars().count(),
+                        selector
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::GetText {
+                selector,
+                timeout_ms,
+                output_var,
+            } => {
+                let mut element = self.find_element(selector, *timeout_ms)?;
+                let text = desktop::get_text(&mut element)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!("Read {} characters from {}", text.chars().count(), selector),
+                );
+                self.context
+                    .set_variable(output_var, Value::String(text), VariableScope::Scenario);
+                Ok(pc + 1)
+            }
+            Instruction::WaitForElement {
+                selector,
+                timeout_ms,
+            } => {
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!("Waiting up to {} ms for {}", timeout_ms, selector),
+                );
+                self.find_element(selector, *timeout_ms)?;
+                Ok(pc + 1)
+            }
+            Instruction::SendKeys {
+                selector,
+                keys,
+                timeout_ms,
+            } => {
+                if let Some(selector) = selector {
+                    let element = self.find_element(selector, *timeout_ms)?;
+                    desktop::focus(&element)?;
+                }
+                desktop::send_keys(keys)?;
+                self.log_info(LogActivity::UiAutomation, format!("Sent keys {}", keys));
+                Ok(pc + 1)
+            }
+            Instruction::LaunchApplication {
+                path,
+                arguments,
+                output_var,
+            } => {
+                let path = self.eval_path(path)?;
+                let arguments = match arguments {
+                    Some(arguments) => self.eval_string(arguments)?,
+                    None => String::new(),
+                };
+                let pid = desktop::launch(&path.to_string_lossy(), &arguments)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!("Launched {} (process {})", path.display(), pid),
+                );
+                if let Some(output_var) = output_var {
+                    self.context.set_variable(
+                        output_var,
+                        Value::Number(f64::from(pid)),
+                        VariableScope::Scenario,
+                    );
+                }
+                Ok(pc + 1)
+            }
+            Instruction::CloseWindow {
+                selector,
+                timeout_ms,
+            } => {
+                let element = self.find_element(selector, *timeout_ms)?;
+                desktop::close_window(&element)?;
+                self.log_info(LogActivity::UiAutomation, format!("Closed {}", selector));
+                Ok(pc + 1)
+            }
+            Instruction::ClickImage {
+                image_path,
+                confidence,
+                click,
+                timeout_ms,
+            } => {
+                let path = self.eval_path(image_path)?;
+                let found = self.find_image(&path, *confidence, *timeout_ms)?;
+                desktop::click_at(found.rect, *click)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!(
+                        "{} click on image {} at ({}, {}), confidence {:.2}",
+                        click.as_str(),
+                        path.display(),
+                        found.rect.left,
+                        found.rect.top,
+                        found.confidence
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::WaitForImage {
+                image_path,
+                confidence,
+                timeout_ms,
+            } => {
+                let path = self.eval_path(image_path)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!(
+                        "Waiting up to {} ms for image {}",
+                        timeout_ms,
+                        path.display()
+                    ),
+                );
+                let found = self.find_image(&path, *confidence, *timeout_ms)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!(
+                        "Found image {} at ({}, {}), confidence {:.2}",
+                        path.display(),
+                        found.rect.left,
+                        found.rect.top,
+                        found.confidence
+                    ),
+                );
+                Ok(pc + 1)
+            }
+            Instruction::TakeScreenshot {
+                selector,
+                path,
+                output_var,
+                timeout_ms,
+            } => {
+                let path = match path {
+                    Some(path) => self.eval_path(path)?,
+                    None => {
+                        let elapsed = self.context.start_time.elapsed().unwrap_or_default();
+                        self.context
+                            .run_folder
+                            .join(format!("screenshot-{}.png", elapsed.as_millis()))
+                    }
+                };
+                let element = match selector {
+                    Some(selector) => Some(self.find_element(selector, *timeout_ms)?),
+                    None => None,
+                };
+                desktop::save_screenshot(element.as_ref(), &path)?;
+                self.log_info(
+                    LogActivity::UiAutomation,
+                    format!(
+                        "Saved screenshot of {} to {}",
+                        selector.as_deref().unwrap_or("the screen"),
+                        path.display()
+                    ),
+                );
+                if let Some(output_var) = output_var {
+                    self.context.set_variable(
+                        output_var,
+                        Value::String(path.to_string_lossy().into_owned()),
+                        VariableScope::Scenario,
+                    );
+                }
+                Ok(pc + 1)
+            }
             Instruction::DebugMarker {
                 node_id,
                 description,
             } => {
                 self.current_node_id = Some(node_id.clone());
 
+                // Entering a loop starts the batches read inside it over.
+                if let Some(body) = self.program.loop_bodies.get(&pc) {
+                    let nodes = self.program.instructions[body.clone()]
+                        .iter()
+                        .filter_map(|instruction| match instruction {
+                            Instruction::DebugMarker { node_id, .. } => Some(node_id.clone()),
+                            _ => None,
+                        })
+                        .collect();
+                    self.csv_cursors.close(&nodes);
+                }
+
                 let timestamp = get_timestamp(self.context.start_time);
-                self.log.log(LogEntry {
+                self.emit_log(LogEntry {
                     timestamp,
                     node_id: Some(node_id.clone()),
                     level: LogLevel::Debug,
@@ -849,7 +1945,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
     fn handle_error(&mut self, error: String, _pc: usize) -> Result<(), String> {
         if error == "Execution stopped by user" {
             let timestamp = get_timestamp(self.context.start_time);
-            self.log.log(LogEntry {
+            self.emit_log(LogEntry {
                 timestamp,
                 node_id: self.current_node_id.clone(),
                 level: LogLevel::Info,
@@ -867,7 +1963,7 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
 
         if let Some(catch_target) = self.error_handlers.pop() {
             let timestamp = get_timestamp(self.context.start_time);
-            self.log.log(LogEntry {
+            self.emit_log(LogEntry {
                 timestamp,
                 node_id: self.current_node_id.clone(),
                 level: LogLevel::Warning,
@@ -875,6 +1971,11 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                 message: format!("Error caught: {error}"),
             });
 
+            // Transactions begun inside the failed try block do not outlive it.
+            let depth = self.error_handlers.len();
+            let rollbacks = self.databases.rollback_deeper_than(depth);
+            self.log_rollbacks(rollbacks);
+
             let mut pc = catch_target;
             while pc < self.program.instructions.len() {
                 match self.execute_instruction(pc) {
@@ -884,14 +1985,20 @@ impl<'a, L: LogOutput> IrExecutor<'a, L> {
                         }
                         pc = next_pc;
                     }
-                    Err(e) => return Err(e),
+                    Err(e) => {
+                        self.save_error_screenshot(pc, &e);
+                        match self.error_output_target(pc, &e) {
+                            Some(target) => pc = target,
+                            None => return Err(e),
+                        }
+                    }
                 }
             }
 
             Ok(())
         } else {
             let timestamp = get_timestamp(self.context.start_time);
-            self.log.log(LogEntry {
+            self.emit_log(LogEntry {
                 timestamp,
                 node_id: self.current_node_id.clone(),
                 level: LogLevel::Error,
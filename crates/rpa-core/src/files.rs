@@ -0,0 +1,409 @@
+use crate::constants::CoreConstants;
+use crate::stop_control::StopControl;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    /// Little-endian with a byte order mark, as written by Windows tools.
+    Utf16,
+    /// Windows-1251, used by legacy Kazakh and Russian exports.
+    Cp1251,
+}
+
+impl TextEncoding {
+    pub fn all() -> [TextEncoding; 3] {
+        [
+            TextEncoding::Utf8,
+            TextEncoding::Utf16,
+            TextEncoding::Cp1251,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16 => "UTF-16",
+            TextEncoding::Cp1251 => "CP1251",
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, String> {
+        match self {
+            TextEncoding::Utf8 => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+                String::from_utf8(bytes.to_vec()).map_err(|e| format!("Invalid UTF-8: {}", e))
+            }
+            TextEncoding::Utf16 => {
+                let (text, _, had_errors) = encoding_rs::UTF_16LE.decode(bytes);
+                if had_errors {
+                    return Err("Invalid UTF-16".to_string());
+                }
+                Ok(text.into_owned())
+            }
+            TextEncoding::Cp1251 => {
+                let (text, had_errors) =
+                    encoding_rs::WINDOWS_1251.decode_without_bom_handling(bytes);
+                if had_errors {
+                    return Err("Invalid CP1251".to_string());
+                }
+                Ok(text.into_owned())
+            }
+        }
+    }
+
+    /// Encodes `text`; `with_bom` only matters for UTF-16, which gets a BOM at the start of a file.
+    pub fn encode(&self, text: &str, with_bom: bool) -> Result<Vec<u8>, String> {
+        match self {
+            TextEncoding::Utf8 => Ok(text.as_bytes().to_vec()),
+            TextEncoding::Utf16 => {
+                let mut bytes = if with_bom {
+                    UTF16_LE_BOM.to_vec()
+                } else {
+                    Vec::new()
+                };
+                bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+                Ok(bytes)
+            }
+            TextEncoding::Cp1251 => {
+                let (bytes, _, had_errors) = encoding_rs::WINDOWS_1251.encode(text);
+                if had_errors {
+                    return Err("Text contains characters that CP1251 cannot represent".to_string());
+                }
+                Ok(bytes.into_owned())
+            }
+        }
+    }
+}
+
+pub fn read_text(path: &Path, encoding: TextEncoding) -> Result<String, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    encoding
+        .decode(&bytes)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+pub fn write_text(
+    path: &Path,
+    content: &str,
+    encoding: TextEncoding,
+    append: bool,
+) -> Result<(), String> {
+    let is_empty = !append || std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+    let bytes = encoding
+        .encode(content, is_empty)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    file.write_all(&bytes)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub fn copy_file(source: &Path, destination: &Path, overwrite: bool) -> Result<(), String> {
+    check_destination(destination, overwrite)?;
+
+    std::fs::copy(source, destination).map_err(|e| {
+        format!(
+            "Failed to copy {} to {}: {}",
+            source.display(),
+            destination.display(),
+            e
+        )
+    })?;
+    Ok(())
+}
+
+/// Renames when possible and falls back to copy and delete across file systems.
+pub fn move_file(source: &Path, destination: &Path, overwrite: bool) -> Result<(), String> {
+    check_destination(destination, overwrite)?;
+
+    if std::fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+
+    copy_file(source, destination, true)?;
+    std::fs::remove_file(source)
+        .map_err(|e| format!("Failed to remove {}: {}", source.display(), e))
+}
+
+fn check_destination(destination: &Path, overwrite: bool) -> Result<(), String> {
+    if !overwrite && destination.exists() {
+        return Err(format!("{} already exists", destination.display()));
+    }
+    Ok(())
+}
+
+/// Deletes a file, or a directory together with its contents.
+pub fn delete_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+
+    result.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+pub fn create_directory(path: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(path)
+        .map_err(|e| format!("Failed to create directory {}: {}", path.display(), e))
+}
+
+/// Files in `directory` matching a glob `pattern` such as `*.csv`, sorted by path.
+pub fn list_files(directory: &Path, pattern: &str, recursive: bool) -> Result<Vec<String>, String> {
+    if !directory.is_dir() {
+        return Err(format!("{} is not a directory", directory.display()));
+    }
+
+    let pattern = if pattern.trim().is_empty() {
+        "*"
+    } else {
+        pattern.trim()
+    };
+    let escaped_dir = glob::Pattern::escape(&directory.to_string_lossy());
+    let full_pattern = if recursive {
+        format!("{}/**/{}", escaped_dir, pattern)
+    } else {
+        format!("{}/{}", escaped_dir, pattern)
+    };
+
+    let paths = glob::glob(&full_pattern)
+        .map_err(|e| format!("Invalid file pattern '{}': {}", pattern, e))?;
+
+    let mut files = Vec::new();
+    for path in paths {
+        let path = path.map_err(|e| format!("Failed to list {}: {}", directory.display(), e))?;
+        if path.is_file() {
+            files.push(path.to_string_lossy().to_string());
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// Polls until `path` exists. Returns an error on timeout or when execution is stopped.
+pub fn wait_for_file(
+    path: &Path,
+    timeout: Duration,
+    stop_control: &StopControl,
+) -> Result<(), String> {
+    let started = Instant::now();
+
+    loop {
+        if path.exists() {
+            return Ok(());
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(format!(
+                "Timed out after {} ms waiting for {}",
+                timeout.as_millis(),
+                path.display()
+            ));
+        }
+
+        let poll =
+            (timeout - elapsed).min(Duration::from_millis(CoreConstants::FILE_POLL_INTERVAL_MS));
+        if !stop_control.sleep_interruptible(poll.as_millis() as u64) {
+            return Err("Execution stopped by user".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodings_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "Сәлем, әлем! Привет, мир!";
+
+        for encoding in TextEncoding::all() {
+            let path = dir.path().join(format!("{}.txt", encoding.as_str()));
+            let text = if encoding == TextEncoding::Cp1251 {
+                "Привет, мир!"
+            } else {
+                text
+            };
+
+            write_text(&path, text, encoding, false).unwrap();
+            write_text(&path, "\nещё", encoding, true).unwrap();
+            let expected = format!("{}\nещё", text);
+            assert_eq!(read_text(&path, encoding).unwrap(), expected);
+        }
+
+        let bytes = std::fs::read(dir.path().join("UTF-16.txt")).unwrap();
+        assert_eq!(&bytes[..2], UTF16_LE_BOM);
+        assert_eq!(
+            bytes.windows(2).filter(|w| *w == UTF16_LE_BOM).count(),
+            1,
+            "appending must not repeat the BOM"
+        );
+    }
+
+    #[test]
+    fn cp1251_matches_legacy_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.txt");
+        std::fs::write(&path, [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2]).unwrap();
+
+        assert_eq!(read_text(&path, TextEncoding::Cp1251).unwrap(), "Привет");
+        assert!(read_text(&path, TextEncoding::Utf8).is_err());
+        assert!(TextEncoding::Cp1251.encode("Сәлем", false).is_err());
+    }
+
+    #[test]
+    fn copy_move_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.txt");
+        let copy = dir.path().join("b.txt");
+        let moved = dir.path().join("sub").join("c.txt");
+        std::fs::write(&source, "data").unwrap();
+
+        copy_file(&source, &copy, false).unwrap();
+        assert!(copy_file(&source, &copy, false).is_err());
+        copy_file(&source, &copy, true).unwrap();
+
+        create_directory(&dir.path().join("sub")).unwrap();
+        move_file(&copy, &moved, false).unwrap();
+        assert!(!copy.exists());
+        assert_eq!(std::fs::read_to_string(&moved).unwrap(), "data");
+
+        delete_path(&dir.path().join("sub")).unwrap();
+        assert!(!moved.exists());
+        assert!(delete_path(&moved).is_err());
+    }
+
+    #[test]
+    fn list_files_with_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        create_directory(&dir.path().join("nested").join("deeper")).unwrap();
+        for file in ["a.csv", "b.txt", "nested/c.csv", "nested/deeper/d.csv"] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+
+        let names = |files: Vec<String>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| {
+                    Path::new(f)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect()
+        };
+
+        let flat = list_files(dir.path(), "*.csv", false).unwrap();
+        assert_eq!(names(flat), ["a.csv"]);
+
+        let recursive = list_files(dir.path(), "*.csv", true).unwrap();
+        assert_eq!(names(recursive).len(), 3);
+
+        let all = list_files(dir.path(), "", false).unwrap();
+        assert_eq!(names(all), ["a.csv", "b.txt"]);
+
+        assert!(list_files(&dir.path().join("missing"), "*", false).is_err());
+    }
+
+    #[test]
+    fn wait_for_file_times_out_and_stops() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("incoming.txt");
+        let stop_control = StopControl::new();
+
+        let err = wait_for_file(&path, Duration::from_millis(50), &stop_control).unwrap_err();
+        assert!(err.contains("Timed out"));
+
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            std::fs::write(writer_path, "ready").unwrap();
+        });
+        wait_for_file(&path, Duration::from_secs(5), &stop_control).unwrap();
+        writer.join().unwrap();
+
+        let stopper = stop_control.clone();
+        let missing = dir.path().join("never.txt");
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            stopper.request_stop();
+        });
+        let started = Instant::now();
+        let err = wait_for_file(&missing, Duration::from_secs(30), &stop_control).unwrap_err();
+        handle.join().unwrap();
+        assert_eq!(err, "Execution stopped by user");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn executor_routes_file_errors_to_error_pin() {
+        use crate::Activity;
+        use crate::log::LogLevel;
+        use crate::test_support::{handle_errors, linear_project, run_project};
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out.txt");
+        let quoted = |path: &Path| format!("\"{}\"", path.display());
+
+        let mut project = linear_project(vec![
+            Activity::WriteTextFile {
+                path: quoted(&file),
+                content: "\"Привет\"".to_string(),
+                encoding: TextEncoding::Cp1251,
+                append: false,
+            },
+            Activity::ReadTextFile {
+                path: quoted(&file),
+                encoding: TextEncoding::Cp1251,
+                output_var: "content".to_string(),
+            },
+            Activity::Log {
+                level: LogLevel::Info,
+                message: "\"read: \" + @content".to_string(),
+            },
+            Activity::ReadTextFile {
+                path: quoted(&dir.path().join("missing.txt")),
+                encoding: TextEncoding::Utf8,
+                output_var: "missing".to_string(),
+            },
+        ]);
+        let missing = project.main_scenario.nodes[4].id.clone();
+        handle_errors(&mut project.main_scenario, &[missing]);
+
+        let (context, log) = run_project(&project);
+        let last_error = context
+            .global_variables
+            .get(CoreConstants::ERROR_VARIABLE_NAME)
+            .cloned();
+
+        assert_eq!(
+            std::fs::read(&file).unwrap(),
+            [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2]
+        );
+        assert!(log.iter().any(|e| e.message == "read: Привет"));
+        assert!(log.iter().any(|e| e.message == "handled"));
+        assert!(last_error.is_some_and(|e| e.to_string().contains("missing.txt")));
+    }
+}
@@ -0,0 +1,734 @@
+use crate::stop_control::StopControl;
+use crate::structured;
+use arc_script::Value;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Pause before the first retry; doubled for every further attempt.
+const RETRY_DELAY_MS: u64 = 500;
+/// Longest pause a `Retry-After` header can ask for before a retry.
+const MAX_RETRY_AFTER_MS: u64 = 60_000;
+/// Largest response body read into a variable; a larger one is an error.
+const MAX_BODY_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+}
+
+impl HttpMethod {
+    pub fn all() -> [HttpMethod; 6] {
+        [
+            HttpMethod::Get,
+            HttpMethod::Post,
+            HttpMethod::Put,
+            HttpMethod::Patch,
+            HttpMethod::Delete,
+            HttpMethod::Head,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Delete => "DELETE",
+            HttpMethod::Head => "HEAD",
+        }
+    }
+
+    /// Whether sending the request twice has the same effect as sending it once.
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, HttpMethod::Post | HttpMethod::Patch)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HttpBodyKind {
+    #[default]
+    None,
+    /// Text sent as is, any other value written with [`structured::to_json`].
+    Json,
+    /// `name=value` pairs sent URL-encoded.
+    Form,
+    Text,
+    /// Contents of the file at the given path.
+    File,
+}
+
+impl HttpBodyKind {
+    pub fn all() -> [HttpBodyKind; 5] {
+        [
+            HttpBodyKind::None,
+            HttpBodyKind::Json,
+            HttpBodyKind::Form,
+            HttpBodyKind::Text,
+            HttpBodyKind::File,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpBodyKind::None => "None",
+            HttpBodyKind::Json => "JSON",
+            HttpBodyKind::Form => "Form (URL-encoded)",
+            HttpBodyKind::Text => "Text",
+            HttpBodyKind::File => "File",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestBody {
+    Empty,
+    Json(String),
+    Form(Vec<(String, String)>),
+    Text(String),
+    File(PathBuf),
+}
+
+impl RequestBody {
+    pub fn from_value(kind: HttpBodyKind, value: &Value) -> Result<Self, String> {
+        Ok(match kind {
+            HttpBodyKind::None => RequestBody::Empty,
+            HttpBodyKind::Json => match value {
+                Value::String(text) => RequestBody::Json(text.clone()),
+                other => RequestBody::Json(structured::to_json(other, false)?),
+            },
+            HttpBodyKind::Form => RequestBody::Form(pairs(value, '=')?),
+            HttpBodyKind::Text => RequestBody::Text(value.to_string()),
+            HttpBodyKind::File => {
+                let path = value.to_string();
+                if path.trim().is_empty() {
+                    return Err("Path is empty".to_string());
+                }
+                RequestBody::File(PathBuf::from(path))
+            }
+        })
+    }
+
+    fn content_type(&self) -> Option<&'static str> {
+        match self {
+            RequestBody::Empty => None,
+            RequestBody::Json(_) => Some("application/json"),
+            RequestBody::Form(_) => Some("application/x-www-form-urlencoded"),
+            RequestBody::Text(_) => Some("text/plain; charset=utf-8"),
+            RequestBody::File(_) => Some("application/octet-stream"),
+        }
+    }
+}
+
+/// Name/value pairs for headers, query parameters and form fields: the fields
+/// of an object, the first row of a table, or one `name<separator>value` per
+/// line of text.
+pub fn pairs(value: &Value, separator: char) -> Result<Vec<(String, String)>, String> {
+    match value {
+        Value::Undefined => Ok(Vec::new()),
+        Value::Object(fields) => Ok(fields
+            .iter()
+            .map(|(name, field)| (name.clone(), field.to_string()))
+            .collect()),
+        Value::Table(table) => Ok(table
+            .rows
+            .first()
+            .map(|row| {
+                table
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, field)| (column.clone(), field.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()),
+        other => other
+            .to_string()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| match line.split_once(separator) {
+                Some((name, value)) if !name.trim().is_empty() => {
+                    Ok((name.trim().to_string(), value.trim().to_string()))
+                }
+                _ => Err(format!(
+                    "Expected 'name{} value', got '{}'",
+                    separator, line
+                )),
+            })
+            .collect(),
+    }
+}
+
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub query: Vec<(String, String)>,
+    pub body: RequestBody,
+    pub timeout: Duration,
+    /// Further attempts after a connection failure or a 429 or 5xx response.
+    /// POST and PATCH, which may have taken effect before the failure, are
+    /// only retried on 429 and 503, where the server did not handle them.
+    /// A `Retry-After` on those two sets the pause, up to a minute.
+    pub retries: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    /// Header names in lower case; repeated headers are joined with `", "`.
+    pub headers: IndexMap<String, Value>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    fn is_retryable(&self, method: HttpMethod) -> bool {
+        match self.status {
+            429 | 503 => true,
+            status => status >= 500 && method.is_idempotent(),
+        }
+    }
+
+    /// Pause in milliseconds asked for by the `Retry-After` header of a 429 or
+    /// 503 response, given in seconds or as an HTTP date.
+    fn retry_after(&self) -> Option<u64> {
+        if !matches!(self.status, 429 | 503) {
+            return None;
+        }
+        let value = self.headers.get("retry-after")?.to_string();
+        let value = value.trim();
+        let delay = match value.parse::<u64>() {
+            Ok(seconds) => seconds.saturating_mul(1000),
+            Err(_) => {
+                let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+                let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+                wait.num_milliseconds().max(0) as u64
+            }
+        };
+        Some(delay.min(MAX_RETRY_AFTER_MS))
+    }
+}
+
+impl HttpRequest {
+    /// Sends the request, retrying as configured. Any response is returned,
+    /// whatever its status; only transport failures are errors.
+    pub fn send(&self, stop_control: &StopControl) -> Result<HttpResponse, String> {
+        if self.url.trim().is_empty() {
+            return Err("URL is empty".to_string());
+        }
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+
+        let mut attempt = 0;
+        loop {
+            let result = self.send_once(&agent);
+            let retry = match &result {
+                Ok(response) => response.is_retryable(self.method),
+                Err(_) => self.method.is_idempotent(),
+            };
+            if !retry || attempt >= self.retries {
+                return result;
+            }
+
+            let delay = result
+                .as_ref()
+                .ok()
+                .and_then(HttpResponse::retry_after)
+                .unwrap_or(RETRY_DELAY_MS << attempt.min(6));
+            attempt += 1;
+            if !stop_control.sleep_interruptible(delay) {
+                return Err("Execution stopped by user".to_string());
+            }
+        }
+    }
+
+    fn send_once(&self, agent: &ureq::Agent) -> Result<HttpResponse, String> {
+        let mut request = agent.request(self.method.as_str(), self.url.trim());
+        for (name, value) in &self.query {
+            request = request.query(name, value);
+        }
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        if let Some(content_type) = self.body.content_type()
+            && request.header("Content-Type").is_none()
+        {
+            request = request.set("Content-Type", content_type);
+        }
+
+        let result = match &self.body {
+            RequestBody::Empty => request.call(),
+            RequestBody::Json(text) | RequestBody::Text(text) => request.send_string(text),
+            RequestBody::Form(fields) => {
+                let fields: Vec<(&str, &str)> = fields
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+                    .collect();
+                request.send_form(&fields)
+            }
+            RequestBody::File(path) => {
+                let bytes = std::fs::read(path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                request.send_bytes(&bytes)
+            }
+        };
+
+        match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => read_response(response),
+            Err(ureq::Error::Transport(e)) => Err(format!(
+                "{} {} failed: {}",
+                self.method.as_str(),
+                self.url.trim(),
+                e
+            )),
+        }
+    }
+}
+
+fn read_response(response: ureq::Response) -> Result<HttpResponse, String> {
+    let status = response.status();
+    let status_text = response.status_text().to_string();
+    let mut headers = IndexMap::new();
+    for name in response.headers_names() {
+        let value = response.all(&name).join(", ");
+        headers.insert(name.to_ascii_lowercase(), Value::String(value));
+    }
+
+    let declared = response
+        .header("Content-Length")
+        .and_then(|length| length.trim().parse::<u64>().ok());
+    if declared.is_some_and(|length| length > MAX_BODY_BYTES) {
+        return Err(body_too_large(MAX_BODY_BYTES));
+    }
+    let bytes = read_body(response.into_reader(), MAX_BODY_BYTES)?;
+
+    Ok(HttpResponse {
+        status,
+        status_text,
+        headers,
+        body: String::from_utf8_lossy(&bytes).into_owned(),
+    })
+}
+
+/// Reads at most `limit` bytes, failing instead of cutting off a longer body.
+fn read_body(reader: impl Read, limit: u64) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+    if bytes.len() as u64 > limit {
+        return Err(body_too_large(limit));
+    }
+    Ok(bytes)
+}
+
+fn body_too_large(limit: u64) -> String {
+    format!("Response body is larger than {} bytes", limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::CoreConstants;
+
+    use crate::Activity;
+    use crate::node_graph::BranchType;
+    use crate::test_support::{linear_project, run_project};
+    use crate::variables::Variables;
+    use arc_script::VariableType;
+
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    /// Local server answering each connection with the next canned response;
+    /// the raw requests it received come back through the receiver.
+    fn serve(responses: Vec<String>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let request = read_request(&mut stream);
+                stream.write_all(response.as_bytes()).unwrap();
+                sender.send(request).unwrap();
+            }
+        });
+        (base, receiver)
+    }
+
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            request.push_str(&line);
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+        }
+        let length = request
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, value)| value.trim().parse().unwrap());
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        request + &String::from_utf8(body).unwrap()
+    }
+
+    fn response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            headers,
+            body.len(),
+            body
+        )
+    }
+
+    fn request(method: HttpMethod, url: String) -> HttpRequest {
+        HttpRequest {
+            method,
+            url,
+            headers: Vec::new(),
+            query: Vec::new(),
+            body: RequestBody::Empty,
+            timeout: Duration::from_secs(5),
+            retries: 0,
+        }
+    }
+
+    #[test]
+    fn pairs_are_read_from_lines_objects_and_tables() {
+        let text = Value::String("Accept: application/json\n\n X-Trace : a:b \n".to_string());
+        assert_eq!(
+            pairs(&text, ':').unwrap(),
+            vec![
+                ("Accept".to_string(), "application/json".to_string()),
+                ("X-Trace".to_string(), "a:b".to_string()),
+            ]
+        );
+
+        let object = structured::parse_json(r#"{"page":2,"q":"a b"}"#).unwrap();
+        assert_eq!(
+            pairs(&object, '=').unwrap(),
+            vec![
+                ("page".to_string(), "2".to_string()),
+                ("q".to_string(), "a b".to_string()),
+            ]
+        );
+        assert!(pairs(&Value::Undefined, '=').unwrap().is_empty());
+        assert!(
+            pairs(&Value::String("page".to_string()), '=')
+                .unwrap_err()
+                .contains("Expected 'name= value'")
+        );
+    }
+
+    #[test]
+    fn request_sends_query_headers_and_body() {
+        let (base, requests) = serve(vec![response(
+            "201 Created",
+            "Content-Type: application/json\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\n",
+            r#"{"id":7}"#,
+        )]);
+        let mut post = request(HttpMethod::Post, format!("{}/orders", base));
+        post.query = vec![("dry run".to_string(), "yes&no".to_string())];
+        post.headers = vec![("Authorization".to_string(), "Bearer t0k".to_string())];
+        post.body = RequestBody::from_value(
+            HttpBodyKind::Json,
+            &structured::parse_json(r#"{"sku":"A-1","qty":2}"#).unwrap(),
+        )
+        .unwrap();
+
+        let response = post.send(&StopControl::new()).unwrap();
+        let received = requests.recv().unwrap();
+
+        assert!(received.starts_with("POST /orders?dry+run=yes%26no HTTP/1.1\r\n"));
+        assert!(received.contains("Authorization: Bearer t0k\r\n"));
+        assert!(received.contains("Content-Type: application/json\r\n"));
+        assert!(received.ends_with("\r\n\r\n{\"sku\":\"A-1\",\"qty\":2}"));
+        assert!(response.is_success());
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, r#"{"id":7}"#);
+        assert_eq!(
+            response.headers.get("set-cookie"),
+            Some(&Value::String("a=1, b=2".to_string()))
+        );
+    }
+
+    #[test]
+    fn form_and_file_bodies() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("scan.bin");
+        std::fs::write(&file, [0x25, 0x50, 0x44, 0x46]).unwrap();
+        let (base, requests) = serve(vec![response("200 OK", "", ""), response("200 OK", "", "")]);
+
+        let mut form = request(HttpMethod::Post, format!("{}/login", base));
+        form.body = RequestBody::from_value(
+            HttpBodyKind::Form,
+            &Value::String("user=robot\npassword=p&ss".to_string()),
+        )
+        .unwrap();
+        form.send(&StopControl::new()).unwrap();
+        let received = requests.recv().unwrap();
+        assert!(received.contains("Content-Type: application/x-www-form-urlencoded\r\n"));
+        assert!(received.ends_with("\r\n\r\nuser=robot&password=p%26ss"));
+
+        let mut upload = request(HttpMethod::Put, format!("{}/files/scan.pdf", base));
+        upload.headers = vec![("Content-Type".to_string(), "application/pdf".to_string())];
+        upload.body = RequestBody::from_value(
+            HttpBodyKind::File,
+            &Value::String(file.display().to_string()),
+        )
+        .unwrap();
+        upload.send(&StopControl::new()).unwrap();
+        let received = requests.recv().unwrap();
+        assert!(received.contains("Content-Type: application/pdf\r\n"));
+        assert!(!received.contains("application/octet-stream"));
+        assert!(received.ends_with("\r\n\r\n%PDF"));
+    }
+
+    #[test]
+    fn server_errors_are_retried() {
+        let (base, requests) = serve(vec![
+            response("503 Service Unavailable", "", "busy"),
+            response("200 OK", "", "ready"),
+        ]);
+        let mut get = request(HttpMethod::Get, format!("{}/status", base));
+        get.retries = 2;
+
+        let response = get.send(&StopControl::new()).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "ready");
+        assert_eq!(requests.iter().count(), 2);
+    }
+
+    #[test]
+    fn client_errors_are_returned_without_retry() {
+        let (base, requests) = serve(vec![response("404 Not Found", "", "no such order")]);
+        let mut get = request(HttpMethod::Get, format!("{}/orders/9", base));
+        get.retries = 3;
+
+        let response = get.send(&StopControl::new()).unwrap();
+
+        assert!(!response.is_success());
+        assert_eq!(response.status, 404);
+        assert_eq!(response.status_text, "Not Found");
+        assert_eq!(response.body, "no such order");
+        assert_eq!(requests.iter().count(), 1);
+    }
+
+    #[test]
+    fn only_idempotent_requests_are_retried_after_failures() {
+        // An empty answer closes the connection without a response.
+        let (base, requests) = serve(vec![String::new(), response("200 OK", "", "")]);
+        let mut post = request(HttpMethod::Post, format!("{}/payments", base));
+        post.retries = 2;
+        assert!(post.send(&StopControl::new()).is_err());
+        assert_eq!(
+            requests.recv().unwrap().lines().next(),
+            Some("POST /payments HTTP/1.1")
+        );
+        assert!(requests.try_recv().is_err());
+
+        let (base, requests) = serve(vec![
+            response("500 Internal Server Error", "", ""),
+            response("503 Service Unavailable", "", ""),
+            response("200 OK", "", ""),
+        ]);
+        post.url = format!("{}/payments", base);
+        assert_eq!(post.send(&StopControl::new()).unwrap().status, 500);
+        assert_eq!(post.send(&StopControl::new()).unwrap().status, 200);
+        assert_eq!(requests.iter().count(), 3);
+
+        let (base, requests) = serve(vec![String::new(), response("200 OK", "", "")]);
+        let mut put = request(HttpMethod::Put, format!("{}/files/a", base));
+        put.retries = 2;
+        assert_eq!(put.send(&StopControl::new()).unwrap().status, 200);
+        assert_eq!(requests.iter().count(), 2);
+    }
+
+    #[test]
+    fn retry_after_sets_the_pause() {
+        let (base, requests) = serve(vec![
+            response("429 Too Many Requests", "Retry-After: 1\r\n", ""),
+            response("200 OK", "", ""),
+        ]);
+        let mut post = request(HttpMethod::Post, format!("{}/payments", base));
+        post.retries = 1;
+
+        let started = std::time::Instant::now();
+        assert_eq!(post.send(&StopControl::new()).unwrap().status, 200);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(requests.iter().count(), 2);
+
+        let busy = |status: u16, retry_after: &str| HttpResponse {
+            status,
+            status_text: String::new(),
+            headers: IndexMap::from([(
+                "retry-after".to_string(),
+                Value::String(retry_after.to_string()),
+            )]),
+            body: String::new(),
+        };
+        assert_eq!(busy(503, " 2 ").retry_after(), Some(2_000));
+        assert_eq!(busy(503, "86400").retry_after(), Some(MAX_RETRY_AFTER_MS));
+        assert_eq!(
+            busy(429, "Wed, 21 Oct 2015 07:28:00 GMT").retry_after(),
+            Some(0)
+        );
+        assert_eq!(busy(429, "soon").retry_after(), None);
+        assert_eq!(busy(500, "2").retry_after(), None);
+    }
+
+    #[test]
+    fn oversized_bodies_are_errors() {
+        assert_eq!(read_body(&b"1234"[..], 4).unwrap(), b"1234");
+        assert_eq!(
+            read_body(&b"12345"[..], 4).unwrap_err(),
+            "Response body is larger than 4 bytes"
+        );
+
+        // A declared length over the limit fails before the body is read.
+        let (base, _requests) = serve(vec![format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        )]);
+        let error = request(HttpMethod::Get, format!("{}/export", base))
+            .send(&StopControl::new())
+            .unwrap_err();
+        assert_eq!(error, body_too_large(MAX_BODY_BYTES));
+    }
+
+    fn http_request(url: String, body_kind: HttpBodyKind, body: &str) -> Activity {
+        Activity::HttpRequest {
+            method: HttpMethod::Post,
+            url,
+            query: String::new(),
+            headers: "\"Authorization: Bearer {@token}\"".to_string(),
+            body_kind,
+            body: body.to_string(),
+            timeout_ms: 5_000,
+            retries: 0,
+            status_var: "status".to_string(),
+            headers_var: "response_headers".to_string(),
+            output_var: "response".to_string(),
+        }
+    }
+
+    /// Runs `activity` after setting `token`, with the activity's Success or
+    /// Error output (per `branch`) leading to End.
+    fn run(activity: Activity, branch: BranchType) -> (Variables, Variables) {
+        let mut project = linear_project(vec![
+            Activity::SetVariable {
+                name: "token".to_string(),
+                value: "t0k".to_string(),
+                var_type: VariableType::String,
+                is_global: false,
+            },
+            activity,
+        ]);
+        project.main_scenario.connections[2].branch_type = branch;
+
+        let (context, _) = run_project(&project);
+        let scenario = context.scope_stack.last().unwrap().variables.clone();
+        (scenario, context.global_variables)
+    }
+
+    #[test]
+    fn executor_binds_response_variables() {
+        let (base, requests) = serve(vec![response(
+            "200 OK",
+            "Content-Type: application/json\r\n",
+            r#"{"ticket":"HD-42"}"#,
+        )]);
+
+        let (scenario, _) = run(
+            http_request(
+                format!("\"{}/tickets\"", base),
+                HttpBodyKind::Text,
+                "\"Printer on floor {1 + 2} is jammed\"",
+            ),
+            BranchType::Default,
+        );
+        let received = requests.recv().unwrap();
+
+        assert!(received.contains("Authorization: Bearer t0k\r\n"));
+        assert!(received.ends_with("\r\n\r\nPrinter on floor 3 is jammed"));
+        assert_eq!(scenario.get("status"), Some(&Value::Number(200.0)));
+        assert_eq!(
+            scenario.get("response"),
+            Some(&Value::String(r#"{"ticket":"HD-42"}"#.to_string()))
+        );
+        let Some(Value::Object(headers)) = scenario.get("response_headers") else {
+            panic!("response headers are not an object");
+        };
+        assert_eq!(
+            headers.get("content-type"),
+            Some(&Value::String("application/json".to_string()))
+        );
+    }
+
+    #[test]
+    fn oversized_response_routes_to_error_branch() {
+        let (base, _requests) = serve(vec![format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        )]);
+
+        let (scenario, globals) = run(
+            http_request(format!("\"{}/export\"", base), HttpBodyKind::None, ""),
+            BranchType::ErrorBranch,
+        );
+        let last_error = globals.get(CoreConstants::ERROR_VARIABLE_NAME).cloned();
+
+        assert_eq!(scenario.get("response"), None);
+        assert!(last_error.is_some_and(|e| e.to_string().contains("Response body is larger")));
+    }
+
+    #[test]
+    fn non_success_status_routes_to_error_branch() {
+        let (base, _requests) = serve(vec![response(
+            "422 Unprocessable Entity",
+            "",
+            "sku is required",
+        )]);
+
+        let (scenario, globals) = run(
+            http_request(format!("\"{}/orders\"", base), HttpBodyKind::None, ""),
+            BranchType::ErrorBranch,
+        );
+        let last_error = globals.get(CoreConstants::ERROR_VARIABLE_NAME).cloned();
+
+        assert_eq!(scenario.get("status"), Some(&Value::Number(422.0)));
+        assert_eq!(
+            scenario.get("response"),
+            Some(&Value::String("sku is required".to_string()))
+        );
+        assert!(
+            last_error
+                .is_some_and(|e| e.to_string().ends_with("returned 422 Unprocessable Entity"))
+        );
+    }
+}
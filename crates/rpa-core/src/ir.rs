@@ -1,5 +1,10 @@
 use crate::constants::CoreConstants;
+use crate::csv::{CsvFormat, CsvRange};
+use crate::desktop::ClickKind;
+use crate::files::TextEncoding;
+use crate::http::{HttpBodyKind, HttpMethod};
 use crate::log::LogLevel;
+use crate::mail::MailSecurity;
 use crate::variables::VariableScope;
 use crate::{
     node_graph::{Activity, BranchType, Project, Scenario},
@@ -8,6 +13,7 @@ use crate::{
 use arc_script::{Expr, Value, parse_expr};
 use shared::NanoId;
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
 #[derive(Debug, Clone)]
 pub enum Instruction {
@@ -87,12 +93,249 @@ pub enum Instruction {
     RunPowershell {
         code: String,
     },
+    ReadTextFile {
+        path: Expr,
+        encoding: TextEncoding,
+        output_var: String,
+    },
+    WriteTextFile {
+        path: Expr,
+        content: Expr,
+        encoding: TextEncoding,
+        append: bool,
+    },
+    CopyFile {
+        source: Expr,
+        destination: Expr,
+        overwrite: bool,
+        remove_source: bool,
+    },
+    DeleteFile {
+        path: Expr,
+    },
+    CreateDirectory {
+        path: Expr,
+    },
+    ListFiles {
+        directory: Expr,
+        pattern: Expr,
+        recursive: bool,
+        output_var: String,
+    },
+    FileExists {
+        path: Expr,
+        output_var: String,
+    },
+    WaitForFile {
+        path: Expr,
+        timeout_ms: u64,
+    },
+    ExcelReadRange {
+        path: Expr,
+        sheet: Expr,
+        range: Option<Expr>,
+        has_headers: bool,
+        output_var: String,
+    },
+    ExcelReadCell {
+        path: Expr,
+        sheet: Expr,
+        cell: Expr,
+        output_var: String,
+    },
+    /// Writes `value` at `cell`: a table fills a range, any other value a single cell.
+    ExcelWrite {
+        path: Expr,
+        sheet: Expr,
+        cell: Expr,
+        value: Expr,
+        write_headers: bool,
+    },
+    ExcelAppendRows {
+        path: Expr,
+        sheet: Expr,
+        value: Expr,
+    },
+    ExcelListSheets {
+        path: Expr,
+        output_var: String,
+    },
+    FillWordTemplate {
+        template_path: Expr,
+        output_path: Expr,
+        values: Option<Expr>,
+    },
+    SendEmail {
+        account: MailAccount,
+        from: Expr,
+        to: Expr,
+        cc: Option<Expr>,
+        subject: Expr,
+        body: Expr,
+        html: bool,
+        attachments: Option<Expr>,
+    },
+    ReadEmails {
+        account: MailAccount,
+        folder: Expr,
+        criteria: Expr,
+        limit: u64,
+        mark_read: bool,
+        attachments_dir: Option<Expr>,
+        output_var: String,
+    },
+    ParseEmail {
+        path: Expr,
+        output_var: String,
+    },
+    SaveAttachments {
+        path: Expr,
+        directory: Expr,
+        include_inline: bool,
+        output_var: String,
+    },
+    ReadCsv {
+        path: Expr,
+        format: CsvFormat,
+        range: CsvRange,
+        output_var: String,
+        row_count_var: Option<String>,
+    },
+    WriteCsv {
+        path: Expr,
+        value: Expr,
+        format: CsvFormat,
+        write_headers: bool,
+    },
+    AppendCsvRow {
+        path: Expr,
+        /// One field per expression, or the rows of a single table.
+        values: Vec<Expr>,
+        format: CsvFormat,
+    },
+    ParseJson {
+        text: Expr,
+        output_var: String,
+    },
+    ToJson {
+        value: Expr,
+        pretty: bool,
+        output_var: String,
+    },
+    ParseXml {
+        text: Expr,
+        output_var: String,
+    },
+    HttpRequest {
+        method: HttpMethod,
+        url: Expr,
+        query: Option<Expr>,
+        headers: Option<Expr>,
+        body_kind: HttpBodyKind,
+        /// `None` when the request has no body.
+        body: Option<Expr>,
+        timeout_ms: u64,
+        retries: u32,
+        status_var: String,
+        headers_var: Option<String>,
+        output_var: String,
+    },
+    ExecuteQuery {
+        connection: String,
+        sql: String,
+        /// Bound to `?1`, `?2`, ... in order.
+        parameters: Vec<Expr>,
+        output_var: String,
+    },
+    ExecuteNonQuery {
+        connection: String,
+        sql: String,
+        parameters: Vec<Expr>,
+        output_var: String,
+    },
+    BeginTransaction {
+        connection: String,
+    },
+    CommitTransaction {
+        connection: String,
+    },
+    RollbackTransaction {
+        connection: String,
+    },
+    Click {
+        selector: String,
+        click: ClickKind,
+        timeout_ms: u64,
+    },
+    TypeInto {
+        selector: String,
+        text: Expr,
+        simulate_typing: bool,
+        timeout_ms: u64,
+    },
+    GetText {
+        selector: String,
+        timeout_ms: u64,
+        output_var: String,
+    },
+    WaitForElement {
+        selector: String,
+        timeout_ms: u64,
+    },
+    SendKeys {
+        /// Element focused before the keys are sent, if any.
+        selector: Option<String>,
+        keys: String,
+        timeout_ms: u64,
+    },
+    LaunchApplication {
+        path: Expr,
+        arguments: Option<Expr>,
+        /// Receives the process id.
+        output_var: Option<String>,
+    },
+    CloseWindow {
+        selector: String,
+        timeout_ms: u64,
+    },
+    ClickImage {
+        image_path: Expr,
+        confidence: f32,
+        click: ClickKind,
+        timeout_ms: u64,
+    },
+    WaitForImage {
+        image_path: Expr,
+        confidence: f32,
+        timeout_ms: u64,
+    },
+    TakeScreenshot {
+        /// Element to capture; the whole screen when not set.
+        selector: Option<String>,
+        /// Where to save the PNG; the run folder when not set.
+        path: Option<Expr>,
+        /// Receives the path of the saved file.
+        output_var: Option<String>,
+        timeout_ms: u64,
+    },
     DebugMarker {
         node_id: NanoId,
         description: String,
     },
 }
 
+/// Server settings shared by the mail instructions. Credentials are optional.
+#[derive(Debug, Clone)]
+pub struct MailAccount {
+    pub server: Expr,
+    pub port: u16,
+    pub security: MailSecurity,
+    pub username: Option<Expr>,
+    pub password: Option<Expr>,
+    pub allow_insecure_login: bool,
+    pub ca_bundle: Option<Expr>,
+}
+
 #[derive(Debug)]
 pub struct IrProgram {
     pub instructions: Vec<Instruction>,
@@ -100,6 +343,10 @@ pub struct IrProgram {
     pub scenario_start_index: HashMap<NanoId, usize>,
     pub scenario_call_graph: HashMap<NanoId, HashSet<NanoId>>,
     pub recursive_scenarios: HashSet<NanoId>,
+    /// Instruction index -> start of the code connected to that node's Error pin.
+    pub error_targets: HashMap<usize, usize>,
+    /// DebugMarker of a Loop or While node -> the instructions of its body.
+    pub loop_bodies: HashMap<usize, Range<usize>>,
 }
 
 impl Default for IrProgram {
@@ -116,6 +363,8 @@ impl IrProgram {
             scenario_start_index: HashMap::new(),
             scenario_call_graph: HashMap::new(),
             recursive_scenarios: HashSet::new(),
+            error_targets: HashMap::new(),
+            loop_bodies: HashMap::new(),
         }
     }
 
@@ -203,9 +452,7 @@ impl<'a> IrBuilder<'a> {
     fn compile_all_called_scenarios(&mut self) -> Result<(), String> {
         let mut scenarios_to_compile = Vec::new();
 
-        for scenario in
-            std::iter::once(&self.project.main_scenario).chain(self.project.scenarios.iter())
-        {
+        for scenario in self.project.all_scenarios() {
             if !self.compiled_scenarios.contains(&scenario.id)
                 && self.call_graph.contains_key(&scenario.id)
             {
@@ -231,6 +478,12 @@ impl<'a> IrBuilder<'a> {
         self.loop_stack.pop()
     }
 
+    fn record_loop_body(&mut self, node_id: &NanoId, body: Range<usize>) {
+        if let Some(&marker) = self.node_start_index.get(node_id) {
+            self.program.loop_bodies.insert(marker, body);
+        }
+    }
+
     fn current_loop(&mut self) -> Result<&mut LoopContext, String> {
         self.loop_stack
             .last_mut()
@@ -402,6 +655,42 @@ impl<'a> IrBuilder<'a> {
                 self.compile_default_next(node_id)?;
             }
             Activity::Note { .. } => {}
+            activity => {
+                let instruction = activity_instruction(activity)
+                    .map_err(|e| format!("Error in node {}: {}", node_id, e))?
+                    .ok_or_else(|| format!("Node {} cannot be compiled", node_id))?;
+                let instruction_idx = self.program.add_instruction(instruction);
+                self.compile_with_error_branch(node_id, instruction_idx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compiles the Default branch, then the Error branch, which errors raised by
+    /// `instruction_idx` jump to instead of aborting the run.
+    fn compile_with_error_branch(
+        &mut self,
+        node_id: NanoId,
+        instruction_idx: usize,
+    ) -> Result<(), String> {
+        self.compile_default_next(node_id.clone())?;
+
+        if let Some(error_node) = self.find_next_node_by_branch(node_id, BranchType::ErrorBranch) {
+            let jump_idx = self
+                .program
+                .add_instruction(Instruction::Jump { target: 0 });
+            let error_start = self.program.instructions.len();
+            self.program
+                .error_targets
+                .insert(instruction_idx, error_start);
+
+            self.compile_from_node(error_node)?;
+
+            let after_error = self.program.instructions.len();
+            if let Instruction::Jump { target } = &mut self.program.instructions[jump_idx] {
+                *target = after_error;
+            }
         }
 
         Ok(())
@@ -511,6 +800,7 @@ impl<'a> IrBuilder<'a> {
         });
 
         let after_loop_start = self.program.instructions.len();
+        self.record_loop_body(&node_id, body_start..after_loop_start);
         if let Some(n) = after_node {
             self.compile_from_node(n)?;
         }
@@ -582,6 +872,7 @@ impl<'a> IrBuilder<'a> {
             .add_instruction(Instruction::Jump { target: check_idx });
 
         let after_loop_start = self.program.instructions.len();
+        self.record_loop_body(&node_id, body_start..after_loop_start);
 
         if let Some(after_node) = after_loop.first() {
             self.compile_from_node(after_node.clone())?;
@@ -667,9 +958,7 @@ impl<'a> IrBuilder<'a> {
     fn compile_called_scenario(&mut self, scenario_id: NanoId) -> Result<(), String> {
         let scenario = self
             .project
-            .scenarios
-            .iter()
-            .find(|s| s.id == scenario_id)
+            .find_scenario(&scenario_id)
             .ok_or_else(|| format!("Scenario {} not found", scenario_id))?
             .clone();
 
@@ -872,8 +1161,42 @@ impl<'a> IrBuilder<'a> {
                     .add_instruction(Instruction::LoopBreak { end_target: 0 });
                 self.current_loop()?.break_instructions.push(break_idx);
             }
-            _ => {
-                self.compile_default_next_called(scenario, node_id)?;
+            activity => match activity_instruction(activity)? {
+                Some(instruction) => {
+                    let instruction_idx = self.program.add_instruction(instruction);
+                    self.compile_with_error_branch_called(scenario, node_id, instruction_idx)?;
+                }
+                None => self.compile_default_next_called(scenario, node_id)?,
+            },
+        }
+
+        Ok(())
+    }
+
+    fn compile_with_error_branch_called(
+        &mut self,
+        scenario: &Scenario,
+        node_id: NanoId,
+        instruction_idx: usize,
+    ) -> Result<(), String> {
+        self.compile_default_next_called(scenario, node_id.clone())?;
+
+        if let Some(error_node) =
+            self.find_next_node_by_branch_called(scenario, node_id, BranchType::ErrorBranch)
+        {
+            let jump_idx = self
+                .program
+                .add_instruction(Instruction::Jump { target: 0 });
+            let error_start = self.program.instructions.len();
+            self.program
+                .error_targets
+                .insert(instruction_idx, error_start);
+
+            self.compile_from_called_scenario(scenario, error_node)?;
+
+            let after_error = self.program.instructions.len();
+            if let Instruction::Jump { target } = &mut self.program.instructions[jump_idx] {
+                *target = after_error;
             }
         }
 
@@ -962,6 +1285,7 @@ impl<'a> IrBuilder<'a> {
         });
 
         let after_loop_start = self.program.instructions.len();
+        self.record_loop_body(&node_id, body_start..after_loop_start);
         if let Some(n) = after_node {
             self.compile_from_called_scenario(scenario, n)?;
         }
@@ -1037,6 +1361,7 @@ impl<'a> IrBuilder<'a> {
             .add_instruction(Instruction::Jump { target: check_idx });
 
         let after_loop_start = self.program.instructions.len();
+        self.record_loop_body(&node_id, body_start..after_loop_start);
 
         if let Some(after_node) = after_loop {
             self.compile_from_called_scenario(scenario, after_node)?;
@@ -1071,3 +1396,489 @@ impl<'a> IrBuilder<'a> {
         Ok(())
     }
 }
+
+fn parse_property(value: &str, property: &str) -> Result<Expr, String> {
+    parse_expr(value).map_err(|e| format!("Invalid {} '{}': {}", property, value, e))
+}
+
+/// Like [`parse_property`], but an empty field means the property is not set.
+fn parse_optional_property(value: &str, property: &str) -> Result<Option<Expr>, String> {
+    if value.trim().is_empty() {
+        Ok(None)
+    } else {
+        parse_property(value, property).map(Some)
+    }
+}
+
+/// One expression per non-empty line.
+fn query_parameters(parameters: &str) -> Result<Vec<Expr>, String> {
+    parameters
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_property(line, "query parameter"))
+        .collect()
+}
+
+fn mail_account(
+    server: &str,
+    port: u16,
+    security: MailSecurity,
+    username: &str,
+    password: &str,
+    allow_insecure_login: bool,
+    ca_bundle: &str,
+) -> Result<MailAccount, String> {
+    Ok(MailAccount {
+        server: parse_property(server, "server")?,
+        port,
+        security,
+        username: parse_optional_property(username, "username")?,
+        password: parse_optional_property(password, "password")?,
+        allow_insecure_login,
+        ca_bundle: parse_optional_property(ca_bundle, "CA bundle")?,
+    })
+}
+
+/// Builds the instruction for a Files, Excel, Word or Mail activity, `None` for any other activity.
+fn activity_instruction(activity: &Activity) -> Result<Option<Instruction>, String> {
+    let instruction = match activity {
+        Activity::ReadTextFile {
+            path,
+            encoding,
+            output_var,
+        } => Instruction::ReadTextFile {
+            path: parse_property(path, "path")?,
+            encoding: *encoding,
+            output_var: output_var.clone(),
+        },
+        Activity::WriteTextFile {
+            path,
+            content,
+            encoding,
+            append,
+        } => Instruction::WriteTextFile {
+            path: parse_property(path, "path")?,
+            content: parse_property(content, "content")?,
+            encoding: *encoding,
+            append: *append,
+        },
+        Activity::CopyFile {
+            source,
+            destination,
+            overwrite,
+        }
+        | Activity::MoveFile {
+            source,
+            destination,
+            overwrite,
+        } => Instruction::CopyFile {
+            source: parse_property(source, "source path")?,
+            destination: parse_property(destination, "destination path")?,
+            overwrite: *overwrite,
+            remove_source: matches!(activity, Activity::MoveFile { .. }),
+        },
+        Activity::DeleteFile { path } => Instruction::DeleteFile {
+            path: parse_property(path, "path")?,
+        },
+        Activity::CreateDirectory { path } => Instruction::CreateDirectory {
+            path: parse_property(path, "directory")?,
+        },
+        Activity::ListFiles {
+            directory,
+            pattern,
+            recursive,
+            output_var,
+        } => Instruction::ListFiles {
+            directory: parse_property(directory, "directory")?,
+            pattern: parse_property(pattern, "pattern")?,
+            recursive: *recursive,
+            output_var: output_var.clone(),
+        },
+        Activity::FileExists { path, output_var } => Instruction::FileExists {
+            path: parse_property(path, "path")?,
+            output_var: output_var.clone(),
+        },
+        Activity::WaitForFile { path, timeout_ms } => Instruction::WaitForFile {
+            path: parse_property(path, "path")?,
+            timeout_ms: *timeout_ms,
+        },
+        Activity::ExcelReadRange {
+            path,
+            sheet,
+            range,
+            has_headers,
+            output_var,
+        } => Instruction::ExcelReadRange {
+            path: parse_property(path, "path")?,
+            sheet: parse_property(sheet, "sheet")?,
+            range: parse_optional_property(range, "range")?,
+            has_headers: *has_headers,
+            output_var: output_var.clone(),
+        },
+        Activity::ExcelReadCell {
+            path,
+            sheet,
+            cell,
+            output_var,
+        } => Instruction::ExcelReadCell {
+            path: parse_property(path, "path")?,
+            sheet: parse_property(sheet, "sheet")?,
+            cell: parse_property(cell, "cell")?,
+            output_var: output_var.clone(),
+        },
+        Activity::ExcelWriteCell {
+            path,
+            sheet,
+            cell,
+            value,
+        } => Instruction::ExcelWrite {
+            path: parse_property(path, "path")?,
+            sheet: parse_property(sheet, "sheet")?,
+            cell: parse_property(cell, "cell")?,
+            value: parse_property(value, "value")?,
+            write_headers: false,
+        },
+        Activity::ExcelWriteRange {
+            path,
+            sheet,
+            cell,
+            value,
+            write_headers,
+        } => Instruction::ExcelWrite {
+            path: parse_property(path, "path")?,
+            sheet: parse_property(sheet, "sheet")?,
+            cell: parse_property(cell, "start cell")?,
+            value: parse_property(value, "table")?,
+            write_headers: *write_headers,
+        },
+        Activity::ExcelAppendRows { path, sheet, value } => Instruction::ExcelAppendRows {
+            path: parse_property(path, "path")?,
+            sheet: parse_property(sheet, "sheet")?,
+            value: parse_property(value, "table")?,
+        },
+        Activity::ExcelListSheets { path, output_var } => Instruction::ExcelListSheets {
+            path: parse_property(path, "path")?,
+            output_var: output_var.clone(),
+        },
+        Activity::FillWordTemplate {
+            template_path,
+            output_path,
+            values,
+        } => Instruction::FillWordTemplate {
+            template_path: parse_property(template_path, "template")?,
+            output_path: parse_property(output_path, "output file")?,
+            values: parse_optional_property(values, "values")?,
+        },
+        Activity::SendEmail {
+            server,
+            port,
+            security,
+            username,
+            password,
+            allow_insecure_login,
+            ca_bundle,
+            from,
+            to,
+            cc,
+            subject,
+            body,
+            html,
+            attachments,
+        } => Instruction::SendEmail {
+            account: mail_account(
+                server,
+                *port,
+                *security,
+                username,
+                password,
+                *allow_insecure_login,
+                ca_bundle,
+            )?,
+            from: parse_property(from, "sender")?,
+            to: parse_property(to, "recipients")?,
+            cc: parse_optional_property(cc, "copy recipients")?,
+            subject: parse_property(subject, "subject")?,
+            body: parse_property(body, "body")?,
+            html: *html,
+            attachments: parse_optional_property(attachments, "attachments")?,
+        },
+        Activity::ReadEmails {
+            server,
+            port,
+            security,
+            username,
+            password,
+            allow_insecure_login,
+            ca_bundle,
+            folder,
+            criteria,
+            limit,
+            mark_read,
+            attachments_dir,
+            output_var,
+        } => Instruction::ReadEmails {
+            account: mail_account(
+                server,
+                *port,
+                *security,
+                username,
+                password,
+                *allow_insecure_login,
+                ca_bundle,
+            )?,
+            folder: parse_property(folder, "folder")?,
+            criteria: parse_property(criteria, "search criteria")?,
+            limit: *limit,
+            mark_read: *mark_read,
+            attachments_dir: parse_optional_property(attachments_dir, "attachments folder")?,
+            output_var: output_var.clone(),
+        },
+        Activity::ParseEmail { path, output_var } => Instruction::ParseEmail {
+            path: parse_property(path, "path")?,
+            output_var: output_var.clone(),
+        },
+        Activity::SaveAttachments {
+            path,
+            directory,
+            include_inline,
+            output_var,
+        } => Instruction::SaveAttachments {
+            path: parse_property(path, "path")?,
+            directory: parse_property(directory, "attachments folder")?,
+            include_inline: *include_inline,
+            output_var: output_var.clone(),
+        },
+        Activity::ReadCsv {
+            path,
+            delimiter,
+            quote,
+            encoding,
+            has_headers,
+            skip_rows,
+            max_rows,
+            output_var,
+            row_count_var,
+        } => Instruction::ReadCsv {
+            path: parse_property(path, "path")?,
+            format: CsvFormat {
+                delimiter: *delimiter,
+                quote: *quote,
+                encoding: *encoding,
+            },
+            range: CsvRange {
+                has_headers: *has_headers,
+                skip_rows: *skip_rows as usize,
+                max_rows: *max_rows as usize,
+            },
+            output_var: output_var.clone(),
+            row_count_var: (!row_count_var.is_empty()).then(|| row_count_var.clone()),
+        },
+        Activity::WriteCsv {
+            path,
+            value,
+            delimiter,
+            quote,
+            encoding,
+            write_headers,
+        } => Instruction::WriteCsv {
+            path: parse_property(path, "path")?,
+            value: parse_property(value, "table")?,
+            format: CsvFormat {
+                delimiter: *delimiter,
+                quote: *quote,
+                encoding: *encoding,
+            },
+            write_headers: *write_headers,
+        },
+        Activity::AppendCsvRow {
+            path,
+            values,
+            delimiter,
+            quote,
+            encoding,
+        } => Instruction::AppendCsvRow {
+            path: parse_property(path, "path")?,
+            values: values
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| parse_property(line, "row value"))
+                .collect::<Result<_, _>>()?,
+            format: CsvFormat {
+                delimiter: *delimiter,
+                quote: *quote,
+                encoding: *encoding,
+            },
+        },
+        Activity::ParseJson { text, output_var } => Instruction::ParseJson {
+            text: parse_property(text, "JSON text")?,
+            output_var: output_var.clone(),
+        },
+        Activity::ToJson {
+            value,
+            pretty,
+            output_var,
+        } => Instruction::ToJson {
+            value: parse_property(value, "value")?,
+            pretty: *pretty,
+            output_var: output_var.clone(),
+        },
+        Activity::ParseXml { text, output_var } => Instruction::ParseXml {
+            text: parse_property(text, "XML text")?,
+            output_var: output_var.clone(),
+        },
+        Activity::HttpRequest {
+            method,
+            url,
+            query,
+            headers,
+            body_kind,
+            body,
+            timeout_ms,
+            retries,
+            status_var,
+            headers_var,
+            output_var,
+        } => Instruction::HttpRequest {
+            method: *method,
+            url: parse_property(url, "URL")?,
+            query: parse_optional_property(query, "query parameters")?,
+            headers: parse_optional_property(headers, "headers")?,
+            body_kind: *body_kind,
+            body: match body_kind {
+                HttpBodyKind::None => None,
+                _ => Some(parse_property(body, "body")?),
+            },
+            timeout_ms: *timeout_ms,
+            retries: *retries,
+            status_var: status_var.clone(),
+            headers_var: (!headers_var.is_empty()).then(|| headers_var.clone()),
+            output_var: output_var.clone(),
+        },
+        Activity::ExecuteQuery {
+            connection,
+            sql,
+            parameters,
+            output_var,
+        } => Instruction::ExecuteQuery {
+            connection: connection.clone(),
+            sql: sql.clone(),
+            parameters: query_parameters(parameters)?,
+            output_var: output_var.clone(),
+        },
+        Activity::ExecuteNonQuery {
+            connection,
+            sql,
+            parameters,
+            output_var,
+        } => Instruction::ExecuteNonQuery {
+            connection: connection.clone(),
+            sql: sql.clone(),
+            parameters: query_parameters(parameters)?,
+            output_var: output_var.clone(),
+        },
+        Activity::BeginTransaction { connection } => Instruction::BeginTransaction {
+            connection: connection.clone(),
+        },
+        Activity::CommitTransaction { connection } => Instruction::CommitTransaction {
+            connection: connection.clone(),
+        },
+        Activity::RollbackTransaction { connection } => Instruction::RollbackTransaction {
+            connection: connection.clone(),
+        },
+        Activity::Click {
+            selector,
+            click,
+            timeout_ms,
+        } => Instruction::Click {
+            selector: selector.trim().to_string(),
+            click: *click,
+            timeout_ms: *timeout_ms,
+        },
+        Activity::TypeInto {
+            selector,
+            text,
+            simulate_typing,
+            timeout_ms,
+        } => Instruction::TypeInto {
+            selector: selector.trim().to_string(),
+            text: parse_property(text, "text")?,
+            simulate_typing: *simulate_typing,
+            timeout_ms: *timeout_ms,
+        },
+        Activity::GetText {
+            selector,
+            timeout_ms,
+            output_var,
+        } => Instruction::GetText {
+            selector: selector.trim().to_string(),
+            timeout_ms: *timeout_ms,
+            output_var: output_var.clone(),
+        },
+        Activity::WaitForElement {
+            selector,
+            timeout_ms,
+        } => Instruction::WaitForElement {
+            selector: selector.trim().to_string(),
+            timeout_ms: *timeout_ms,
+        },
+        Activity::SendKeys {
+            selector,
+            keys,
+            timeout_ms,
+        } => Instruction::SendKeys {
+            selector: (!selector.trim().is_empty()).then(|| selector.trim().to_string()),
+            keys: keys.trim().to_string(),
+            timeout_ms: *timeout_ms,
+        },
+        Activity::LaunchApplication {
+            path,
+            arguments,
+            output_var,
+        } => Instruction::LaunchApplication {
+            path: parse_property(path, "path")?,
+            arguments: parse_optional_property(arguments, "arguments")?,
+            output_var: (!output_var.is_empty()).then(|| output_var.clone()),
+        },
+        Activity::CloseWindow {
+            selector,
+            timeout_ms,
+        } => Instruction::CloseWindow {
+            selector: selector.trim().to_string(),
+            timeout_ms: *timeout_ms,
+        },
+        Activity::ClickImage {
+            image_path,
+            confidence,
+            click,
+            timeout_ms,
+        } => Instruction::ClickImage {
+            image_path: parse_property(image_path, "image path")?,
+            confidence: *confidence,
+            click: *click,
+            timeout_ms: *timeout_ms,
+        },
+        Activity::WaitForImage {
+            image_path,
+            confidence,
+            timeout_ms,
+        } => Instruction::WaitForImage {
+            image_path: parse_property(image_path, "image path")?,
+            confidence: *confidence,
+            timeout_ms: *timeout_ms,
+        },
+        Activity::TakeScreenshot {
+            selector,
+            path,
+            output_var,
+            timeout_ms,
+        } => Instruction::TakeScreenshot {
+            selector: (!selector.trim().is_empty()).then(|| selector.trim().to_string()),
+            path: parse_optional_property(path, "path")?,
+            output_var: (!output_var.is_empty()).then(|| output_var.clone()),
+            timeout_ms: *timeout_ms,
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(instruction))
+}
@@ -1,14 +1,30 @@
 pub mod activity_metadata;
 pub mod constants;
+pub mod csv;
+pub mod database;
+pub mod desktop;
+pub mod diff;
 pub mod evaluator_adapter;
 pub mod events;
+pub mod excel;
 pub mod execution;
+pub mod files;
+pub mod http;
 pub mod ir;
+pub mod library;
 pub mod log;
+pub mod mail;
 pub mod node_graph;
+pub mod project_format;
+pub mod recording;
+pub mod secrets;
 pub mod stop_control;
+pub mod structured;
+#[cfg(test)]
+mod test_support;
 pub mod validation;
 pub mod variables;
+pub mod word;
 
 pub use activity_metadata::{
     ActivityCategory, ActivityMetadata, ColorCategory, PinConfig, PropertyDef, PropertyType,
@@ -17,7 +33,11 @@ pub use constants::{ActivityCategories, ActivityDefaults, CoreConstants};
 pub use events::{ExecutionCommand, ExecutionEvent, ExecutionSnapshot};
 pub use execution::{execute_project_with_typed_vars, get_timestamp};
 pub use ir::{Instruction, IrBuilder, IrProgram};
+pub use library::{LibraryImport, ScenarioLibrary};
 pub use node_graph::{Activity, BranchType, Connection, Node, Project, ProjectFile, Scenario};
+pub use project_format::{
+    CURRENT_FORMAT_VERSION, LoadedProject, load_project, save_project, save_project_dir,
+};
 pub use stop_control::StopControl;
 pub use validation::{
     ScenarioValidator, ValidationCache, ValidationIssue, ValidationLevel, ValidationResult,
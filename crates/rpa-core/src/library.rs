@@ -0,0 +1,278 @@
+use crate::node_graph::{Project, Scenario};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::Path;
+
+pub const LIBRARY_EXTENSION: &str = "rpalib";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioLibrary {
+    pub name: String,
+    pub version: String,
+    pub scenarios: Vec<Scenario>,
+    #[serde(skip)]
+    pub source_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryFile {
+    pub library: ScenarioLibrary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryImport {
+    pub path: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LibraryVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl LibraryVersion {
+    pub fn parse(version: &str) -> Result<Self, String> {
+        let mut parts = version.trim().split('.');
+        let mut next_part = |name: &str| -> Result<u64, String> {
+            match parts.next() {
+                Some(part) => part
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid {} component in version '{}'", name, version)),
+                None => Ok(0),
+            }
+        };
+
+        let major = next_part("major")?;
+        let minor = next_part("minor")?;
+        let patch = next_part("patch")?;
+
+        if parts.next().is_some() {
+            return Err(format!("Too many components in version '{}'", version));
+        }
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// Caret compatibility: same major version (same minor for 0.x) and not older than `required`.
+    pub fn satisfies(&self, required: &LibraryVersion) -> bool {
+        if self.major != required.major {
+            return false;
+        }
+        if self.major == 0 && self.minor != required.minor {
+            return false;
+        }
+        self >= required
+    }
+}
+
+impl Display for LibraryVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl ScenarioLibrary {
+    pub fn new(name: &str, version: &str, scenarios: Vec<Scenario>) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            scenarios,
+            source_path: String::new(),
+        }
+    }
+}
+
+impl LibraryImport {
+    pub fn new(path: &str, version: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    /// Checks the loaded library version against the requested one. An empty request accepts any version.
+    pub fn check_version(&self, library: &ScenarioLibrary) -> Result<(), String> {
+        if self.version.trim().is_empty() {
+            return Ok(());
+        }
+
+        let required = LibraryVersion::parse(&self.version)?;
+        let actual = LibraryVersion::parse(&library.version)?;
+
+        if actual.satisfies(&required) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Library '{}' has version {}, but {} is required",
+                library.name, actual, required
+            ))
+        }
+    }
+}
+
+pub fn load_library(path: &Path) -> Result<ScenarioLibrary, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read library {}: {}", path.display(), e))?;
+
+    serde_json::from_str::<LibraryFile>(&contents)
+        .map(|lf| lf.library)
+        .map_err(|e| format!("Failed to parse library {}: {}", path.display(), e))
+}
+
+pub fn save_library(library: &ScenarioLibrary, path: &Path) -> Result<(), String> {
+    let library_file = LibraryFile {
+        library: library.clone(),
+    };
+
+    let json = serde_json::to_string_pretty(&library_file)
+        .map_err(|e| format!("Failed to serialize library: {}", e))?;
+
+    std::fs::write(path, json)
+        .map_err(|e| format!("Failed to write library {}: {}", path.display(), e))
+}
+
+/// Loads every import of `project` relative to `base_dir` into `project.libraries`.
+///
+/// Imports that fail to load are left out and reported in the returned list; the validator
+/// reports them again as errors, so callers may only log these messages.
+pub fn resolve_imports(project: &mut Project, base_dir: &Path) -> Vec<String> {
+    let mut errors = Vec::new();
+    project.libraries.clear();
+
+    for import in &project.imports {
+        let path = base_dir.join(&import.path);
+        match load_library(&path) {
+            Ok(mut library) => {
+                library.source_path = import.path.clone();
+                project.libraries.push(library);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_partial_versions() {
+        assert_eq!(
+            LibraryVersion::parse("1.2").unwrap(),
+            LibraryVersion {
+                major: 1,
+                minor: 2,
+                patch: 0
+            }
+        );
+        assert!(LibraryVersion::parse("1.x").is_err());
+        assert!(LibraryVersion::parse("1.2.3.4").is_err());
+    }
+
+    #[test]
+    fn caret_compatibility() {
+        let v = |s| LibraryVersion::parse(s).unwrap();
+
+        assert!(v("1.4.0").satisfies(&v("1.2.0")));
+        assert!(!v("1.1.9").satisfies(&v("1.2.0")));
+        assert!(!v("2.0.0").satisfies(&v("1.2.0")));
+        assert!(v("0.3.5").satisfies(&v("0.3.1")));
+        assert!(!v("0.4.0").satisfies(&v("0.3.1")));
+    }
+
+    #[test]
+    fn empty_import_version_accepts_anything() {
+        let library = ScenarioLibrary::new("common", "7.0.0", Vec::new());
+        assert!(
+            LibraryImport::new("common.rpalib", "")
+                .check_version(&library)
+                .is_ok()
+        );
+        assert!(
+            LibraryImport::new("common.rpalib", "6.1")
+                .check_version(&library)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn resolve_imports_reports_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let library = ScenarioLibrary::new("common", "1.0.0", vec![Scenario::new("Login")]);
+        save_library(&library, &dir.path().join("common.rpalib")).unwrap();
+
+        let mut project = Project::new("Test", crate::Variables::new());
+        project.imports = vec![
+            LibraryImport::new("common.rpalib", "1.0"),
+            LibraryImport::new("missing.rpalib", "1.0"),
+        ];
+
+        let errors = resolve_imports(&mut project, dir.path());
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(project.libraries.len(), 1);
+        assert_eq!(project.libraries[0].source_path, "common.rpalib");
+        assert_eq!(project.libraries[0].scenarios[0].name, "Login");
+    }
+
+    #[test]
+    fn call_scenario_resolves_into_imported_library() {
+        use crate::test_support::linear_scenario;
+        use crate::validation::ErrorCode;
+        use crate::{Activity, IrBuilder, ScenarioValidator};
+
+        let mut login = Scenario::new("Login");
+        linear_scenario(&mut login, Vec::new());
+        let login_id = login.id.clone();
+
+        let mut project = Project::new("Test", crate::Variables::new());
+        let mut main = project.main_scenario.clone();
+        linear_scenario(
+            &mut main,
+            vec![Activity::CallScenario {
+                scenario_id: login_id.clone(),
+                parameters: Vec::new(),
+            }],
+        );
+        project.main_scenario = main;
+        project.imports = vec![LibraryImport::new("common.rpalib", "2.0")];
+
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        let codes: Vec<_> = result.errors.iter().map(|e| e.code.clone()).collect();
+        assert!(codes.contains(&ErrorCode::E105));
+        assert!(codes.contains(&ErrorCode::E103));
+
+        let mut library = ScenarioLibrary::new("common", "1.3.0", vec![login]);
+        library.source_path = "common.rpalib".to_string();
+        project.libraries = vec![library];
+
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        let codes: Vec<_> = result.errors.iter().map(|e| e.code.clone()).collect();
+        assert_eq!(codes, vec![ErrorCode::E106]);
+
+        project.imports[0].version = "1.2".to_string();
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        assert!(result.is_valid(), "{:?}", result.errors);
+
+        let mut variables = project.variables.clone();
+        let program = IrBuilder::new(
+            &project.main_scenario,
+            &project,
+            &result.reachable_nodes,
+            &mut variables,
+        )
+        .build()
+        .unwrap();
+        assert!(program.scenario_start_index.contains_key(&login_id));
+    }
+}
@@ -22,6 +22,31 @@ pub enum LogActivity {
     RunPowershell,
     Note,
     TryCatch,
+    ReadTextFile,
+    WriteTextFile,
+    CopyFile,
+    MoveFile,
+    DeleteFile,
+    CreateDirectory,
+    ListFiles,
+    FileExists,
+    WaitForFile,
+    ExcelRead,
+    ExcelWrite,
+    ExcelListSheets,
+    FillWordTemplate,
+    SendEmail,
+    ReadEmails,
+    ParseEmail,
+    SaveAttachments,
+    ReadCsv,
+    WriteCsv,
+    ParseJson,
+    ToJson,
+    ParseXml,
+    HttpRequest,
+    Database,
+    UiAutomation,
     Execution,
     System,
 }
@@ -44,6 +69,31 @@ impl LogActivity {
             LogActivity::RunPowershell => "RUN POWERSHELL",
             LogActivity::Note => "NOTE",
             LogActivity::TryCatch => "TRY CATCH",
+            LogActivity::ReadTextFile => "READ TEXT FILE",
+            LogActivity::WriteTextFile => "WRITE TEXT FILE",
+            LogActivity::CopyFile => "COPY FILE",
+            LogActivity::MoveFile => "MOVE FILE",
+            LogActivity::DeleteFile => "DELETE FILE",
+            LogActivity::CreateDirectory => "CREATE DIRECTORY",
+            LogActivity::ListFiles => "LIST FILES",
+            LogActivity::FileExists => "FILE EXISTS",
+            LogActivity::WaitForFile => "WAIT FOR FILE",
+            LogActivity::ExcelRead => "EXCEL READ",
+            LogActivity::ExcelWrite => "EXCEL WRITE",
+            LogActivity::ExcelListSheets => "EXCEL LIST SHEETS",
+            LogActivity::FillWordTemplate => "FILL WORD TEMPLATE",
+            LogActivity::SendEmail => "SEND EMAIL",
+            LogActivity::ReadEmails => "READ EMAILS",
+            LogActivity::ParseEmail => "PARSE EMAIL",
+            LogActivity::SaveAttachments => "SAVE ATTACHMENTS",
+            LogActivity::ReadCsv => "READ CSV",
+            LogActivity::WriteCsv => "WRITE CSV",
+            LogActivity::ParseJson => "PARSE JSON",
+            LogActivity::ToJson => "TO JSON",
+            LogActivity::ParseXml => "PARSE XML",
+            LogActivity::HttpRequest => "HTTP REQUEST",
+            LogActivity::Database => "DATABASE",
+            LogActivity::UiAutomation => "UI AUTOMATION",
             LogActivity::Execution => "EXECUTION",
             LogActivity::System => "SYSTEM",
         }
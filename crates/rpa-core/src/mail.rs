@@ -0,0 +1,291 @@
+use arc_script::{Table, Value};
+use office::EmailMessage;
+
+pub use office::MailSecurity;
+
+/// Columns of the table produced by ReadEmails, one row per message.
+pub const MESSAGE_COLUMNS: [&str; 8] = [
+    "From",
+    "To",
+    "Cc",
+    "Subject",
+    "Date",
+    "Body",
+    "MessageId",
+    "Attachments",
+];
+
+/// One row per message; attachments are listed one per line, like ListFiles output.
+pub fn messages_to_table(messages: &[EmailMessage]) -> Table {
+    let rows = messages
+        .iter()
+        .map(|message| {
+            [
+                &message.from,
+                &message.to,
+                &message.cc,
+                &message.subject,
+                &message.timestamp,
+                &message.body,
+                &message.message_id,
+                &message.attachments.join("\n"),
+            ]
+            .into_iter()
+            .map(|text| Value::String(text.clone()))
+            .collect()
+        })
+        .collect();
+
+    Table::new(MESSAGE_COLUMNS.map(String::from).to_vec(), rows)
+}
+
+/// File paths to attach: one per line of a string, or the first column of a table.
+pub fn attachment_paths(value: &Value) -> Vec<String> {
+    match value {
+        Value::Undefined => Vec::new(),
+        Value::Table(table) => table
+            .rows
+            .iter()
+            .filter_map(|row| row.first())
+            .map(ToString::to_string)
+            .filter(|path| !path.trim().is_empty())
+            .collect(),
+        other => other
+            .to_string()
+            .lines()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(String::from)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::CoreConstants;
+
+    use crate::node_graph::BranchType;
+
+    use crate::test_support::{linear_project, run_project_with};
+    use crate::validation::ErrorCode;
+    use crate::variables::{VariableScope, Variables};
+    use crate::{Activity, ActivityDefaults, ScenarioValidator};
+
+    #[test]
+    fn messages_become_table_rows() {
+        let message = EmailMessage {
+            from: "bank@example.kz".to_string(),
+            subject: "Statement".to_string(),
+            attachments: vec!["a.pdf".to_string(), "b.pdf".to_string()],
+            ..Default::default()
+        };
+        let table = messages_to_table(&[message]);
+
+        assert_eq!(table.columns.len(), MESSAGE_COLUMNS.len());
+        assert_eq!(
+            table.rows[0][0],
+            Value::String("bank@example.kz".to_string())
+        );
+        assert_eq!(table.rows[0][3], Value::String("Statement".to_string()));
+        assert_eq!(table.rows[0][7], Value::String("a.pdf\nb.pdf".to_string()));
+
+        assert_eq!(
+            attachment_paths(&Value::String(" a.pdf \n\nb.pdf".to_string())),
+            ["a.pdf", "b.pdf"]
+        );
+        assert!(attachment_paths(&Value::Undefined).is_empty());
+    }
+
+    /// Runs `activities` between Start and End, each linked to the next through
+    /// `branch`, and returns the scenario and global variables afterwards. The
+    /// secret `@mail_password` holds `"secret"`.
+    fn run(activities: Vec<Activity>, branch: BranchType) -> (Variables, Variables) {
+        let mut project = linear_project(activities);
+        for connection in &mut project.main_scenario.connections[1..] {
+            connection.branch_type = branch.clone();
+        }
+        project
+            .variables
+            .create_secret("mail_password", VariableScope::Global);
+        let mut globals = project.variables.clone();
+        globals.set(
+            "mail_password",
+            Value::String("secret".to_string()),
+            VariableScope::Global,
+        );
+
+        let (context, _) = run_project_with(&project, globals);
+        let scenario = context.scope_stack.last().unwrap().variables.clone();
+        (scenario, context.global_variables)
+    }
+
+    #[test]
+    fn mail_errors_route_to_error_branch() {
+        // Bind and drop a listener so the port is very likely closed.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let (_, globals) = run(
+            vec![
+                Activity::SendEmail {
+                    server: "\"127.0.0.1\"".to_string(),
+                    port,
+                    security: MailSecurity::None,
+                    username: String::new(),
+                    password: String::new(),
+                    allow_insecure_login: false,
+                    ca_bundle: String::new(),
+                    from: "\"robot@example.com\"".to_string(),
+                    to: "\"ops@example.com\"".to_string(),
+                    cc: String::new(),
+                    subject: "\"Report\"".to_string(),
+                    body: "\"Done\"".to_string(),
+                    html: false,
+                    attachments: String::new(),
+                },
+                Activity::ReadEmails {
+                    server: "\"127.0.0.1\"".to_string(),
+                    port,
+                    security: MailSecurity::None,
+                    username: String::new(),
+                    password: String::new(),
+                    allow_insecure_login: false,
+                    ca_bundle: String::new(),
+                    folder: ActivityDefaults::MAIL_FOLDER.to_string(),
+                    criteria: ActivityDefaults::MAIL_CRITERIA.to_string(),
+                    limit: ActivityDefaults::MAIL_LIMIT,
+                    mark_read: false,
+                    attachments_dir: String::new(),
+                    output_var: "emails".to_string(),
+                },
+            ],
+            BranchType::ErrorBranch,
+        );
+        let last_error = globals.get(CoreConstants::ERROR_VARIABLE_NAME).cloned();
+
+        assert!(last_error.is_some_and(|e| e.to_string().contains("Failed to connect")));
+    }
+
+    #[test]
+    fn plain_text_login_needs_opt_in() {
+        let send = |allow_insecure_login: bool| {
+            let (_, globals) = run(
+                vec![Activity::SendEmail {
+                    server: "\"127.0.0.1\"".to_string(),
+                    port: 1,
+                    security: MailSecurity::None,
+                    username: "\"robot\"".to_string(),
+                    password: "@mail_password".to_string(),
+                    allow_insecure_login,
+                    ca_bundle: String::new(),
+                    from: "\"robot@example.com\"".to_string(),
+                    to: "\"ops@example.com\"".to_string(),
+                    cc: String::new(),
+                    subject: "\"Report\"".to_string(),
+                    body: "\"Done\"".to_string(),
+                    html: false,
+                    attachments: String::new(),
+                }],
+                BranchType::ErrorBranch,
+            );
+            globals
+                .get(CoreConstants::ERROR_VARIABLE_NAME)
+                .unwrap()
+                .to_string()
+        };
+
+        assert!(send(false).contains("unencrypted connection"));
+        assert!(send(true).contains("Failed to connect"));
+    }
+
+    #[test]
+    fn mail_passwords_must_be_secret() {
+        let codes = |password: &str| {
+            let mut project = linear_project(vec![Activity::ReadEmails {
+                server: "\"imap.example.com\"".to_string(),
+                port: ActivityDefaults::IMAP_PORT,
+                security: MailSecurity::Tls,
+                username: "\"robot\"".to_string(),
+                password: password.to_string(),
+                allow_insecure_login: false,
+                ca_bundle: String::new(),
+                folder: "\"INBOX\"".to_string(),
+                criteria: "\"UNSEEN\"".to_string(),
+                limit: 10,
+                mark_read: false,
+                attachments_dir: String::new(),
+                output_var: "emails".to_string(),
+            }]);
+            project
+                .variables
+                .create_secret("mail_password", VariableScope::Global);
+
+            ScenarioValidator::new(&project.main_scenario, &project)
+                .validate()
+                .errors
+                .into_iter()
+                .map(|issue| issue.code)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(codes("\"hunter2\""), [ErrorCode::E113]);
+        assert!(codes("@mail_password").is_empty());
+    }
+
+    #[test]
+    fn eml_files_are_parsed_and_attachments_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let eml = dir.path().join("statement.eml");
+        let message = EmailMessage {
+            from: "bank@example.kz".to_string(),
+            to: "ops@example.kz".to_string(),
+            subject: "Выписка".to_string(),
+            body: "Во вложении.".to_string(),
+            ..Default::default()
+        };
+        let attachment = office::Attachment {
+            filename: "statement.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            content_id: None,
+            data: b"%PDF-1.4".to_vec(),
+        };
+        office::Mime::save_draft(&eml, &message, &[attachment]).unwrap();
+        let path = format!("{:?}", eml.display().to_string());
+        let out = dir.path().join("out");
+
+        let (variables, _) = run(
+            vec![
+                Activity::ParseEmail {
+                    path: path.clone(),
+                    output_var: "email".to_string(),
+                },
+                Activity::SaveAttachments {
+                    path,
+                    directory: format!("{:?}", out.display().to_string()),
+                    include_inline: false,
+                    output_var: "saved".to_string(),
+                },
+            ],
+            BranchType::Default,
+        );
+
+        let Some(Value::Table(table)) = variables.get("email") else {
+            panic!("email is not a table");
+        };
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(table.rows[0][3], Value::String("Выписка".to_string()));
+        assert_eq!(table.rows[0][5], Value::String("Во вложении.".to_string()));
+        assert_eq!(table.rows[0][7], Value::String("statement.pdf".to_string()));
+
+        let saved = out.join("statement.pdf");
+        assert_eq!(
+            variables.get("saved"),
+            Some(&Value::String(saved.display().to_string()))
+        );
+        assert_eq!(std::fs::read(saved).unwrap(), b"%PDF-1.4");
+    }
+}
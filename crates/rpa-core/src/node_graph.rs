@@ -1,5 +1,12 @@
+use crate::csv::{CsvDelimiter, CsvQuote};
+use crate::database::DatabaseConnection;
+use crate::desktop::ClickKind;
+use crate::files::TextEncoding;
+use crate::http::{HttpBodyKind, HttpMethod};
+use crate::library::{LibraryImport, ScenarioLibrary};
 use crate::log::LogLevel;
 use crate::log::LogStorage;
+use crate::mail::MailSecurity;
 use crate::variables::{VariableScope, Variables};
 use arc_script::VariableType;
 use serde::{Deserialize, Serialize};
@@ -13,13 +20,30 @@ pub struct Project {
     #[serde(skip)]
     pub execution_log: LogStorage,
     pub variables: Variables,
+    #[serde(default)]
+    pub imports: Vec<LibraryImport>,
+    #[serde(skip)]
+    pub libraries: Vec<ScenarioLibrary>,
+    #[serde(default)]
+    pub databases: Vec<DatabaseConnection>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectFile {
+    #[serde(default)]
+    pub format_version: u32,
     pub project: Project,
 }
 
+impl ProjectFile {
+    pub fn new(project: Project) -> Self {
+        Self {
+            format_version: crate::project_format::CURRENT_FORMAT_VERSION,
+            project,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Scenario {
     pub id: NanoId,
@@ -40,8 +64,31 @@ impl Project {
             scenarios: Vec::new(),
             execution_log: LogStorage::new(),
             variables,
+            imports: Vec::new(),
+            libraries: Vec::new(),
+            databases: Vec::new(),
         }
     }
+
+    /// Looks a scenario up in the project itself and then in the imported libraries.
+    pub fn find_scenario(&self, id: &NanoId) -> Option<&Scenario> {
+        self.all_scenarios().find(|s| s.id == *id)
+    }
+
+    pub fn all_scenarios(&self) -> impl Iterator<Item = &Scenario> {
+        std::iter::once(&self.main_scenario)
+            .chain(self.scenarios.iter())
+            .chain(self.imported_scenarios())
+    }
+
+    pub fn imported_scenarios(&self) -> impl Iterator<Item = &Scenario> {
+        self.libraries.iter().flat_map(|lib| lib.scenarios.iter())
+    }
+
+    /// Scenarios that a `CallScenario` node may target: local ones first, then imported ones.
+    pub fn callable_scenarios(&self) -> impl Iterator<Item = &Scenario> {
+        self.scenarios.iter().chain(self.imported_scenarios())
+    }
 }
 
 impl Scenario {
@@ -185,16 +232,377 @@ pub enum Activity {
         height: f32,
     },
     TryCatch,
+    ReadTextFile {
+        path: String,
+        #[serde(default)]
+        encoding: TextEncoding,
+        output_var: String,
+    },
+    WriteTextFile {
+        path: String,
+        content: String,
+        #[serde(default)]
+        encoding: TextEncoding,
+        #[serde(default)]
+        append: bool,
+    },
+    CopyFile {
+        source: String,
+        destination: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    MoveFile {
+        source: String,
+        destination: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    DeleteFile {
+        path: String,
+    },
+    CreateDirectory {
+        path: String,
+    },
+    ListFiles {
+        directory: String,
+        pattern: String,
+        #[serde(default)]
+        recursive: bool,
+        output_var: String,
+    },
+    FileExists {
+        path: String,
+        output_var: String,
+    },
+    WaitForFile {
+        path: String,
+        timeout_ms: u64,
+    },
+    ExcelReadRange {
+        path: String,
+        sheet: String,
+        range: String,
+        #[serde(default)]
+        has_headers: bool,
+        output_var: String,
+    },
+    ExcelReadCell {
+        path: String,
+        sheet: String,
+        cell: String,
+        output_var: String,
+    },
+    ExcelWriteCell {
+        path: String,
+        sheet: String,
+        cell: String,
+        value: String,
+    },
+    ExcelWriteRange {
+        path: String,
+        sheet: String,
+        cell: String,
+        value: String,
+        #[serde(default)]
+        write_headers: bool,
+    },
+    ExcelAppendRows {
+        path: String,
+        sheet: String,
+        value: String,
+    },
+    ExcelListSheets {
+        path: String,
+        output_var: String,
+    },
+    FillWordTemplate {
+        template_path: String,
+        output_path: String,
+        #[serde(default)]
+        values: String,
+    },
+    SendEmail {
+        server: String,
+        port: u16,
+        #[serde(default)]
+        security: MailSecurity,
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+        #[serde(default)]
+        allow_insecure_login: bool,
+        /// Path expression of a PEM file with extra trusted CA certificates.
+        #[serde(default)]
+        ca_bundle: String,
+        from: String,
+        to: String,
+        #[serde(default)]
+        cc: String,
+        subject: String,
+        body: String,
+        #[serde(default)]
+        html: bool,
+        #[serde(default)]
+        attachments: String,
+    },
+    ReadEmails {
+        server: String,
+        port: u16,
+        #[serde(default)]
+        security: MailSecurity,
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+        #[serde(default)]
+        allow_insecure_login: bool,
+        /// Path expression of a PEM file with extra trusted CA certificates.
+        #[serde(default)]
+        ca_bundle: String,
+        folder: String,
+        criteria: String,
+        limit: u64,
+        #[serde(default)]
+        mark_read: bool,
+        #[serde(default)]
+        attachments_dir: String,
+        output_var: String,
+    },
+    ParseEmail {
+        path: String,
+        output_var: String,
+    },
+    SaveAttachments {
+        path: String,
+        directory: String,
+        #[serde(default)]
+        include_inline: bool,
+        output_var: String,
+    },
+    ReadCsv {
+        path: String,
+        #[serde(default)]
+        delimiter: CsvDelimiter,
+        #[serde(default)]
+        quote: CsvQuote,
+        #[serde(default)]
+        encoding: TextEncoding,
+        has_headers: bool,
+        #[serde(default)]
+        skip_rows: u64,
+        #[serde(default)]
+        max_rows: u64,
+        output_var: String,
+        #[serde(default)]
+        row_count_var: String,
+    },
+    WriteCsv {
+        path: String,
+        value: String,
+        #[serde(default)]
+        delimiter: CsvDelimiter,
+        #[serde(default)]
+        quote: CsvQuote,
+        #[serde(default)]
+        encoding: TextEncoding,
+        write_headers: bool,
+    },
+    AppendCsvRow {
+        path: String,
+        values: String,
+        #[serde(default)]
+        delimiter: CsvDelimiter,
+        #[serde(default)]
+        quote: CsvQuote,
+        #[serde(default)]
+        encoding: TextEncoding,
+    },
+    ParseJson {
+        text: String,
+        output_var: String,
+    },
+    ToJson {
+        value: String,
+        #[serde(default)]
+        pretty: bool,
+        output_var: String,
+    },
+    ParseXml {
+        text: String,
+        output_var: String,
+    },
+    HttpRequest {
+        #[serde(default)]
+        method: HttpMethod,
+        url: String,
+        #[serde(default)]
+        query: String,
+        #[serde(default)]
+        headers: String,
+        #[serde(default)]
+        body_kind: HttpBodyKind,
+        #[serde(default)]
+        body: String,
+        timeout_ms: u64,
+        #[serde(default)]
+        retries: u32,
+        status_var: String,
+        #[serde(default)]
+        headers_var: String,
+        output_var: String,
+    },
+    ExecuteQuery {
+        connection: String,
+        sql: String,
+        #[serde(default)]
+        parameters: String,
+        output_var: String,
+    },
+    ExecuteNonQuery {
+        connection: String,
+        sql: String,
+        #[serde(default)]
+        parameters: String,
+        output_var: String,
+    },
+    BeginTransaction {
+        connection: String,
+    },
+    CommitTransaction {
+        connection: String,
+    },
+    RollbackTransaction {
+        connection: String,
+    },
+    Click {
+        selector: String,
+        #[serde(default)]
+        click: ClickKind,
+        timeout_ms: u64,
+    },
+    TypeInto {
+        selector: String,
+        text: String,
+        #[serde(default)]
+        simulate_typing: bool,
+        timeout_ms: u64,
+    },
+    GetText {
+        selector: String,
+        timeout_ms: u64,
+        output_var: String,
+    },
+    WaitForElement {
+        selector: String,
+        timeout_ms: u64,
+    },
+    SendKeys {
+        #[serde(default)]
+        selector: String,
+        keys: String,
+        timeout_ms: u64,
+    },
+    LaunchApplication {
+        path: String,
+        #[serde(default)]
+        arguments: String,
+        #[serde(default)]
+        output_var: String,
+    },
+    CloseWindow {
+        selector: String,
+        timeout_ms: u64,
+    },
+    ClickImage {
+        image_path: String,
+        confidence: f32,
+        #[serde(default)]
+        click: ClickKind,
+        timeout_ms: u64,
+    },
+    WaitForImage {
+        image_path: String,
+        confidence: f32,
+        timeout_ms: u64,
+    },
+    TakeScreenshot {
+        #[serde(default)]
+        selector: String,
+        #[serde(default)]
+        path: String,
+        #[serde(default)]
+        output_var: String,
+        timeout_ms: u64,
+    },
 }
 
 impl Activity {
     pub fn can_have_error_output(&self) -> bool {
         matches!(
             self,
-            Activity::CallScenario { .. } | Activity::RunPowershell { .. }
+            Activity::CallScenario { .. }
+                | Activity::RunPowershell { .. }
+                | Activity::ReadTextFile { .. }
+                | Activity::WriteTextFile { .. }
+                | Activity::CopyFile { .. }
+                | Activity::MoveFile { .. }
+                | Activity::DeleteFile { .. }
+                | Activity::CreateDirectory { .. }
+                | Activity::ListFiles { .. }
+                | Activity::FileExists { .. }
+                | Activity::WaitForFile { .. }
+                | Activity::ExcelReadRange { .. }
+                | Activity::ExcelReadCell { .. }
+                | Activity::ExcelWriteCell { .. }
+                | Activity::ExcelWriteRange { .. }
+                | Activity::ExcelAppendRows { .. }
+                | Activity::ExcelListSheets { .. }
+                | Activity::FillWordTemplate { .. }
+                | Activity::SendEmail { .. }
+                | Activity::ReadEmails { .. }
+                | Activity::ParseEmail { .. }
+                | Activity::SaveAttachments { .. }
+                | Activity::ReadCsv { .. }
+                | Activity::WriteCsv { .. }
+                | Activity::AppendCsvRow { .. }
+                | Activity::ParseJson { .. }
+                | Activity::ToJson { .. }
+                | Activity::ParseXml { .. }
+                | Activity::HttpRequest { .. }
+                | Activity::ExecuteQuery { .. }
+                | Activity::ExecuteNonQuery { .. }
+                | Activity::BeginTransaction { .. }
+                | Activity::CommitTransaction { .. }
+                | Activity::RollbackTransaction { .. }
+                | Activity::Click { .. }
+                | Activity::TypeInto { .. }
+                | Activity::GetText { .. }
+                | Activity::WaitForElement { .. }
+                | Activity::SendKeys { .. }
+                | Activity::LaunchApplication { .. }
+                | Activity::CloseWindow { .. }
+                | Activity::ClickImage { .. }
+                | Activity::WaitForImage { .. }
+                | Activity::TakeScreenshot { .. }
         )
     }
 
+    /// The selector of the UI activities, which target a window or control.
+    pub fn selector_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Activity::Click { selector, .. }
+            | Activity::TypeInto { selector, .. }
+            | Activity::GetText { selector, .. }
+            | Activity::WaitForElement { selector, .. }
+            | Activity::SendKeys { selector, .. }
+            | Activity::CloseWindow { selector, .. }
+            | Activity::TakeScreenshot { selector, .. } => Some(selector),
+            _ => None,
+        }
+    }
+
     pub fn iter_as_str() -> impl Iterator<Item = &'static str> {
         [
             "Start",
@@ -212,6 +620,48 @@ impl Activity {
             "RunPowershell",
             "Note",
             "TryCatch",
+            "ReadTextFile",
+            "WriteTextFile",
+            "CopyFile",
+            "MoveFile",
+            "DeleteFile",
+            "CreateDirectory",
+            "ListFiles",
+            "FileExists",
+            "WaitForFile",
+            "ExcelReadRange",
+            "ExcelReadCell",
+            "ExcelWriteCell",
+            "ExcelWriteRange",
+            "ExcelAppendRows",
+            "ExcelListSheets",
+            "FillWordTemplate",
+            "SendEmail",
+            "ReadEmails",
+            "ParseEmail",
+            "SaveAttachments",
+            "ReadCsv",
+            "WriteCsv",
+            "AppendCsvRow",
+            "ParseJson",
+            "ToJson",
+            "ParseXml",
+            "HttpRequest",
+            "ExecuteQuery",
+            "ExecuteNonQuery",
+            "BeginTransaction",
+            "CommitTransaction",
+            "RollbackTransaction",
+            "Click",
+            "TypeInto",
+            "GetText",
+            "WaitForElement",
+            "SendKeys",
+            "LaunchApplication",
+            "CloseWindow",
+            "ClickImage",
+            "WaitForImage",
+            "TakeScreenshot",
         ]
         .iter()
         .copied()
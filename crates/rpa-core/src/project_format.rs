@@ -0,0 +1,422 @@
+use crate::database::DatabaseConnection;
+use crate::library::{LibraryImport, resolve_imports};
+use crate::node_graph::{Project, ProjectFile};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+use std::path::Path;
+
+pub const PROJECT_EXTENSION: &str = "rpa";
+pub const PROJECT_MANIFEST: &str = "project.toml";
+pub const SCENARIOS_DIR: &str = "scenarios";
+/// Project variables sit next to the manifest as JSON: TOML has no null for
+/// undefined values and cannot hold every structured value.
+pub const VARIABLES_FILE: &str = "variables.json";
+const MAIN_SCENARIO_FILE: &str = "main.json";
+
+/// Version written by this build. Bump it together with a new step in [`MIGRATIONS`].
+pub const CURRENT_FORMAT_VERSION: u32 = 2;
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[n]` upgrades a document from format version `n` to `n + 1`.
+const MIGRATIONS: [Migration; CURRENT_FORMAT_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2];
+
+pub struct LoadedProject {
+    pub project: Project,
+    /// Version the file was stored in, before migrations were applied.
+    pub source_version: u32,
+    /// Non-fatal problems, e.g. imported libraries that could not be loaded.
+    pub warnings: Vec<String>,
+}
+
+/// Version 0 files are a bare `Project`, version 1 wraps it in `{"project": ...}`,
+/// later versions carry an explicit `format_version`.
+pub fn detect_version(document: &Value) -> Result<u32, String> {
+    let Some(object) = document.as_object() else {
+        return Err("Project file must contain a JSON object".to_string());
+    };
+
+    if !object.contains_key("project") {
+        return Ok(0);
+    }
+
+    match object.get("format_version") {
+        None => Ok(1),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid format_version: {}", version)),
+    }
+}
+
+/// Upgrades `document` step by step to [`CURRENT_FORMAT_VERSION`].
+pub fn migrate(mut document: Value) -> Result<(Value, u32), String> {
+    let source_version = detect_version(&document)?;
+
+    if source_version > CURRENT_FORMAT_VERSION {
+        return Err(format!(
+            "Project format version {} is newer than supported version {}",
+            source_version, CURRENT_FORMAT_VERSION
+        ));
+    }
+
+    for migration in &MIGRATIONS[source_version as usize..] {
+        document = migration(document)?;
+    }
+
+    Ok((document, source_version))
+}
+
+pub fn parse_project(contents: &str) -> Result<(Project, u32), String> {
+    let document: Value =
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse project: {}", e))?;
+
+    project_from_document(document)
+}
+
+fn project_from_document(document: Value) -> Result<(Project, u32), String> {
+    let (document, source_version) = migrate(document)?;
+
+    let project_file: ProjectFile =
+        serde_json::from_value(document).map_err(|e| format!("Failed to parse project: {}", e))?;
+
+    Ok((project_file.project, source_version))
+}
+
+/// Pretty-printed JSON with a stable key order, so saved projects diff cleanly.
+pub fn project_to_json(project: &Project) -> Result<String, String> {
+    let project_file = ProjectFile::new(project.clone());
+
+    to_pretty_json(&project_file).map_err(|e| format!("Failed to serialize project: {}", e))
+}
+
+fn to_pretty_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    let mut json = serde_json::to_string_pretty(value)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Returns the project directory when `path` points at a directory layout project,
+/// either the directory itself or its `project.toml`.
+pub fn project_dir(path: &Path) -> Option<&Path> {
+    if path.is_dir() {
+        Some(path)
+    } else if path
+        .file_name()
+        .is_some_and(|name| name == PROJECT_MANIFEST)
+    {
+        path.parent()
+    } else {
+        None
+    }
+}
+
+/// Reads, migrates and parses a project file or directory, then resolves its library imports.
+pub fn load_project(path: &Path) -> Result<LoadedProject, String> {
+    let (mut project, source_version, base_dir) = match project_dir(path) {
+        Some(dir) => {
+            let document = read_project_dir(dir)?;
+            let (project, source_version) = project_from_document(document)?;
+            (project, source_version, dir)
+        }
+        None => {
+            let contents =
+                std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+            let (project, source_version) = parse_project(&contents)?;
+            (
+                project,
+                source_version,
+                path.parent().unwrap_or_else(|| Path::new(".")),
+            )
+        }
+    };
+
+    let warnings = resolve_imports(&mut project, base_dir);
+
+    Ok(LoadedProject {
+        project,
+        source_version,
+        warnings,
+    })
+}
+
+/// Saves as a single JSON file, or as a directory layout if `path` names one.
+pub fn save_project(project: &Project, path: &Path) -> Result<(), String> {
+    if let Some(dir) = project_dir(path) {
+        return save_project_dir(project, dir);
+    }
+
+    let json = project_to_json(project)?;
+
+    std::fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+#[derive(Serialize)]
+struct ProjectManifest<'a> {
+    format_version: u32,
+    name: &'a str,
+    main_scenario: String,
+    scenarios: Vec<String>,
+    imports: &'a [LibraryImport],
+    databases: &'a [DatabaseConnection],
+}
+
+/// Writes `project.toml`, `variables.json` and one JSON file per scenario under
+/// `scenarios/`.
+///
+/// Scenario files are named after the scenarios; files the previous manifest listed
+/// for renamed or deleted scenarios are removed. Refuses to write into a non-empty
+/// folder that has no manifest, so it never clobbers files it did not create.
+pub fn save_project_dir(project: &Project, dir: &Path) -> Result<(), String> {
+    let previous_files = previous_scenario_files(dir)?;
+    let scenarios_dir = dir.join(SCENARIOS_DIR);
+    std::fs::create_dir_all(&scenarios_dir)
+        .map_err(|e| format!("Failed to create {}: {}", scenarios_dir.display(), e))?;
+
+    let mut used_names = HashSet::from([MAIN_SCENARIO_FILE.to_string()]);
+    let scenario_files: Vec<String> = project
+        .scenarios
+        .iter()
+        .map(|scenario| unique_file_name(&scenario.name, &mut used_names))
+        .collect();
+
+    let scenarios = std::iter::once((&project.main_scenario, MAIN_SCENARIO_FILE)).chain(
+        project
+            .scenarios
+            .iter()
+            .zip(scenario_files.iter().map(String::as_str)),
+    );
+    for (scenario, file_name) in scenarios {
+        let path = scenarios_dir.join(file_name);
+        let json = to_pretty_json(scenario)
+            .map_err(|e| format!("Failed to serialize scenario '{}': {}", scenario.name, e))?;
+        std::fs::write(&path, json)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    remove_stale_scenario_files(&scenarios_dir, &previous_files, &used_names)?;
+
+    let variables_path = dir.join(VARIABLES_FILE);
+    let json = to_pretty_json(&project.variables)
+        .map_err(|e| format!("Failed to serialize variables: {}", e))?;
+    std::fs::write(&variables_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", variables_path.display(), e))?;
+
+    let manifest = ProjectManifest {
+        format_version: CURRENT_FORMAT_VERSION,
+        name: &project.name,
+        main_scenario: MAIN_SCENARIO_FILE.to_string(),
+        scenarios: scenario_files,
+        imports: &project.imports,
+        databases: &project.databases,
+    };
+    let manifest = toml::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize {}: {}", PROJECT_MANIFEST, e))?;
+
+    let manifest_path = dir.join(PROJECT_MANIFEST);
+    std::fs::write(&manifest_path, manifest)
+        .map_err(|e| format!("Failed to write {}: {}", manifest_path.display(), e))
+}
+
+/// Assembles a directory layout back into the single-document form used by [`migrate`].
+fn read_project_dir(dir: &Path) -> Result<Value, String> {
+    let manifest_path = dir.join(PROJECT_MANIFEST);
+    let contents = std::fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?;
+    let manifest: toml::Table = toml::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {}", manifest_path.display(), e))?;
+
+    let Value::Object(mut project) = serde_json::to_value(manifest)
+        .map_err(|e| format!("Failed to parse {}: {}", manifest_path.display(), e))?
+    else {
+        return Err(format!("{} must be a table", PROJECT_MANIFEST));
+    };
+
+    let format_version = project
+        .remove("format_version")
+        .ok_or_else(|| format!("{} has no format_version", PROJECT_MANIFEST))?;
+
+    let scenarios_dir = dir.join(SCENARIOS_DIR);
+    let read_scenario = |file: &Value| -> Result<Value, String> {
+        let file = file.as_str().ok_or_else(|| {
+            format!(
+                "Scenario file names in {} must be strings",
+                PROJECT_MANIFEST
+            )
+        })?;
+        let path = scenarios_dir.join(file);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    };
+
+    let main_scenario = project
+        .get("main_scenario")
+        .ok_or_else(|| format!("{} has no main_scenario", PROJECT_MANIFEST))
+        .and_then(read_scenario)?;
+    let scenarios = match project.get("scenarios") {
+        Some(Value::Array(files)) => files.iter().map(read_scenario).collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(format!(
+                "'scenarios' in {} must be a list",
+                PROJECT_MANIFEST
+            ));
+        }
+        None => Vec::new(),
+    };
+
+    project.insert("main_scenario".to_string(), main_scenario);
+    project.insert("scenarios".to_string(), Value::Array(scenarios));
+
+    // Projects saved before the sidecar existed keep their variables in the manifest.
+    let variables_path = dir.join(VARIABLES_FILE);
+    if variables_path.exists() {
+        let contents = std::fs::read_to_string(&variables_path)
+            .map_err(|e| format!("Failed to read {}: {}", variables_path.display(), e))?;
+        let variables = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", variables_path.display(), e))?;
+        project.insert("variables".to_string(), variables);
+    }
+    project
+        .entry("variables")
+        .or_insert_with(|| json!({ "values": {} }));
+
+    Ok(json!({ "format_version": format_version, "project": project }))
+}
+
+fn unique_file_name(scenario_name: &str, used_names: &mut HashSet<String>) -> String {
+    let mut stem: String = scenario_name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    stem = stem
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() {
+        stem = "scenario".to_string();
+    }
+
+    let mut file_name = format!("{}.json", stem);
+    let mut suffix = 2;
+    while !used_names.insert(file_name.clone()) {
+        file_name = format!("{}-{}.json", stem, suffix);
+        suffix += 1;
+    }
+
+    file_name
+}
+
+/// Scenario files listed in the manifest already in `dir`, or none for an empty folder.
+fn previous_scenario_files(dir: &Path) -> Result<Vec<String>, String> {
+    let manifest_path = dir.join(PROJECT_MANIFEST);
+    if !manifest_path.exists() {
+        let is_empty = match std::fs::read_dir(dir) {
+            Ok(mut entries) => entries.next().is_none(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+            Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+        };
+        if is_empty {
+            return Ok(Vec::new());
+        }
+        return Err(format!(
+            "{} is not empty and has no {}; choose an empty folder or an existing project",
+            dir.display(),
+            PROJECT_MANIFEST
+        ));
+    }
+
+    let contents = std::fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?;
+    let manifest: toml::Table = toml::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {}", manifest_path.display(), e))?;
+
+    let main_scenario = manifest.get("main_scenario").into_iter();
+    let scenarios = manifest
+        .get("scenarios")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten();
+    Ok(main_scenario
+        .chain(scenarios)
+        .filter_map(toml::Value::as_str)
+        .map(str::to_string)
+        .collect())
+}
+
+/// Removes the files in `previous` that are not in `keep`. Names that are not plain
+/// file names are skipped, so a hand-edited manifest cannot point outside `scenarios/`.
+fn remove_stale_scenario_files(
+    scenarios_dir: &Path,
+    previous: &[String],
+    keep: &HashSet<String>,
+) -> Result<(), String> {
+    for name in previous {
+        let is_plain_name = Path::new(name).file_name() == Some(std::ffi::OsStr::new(name));
+        if !is_plain_name || keep.contains(name) {
+            continue;
+        }
+
+        let path = scenarios_dir.join(name);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+        }
+    }
+
+    Ok(())
+}
+
+fn project_object(document: &mut Value) -> Result<&mut Map<String, Value>, String> {
+    document
+        .get_mut("project")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| "Project file has no 'project' object".to_string())
+}
+
+/// Bare `Project` documents get the `{"project": ...}` wrapper.
+fn migrate_v0_to_v1(document: Value) -> Result<Value, String> {
+    Ok(json!({ "project": document }))
+}
+
+/// Adds the explicit version, library imports and the scenario fields that used to be implied.
+fn migrate_v1_to_v2(mut document: Value) -> Result<Value, String> {
+    let project = project_object(&mut document)?;
+
+    project
+        .entry("imports")
+        .or_insert_with(|| Value::Array(Vec::new()));
+
+    for (key, value) in project.iter_mut() {
+        match key.as_str() {
+            "main_scenario" => fill_scenario_defaults(value),
+            "scenarios" => value
+                .as_array_mut()
+                .into_iter()
+                .flatten()
+                .for_each(fill_scenario_defaults),
+            _ => {}
+        }
+    }
+
+    document["format_version"] = json!(2);
+    Ok(document)
+}
+
+fn fill_scenario_defaults(scenario: &mut Value) {
+    if let Some(scenario) = scenario.as_object_mut() {
+        scenario
+            .entry("parameters")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        scenario
+            .entry("variables")
+            .or_insert_with(|| json!({ "values": {} }));
+    }
+}
@@ -0,0 +1,304 @@
+use crate::constants::ActivityDefaults;
+use crate::desktop::ClickKind;
+use crate::node_graph::Activity;
+use std::time::Duration;
+use ui_automation::{MouseButton, RecordedAction, RecordedEvent};
+
+/// Two left clicks on one element closer than this are a double click
+const DOUBLE_CLICK_MS: u128 = 500;
+/// A pause this long before a click means the user waited for the target
+const WAIT_PAUSE_MS: u128 = 2000;
+/// Inferred waits allow this many times the pause the user took
+const WAIT_PAUSE_FACTOR: u64 = 3;
+
+/// Turns recorded input into a draft sequence of UI activities:
+///
+/// - clicks become `Click`, two quick ones on the same element `Double`;
+/// - text typed after a click becomes `TypeInto` on the clicked element;
+/// - other keys, and text typed once focus may have moved, become
+///   `SendKeys`;
+/// - a click in another window, or after a long pause, is preceded by a
+///   `WaitForElement` whose timeout grows with the pause.
+///
+/// Clicks on elements that could not be identified are dropped.
+pub fn draft_activities(events: &[RecordedEvent]) -> Vec<Activity> {
+    let mut draft = Draft::default();
+    for event in events {
+        match &event.action {
+            RecordedAction::Click { button, target } => match target {
+                Some(target) => draft.click(*button, &target.selector, &target.window, event.at),
+                None => {
+                    draft.flush();
+                    draft.focus = None;
+                }
+            },
+            RecordedAction::Key { combination, text } => draft.key(combination, *text),
+        }
+        draft.last_event = Some(event.at);
+    }
+    draft.flush();
+    draft.activities
+}
+
+#[derive(Default)]
+struct Draft {
+    activities: Vec<Activity>,
+    /// Selector of the clicked element, while typing still goes into it
+    focus: Option<String>,
+    /// Window and time of the last click
+    last_click: Option<(String, Duration)>,
+    last_event: Option<Duration>,
+    typed: String,
+    keys: Vec<String>,
+}
+
+impl Draft {
+    fn click(&mut self, button: MouseButton, selector: &str, window: &str, at: Duration) {
+        let pending = !self.typed.is_empty() || !self.keys.is_empty();
+        if button == MouseButton::Left
+            && !pending
+            && let Some((_, clicked_at)) = &self.last_click
+            && at.saturating_sub(*clicked_at).as_millis() < DOUBLE_CLICK_MS
+            && let Some(Activity::Click {
+                selector: previous,
+                click: click @ ClickKind::Single,
+                ..
+            }) = self.activities.last_mut()
+            && previous == selector
+        {
+            *click = ClickKind::Double;
+            return;
+        }
+
+        self.flush();
+
+        let pause = self
+            .last_event
+            .map_or(Duration::ZERO, |last| at.saturating_sub(last));
+        let other_window = self
+            .last_click
+            .as_ref()
+            .is_some_and(|(last_window, _)| last_window != window);
+        if other_window || pause.as_millis() >= WAIT_PAUSE_MS {
+            let waited = u64::try_from(pause.as_millis()).unwrap_or(u64::MAX);
+            let timeout_ms = waited
+                .saturating_mul(WAIT_PAUSE_FACTOR)
+                .div_ceil(1000)
+                .saturating_mul(1000)
+                .max(ActivityDefaults::UI_TIMEOUT_MS);
+            self.activities.push(Activity::WaitForElement {
+                selector: selector.to_string(),
+                timeout_ms,
+            });
+        }
+
+        self.activities.push(Activity::Click {
+            selector: selector.to_string(),
+            click: match button {
+                MouseButton::Left => ClickKind::Single,
+                MouseButton::Right => ClickKind::Right,
+            },
+            timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+        });
+        self.focus = Some(selector.to_string());
+        self.last_click = Some((window.to_string(), at));
+    }
+
+    fn key(&mut self, combination: &str, text: Option<char>) {
+        if self.focus.is_some() && self.keys.is_empty() {
+            if let Some(ch) = text {
+                self.typed.push(ch);
+                return;
+            }
+            if combination == "backspace" && self.typed.pop().is_some() {
+                return;
+            }
+        }
+
+        if !self.typed.is_empty() {
+            self.flush();
+        }
+        self.keys.push(combination.to_string());
+    }
+
+    /// Emits the text and keys collected since the last click
+    fn flush(&mut self) {
+        let selector = self.focus.clone().unwrap_or_default();
+        if !self.typed.is_empty() {
+            self.activities.push(Activity::TypeInto {
+                selector: selector.clone(),
+                text: std::mem::take(&mut self.typed),
+                simulate_typing: true,
+                timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+            });
+        }
+        if !self.keys.is_empty() {
+            self.activities.push(Activity::SendKeys {
+                selector,
+                keys: std::mem::take(&mut self.keys).join(" "),
+                timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ui_automation::ClickTarget;
+
+    const BILLING: &str = "Window>process~=billing;class~=Billing";
+    const AMOUNT: &str = "Window>process~=billing;class~=Billing>>Control>role~=text";
+    const SAVE: &str = "Window>process~=billing;class~=Billing>>Control>role~=push button";
+    const DIALOG: &str = "Window>process~=billing;class~=Dialog";
+    const CONFIRM: &str = "Window>process~=billing;class~=Dialog>>Control>text~=Yes";
+
+    fn click(ms: u64, selector: &str, window: &str) -> RecordedEvent {
+        RecordedEvent {
+            action: RecordedAction::Click {
+                button: MouseButton::Left,
+                target: Some(ClickTarget {
+                    selector: selector.to_string(),
+                    window: window.to_string(),
+                }),
+            },
+            at: Duration::from_millis(ms),
+        }
+    }
+
+    fn key(ms: u64, combination: &str, text: Option<char>) -> RecordedEvent {
+        RecordedEvent {
+            action: RecordedAction::Key {
+                combination: combination.to_string(),
+                text,
+            },
+            at: Duration::from_millis(ms),
+        }
+    }
+
+    fn typed(ms: u64, text: &str) -> Vec<RecordedEvent> {
+        text.chars()
+            .map(|ch| {
+                let name = ch.to_lowercase().to_string();
+                let combination = if ch.is_uppercase() {
+                    format!("shift+{name}")
+                } else {
+                    name
+                };
+                key(ms, &combination, Some(ch))
+            })
+            .collect()
+    }
+
+    fn click_activity(selector: &str, click: ClickKind) -> Activity {
+        Activity::Click {
+            selector: selector.to_string(),
+            click,
+            timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+        }
+    }
+
+    #[test]
+    fn typing_after_a_click_goes_into_the_clicked_element() {
+        let mut events = vec![click(0, AMOUNT, BILLING)];
+        events.extend(typed(300, "42,5"));
+        events.push(key(400, "backspace", None));
+        events.extend(typed(500, "0"));
+        events.push(key(600, "tab", None));
+        events.extend(typed(700, "Ok"));
+        events.push(key(800, "ctrl+s", None));
+
+        assert_eq!(
+            draft_activities(&events),
+            [
+                click_activity(AMOUNT, ClickKind::Single),
+                Activity::TypeInto {
+                    selector: AMOUNT.to_string(),
+                    text: "42,0".to_string(),
+                    simulate_typing: true,
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+                Activity::SendKeys {
+                    selector: AMOUNT.to_string(),
+                    keys: "tab shift+o k ctrl+s".to_string(),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn quick_clicks_on_one_element_are_a_double_click() {
+        let events = [
+            click(0, SAVE, BILLING),
+            click(200, SAVE, BILLING),
+            click(1000, SAVE, BILLING),
+            click(1300, AMOUNT, BILLING),
+        ];
+
+        assert_eq!(
+            draft_activities(&events),
+            [
+                click_activity(SAVE, ClickKind::Double),
+                click_activity(SAVE, ClickKind::Single),
+                click_activity(AMOUNT, ClickKind::Single),
+            ]
+        );
+    }
+
+    #[test]
+    fn waits_are_inferred_from_window_changes_and_pauses() {
+        let events = [
+            click(0, SAVE, BILLING),
+            click(800, CONFIRM, DIALOG),
+            click(9200, AMOUNT, BILLING),
+        ];
+
+        assert_eq!(
+            draft_activities(&events),
+            [
+                click_activity(SAVE, ClickKind::Single),
+                Activity::WaitForElement {
+                    selector: CONFIRM.to_string(),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+                click_activity(CONFIRM, ClickKind::Single),
+                Activity::WaitForElement {
+                    selector: AMOUNT.to_string(),
+                    timeout_ms: 26_000,
+                },
+                click_activity(AMOUNT, ClickKind::Single),
+            ]
+        );
+    }
+
+    #[test]
+    fn unidentified_clicks_and_keys_without_focus_become_send_keys() {
+        let mut events = typed(0, "hi");
+        events.push(RecordedEvent {
+            action: RecordedAction::Click {
+                button: MouseButton::Right,
+                target: None,
+            },
+            at: Duration::from_millis(100),
+        });
+        events.push(key(200, "enter", None));
+
+        assert_eq!(
+            draft_activities(&events),
+            [
+                Activity::SendKeys {
+                    selector: String::new(),
+                    keys: "h i".to_string(),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+                Activity::SendKeys {
+                    selector: String::new(),
+                    keys: "enter".to_string(),
+                    timeout_ms: ActivityDefaults::UI_TIMEOUT_MS,
+                },
+            ]
+        );
+        assert!(draft_activities(&[]).is_empty());
+    }
+}
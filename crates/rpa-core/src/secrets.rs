@@ -0,0 +1,412 @@
+//! Secret variables and the encrypted vault that stores them.
+//!
+//! Threat model: the vault keeps secrets out of project files, logs and version
+//! control, and away from other users of the machine. `vault.json` and `vault.key`
+//! are created readable by the owner only (mode 0600 on Unix; on Windows they live in
+//! the per-user `%APPDATA%`). Without `RPA_VAULT_PASSWORD` the key file sits next to
+//! the vault, so anything running as the same user, or a copy of both files, can
+//! decrypt it. Set `RPA_VAULT_PASSWORD` (and do not keep it on disk) when the vault
+//! must also survive a stolen or backed-up config directory.
+
+use crate::node_graph::Project;
+use crate::variables::{VariableScope, Variables};
+use arc_script::Value;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const SECRET_MASK: &str = "***";
+
+/// Overrides the vault location.
+pub const VAULT_PATH_ENV: &str = "RPA_VAULT";
+/// Passphrase for the vault. Without it a random key file next to the vault is used.
+pub const VAULT_PASSWORD_ENV: &str = "RPA_VAULT_PASSWORD";
+/// `RPA_SECRET_<NAME>` supplies a secret from the environment, taking priority over the vault.
+pub const SECRET_ENV_PREFIX: &str = "RPA_SECRET_";
+
+const VAULT_FILE: &str = "vault.json";
+const KEY_FILE: &str = "vault.key";
+const SALT_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Encrypted name/value store for secret variables, kept outside of project files.
+pub struct SecretVault {
+    path: PathBuf,
+    key: Key,
+    salt: [u8; SALT_LEN],
+    entries: BTreeMap<String, String>,
+}
+
+impl SecretVault {
+    /// `RPA_VAULT`, or `vault.json` in the per-user config directory.
+    pub fn default_path() -> PathBuf {
+        if let Ok(path) = std::env::var(VAULT_PATH_ENV) {
+            return PathBuf::from(path);
+        }
+        config_dir().join("qazcode-rpa").join(VAULT_FILE)
+    }
+
+    /// Opens the vault at [`SecretVault::default_path`] with the passphrase from
+    /// `RPA_VAULT_PASSWORD` or the local key file.
+    pub fn open_default() -> Result<Self, String> {
+        let path = Self::default_path();
+        let passphrase = match std::env::var(VAULT_PASSWORD_ENV) {
+            Ok(passphrase) => passphrase,
+            Err(_) => read_or_create_key_file(&path.with_file_name(KEY_FILE))?,
+        };
+        Self::open(&path, &passphrase)
+    }
+
+    /// Opens an existing vault, or an empty one if `path` does not exist yet.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, String> {
+        if !path.exists() {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            return Ok(Self {
+                path: path.to_path_buf(),
+                key: derive_key(passphrase, &salt)?,
+                salt,
+                entries: BTreeMap::new(),
+            });
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read vault {}: {}", path.display(), e))?;
+        let file: VaultFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse vault {}: {}", path.display(), e))?;
+
+        let salt: [u8; SALT_LEN] = decode_hex(&file.salt)?
+            .try_into()
+            .map_err(|_| "Invalid vault salt".to_string())?;
+        let nonce = decode_hex(&file.nonce)?;
+        if nonce.len() != 12 {
+            return Err("Invalid vault nonce".to_string());
+        }
+        let ciphertext = decode_hex(&file.ciphertext)?;
+
+        let key = derive_key(passphrase, &salt)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                "Failed to decrypt vault: wrong passphrase or corrupted file".to_string()
+            })?;
+        let entries = serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Failed to parse vault contents: {}", e))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            key,
+            salt,
+            entries,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(String::as_str)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.entries.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.entries.remove(name).is_some()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Re-encrypts all entries with a fresh nonce and writes the vault file.
+    pub fn save(&self) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(&self.entries)
+            .map_err(|e| format!("Failed to serialize vault: {}", e))?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt vault".to_string())?;
+
+        let file = VaultFile {
+            salt: encode_hex(&self.salt),
+            nonce: encode_hex(&nonce),
+            ciphertext: encode_hex(&ciphertext),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize vault: {}", e))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        write_private(&self.path, json.as_bytes())
+            .map_err(|e| format!("Failed to write vault {}: {}", self.path.display(), e))
+    }
+}
+
+/// Environment variable that overrides the vault for secret `name`.
+pub fn secret_env_var(name: &str) -> String {
+    format!("{}{}", SECRET_ENV_PREFIX, name.to_uppercase())
+}
+
+/// Fills in every secret variable from the environment or `vault`.
+/// Returns the names of secrets that were found in neither.
+pub fn resolve_secrets(variables: &mut Variables, vault: Option<&SecretVault>) -> Vec<String> {
+    let names: Vec<(String, VariableScope)> = variables
+        .secret_names()
+        .map(|name| {
+            let scope = variables
+                .get_scope(name)
+                .cloned()
+                .unwrap_or(VariableScope::Global);
+            (name.to_string(), scope)
+        })
+        .collect();
+
+    let mut missing = Vec::new();
+    for (name, scope) in names {
+        let value = std::env::var(secret_env_var(&name))
+            .ok()
+            .or_else(|| vault.and_then(|v| v.get(&name)).map(str::to_string));
+
+        match value {
+            Some(value) => variables.set(&name, Value::String(value), scope),
+            None => missing.push(name),
+        }
+    }
+    missing
+}
+
+/// Resolves secrets in the project variables and in every scenario of the project.
+pub fn resolve_project_secrets(project: &mut Project, vault: Option<&SecretVault>) -> Vec<String> {
+    let mut missing = resolve_secrets(&mut project.variables, vault);
+    let scenarios = std::iter::once(&mut project.main_scenario).chain(project.scenarios.iter_mut());
+    for scenario in scenarios {
+        missing.extend(resolve_secrets(&mut scenario.variables, vault));
+    }
+    missing.sort();
+    missing.dedup();
+    missing
+}
+
+/// Replaces every occurrence of a secret value in `text` with [`SECRET_MASK`].
+pub fn mask_secrets(text: &str, secrets: &[String]) -> String {
+    let mut masked = text.to_string();
+    for secret in secrets {
+        if !secret.is_empty() && masked.contains(secret.as_str()) {
+            masked = masked.replace(secret.as_str(), SECRET_MASK);
+        }
+    }
+    masked
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive vault key: {}", e))?;
+    Ok(key)
+}
+
+/// The key file alone decrypts the vault, see the threat model above.
+fn read_or_create_key_file(path: &Path) -> Result<String, String> {
+    if path.exists() {
+        restrict_permissions(path)
+            .map_err(|e| format!("Failed to protect vault key {}: {}", path.display(), e))?;
+        return std::fs::read_to_string(path)
+            .map(|key| key.trim().to_string())
+            .map_err(|e| format!("Failed to read vault key {}: {}", path.display(), e));
+    }
+
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let key = encode_hex(&key);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    write_private(path, key.as_bytes())
+        .map_err(|e| format!("Failed to write vault key {}: {}", path.display(), e))?;
+
+    Ok(key)
+}
+
+/// Writes `contents` to a file only the current user can read. The mode is set before
+/// anything is written, also when the file already existed with a wider one.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    restrict_permissions(path)?;
+    file.write_all(contents)
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+/// Files under `%APPDATA%` inherit the per-user ACL of the profile.
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn config_dir() -> PathBuf {
+    let var = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let dir = if cfg!(windows) {
+        var("APPDATA")
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")))
+    };
+    dir.unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("Invalid hex data in vault".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| "Invalid hex data in vault".to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vault_round_trip_requires_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.json");
+
+        let mut vault = SecretVault::open(&path, "correct horse").unwrap();
+        vault.set("api_token", "s3cr3t-value");
+        vault.save().unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("s3cr3t-value"));
+        assert!(!contents.contains("api_token"));
+
+        let reopened = SecretVault::open(&path, "correct horse").unwrap();
+        assert_eq!(reopened.get("api_token"), Some("s3cr3t-value"));
+        assert!(SecretVault::open(&path, "wrong").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn vault_and_key_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let vault_path = dir.path().join(VAULT_FILE);
+        let key_path = dir.path().join(KEY_FILE);
+        std::fs::write(&vault_path, "").unwrap();
+        std::fs::set_permissions(&vault_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let key = read_or_create_key_file(&key_path).unwrap();
+        let mut vault = SecretVault::open(&dir.path().join("new.json"), &key).unwrap();
+        vault.path = vault_path.clone();
+        vault.set("api_token", "s3cr3t-value");
+        vault.save().unwrap();
+
+        for path in [vault_path, key_path] {
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn resolves_secrets_and_never_serializes_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = SecretVault::open(&dir.path().join("vault.json"), "pw").unwrap();
+        vault.set("password", "hunter2");
+
+        let mut variables = Variables::new();
+        variables.create_secret("password", VariableScope::Global);
+        variables.create_secret("rpa_test_unset_secret", VariableScope::Global);
+        variables.set("user", Value::String("bob".into()), VariableScope::Global);
+
+        let missing = resolve_secrets(&mut variables, Some(&vault));
+        assert_eq!(missing, vec!["rpa_test_unset_secret".to_string()]);
+        assert_eq!(
+            variables.get("password"),
+            Some(&Value::String("hunter2".into()))
+        );
+        assert!(variables.is_secret("password"));
+
+        let json = serde_json::to_string(&variables).unwrap();
+        assert!(!json.contains("hunter2"));
+        assert!(json.contains("bob"));
+
+        let reloaded: Variables = serde_json::from_str(&json).unwrap();
+        assert!(reloaded.is_secret("password"));
+        assert_eq!(reloaded.get("password"), Some(&Value::Undefined));
+    }
+
+    #[test]
+    fn masks_secret_values() {
+        let secrets = vec!["hunter2".to_string()];
+        assert_eq!(
+            mask_secrets("login with hunter2 and hunter2", &secrets),
+            "login with *** and ***"
+        );
+        assert_eq!(mask_secrets("nothing here", &secrets), "nothing here");
+    }
+
+    #[test]
+    fn executor_masks_secrets_in_logs() {
+        use crate::Activity;
+        use crate::log::LogLevel;
+        use crate::test_support::{linear_project, run_project_with};
+
+        let project = linear_project(vec![Activity::Log {
+            level: LogLevel::Info,
+            message: "\"token=\" + @password".to_string(),
+        }]);
+
+        let mut variables = Variables::new();
+        variables.create_secret("password", VariableScope::Global);
+        variables.set(
+            "password",
+            Value::String("hunter2".into()),
+            VariableScope::Global,
+        );
+
+        let (_, log) = run_project_with(&project, variables);
+
+        assert!(log.iter().any(|e| e.message == "token=***"));
+        assert!(log.iter().all(|e| !e.message.contains("hunter2")));
+    }
+}
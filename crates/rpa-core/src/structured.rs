@@ -0,0 +1,265 @@
+use arc_script::{Value, xml_element};
+use indexmap::IndexMap;
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// JSON as read from text. Objects are kept in an `IndexMap` so their fields
+/// stay in document order.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(IndexMap<String, Json>),
+}
+
+impl From<Json> for Value {
+    fn from(json: Json) -> Self {
+        match json {
+            Json::Null => Value::Undefined,
+            Json::Bool(b) => Value::Boolean(b),
+            Json::Number(n) => Value::Number(n),
+            Json::String(s) => Value::String(s),
+            Json::Array(items) => Value::List(items.into_iter().map(Value::from).collect()),
+            Json::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, field)| (name, Value::from(field)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Writes a value as JSON: whole numbers without a fraction, `Undefined` as
+/// `null` and a table as an array with one object per row.
+struct JsonValue<'a>(&'a Value);
+
+impl Serialize for JsonValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 9.0e15 => {
+                serializer.serialize_i64(*n as i64)
+            }
+            Value::Number(n) if n.is_finite() => serializer.serialize_f64(*n),
+            Value::Number(_) | Value::Undefined => serializer.serialize_unit(),
+            Value::Boolean(b) => serializer.serialize_bool(*b),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Table(table) => {
+                let mut seq = serializer.serialize_seq(Some(table.rows.len()))?;
+                for row in &table.rows {
+                    let fields = table.columns.iter().cloned().zip(row.iter().cloned());
+                    seq.serialize_element(&JsonValue(&Value::Object(fields.collect())))?;
+                }
+                seq.end()
+            }
+            Value::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&JsonValue(item))?;
+                }
+                seq.end()
+            }
+            Value::Object(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, field) in fields {
+                    map.serialize_entry(name, &JsonValue(field))?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Parses JSON text into structured values: arrays become lists, objects keep
+/// their field order and `null` becomes `Undefined`.
+pub fn parse_json(text: &str) -> Result<Value, String> {
+    serde_json::from_str::<Json>(text)
+        .map(Value::from)
+        .map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// Inverse of [`parse_json`]; a table becomes an array with one object per row.
+pub fn to_json(value: &Value, pretty: bool) -> Result<String, String> {
+    let json = if pretty {
+        serde_json::to_string_pretty(&JsonValue(value))
+    } else {
+        serde_json::to_string(&JsonValue(value))
+    };
+    json.map_err(|e| format!("Failed to write JSON: {}", e))
+}
+
+/// Parses an XML document into its root element, built with [`xml_element`]
+/// so that `xpath` can query it. Names lose their namespace prefix and the
+/// text directly inside an element is trimmed.
+pub fn parse_xml(text: &str) -> Result<Value, String> {
+    let document = roxmltree::Document::parse(text).map_err(|e| format!("Invalid XML: {}", e))?;
+    Ok(element_to_value(document.root_element()))
+}
+
+fn element_to_value(node: roxmltree::Node) -> Value {
+    let attributes = node
+        .attributes()
+        .map(|attr| {
+            (
+                attr.name().to_string(),
+                Value::String(attr.value().to_string()),
+            )
+        })
+        .collect();
+    let text: String = node
+        .children()
+        .filter(|child| child.is_text())
+        .filter_map(|child| child.text())
+        .collect();
+    let children = node
+        .children()
+        .filter(|child| child.is_element())
+        .map(element_to_value)
+        .collect();
+
+    xml_element(
+        node.tag_name().name().to_string(),
+        attributes,
+        text.trim().to_string(),
+        children,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::log::LogLevel;
+
+    use crate::validation::ErrorCode;
+
+    use crate::test_support::{linear_project, run_project};
+    use crate::{Activity, ScenarioValidator};
+    use arc_script::{JsonPath, Table, VariableType, XPath};
+
+    fn set_text(name: &str, value: &str) -> Activity {
+        Activity::SetVariable {
+            name: name.to_string(),
+            value: value.to_string(),
+            var_type: VariableType::String,
+            is_global: false,
+        }
+    }
+
+    #[test]
+    fn json_round_trips_in_field_order() {
+        let text = r#"{"zeta":1,"alpha":[true,null,2.5,"x\"y"],"nested":{"b":{},"a":[]}}"#;
+        let value = parse_json(text).unwrap();
+
+        assert_eq!(to_json(&value, false).unwrap(), text);
+        assert_eq!(value.to_string(), text);
+        assert_eq!(
+            JsonPath::parse("$.alpha[-1]").unwrap().select(&value),
+            Value::String("x\"y".to_string())
+        );
+        assert!(
+            parse_json("{\"a\":")
+                .unwrap_err()
+                .starts_with("Invalid JSON")
+        );
+
+        let table = Value::Table(Table::new(
+            vec!["Name".to_string(), "Qty".to_string()],
+            vec![vec![Value::String("Pen".to_string()), Value::Number(3.0)]],
+        ));
+        assert_eq!(
+            to_json(&table, true).unwrap(),
+            "[\n  {\n    \"Name\": \"Pen\",\n    \"Qty\": 3\n  }\n]"
+        );
+    }
+
+    #[test]
+    fn xml_is_parsed_into_queryable_elements() {
+        let value = parse_xml(
+            r#"<?xml version="1.0"?>
+            <ns:order xmlns:ns="urn:orders" id="7">
+                <item sku="A-1">Pen</item>
+                <item sku="B-2"><![CDATA[Ink & paper]]></item>
+            </ns:order>"#,
+        )
+        .unwrap();
+        let select = |path: &str| XPath::parse(path).unwrap().select(&value);
+
+        assert_eq!(select("/order/@id"), Value::String("7".to_string()));
+        assert_eq!(
+            select("//item[@sku='B-2']/text()"),
+            Value::String("Ink & paper".to_string())
+        );
+        assert_eq!(select("/order/item/@sku").to_string(), r#"["A-1","B-2"]"#);
+        assert!(parse_xml("<order>").unwrap_err().starts_with("Invalid XML"));
+    }
+
+    #[test]
+    fn executor_parses_queries_and_converts() {
+        let project = linear_project(vec![
+            set_text(
+                "payload",
+                r#"{"items":[{"sku":"A-1","qty":2},{"sku":"B-2","qty":5}]}"#,
+            ),
+            set_text(
+                "response",
+                r#"<order><total currency="KZT">700</total></order>"#,
+            ),
+            Activity::ParseJson {
+                text: "@payload".to_string(),
+                output_var: "order".to_string(),
+            },
+            Activity::ParseXml {
+                text: "@response".to_string(),
+                output_var: "doc".to_string(),
+            },
+            Activity::ToJson {
+                value: "json_path(@order, \"$.items[*].sku\")".to_string(),
+                pretty: false,
+                output_var: "skus".to_string(),
+            },
+            Activity::Log {
+                level: LogLevel::Info,
+                message: "\"\" + json_path(@order, \"$.items[1].qty\") * 2 + \" \" \
+                          + xpath(@doc, \"/order/total/@currency\")"
+                    .to_string(),
+            },
+        ]);
+
+        let (context, log) = run_project(&project);
+        let scenario = context.scope_stack.last().unwrap().variables.clone();
+
+        assert_eq!(
+            scenario.get("skus"),
+            Some(&Value::String(r#"["A-1","B-2"]"#.to_string()))
+        );
+        assert!(matches!(scenario.get("doc"), Some(Value::Object(_))));
+        assert!(log.iter().any(|e| e.message == "10 KZT"));
+    }
+
+    #[test]
+    fn validator_reports_invalid_paths() {
+        let project = linear_project(vec![
+            Activity::Evaluate {
+                expression: "json_path(@order, \"items[0]\")".to_string(),
+            },
+            Activity::IfCondition {
+                condition: "xpath(@doc, \"/order/@id/text()\") == \"7\"".to_string(),
+            },
+        ]);
+
+        let result = ScenarioValidator::new(&project.main_scenario, &project).validate();
+        let paths: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|issue| issue.code == ErrorCode::E107)
+            .collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].message.contains("Invalid JSON path 'items[0]'"));
+        assert!(paths[1].message.contains("Invalid XPath"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Project builders and a runner shared by the activity tests.
+
+use crate::execution::{ExecutionContext, IrExecutor, ScopeFrame};
+use crate::log::{LogEntry, LogLevel};
+use crate::node_graph::{BranchType, Node, Scenario};
+use crate::variables::Variables;
+use crate::{Activity, IrBuilder, Project, ScenarioValidator, StopControl};
+use shared::NanoId;
+
+/// Appends a node for each activity to `scenario` and returns their ids in order.
+pub fn add_nodes(
+    scenario: &mut Scenario,
+    activities: impl IntoIterator<Item = Activity>,
+) -> Vec<NanoId> {
+    activities
+        .into_iter()
+        .map(|activity| {
+            let id = NanoId::default();
+            scenario.nodes.push(Node {
+                id: id.clone(),
+                activity,
+                x: 0.0,
+                y: 0.0,
+                width: 128.0,
+                height: 64.0,
+            });
+            id
+        })
+        .collect()
+}
+
+/// Adds Start → `activities` → End to `scenario`, each linked to the next by a
+/// Default connection. Returns the ids of all of them, Start first.
+pub fn linear_scenario(scenario: &mut Scenario, activities: Vec<Activity>) -> Vec<NanoId> {
+    let scenario_id = scenario.id.clone();
+    let activities = std::iter::once(Activity::Start {
+        scenario_id: scenario_id.clone(),
+    })
+    .chain(activities)
+    .chain(std::iter::once(Activity::End { scenario_id }));
+
+    let ids = add_nodes(scenario, activities);
+    for pair in ids.windows(2) {
+        scenario.add_connection_with_branch(pair[0].clone(), pair[1].clone(), BranchType::Default);
+    }
+    ids
+}
+
+/// A project whose main scenario is Start → `activities` → End, so node `n + 1`
+/// and connection `n + 1` belong to `activities[n]`.
+pub fn linear_project(activities: Vec<Activity>) -> Project {
+    let mut project = Project::new("Test", Variables::new());
+    linear_scenario(&mut project.main_scenario, activities);
+    project
+}
+
+/// Links the Error output of every node in `from` to a Log of `"handled"` that
+/// then joins End.
+pub fn handle_errors(scenario: &mut Scenario, from: &[NanoId]) {
+    let end = scenario
+        .nodes
+        .iter()
+        .find(|node| matches!(node.activity, Activity::End { .. }))
+        .map(|node| node.id.clone())
+        .expect("scenario has no End node");
+    let handled = add_nodes(
+        scenario,
+        [Activity::Log {
+            level: LogLevel::Warning,
+            message: "\"handled\"".to_string(),
+        }],
+    )
+    .remove(0);
+
+    for id in from {
+        scenario.add_connection_with_branch(id.clone(), handled.clone(), BranchType::ErrorBranch);
+    }
+    scenario.add_connection_with_branch(handled, end, BranchType::Default);
+}
+
+/// Validates, builds and runs the main scenario, and returns the context and log
+/// it finished with.
+pub fn run_project(project: &Project) -> (ExecutionContext, Vec<LogEntry>) {
+    run_project_with(project, Variables::new())
+}
+
+/// [`run_project`] starting from `globals` instead of empty global variables.
+pub fn run_project_with(
+    project: &Project,
+    globals: Variables,
+) -> (ExecutionContext, Vec<LogEntry>) {
+    let mut variables = globals;
+    let result = ScenarioValidator::new(&project.main_scenario, project).validate();
+    assert!(result.is_valid(), "{:?}", result.errors);
+    let program = IrBuilder::new(
+        &project.main_scenario,
+        project,
+        &result.reachable_nodes,
+        &mut variables,
+    )
+    .build()
+    .unwrap();
+
+    let context = ExecutionContext::new_without_sender(
+        std::time::SystemTime::now(),
+        vec![ScopeFrame {
+            scenario_id: project.main_scenario.id.clone(),
+            variables: Variables::new(),
+        }],
+        variables,
+        StopControl::new(),
+    );
+    let mut log: Vec<LogEntry> = Vec::new();
+    let mut executor = IrExecutor::new(&program, project, context, &mut log);
+    executor.execute().unwrap();
+    let context = executor.context;
+    (context, log)
+}
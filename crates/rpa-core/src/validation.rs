@@ -1,4 +1,5 @@
 use crate::constants::{CoreConstants, ValidationConstants};
+use crate::desktop;
 use crate::execution::LogOutput;
 use crate::log::{LogActivity, LogEntry, LogLevel};
 use crate::node_graph::{Activity, BranchType, Project, Scenario};
@@ -17,6 +18,15 @@ pub enum ErrorCode {
     E102, // Loop with zero step
     E103, // CallScenario references non-existent scenario
     E104, // Invalid condition syntax
+    E105, // Imported library could not be loaded
+    E106, // Imported library version is incompatible
+    E107, // Invalid JSON path or XPath in an expression
+    E108, // Database activity references an undefined connection
+    E109, // Database query has no SQL
+    E110, // Invalid UI element selector
+    E111, // Send Keys has no keys
+    E112, // Image match confidence outside 0..=1
+    E113, // Password is not a secret variable
     E201, // Empty variable name
     W001, // If node missing True branch
     W002, // If node missing False branch
@@ -38,6 +48,15 @@ impl Display for ErrorCode {
             ErrorCode::E102 => write!(f, "E102"),
             ErrorCode::E103 => write!(f, "E103"),
             ErrorCode::E104 => write!(f, "E104"),
+            ErrorCode::E105 => write!(f, "E105"),
+            ErrorCode::E106 => write!(f, "E106"),
+            ErrorCode::E107 => write!(f, "E107"),
+            ErrorCode::E108 => write!(f, "E108"),
+            ErrorCode::E109 => write!(f, "E109"),
+            ErrorCode::E110 => write!(f, "E110"),
+            ErrorCode::E111 => write!(f, "E111"),
+            ErrorCode::E112 => write!(f, "E112"),
+            ErrorCode::E113 => write!(f, "E113"),
             ErrorCode::E201 => write!(f, "E201"),
             ErrorCode::W001 => write!(f, "W001"),
             ErrorCode::W002 => write!(f, "W002"),
@@ -390,6 +409,11 @@ impl<'a> ScenarioValidator<'a> {
 
         issues.extend(self.check_loop_parameters(reachable_nodes));
         issues.extend(self.check_condition_syntax(reachable_nodes));
+        issues.extend(self.check_query_paths(reachable_nodes));
+        issues.extend(self.check_database_activities(reachable_nodes));
+        issues.extend(self.check_mail_activities(reachable_nodes));
+        issues.extend(self.check_ui_activities(reachable_nodes));
+        issues.extend(self.check_library_imports());
         issues.extend(self.check_scenario_references(reachable_nodes));
         issues.extend(self.check_recursive_scenarios(CoreConstants::MAX_RECURSION_DEPTH));
 
@@ -470,6 +494,199 @@ impl<'a> ScenarioValidator<'a> {
         issues
     }
 
+    fn check_query_paths(&self, reachable_nodes: &HashSet<NanoId>) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for node in &self.scenario.nodes {
+            if !reachable_nodes.contains(&node.id) {
+                continue;
+            }
+
+            let expressions: &[&String] = match &node.activity {
+                Activity::Log { message, .. } => &[message],
+                Activity::Evaluate { expression } => &[expression],
+                Activity::IfCondition { condition } | Activity::While { condition } => &[condition],
+                Activity::ParseJson { text, .. } | Activity::ParseXml { text, .. } => &[text],
+                Activity::ToJson { value, .. } => &[value],
+                Activity::HttpRequest { url, body, .. } => &[url, body],
+                Activity::TypeInto { text, .. } => &[text],
+                _ => &[],
+            };
+
+            // Expressions that do not parse are reported when the scenario is compiled.
+            for expr in expressions
+                .iter()
+                .filter_map(|e| arc_script::parse_expr(e).ok())
+            {
+                if let Err(msg) = arc_script::validate_paths(&expr) {
+                    issues.push(ValidationIssue::new_error(
+                        Some(node.id.clone()),
+                        format!("{} in node ({})", msg, node.id),
+                        ErrorCode::E107,
+                    ));
+                }
+            }
+        }
+
+        issues
+    }
+
+    fn check_ui_activities(&self, reachable_nodes: &HashSet<NanoId>) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for node in &self.scenario.nodes {
+            if !reachable_nodes.contains(&node.id) {
+                continue;
+            }
+
+            let selector = match &node.activity {
+                Activity::Click { selector, .. }
+                | Activity::TypeInto { selector, .. }
+                | Activity::GetText { selector, .. }
+                | Activity::WaitForElement { selector, .. }
+                | Activity::CloseWindow { selector, .. } => Some(selector),
+                // The element to focus first is optional.
+                Activity::SendKeys { selector, keys, .. } => {
+                    if desktop::key_combinations(keys).is_empty() {
+                        issues.push(ValidationIssue::new_error(
+                            Some(node.id.clone()),
+                            format!("Keys are empty in node ({})", node.id),
+                            ErrorCode::E111,
+                        ));
+                    }
+                    (!selector.trim().is_empty()).then_some(selector)
+                }
+                // Without a selector the whole screen is captured.
+                Activity::TakeScreenshot { selector, .. } => {
+                    (!selector.trim().is_empty()).then_some(selector)
+                }
+                Activity::ClickImage { confidence, .. }
+                | Activity::WaitForImage { confidence, .. } => {
+                    if !(0.0..=1.0).contains(confidence) {
+                        issues.push(ValidationIssue::new_error(
+                            Some(node.id.clone()),
+                            format!(
+                                "Confidence {} is not between 0 and 1 in node ({})",
+                                confidence, node.id
+                            ),
+                            ErrorCode::E112,
+                        ));
+                    }
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(selector) = selector
+                && let Err(msg) = desktop::check_selector(selector)
+            {
+                issues.push(ValidationIssue::new_error(
+                    Some(node.id.clone()),
+                    format!("Invalid selector in node ({}): {}", node.id, msg),
+                    ErrorCode::E110,
+                ));
+            }
+        }
+
+        issues
+    }
+
+    fn check_database_activities(&self, reachable_nodes: &HashSet<NanoId>) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for node in &self.scenario.nodes {
+            if !reachable_nodes.contains(&node.id) {
+                continue;
+            }
+
+            let (connection, sql) = match &node.activity {
+                Activity::ExecuteQuery {
+                    connection, sql, ..
+                }
+                | Activity::ExecuteNonQuery {
+                    connection, sql, ..
+                } => (connection, Some(sql)),
+                Activity::BeginTransaction { connection }
+                | Activity::CommitTransaction { connection }
+                | Activity::RollbackTransaction { connection } => (connection, None),
+                _ => continue,
+            };
+
+            match self
+                .project
+                .databases
+                .iter()
+                .find(|d| d.name == *connection)
+            {
+                Some(definition) => issues.extend(self.check_secret(
+                    &node.id,
+                    &definition.password,
+                    &format!("Password of database connection '{}'", connection),
+                )),
+                None => issues.push(ValidationIssue::new_error(
+                    Some(node.id.clone()),
+                    format!(
+                        "Database connection '{}' is not defined in node ({})",
+                        connection, node.id
+                    ),
+                    ErrorCode::E108,
+                )),
+            }
+            if sql.is_some_and(|sql| sql.trim().is_empty()) {
+                issues.push(ValidationIssue::new_error(
+                    Some(node.id.clone()),
+                    format!("SQL statement is empty in node ({})", node.id),
+                    ErrorCode::E109,
+                ));
+            }
+        }
+
+        issues
+    }
+
+    fn check_mail_activities(&self, reachable_nodes: &HashSet<NanoId>) -> Vec<ValidationIssue> {
+        self.scenario
+            .nodes
+            .iter()
+            .filter(|node| reachable_nodes.contains(&node.id))
+            .filter_map(|node| match &node.activity {
+                Activity::SendEmail { password, .. } | Activity::ReadEmails { password, .. } => {
+                    self.check_secret(&node.id, password, "Mail password")
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Credentials must come from a secret variable such as `@db_password`, so
+    /// they stay out of the project file, its history and the run log.
+    fn check_secret(
+        &self,
+        node_id: &NanoId,
+        expression: &str,
+        what: &str,
+    ) -> Option<ValidationIssue> {
+        if expression.trim().is_empty() {
+            return None;
+        }
+        let is_secret = match arc_script::parse_expr(expression) {
+            Ok(arc_script::Expr::Load(name)) => {
+                self.project.variables.is_secret(&name) || self.scenario.variables.is_secret(&name)
+            }
+            _ => false,
+        };
+        (!is_secret).then(|| {
+            ValidationIssue::new_error(
+                Some(node_id.clone()),
+                format!(
+                    "{} in node ({}) must be a secret variable, not a literal or a plain variable",
+                    what, node_id
+                ),
+                ErrorCode::E113,
+            )
+        })
+    }
+
     fn check_scenario_references(&self, reachable_nodes: &HashSet<NanoId>) -> Vec<ValidationIssue> {
         let mut issues = Vec::new();
 
@@ -481,8 +698,7 @@ impl<'a> ScenarioValidator<'a> {
             if let Activity::CallScenario { scenario_id, .. } = &node.activity
                 && !scenario_id.as_str().is_empty()
             {
-                let scenario_exists = self.project.scenarios.iter().any(|s| s.id == *scenario_id)
-                    || self.project.main_scenario.id == *scenario_id;
+                let scenario_exists = self.project.find_scenario(scenario_id).is_some();
 
                 if !scenario_exists {
                     issues.push(ValidationIssue::new_error(
@@ -500,6 +716,37 @@ impl<'a> ScenarioValidator<'a> {
         issues
     }
 
+    fn check_library_imports(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for import in &self.project.imports {
+            let library = self
+                .project
+                .libraries
+                .iter()
+                .find(|lib| lib.source_path == import.path);
+
+            match library {
+                None => issues.push(ValidationIssue::new_error(
+                    None,
+                    format!("Imported library '{}' could not be loaded", import.path),
+                    ErrorCode::E105,
+                )),
+                Some(library) => {
+                    if let Err(msg) = import.check_version(library) {
+                        issues.push(ValidationIssue::new_error(
+                            None,
+                            format!("Imported library '{}': {}", import.path, msg),
+                            ErrorCode::E106,
+                        ));
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
     fn check_recursive_scenarios(&self, depth_limit: usize) -> Vec<ValidationIssue> {
         let mut issues = Vec::new();
         let mut visited = HashSet::new();
@@ -552,14 +799,7 @@ impl<'a> ScenarioValidator<'a> {
         visited.insert(scenario_id.clone());
         call_stack.push(scenario_id.clone());
 
-        let scenario = if self.project.main_scenario.id == scenario_id {
-            &self.project.main_scenario
-        } else {
-            self.project
-                .scenarios
-                .iter()
-                .find(|s| s.id == scenario_id)?
-        };
+        let scenario = self.project.find_scenario(&scenario_id)?;
 
         for node in &scenario.nodes {
             if let Activity::CallScenario {
@@ -583,16 +823,10 @@ impl<'a> ScenarioValidator<'a> {
     }
 
     fn get_scenario_name(&self, scenario_id: NanoId) -> String {
-        if self.project.main_scenario.id == scenario_id {
-            self.project.main_scenario.name.clone()
-        } else {
-            self.project
-                .scenarios
-                .iter()
-                .find(|s| s.id == scenario_id)
-                .map(|s| s.name.clone())
-                .unwrap_or_else(|| format!("<unknown:{}>", scenario_id))
-        }
+        self.project
+            .find_scenario(&scenario_id)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| format!("<unknown:{}>", scenario_id))
     }
 
     fn validate_data_flow(&self, reachable_nodes: &HashSet<NanoId>) -> Vec<ValidationIssue> {
@@ -631,6 +865,38 @@ impl<'a> ScenarioValidator<'a> {
                         ));
                     }
                 }
+                Activity::HttpRequest { status_var, .. } if status_var.is_empty() => {
+                    issues.push(ValidationIssue::new_error(
+                        Some(node.id.clone()),
+                        format!("Status variable name is empty in node ({})", node.id),
+                        ErrorCode::E201,
+                    ));
+                }
+                Activity::ReadTextFile { output_var, .. }
+                | Activity::ListFiles { output_var, .. }
+                | Activity::FileExists { output_var, .. }
+                | Activity::ExcelReadRange { output_var, .. }
+                | Activity::ExcelReadCell { output_var, .. }
+                | Activity::ExcelListSheets { output_var, .. }
+                | Activity::ReadEmails { output_var, .. }
+                | Activity::ParseEmail { output_var, .. }
+                | Activity::SaveAttachments { output_var, .. }
+                | Activity::ReadCsv { output_var, .. }
+                | Activity::ParseJson { output_var, .. }
+                | Activity::ToJson { output_var, .. }
+                | Activity::ParseXml { output_var, .. }
+                | Activity::HttpRequest { output_var, .. }
+                | Activity::ExecuteQuery { output_var, .. }
+                | Activity::ExecuteNonQuery { output_var, .. }
+                | Activity::GetText { output_var, .. }
+                    if output_var.is_empty() =>
+                {
+                    issues.push(ValidationIssue::new_error(
+                        Some(node.id.clone()),
+                        format!("Output variable name is empty in node ({})", node.id),
+                        ErrorCode::E201,
+                    ));
+                }
                 _ => {}
             }
         }
@@ -705,6 +971,51 @@ impl<'a> ScenarioValidator<'a> {
             Activity::Loop { index, .. } => {
                 defined_vars.insert(index.clone());
             }
+            Activity::ReadTextFile { output_var, .. }
+            | Activity::ListFiles { output_var, .. }
+            | Activity::FileExists { output_var, .. }
+            | Activity::ExcelReadRange { output_var, .. }
+            | Activity::ExcelReadCell { output_var, .. }
+            | Activity::ExcelListSheets { output_var, .. }
+            | Activity::ReadEmails { output_var, .. }
+            | Activity::ParseEmail { output_var, .. }
+            | Activity::SaveAttachments { output_var, .. }
+            | Activity::ParseJson { output_var, .. }
+            | Activity::ToJson { output_var, .. }
+            | Activity::ParseXml { output_var, .. }
+            | Activity::ExecuteQuery { output_var, .. }
+            | Activity::ExecuteNonQuery { output_var, .. }
+            | Activity::GetText { output_var, .. } => {
+                defined_vars.insert(output_var.clone());
+            }
+            Activity::ReadCsv {
+                output_var,
+                row_count_var,
+                ..
+            } => {
+                defined_vars.insert(output_var.clone());
+                if !row_count_var.is_empty() {
+                    defined_vars.insert(row_count_var.clone());
+                }
+            }
+            Activity::LaunchApplication { output_var, .. }
+            | Activity::TakeScreenshot { output_var, .. }
+                if !output_var.is_empty() =>
+            {
+                defined_vars.insert(output_var.clone());
+            }
+            Activity::HttpRequest {
+                status_var,
+                headers_var,
+                output_var,
+                ..
+            } => {
+                defined_vars.insert(status_var.clone());
+                if !headers_var.is_empty() {
+                    defined_vars.insert(headers_var.clone());
+                }
+                defined_vars.insert(output_var.clone());
+            }
             _ => {}
         }
 
@@ -906,6 +1217,60 @@ fn get_activity_name(activity: &Activity) -> String {
         Activity::RunPowershell { .. } => "RunPowershell".to_string(),
         Activity::Note { .. } => "Note".to_string(),
         Activity::TryCatch => "TryCatch".to_string(),
+        Activity::ReadTextFile { path, .. } => format!("ReadTextFile '{}'", path),
+        Activity::WriteTextFile { path, .. } => format!("WriteTextFile '{}'", path),
+        Activity::CopyFile { source, .. } => format!("CopyFile '{}'", source),
+        Activity::MoveFile { source, .. } => format!("MoveFile '{}'", source),
+        Activity::DeleteFile { path } => format!("DeleteFile '{}'", path),
+        Activity::CreateDirectory { path } => format!("CreateDirectory '{}'", path),
+        Activity::ListFiles { directory, .. } => format!("ListFiles '{}'", directory),
+        Activity::FileExists { path, .. } => format!("FileExists '{}'", path),
+        Activity::WaitForFile { path, .. } => format!("WaitForFile '{}'", path),
+        Activity::ExcelReadRange { path, .. } => format!("ExcelReadRange '{}'", path),
+        Activity::ExcelReadCell { path, .. } => format!("ExcelReadCell '{}'", path),
+        Activity::ExcelWriteCell { path, .. } => format!("ExcelWriteCell '{}'", path),
+        Activity::ExcelWriteRange { path, .. } => format!("ExcelWriteRange '{}'", path),
+        Activity::ExcelAppendRows { path, .. } => format!("ExcelAppendRows '{}'", path),
+        Activity::ExcelListSheets { path, .. } => format!("ExcelListSheets '{}'", path),
+        Activity::FillWordTemplate { template_path, .. } => {
+            format!("FillWordTemplate '{}'", template_path)
+        }
+        Activity::SendEmail { subject, .. } => format!("SendEmail '{}'", subject),
+        Activity::ReadEmails { folder, .. } => format!("ReadEmails '{}'", folder),
+        Activity::ParseEmail { path, .. } => format!("ParseEmail '{}'", path),
+        Activity::SaveAttachments { path, .. } => format!("SaveAttachments '{}'", path),
+        Activity::ReadCsv { path, .. } => format!("ReadCsv '{}'", path),
+        Activity::WriteCsv { path, .. } => format!("WriteCsv '{}'", path),
+        Activity::AppendCsvRow { path, .. } => format!("AppendCsvRow '{}'", path),
+        Activity::ParseJson { output_var, .. } => format!("ParseJson '{}'", output_var),
+        Activity::ToJson { output_var, .. } => format!("ToJson '{}'", output_var),
+        Activity::ParseXml { output_var, .. } => format!("ParseXml '{}'", output_var),
+        Activity::HttpRequest { method, url, .. } => {
+            format!("HttpRequest {} '{}'", method.as_str(), url)
+        }
+        Activity::ExecuteQuery { connection, .. } => format!("ExecuteQuery '{}'", connection),
+        Activity::ExecuteNonQuery { connection, .. } => {
+            format!("ExecuteNonQuery '{}'", connection)
+        }
+        Activity::BeginTransaction { connection } => {
+            format!("BeginTransaction '{}'", connection)
+        }
+        Activity::CommitTransaction { connection } => {
+            format!("CommitTransaction '{}'", connection)
+        }
+        Activity::RollbackTransaction { connection } => {
+            format!("RollbackTransaction '{}'", connection)
+        }
+        Activity::Click { selector, .. } => format!("Click '{}'", selector),
+        Activity::TypeInto { selector, .. } => format!("TypeInto '{}'", selector),
+        Activity::GetText { selector, .. } => format!("GetText '{}'", selector),
+        Activity::WaitForElement { selector, .. } => format!("WaitForElement '{}'", selector),
+        Activity::SendKeys { keys, .. } => format!("SendKeys '{}'", keys),
+        Activity::LaunchApplication { path, .. } => format!("LaunchApplication '{}'", path),
+        Activity::CloseWindow { selector, .. } => format!("CloseWindow '{}'", selector),
+        Activity::ClickImage { image_path, .. } => format!("ClickImage '{}'", image_path),
+        Activity::WaitForImage { image_path, .. } => format!("WaitForImage '{}'", image_path),
+        Activity::TakeScreenshot { selector, .. } => format!("TakeScreenshot '{}'", selector),
     }
 }
 
@@ -1066,6 +1431,466 @@ fn hash_activity(activity: &Activity, hasher: &mut DefaultHasher) {
         Activity::TryCatch => 13_u8.hash(hasher),
         Activity::Continue => 14_u8.hash(hasher),
         Activity::Break => 15_u8.hash(hasher),
+        Activity::ReadTextFile {
+            path,
+            encoding,
+            output_var,
+        } => {
+            16_u8.hash(hasher);
+            path.hash(hasher);
+            encoding.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::WriteTextFile {
+            path,
+            content,
+            encoding,
+            append,
+        } => {
+            17_u8.hash(hasher);
+            path.hash(hasher);
+            content.hash(hasher);
+            encoding.hash(hasher);
+            append.hash(hasher);
+        }
+        Activity::CopyFile {
+            source,
+            destination,
+            overwrite,
+        } => {
+            18_u8.hash(hasher);
+            source.hash(hasher);
+            destination.hash(hasher);
+            overwrite.hash(hasher);
+        }
+        Activity::MoveFile {
+            source,
+            destination,
+            overwrite,
+        } => {
+            19_u8.hash(hasher);
+            source.hash(hasher);
+            destination.hash(hasher);
+            overwrite.hash(hasher);
+        }
+        Activity::DeleteFile { path } => {
+            20_u8.hash(hasher);
+            path.hash(hasher);
+        }
+        Activity::CreateDirectory { path } => {
+            21_u8.hash(hasher);
+            path.hash(hasher);
+        }
+        Activity::ListFiles {
+            directory,
+            pattern,
+            recursive,
+            output_var,
+        } => {
+            22_u8.hash(hasher);
+            directory.hash(hasher);
+            pattern.hash(hasher);
+            recursive.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::FileExists { path, output_var } => {
+            23_u8.hash(hasher);
+            path.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::WaitForFile { path, timeout_ms } => {
+            24_u8.hash(hasher);
+            path.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::ExcelReadRange {
+            path,
+            sheet,
+            range,
+            has_headers,
+            output_var,
+        } => {
+            25_u8.hash(hasher);
+            path.hash(hasher);
+            sheet.hash(hasher);
+            range.hash(hasher);
+            has_headers.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ExcelReadCell {
+            path,
+            sheet,
+            cell,
+            output_var,
+        } => {
+            26_u8.hash(hasher);
+            path.hash(hasher);
+            sheet.hash(hasher);
+            cell.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ExcelWriteCell {
+            path,
+            sheet,
+            cell,
+            value,
+        } => {
+            27_u8.hash(hasher);
+            path.hash(hasher);
+            sheet.hash(hasher);
+            cell.hash(hasher);
+            value.hash(hasher);
+        }
+        Activity::ExcelWriteRange {
+            path,
+            sheet,
+            cell,
+            value,
+            write_headers,
+        } => {
+            28_u8.hash(hasher);
+            path.hash(hasher);
+            sheet.hash(hasher);
+            cell.hash(hasher);
+            value.hash(hasher);
+            write_headers.hash(hasher);
+        }
+        Activity::ExcelAppendRows { path, sheet, value } => {
+            29_u8.hash(hasher);
+            path.hash(hasher);
+            sheet.hash(hasher);
+            value.hash(hasher);
+        }
+        Activity::ExcelListSheets { path, output_var } => {
+            30_u8.hash(hasher);
+            path.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::FillWordTemplate {
+            template_path,
+            output_path,
+            values,
+        } => {
+            31_u8.hash(hasher);
+            template_path.hash(hasher);
+            output_path.hash(hasher);
+            values.hash(hasher);
+        }
+        Activity::SendEmail {
+            server,
+            port,
+            security,
+            username,
+            password,
+            allow_insecure_login,
+            ca_bundle,
+            from,
+            to,
+            cc,
+            subject,
+            body,
+            html,
+            attachments,
+        } => {
+            32_u8.hash(hasher);
+            server.hash(hasher);
+            port.hash(hasher);
+            security.hash(hasher);
+            username.hash(hasher);
+            password.hash(hasher);
+            allow_insecure_login.hash(hasher);
+            ca_bundle.hash(hasher);
+            from.hash(hasher);
+            to.hash(hasher);
+            cc.hash(hasher);
+            subject.hash(hasher);
+            body.hash(hasher);
+            html.hash(hasher);
+            attachments.hash(hasher);
+        }
+        Activity::ReadEmails {
+            server,
+            port,
+            security,
+            username,
+            password,
+            allow_insecure_login,
+            ca_bundle,
+            folder,
+            criteria,
+            limit,
+            mark_read,
+            attachments_dir,
+            output_var,
+        } => {
+            33_u8.hash(hasher);
+            server.hash(hasher);
+            port.hash(hasher);
+            security.hash(hasher);
+            username.hash(hasher);
+            password.hash(hasher);
+            allow_insecure_login.hash(hasher);
+            ca_bundle.hash(hasher);
+            folder.hash(hasher);
+            criteria.hash(hasher);
+            limit.hash(hasher);
+            mark_read.hash(hasher);
+            attachments_dir.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ParseEmail { path, output_var } => {
+            34_u8.hash(hasher);
+            path.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::SaveAttachments {
+            path,
+            directory,
+            include_inline,
+            output_var,
+        } => {
+            35_u8.hash(hasher);
+            path.hash(hasher);
+            directory.hash(hasher);
+            include_inline.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ReadCsv {
+            path,
+            delimiter,
+            quote,
+            encoding,
+            has_headers,
+            skip_rows,
+            max_rows,
+            output_var,
+            row_count_var,
+        } => {
+            36_u8.hash(hasher);
+            path.hash(hasher);
+            delimiter.hash(hasher);
+            quote.hash(hasher);
+            encoding.hash(hasher);
+            has_headers.hash(hasher);
+            skip_rows.hash(hasher);
+            max_rows.hash(hasher);
+            output_var.hash(hasher);
+            row_count_var.hash(hasher);
+        }
+        Activity::WriteCsv {
+            path,
+            value,
+            delimiter,
+            quote,
+            encoding,
+            write_headers,
+        } => {
+            37_u8.hash(hasher);
+            path.hash(hasher);
+            value.hash(hasher);
+            delimiter.hash(hasher);
+            quote.hash(hasher);
+            encoding.hash(hasher);
+            write_headers.hash(hasher);
+        }
+        Activity::AppendCsvRow {
+            path,
+            values,
+            delimiter,
+            quote,
+            encoding,
+        } => {
+            38_u8.hash(hasher);
+            path.hash(hasher);
+            values.hash(hasher);
+            delimiter.hash(hasher);
+            quote.hash(hasher);
+            encoding.hash(hasher);
+        }
+        Activity::ParseJson { text, output_var } => {
+            39_u8.hash(hasher);
+            text.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ToJson {
+            value,
+            pretty,
+            output_var,
+        } => {
+            40_u8.hash(hasher);
+            value.hash(hasher);
+            pretty.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ParseXml { text, output_var } => {
+            41_u8.hash(hasher);
+            text.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::HttpRequest {
+            method,
+            url,
+            query,
+            headers,
+            body_kind,
+            body,
+            timeout_ms,
+            retries,
+            status_var,
+            headers_var,
+            output_var,
+        } => {
+            42_u8.hash(hasher);
+            method.hash(hasher);
+            url.hash(hasher);
+            query.hash(hasher);
+            headers.hash(hasher);
+            body_kind.hash(hasher);
+            body.hash(hasher);
+            timeout_ms.hash(hasher);
+            retries.hash(hasher);
+            status_var.hash(hasher);
+            headers_var.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ExecuteQuery {
+            connection,
+            sql,
+            parameters,
+            output_var,
+        } => {
+            43_u8.hash(hasher);
+            connection.hash(hasher);
+            sql.hash(hasher);
+            parameters.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::ExecuteNonQuery {
+            connection,
+            sql,
+            parameters,
+            output_var,
+        } => {
+            44_u8.hash(hasher);
+            connection.hash(hasher);
+            sql.hash(hasher);
+            parameters.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::BeginTransaction { connection } => {
+            45_u8.hash(hasher);
+            connection.hash(hasher);
+        }
+        Activity::CommitTransaction { connection } => {
+            46_u8.hash(hasher);
+            connection.hash(hasher);
+        }
+        Activity::RollbackTransaction { connection } => {
+            47_u8.hash(hasher);
+            connection.hash(hasher);
+        }
+        Activity::Click {
+            selector,
+            click,
+            timeout_ms,
+        } => {
+            48_u8.hash(hasher);
+            selector.hash(hasher);
+            click.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::TypeInto {
+            selector,
+            text,
+            simulate_typing,
+            timeout_ms,
+        } => {
+            49_u8.hash(hasher);
+            selector.hash(hasher);
+            text.hash(hasher);
+            simulate_typing.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::GetText {
+            selector,
+            timeout_ms,
+            output_var,
+        } => {
+            50_u8.hash(hasher);
+            selector.hash(hasher);
+            timeout_ms.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::WaitForElement {
+            selector,
+            timeout_ms,
+        } => {
+            51_u8.hash(hasher);
+            selector.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::SendKeys {
+            selector,
+            keys,
+            timeout_ms,
+        } => {
+            52_u8.hash(hasher);
+            selector.hash(hasher);
+            keys.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::LaunchApplication {
+            path,
+            arguments,
+            output_var,
+        } => {
+            53_u8.hash(hasher);
+            path.hash(hasher);
+            arguments.hash(hasher);
+            output_var.hash(hasher);
+        }
+        Activity::CloseWindow {
+            selector,
+            timeout_ms,
+        } => {
+            54_u8.hash(hasher);
+            selector.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::ClickImage {
+            image_path,
+            confidence,
+            click,
+            timeout_ms,
+        } => {
+            55_u8.hash(hasher);
+            image_path.hash(hasher);
+            confidence.to_bits().hash(hasher);
+            click.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::WaitForImage {
+            image_path,
+            confidence,
+            timeout_ms,
+        } => {
+            56_u8.hash(hasher);
+            image_path.hash(hasher);
+            confidence.to_bits().hash(hasher);
+            timeout_ms.hash(hasher);
+        }
+        Activity::TakeScreenshot {
+            selector,
+            path,
+            output_var,
+            timeout_ms,
+        } => {
+            57_u8.hash(hasher);
+            selector.hash(hasher);
+            path.hash(hasher);
+            output_var.hash(hasher);
+            timeout_ms.hash(hasher);
+        }
     }
 }
 
@@ -1108,16 +1933,12 @@ pub fn compute_call_graph(
 
         in_progress.insert(scenario_id.clone());
 
-        let scenario = if project.main_scenario.id == scenario_id {
-            &project.main_scenario
-        } else {
-            match project.scenarios.iter().find(|s| s.id == scenario_id) {
-                Some(s) => s,
-                None => {
-                    in_progress.remove(&scenario_id);
-                    visited.insert(scenario_id);
-                    return;
-                }
+        let scenario = match project.find_scenario(&scenario_id) {
+            Some(s) => s,
+            None => {
+                in_progress.remove(&scenario_id);
+                visited.insert(scenario_id);
+                return;
             }
         };
 
@@ -1,6 +1,6 @@
 use arc_script::Value;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VariableScope {
@@ -12,19 +12,50 @@ pub enum VariableScope {
 pub struct Variable {
     value: Value,
     scope: VariableScope,
+    /// Secret values come from the vault or environment at run time and are never saved.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    secret: bool,
 }
 
 impl Variable {
     pub fn new(value: Value, scope: VariableScope) -> Self {
-        Self { value, scope }
+        Self {
+            value,
+            scope,
+            secret: false,
+        }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Variables {
+    #[serde(serialize_with = "serialize_sorted")]
     values: HashMap<String, Variable>,
 }
 
+/// Keeps saved projects stable between runs, `HashMap` iteration order is random.
+/// Secret values are replaced with `Undefined` so they never reach the project file.
+fn serialize_sorted<S: Serializer>(
+    values: &HashMap<String, Variable>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    values
+        .iter()
+        .map(|(name, var)| {
+            let var = if var.secret {
+                Variable {
+                    value: Value::Undefined,
+                    ..var.clone()
+                }
+            } else {
+                var.clone()
+            };
+            (name, var)
+        })
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
 impl Default for Variables {
     fn default() -> Self {
         Self::new()
@@ -48,8 +79,47 @@ impl Variables {
     }
 
     pub fn set(&mut self, name: &str, value: Value, scope: VariableScope) {
+        let secret = self.is_secret(name);
+        self.values.insert(
+            name.to_owned(),
+            Variable {
+                value,
+                scope,
+                secret,
+            },
+        );
+    }
+
+    /// Declares a secret variable. Its value is filled in by `secrets::resolve_secrets`.
+    pub fn create_secret(&mut self, name: &str, scope: VariableScope) {
+        self.values.insert(
+            name.to_owned(),
+            Variable {
+                value: Value::Undefined,
+                scope,
+                secret: true,
+            },
+        );
+    }
+
+    pub fn is_secret(&self, name: &str) -> bool {
+        self.values.get(name).is_some_and(|var| var.secret)
+    }
+
+    pub fn secret_names(&self) -> impl Iterator<Item = &str> {
+        self.values
+            .iter()
+            .filter(|(_, var)| var.secret)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Current values of secret variables as text, for masking them in logs.
+    pub fn secret_values(&self) -> impl Iterator<Item = String> {
         self.values
-            .insert(name.to_owned(), Variable::new(value, scope));
+            .values()
+            .filter(|var| var.secret && !matches!(var.value, Value::Undefined))
+            .map(|var| var.value.to_string())
+            .filter(|value| !value.is_empty())
     }
 
     pub fn get(&self, name: &str) -> Option<&Value> {
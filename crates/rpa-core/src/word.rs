@@ -0,0 +1,171 @@
+use crate::variables::Variables;
+use arc_script::Value;
+use office::TemplateValue;
+use std::collections::BTreeMap;
+
+/// Booleans stay flags for `{#if}` sections, tables become lists for repeated
+/// rows and everything else is filled in as text.
+pub fn value_to_template(value: &Value) -> TemplateValue {
+    match value {
+        Value::Boolean(b) => TemplateValue::Bool(*b),
+        Value::Table(table) => TemplateValue::Rows(
+            table
+                .rows
+                .iter()
+                .map(|row| {
+                    table
+                        .columns
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().map(ToString::to_string))
+                        .collect()
+                })
+                .collect(),
+        ),
+        Value::Undefined => TemplateValue::Text(String::new()),
+        Value::Number(_) | Value::String(_) | Value::List(_) | Value::Object(_) => {
+            TemplateValue::Text(value.to_string())
+        }
+    }
+}
+
+/// Template fields from the variables visible to the activity, overridden by
+/// the columns of the first row of `record` when it is given. Secret variables
+/// are left out so they cannot end up in a document.
+pub fn template_values(
+    variables: &Variables,
+    record: Option<&Value>,
+) -> Result<BTreeMap<String, TemplateValue>, String> {
+    let mut values: BTreeMap<String, TemplateValue> = variables
+        .iter()
+        .filter(|(name, _, _)| !variables.is_secret(name))
+        .map(|(name, value, _)| (name.to_string(), value_to_template(value)))
+        .collect();
+
+    match record {
+        None | Some(Value::Undefined) => {}
+        Some(Value::Table(table)) => {
+            if let Some(row) = table.rows.first() {
+                for (column, value) in table.columns.iter().zip(row) {
+                    values.insert(column.clone(), value_to_template(value));
+                }
+            }
+        }
+        Some(other) => {
+            return Err(format!("Template values must be a table, got '{}'", other));
+        }
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Activity;
+    use crate::constants::CoreConstants;
+    use crate::node_graph::BranchType;
+    use crate::test_support::{linear_project, run_project_with};
+    use crate::variables::VariableScope;
+    use arc_script::Table;
+    use office::Word;
+
+    #[test]
+    fn variables_and_record_become_template_values() {
+        let mut variables = Variables::new();
+        variables.set(
+            "client",
+            Value::String("Aigerim".to_string()),
+            VariableScope::Global,
+        );
+        variables.set("amount", Value::Number(1500.0), VariableScope::Scenario);
+        variables.set("vip", Value::Boolean(true), VariableScope::Scenario);
+        variables.set(
+            "items",
+            Value::Table(Table::new(
+                vec!["Name".to_string(), "Qty".to_string()],
+                vec![vec![Value::String("Pen".to_string()), Value::Number(2.0)]],
+            )),
+            VariableScope::Scenario,
+        );
+        variables.create_secret("password", VariableScope::Global);
+        variables.set(
+            "password",
+            Value::String("hunter2".to_string()),
+            VariableScope::Global,
+        );
+
+        let record = Value::Table(Table::new(
+            vec!["client".to_string(), "number".to_string()],
+            vec![
+                vec![Value::String("Dana".to_string()), Value::Number(117.0)],
+                vec![Value::String("ignored".to_string()), Value::Number(0.0)],
+            ],
+        ));
+        let values = template_values(&variables, Some(&record)).unwrap();
+
+        assert_eq!(values["client"], TemplateValue::Text("Dana".to_string()));
+        assert_eq!(values["number"], TemplateValue::Text("117".to_string()));
+        assert_eq!(values["amount"], TemplateValue::Text("1500".to_string()));
+        assert_eq!(values["vip"], TemplateValue::Bool(true));
+        assert_eq!(
+            values["items"],
+            TemplateValue::Rows(vec![BTreeMap::from([
+                ("Name".to_string(), "Pen".to_string()),
+                ("Qty".to_string(), "2".to_string()),
+            ])])
+        );
+        assert!(!values.contains_key("password"));
+        assert!(template_values(&variables, Some(&Value::Number(1.0))).is_err());
+    }
+
+    #[test]
+    fn executor_fills_templates_and_routes_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("template.docx");
+        let output = dir.path().join("out.docx");
+        let document = Word::add_paragraph(Word::create_document(), "Dear {client}, {greeting}");
+        Word::write(&document, &template).unwrap();
+
+        let mut project = linear_project(vec![
+            Activity::FillWordTemplate {
+                template_path: format!("\"{}\"", template.display()),
+                output_path: format!("\"{}\"", output.display()),
+                values: "@row".to_string(),
+            },
+            Activity::FillWordTemplate {
+                template_path: format!("\"{}\"", template.display()),
+                output_path: format!("\"{}\"", dir.path().join("fail.docx").display()),
+                values: String::new(),
+            },
+        ]);
+        let failing = project.main_scenario.nodes[2].id.clone();
+        let end = project.main_scenario.nodes[3].id.clone();
+        project
+            .main_scenario
+            .add_connection_with_branch(failing, end, BranchType::ErrorBranch);
+
+        let mut globals = Variables::new();
+        globals.set(
+            "greeting",
+            Value::String("welcome".to_string()),
+            VariableScope::Global,
+        );
+        globals.set(
+            "row",
+            Value::Table(Table::new(
+                vec!["client".to_string()],
+                vec![vec![Value::String("Aigerim".to_string())]],
+            )),
+            VariableScope::Global,
+        );
+        let (context, _) = run_project_with(&project, globals);
+        let last_error = context
+            .global_variables
+            .get(CoreConstants::ERROR_VARIABLE_NAME)
+            .cloned();
+
+        assert_eq!(Word::read(&output).unwrap(), "Dear Aigerim, welcome");
+        assert!(last_error.is_some_and(|e| e.to_string().contains("'client'")));
+    }
+}
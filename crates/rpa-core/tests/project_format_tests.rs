@@ -0,0 +1,238 @@
+use arc_script::Value;
+use rpa_core::project_format::{
+    PROJECT_MANIFEST, SCENARIOS_DIR, VARIABLES_FILE, detect_version, migrate, parse_project,
+    project_to_json,
+};
+use rpa_core::variables::VariableScope;
+use rpa_core::{Activity, CURRENT_FORMAT_VERSION, Project, Scenario};
+
+const FIXTURES: [(&str, u32); 3] = [
+    (include_str!("fixtures/project_v0.rpa"), 0),
+    (include_str!("fixtures/project_v1.rpa"), 1),
+    (include_str!("fixtures/project_v2.rpa"), 2),
+];
+
+/// `project_v2.rpa` as this build writes it.
+const PRETTY_FIXTURE: &str = include_str!("fixtures/project_v2_pretty.rpa");
+
+fn current_fixture() -> Project {
+    parse_project(FIXTURES[CURRENT_FORMAT_VERSION as usize].0)
+        .unwrap()
+        .0
+}
+
+#[test]
+fn test_detects_each_fixture_version() {
+    for (contents, version) in FIXTURES {
+        let document: serde_json::Value = serde_json::from_str(contents).unwrap();
+        assert_eq!(detect_version(&document).unwrap(), version);
+    }
+}
+
+#[test]
+fn test_every_version_migrates_to_same_project() {
+    let expected = current_fixture();
+
+    for (contents, version) in FIXTURES {
+        let (project, source_version) = parse_project(contents).unwrap();
+        assert_eq!(source_version, version);
+        assert_eq!(
+            project, expected,
+            "fixture v{} differs after migration",
+            version
+        );
+    }
+
+    assert_eq!(expected.scenarios.len(), 1);
+    assert!(expected.imports.is_empty());
+    assert!(expected.main_scenario.nodes.iter().any(|n| matches!(
+        &n.activity,
+        Activity::CallScenario { parameters, .. } if parameters.len() == 1
+    )));
+}
+
+#[test]
+fn test_round_trip_writes_current_version() {
+    for (contents, version) in FIXTURES {
+        let (project, _) = parse_project(contents).unwrap();
+        let json = project_to_json(&project).unwrap();
+
+        let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(detect_version(&document).unwrap(), CURRENT_FORMAT_VERSION);
+
+        let (reloaded, source_version) = parse_project(&json).unwrap();
+        assert_eq!(source_version, CURRENT_FORMAT_VERSION);
+        assert_eq!(
+            reloaded, project,
+            "round trip of fixture v{} changed it",
+            version
+        );
+    }
+}
+
+#[test]
+fn test_current_version_is_not_migrated() {
+    let contents = FIXTURES[CURRENT_FORMAT_VERSION as usize].0;
+    let document: serde_json::Value = serde_json::from_str(contents).unwrap();
+
+    let (migrated, _) = migrate(document.clone()).unwrap();
+    assert_eq!(migrated, document);
+}
+
+#[test]
+fn test_rejects_newer_format_version() {
+    let contents = format!(
+        r#"{{"format_version":{},"project":{{}}}}"#,
+        CURRENT_FORMAT_VERSION + 1
+    );
+
+    let result = parse_project(&contents);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("newer"));
+}
+
+#[test]
+fn test_load_and_save_through_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("legacy.rpa");
+    std::fs::write(&path, FIXTURES[1].0).unwrap();
+
+    let loaded = rpa_core::load_project(&path).unwrap();
+    assert_eq!(loaded.source_version, 1);
+    assert!(loaded.warnings.is_empty());
+
+    rpa_core::save_project(&loaded.project, &path).unwrap();
+    let reloaded = rpa_core::load_project(&path).unwrap();
+    assert_eq!(reloaded.source_version, CURRENT_FORMAT_VERSION);
+    assert_eq!(reloaded.project, loaded.project);
+}
+
+#[test]
+fn test_saved_json_is_pretty_and_stable() {
+    let mut project = current_fixture();
+    for name in ["zeta", "alpha", "mid", "beta", "omega"] {
+        project.variables.set(
+            name,
+            arc_script::Value::Number(1.0),
+            rpa_core::variables::VariableScope::Global,
+        );
+    }
+
+    let first = project_to_json(&project).unwrap();
+    let second = project_to_json(&project.clone()).unwrap();
+    assert_eq!(first, second);
+    assert!(first.lines().count() > 50);
+    assert!(first.starts_with("{\n  \"format_version\""));
+
+    let alpha = first.find("\"alpha\"").unwrap();
+    let zeta = first.find("\"zeta\"").unwrap();
+    assert!(alpha < zeta);
+}
+
+#[test]
+fn test_saved_json_matches_pretty_fixture() {
+    let json = project_to_json(&current_fixture()).unwrap();
+    assert_eq!(json, PRETTY_FIXTURE);
+}
+
+#[test]
+fn test_directory_layout_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join(PROJECT_MANIFEST);
+    let project = current_fixture();
+
+    rpa_core::save_project(&project, &manifest).unwrap();
+    assert!(manifest.exists());
+    assert!(dir.path().join(SCENARIOS_DIR).join("main.json").exists());
+    assert!(dir.path().join(SCENARIOS_DIR).join("login.json").exists());
+
+    for path in [manifest.as_path(), dir.path()] {
+        let loaded = rpa_core::load_project(path).unwrap();
+        assert_eq!(loaded.source_version, CURRENT_FORMAT_VERSION);
+        assert_eq!(loaded.project, project);
+    }
+}
+
+#[test]
+fn test_directory_layout_round_trips_variables() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut project = current_fixture();
+    let variables = &mut project.variables;
+    variables.create_secret("api_key", VariableScope::Global);
+    variables.create_variable("pending", VariableScope::Global);
+    let order = Value::Object(
+        [
+            ("id".to_string(), Value::Number(7.0)),
+            (
+                "lines".to_string(),
+                Value::List(vec![
+                    Value::String("Pen".to_string()),
+                    Value::Undefined,
+                    Value::Object(
+                        [("paid".to_string(), Value::Boolean(true))]
+                            .into_iter()
+                            .collect(),
+                    ),
+                ]),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    variables.set("order", order, VariableScope::Global);
+
+    rpa_core::save_project_dir(&project, dir.path()).unwrap();
+    assert!(dir.path().join(VARIABLES_FILE).exists());
+
+    let loaded = rpa_core::load_project(dir.path()).unwrap();
+    assert_eq!(loaded.project, project);
+    assert!(loaded.project.variables.is_secret("api_key"));
+}
+
+#[test]
+fn test_directory_layout_removes_stale_scenarios() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut project = current_fixture();
+    rpa_core::save_project_dir(&project, dir.path()).unwrap();
+
+    project.scenarios[0].name = "Sign in".to_string();
+    project.scenarios.push(Scenario::new("Sign in"));
+    rpa_core::save_project_dir(&project, dir.path()).unwrap();
+
+    let mut files: Vec<String> = std::fs::read_dir(dir.path().join(SCENARIOS_DIR))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["main.json", "sign-in-2.json", "sign-in.json"]);
+
+    let loaded = rpa_core::load_project(dir.path()).unwrap();
+    assert_eq!(loaded.project, project);
+}
+
+#[test]
+fn test_directory_layout_keeps_files_it_did_not_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut project = current_fixture();
+    rpa_core::save_project_dir(&project, dir.path()).unwrap();
+
+    let notes = dir.path().join(SCENARIOS_DIR).join("notes.json");
+    std::fs::write(&notes, "{}").unwrap();
+    project.scenarios.clear();
+    rpa_core::save_project_dir(&project, dir.path()).unwrap();
+
+    assert!(notes.exists());
+    assert!(!dir.path().join(SCENARIOS_DIR).join("login.json").exists());
+}
+
+#[test]
+fn test_directory_layout_refuses_non_project_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let existing = dir.path().join("data.json");
+    std::fs::write(&existing, "{}").unwrap();
+
+    let result = rpa_core::save_project_dir(&current_fixture(), dir.path());
+    assert!(result.unwrap_err().contains(PROJECT_MANIFEST));
+    assert!(!dir.path().join(SCENARIOS_DIR).exists());
+    assert_eq!(std::fs::read_to_string(&existing).unwrap(), "{}");
+}
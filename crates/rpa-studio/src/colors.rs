@@ -12,6 +12,15 @@ impl ColorPalette {
     pub const CONTROL_FLOW: Color32 = Color32::from_rgb(220, 140, 60);
     pub const EXECUTION: Color32 = Color32::from_rgb(140, 100, 180);
     pub const NOTE: Color32 = Color32::from_rgb(255, 255, 200);
+    pub const FILES: Color32 = Color32::from_rgb(60, 150, 140);
+    pub const EXCEL: Color32 = Color32::from_rgb(40, 130, 70);
+    pub const WORD: Color32 = Color32::from_rgb(45, 90, 170);
+    pub const MAIL: Color32 = Color32::from_rgb(190, 110, 50);
+    pub const CSV: Color32 = Color32::from_rgb(90, 140, 60);
+    pub const DATA: Color32 = Color32::from_rgb(130, 90, 160);
+    pub const WEB: Color32 = Color32::from_rgb(50, 120, 160);
+    pub const DATABASE: Color32 = Color32::from_rgb(150, 110, 70);
+    pub const UI_AUTOMATION: Color32 = Color32::from_rgb(170, 80, 130);
 
     pub const CONNECTION_TRUE: Color32 = Color32::from_rgb(50, 220, 100);
     pub const CONNECTION_FALSE: Color32 = Color32::from_rgb(220, 80, 80);
@@ -42,6 +51,15 @@ impl ColorPalette {
             ColorCategory::ControlFlow => Self::CONTROL_FLOW,
             ColorCategory::Execution => Self::EXECUTION,
             ColorCategory::Note => Self::NOTE,
+            ColorCategory::Files => Self::FILES,
+            ColorCategory::Excel => Self::EXCEL,
+            ColorCategory::Word => Self::WORD,
+            ColorCategory::Mail => Self::MAIL,
+            ColorCategory::Csv => Self::CSV,
+            ColorCategory::Data => Self::DATA,
+            ColorCategory::Web => Self::WEB,
+            ColorCategory::Database => Self::DATABASE,
+            ColorCategory::UiAutomation => Self::UI_AUTOMATION,
             _ => Self::BASIC_OPS,
         }
     }
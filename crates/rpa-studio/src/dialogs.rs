@@ -12,6 +12,7 @@ pub struct AddVariableDialog {
     pub value: String,
     pub var_type: VariableType,
     pub is_global: bool,
+    pub is_secret: bool,
 }
 
 impl Default for AddVariableDialog {
@@ -22,6 +23,7 @@ impl Default for AddVariableDialog {
             value: String::new(),
             var_type: VariableType::String,
             is_global: false,
+            is_secret: false,
         }
     }
 }
@@ -31,6 +33,11 @@ pub struct RenameScenarioDialog {
     pub scenario_index: Option<usize>,
 }
 
+#[derive(Default)]
+pub struct DatabaseConnectionDialog {
+    pub connection_index: Option<usize>,
+}
+
 pub struct VariableBindingDialog {
     pub show: bool,
     pub scenario_id: NanoId,
@@ -77,11 +84,29 @@ pub struct DebugDialogs {
     pub compilation_error: Option<String>,
 }
 
+pub struct ExportLibraryDialog {
+    pub show: bool,
+    pub name: String,
+    pub version: String,
+}
+
+impl Default for ExportLibraryDialog {
+    fn default() -> Self {
+        Self {
+            show: false,
+            name: String::new(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct DialogState {
     pub settings: SettingsDialog,
     pub add_variable: AddVariableDialog,
     pub rename_scenario: RenameScenarioDialog,
+    pub database_connection: DatabaseConnectionDialog,
+    pub export_library: ExportLibraryDialog,
     pub var_binding_dialog: VariableBindingDialog,
     pub debug: DebugDialogs,
     pub selected_log_entry: Option<usize>,
@@ -3,10 +3,13 @@ use crate::{
     ext::ScenarioExt,
     state::{ClipboardData, RpaApp},
     ui::canvas,
-    ui_constants::UiConstants,
+    ui_constants::{UiConstants, snap_to_grid},
 };
 use eframe::egui;
 use egui::Vec2;
+use rpa_core::log::{LogActivity, LogEntry, LogLevel};
+use rpa_core::recording::draft_activities;
+use rpa_core::{Activity, BranchType};
 use shared::NanoId;
 use std::collections::HashSet;
 
@@ -21,7 +24,8 @@ impl RpaApp {
             let cut_event = ctx.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Cut)));
             let has_selected = !self.selected_nodes.is_empty();
             let no_settings = !self.dialogs.settings.show;
-            let no_rename = self.dialogs.rename_scenario.scenario_index.is_none();
+            let no_rename = self.dialogs.rename_scenario.scenario_index.is_none()
+                && self.dialogs.database_connection.connection_index.is_none();
 
             if copy_event && has_selected && no_settings && no_rename {
                 self.copy_selected_nodes();
@@ -292,4 +296,85 @@ impl RpaApp {
         view.connection_renderer.increment_generation();
         view.minimap_needs_update = true;
     }
+
+    pub fn start_recording(&mut self) {
+        match ui_automation::Recorder::start() {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(e) => self.log_recording(LogLevel::Error, format!("Cannot record: {}", e)),
+        }
+    }
+
+    /// Stops the recorder and drops what it captured on the canvas
+    pub fn stop_recording(&mut self) {
+        let Some(recorder) = self.recorder.take() else {
+            return;
+        };
+
+        let activities = draft_activities(&recorder.stop());
+        if activities.is_empty() {
+            self.log_recording(LogLevel::Warning, "Nothing was recorded".to_string());
+            return;
+        }
+        self.log_recording(
+            LogLevel::Info,
+            format!("Recorded {} activities", activities.len()),
+        );
+        self.insert_recorded_activities(activities);
+    }
+
+    fn log_recording(&mut self, level: LogLevel, message: String) {
+        self.project.execution_log.push(LogEntry {
+            timestamp: "[00:00.00]".to_string(),
+            node_id: None,
+            level,
+            activity: LogActivity::System,
+            message,
+        });
+        self.needs_repaint = true;
+    }
+
+    /// Adds the activities as a chain of connected nodes, laid out in a
+    /// column to the right of the existing nodes.
+    fn insert_recorded_activities(&mut self, activities: Vec<Activity>) {
+        let scenario = self.get_current_scenario_mut();
+        let spacing = 2.0 * UiConstants::GRID_SIZE;
+        let (right, top) = scenario
+            .nodes
+            .iter()
+            .fold((f32::MIN, f32::MAX), |(right, top), node| {
+                (right.max(node.x + node.width), top.min(node.y))
+            });
+        let (x, mut y) = if scenario.nodes.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (
+                snap_to_grid(right + spacing, UiConstants::GRID_SIZE),
+                snap_to_grid(top, UiConstants::GRID_SIZE),
+            )
+        };
+
+        let mut previous: Option<NanoId> = None;
+        let mut first = None;
+        for activity in activities {
+            scenario.add_node(activity, x, y);
+            let Some(node) = scenario.nodes.last() else {
+                continue;
+            };
+            let id = node.id.clone();
+            y += node.height + spacing;
+
+            if let Some(from) = previous.replace(id.clone()) {
+                scenario.add_connection_with_branch(from, id.clone(), BranchType::Default);
+            }
+            first.get_or_insert(id);
+        }
+
+        self.invalidate_current_scenario();
+        let view = self.get_current_scenario_view_mut();
+        view.connection_renderer.increment_generation();
+        view.minimap_needs_update = true;
+        self.pending_node_focus = first;
+        self.undo_redo.add_undo(&self.project);
+        self.needs_repaint = true;
+    }
 }
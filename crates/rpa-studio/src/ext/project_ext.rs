@@ -13,6 +13,9 @@ impl ProjectExt for Project {
             scenarios: Vec::new(),
             execution_log: LogStorage::new(),
             variables,
+            imports: Vec::new(),
+            libraries: Vec::new(),
+            databases: Vec::new(),
         }
     }
 }
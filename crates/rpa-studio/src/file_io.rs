@@ -1,6 +1,10 @@
 use crate::state::RpaApp;
+use rpa_core::CURRENT_FORMAT_VERSION;
+use rpa_core::library::{
+    LIBRARY_EXTENSION, LibraryImport, ScenarioLibrary, load_library, resolve_imports, save_library,
+};
 use rpa_core::log::{LogActivity, LogEntry, LogLevel};
-use rpa_core::{Project, ProjectFile};
+use rpa_core::project_format::{PROJECT_EXTENSION, PROJECT_MANIFEST};
 use rust_i18n::t;
 
 impl RpaApp {
@@ -14,115 +18,213 @@ impl RpaApp {
 
     pub fn save_project_as(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
-            .add_filter("RPA Project", &["rpa"])
+            .add_filter("RPA Project", &[PROJECT_EXTENSION])
             .save_file()
         {
             self.save_to_file(path);
         }
     }
 
+    pub fn save_project_as_directory(&mut self) {
+        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+            self.save_to_file(dir.join(PROJECT_MANIFEST));
+        }
+    }
+
     pub fn save_to_file(&mut self, path: std::path::PathBuf) {
-        let project_file = ProjectFile {
-            project: self.project.clone(),
-        };
+        match rpa_core::save_project(&self.project, &path) {
+            Ok(()) => {
+                self.current_file = Some(path.clone());
+                // TODO: Decide undo history behavior on save:
+                // - Option 1: Clear history on file save (cleaner UX, current recommendation)
+                // - Option 2: Persist history to temporary state file (more powerful, uses disk space)
+                // - Option 3: Keep history independent of save (current implementation)
+                // When decided, call: self.undo_redo.clear_undo_history();
+                self.project.execution_log.push(LogEntry {
+                    timestamp: "[00:00.00]".to_string(),
+                    node_id: None,
+                    level: LogLevel::Info,
+                    activity: LogActivity::System,
+                    message: t!("system_messages.project_saved", path = path.display()).to_string(),
+                });
+            }
+            Err(e) => {
+                self.project.execution_log.push(LogEntry {
+                    timestamp: "[00:00.00]".to_string(),
+                    node_id: None,
+                    level: LogLevel::Error,
+                    activity: LogActivity::System,
+                    message: t!("system_messages.failed_save", error = e).to_string(),
+                });
+            }
+        }
+    }
 
-        match serde_json::to_string(&project_file) {
-            Ok(json) => match std::fs::write(&path, json) {
-                Ok(_) => {
-                    self.current_file = Some(path.clone());
-                    // TODO: Decide undo history behavior on save:
-                    // - Option 1: Clear history on file save (cleaner UX, current recommendation)
-                    // - Option 2: Persist history to temporary state file (more powerful, uses disk space)
-                    // - Option 3: Keep history independent of save (current implementation)
-                    // When decided, call: self.undo_redo.clear_undo_history();
-                    self.project.execution_log.push(LogEntry {
-                        timestamp: "[00:00.00]".to_string(),
+    pub fn open_project(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("RPA Project", &[PROJECT_EXTENSION, "toml"])
+            .pick_file()
+        {
+            match rpa_core::load_project(&path) {
+                Ok(loaded) => {
+                    let mut project = loaded.project;
+                    project.execution_log.clear();
+                    project.execution_log.push(LogEntry {
+                        timestamp: "".to_string(),
                         node_id: None,
                         level: LogLevel::Info,
                         activity: LogActivity::System,
-                        message: t!("system_messages.project_saved", path = path.display())
+                        message: t!("system_messages.project_loaded", path = path.display())
                             .to_string(),
                     });
+
+                    if loaded.source_version < CURRENT_FORMAT_VERSION {
+                        project.execution_log.push(LogEntry {
+                            timestamp: "".to_string(),
+                            node_id: None,
+                            level: LogLevel::Info,
+                            activity: LogActivity::System,
+                            message: t!(
+                                "system_messages.project_migrated",
+                                from = loaded.source_version,
+                                to = CURRENT_FORMAT_VERSION
+                            )
+                            .to_string(),
+                        });
+                    }
+
+                    for warning in loaded.warnings {
+                        project.execution_log.push(LogEntry {
+                            timestamp: "".to_string(),
+                            node_id: None,
+                            level: LogLevel::Warning,
+                            activity: LogActivity::System,
+                            message: warning,
+                        });
+                    }
+
+                    self.project = project;
+                    self.current_scenario_index = None;
+                    self.init_current_scenario_view();
+
+                    rust_i18n::set_locale(&self.settings.language);
+                    self.current_file = Some(path);
+
+                    self.selected_nodes.clear();
                 }
                 Err(e) => {
                     self.project.execution_log.push(LogEntry {
-                        timestamp: "[00:00.00]".to_string(),
+                        timestamp: "".to_string(),
                         node_id: None,
                         level: LogLevel::Error,
                         activity: LogActivity::System,
-                        message: t!("system_messages.failed_save", error = e).to_string(),
+                        message: t!("system_messages.failed_parse", error = e).to_string(),
                     });
                 }
-            },
-            Err(e) => {
-                self.project.execution_log.push(LogEntry {
-                    timestamp: "[00:00.00]".to_string(),
-                    node_id: None,
-                    level: LogLevel::Error,
-                    activity: LogActivity::System,
-                    message: t!("system_messages.failed_serialize", error = e).to_string(),
-                });
             }
         }
     }
 
-    pub fn open_project(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("RPA Project", &["rpa"])
+    fn project_dir(&self) -> std::path::PathBuf {
+        self.current_file
+            .as_ref()
+            .and_then(|p| p.parent())
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| std::path::PathBuf::from("."))
+    }
+
+    pub fn import_library(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("RPA Library", &[LIBRARY_EXTENSION])
             .pick_file()
-        {
-            match std::fs::read_to_string(&path) {
-                Ok(contents) => {
-                    let result = serde_json::from_str::<ProjectFile>(&contents).or_else(|_| {
-                        serde_json::from_str::<Project>(&contents)
-                            .map(|project| ProjectFile { project })
-                    });
+        else {
+            return;
+        };
 
-                    match result {
-                        Ok(mut project_file) => {
-                            project_file.project.execution_log.clear();
-                            project_file.project.execution_log.push(LogEntry {
-                                timestamp: "".to_string(),
-                                node_id: None,
-                                level: LogLevel::Info,
-                                activity: LogActivity::System,
-                                message: t!(
-                                    "system_messages.project_loaded",
-                                    path = path.display()
-                                )
-                                .to_string(),
-                            });
-
-                            self.project = project_file.project;
-                            self.current_scenario_index = None;
-                            self.init_current_scenario_view();
-
-                            rust_i18n::set_locale(&self.settings.language);
-                            self.current_file = Some(path);
-
-                            self.selected_nodes.clear();
-                        }
-                        Err(e) => {
-                            self.project.execution_log.push(LogEntry {
-                                timestamp: "".to_string(),
-                                node_id: None,
-                                level: LogLevel::Error,
-                                activity: LogActivity::System,
-                                message: t!("system_messages.failed_parse", error = e).to_string(),
-                            });
-                        }
-                    }
+        let (level, message) = match load_library(&path) {
+            Ok(library) => {
+                let base_dir = self.project_dir();
+                let import_path = path
+                    .strip_prefix(&base_dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .to_string();
+
+                if self.project.imports.iter().any(|i| i.path == import_path) {
+                    return;
                 }
-                Err(e) => {
+
+                self.project
+                    .imports
+                    .push(LibraryImport::new(&import_path, &library.version));
+                for error in resolve_imports(&mut self.project, &base_dir) {
                     self.project.execution_log.push(LogEntry {
                         timestamp: "[00:00.00]".to_string(),
                         node_id: None,
-                        level: LogLevel::Error,
+                        level: LogLevel::Warning,
                         activity: LogActivity::System,
-                        message: t!("system_messages.failed_read", error = e).to_string(),
+                        message: error,
                     });
                 }
+
+                (
+                    LogLevel::Info,
+                    t!(
+                        "system_messages.library_imported",
+                        name = library.name,
+                        version = library.version
+                    )
+                    .to_string(),
+                )
             }
+            Err(e) => (LogLevel::Error, e),
+        };
+
+        self.project.execution_log.push(LogEntry {
+            timestamp: "[00:00.00]".to_string(),
+            node_id: None,
+            level,
+            activity: LogActivity::System,
+            message,
+        });
+    }
+
+    pub fn remove_library_import(&mut self, index: usize) {
+        if index < self.project.imports.len() {
+            self.project.imports.remove(index);
+            let base_dir = self.project_dir();
+            resolve_imports(&mut self.project, &base_dir);
         }
     }
+
+    pub fn export_library(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("RPA Library", &[LIBRARY_EXTENSION])
+            .save_file()
+        else {
+            return;
+        };
+
+        let library = ScenarioLibrary::new(
+            &self.dialogs.export_library.name,
+            &self.dialogs.export_library.version,
+            self.project.scenarios.clone(),
+        );
+
+        let (level, message) = match save_library(&library, &path) {
+            Ok(()) => (
+                LogLevel::Info,
+                t!("system_messages.library_exported", path = path.display()).to_string(),
+            ),
+            Err(e) => (LogLevel::Error, e),
+        };
+
+        self.project.execution_log.push(LogEntry {
+            timestamp: "[00:00.00]".to_string(),
+            node_id: None,
+            level,
+            activity: LogActivity::System,
+            message,
+        });
+    }
 }
@@ -17,7 +17,9 @@ use eframe::egui;
 use egui::IconData;
 use rpa_core::execution::{ExecutionContext, ScopeFrame};
 use rpa_core::log::{LogActivity, LogEntry, LogLevel};
-use rpa_core::{CoreConstants, IrBuilder, ScenarioValidator, get_timestamp};
+use rpa_core::secrets::{SecretVault, resolve_project_secrets, resolve_secrets, secret_env_var};
+use rpa_core::variables::Variables;
+use rpa_core::{CoreConstants, IrBuilder, Project, ScenarioValidator, get_timestamp};
 use rust_i18n::t;
 use state::RpaApp;
 use std::sync::mpsc::{channel, sync_channel};
@@ -176,11 +178,13 @@ impl RpaApp {
         };
 
         let stop_control = self.stop_control.clone();
-        let variables = self.global_variables.clone();
+        let mut variables = self.global_variables.clone();
+        let mut project = self.project.clone();
+        self.resolve_secrets(&mut variables, &mut project, start_time);
 
         let scope_stack = vec![ScopeFrame {
-            scenario_id: self.project.main_scenario.id.clone(),
-            variables: self.project.main_scenario.variables.clone(),
+            scenario_id: project.main_scenario.id.clone(),
+            variables: project.main_scenario.variables.clone(),
         }];
 
         let context = ExecutionContext::new_without_sender(
@@ -196,7 +200,7 @@ impl RpaApp {
         self.event_receiver = Some(event_rx);
         self.cmd_sender = Some(cmd_tx);
 
-        let project = std::sync::Arc::new(self.project.clone());
+        let project = std::sync::Arc::new(project);
 
         std::thread::spawn(move || {
             let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
@@ -253,6 +257,54 @@ impl RpaApp {
         });
     }
 
+    /// Fills secret variables for a run; the project itself keeps only the secret names.
+    fn resolve_secrets(
+        &mut self,
+        variables: &mut Variables,
+        project: &mut Project,
+        start_time: SystemTime,
+    ) {
+        let has_secrets = project
+            .all_scenarios()
+            .map(|s| &s.variables)
+            .chain([&project.variables, &*variables])
+            .any(|vars| vars.secret_names().next().is_some());
+        if !has_secrets {
+            return;
+        }
+
+        let vault = match SecretVault::open_default() {
+            Ok(vault) => Some(vault),
+            Err(e) => {
+                self.project.execution_log.push(LogEntry {
+                    timestamp: get_timestamp(start_time),
+                    node_id: None,
+                    level: LogLevel::Warning,
+                    activity: LogActivity::System,
+                    message: t!("system_messages.vault_error", error = e).to_string(),
+                });
+                None
+            }
+        };
+
+        let mut missing = resolve_secrets(variables, vault.as_ref());
+        missing.extend(resolve_project_secrets(project, vault.as_ref()));
+        for name in missing {
+            self.project.execution_log.push(LogEntry {
+                timestamp: get_timestamp(start_time),
+                node_id: None,
+                level: LogLevel::Warning,
+                activity: LogActivity::System,
+                message: t!(
+                    "system_messages.secret_missing",
+                    name = name,
+                    env = secret_env_var(&name)
+                )
+                .to_string(),
+            });
+        }
+    }
+
     fn compile_ir_for_debug(&mut self) {
         let scenario = self.get_current_scenario();
         let validator = ScenarioValidator::new(scenario, &self.project);
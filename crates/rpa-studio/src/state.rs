@@ -56,6 +56,8 @@ pub struct RpaApp {
     pub needs_repaint: bool,
     pub is_interacting: bool,
     pub quick_connect_start_pos: Option<egui::Pos2>,
+    /// Set while user actions are being recorded into a draft workflow
+    pub recorder: Option<ui_automation::Recorder>,
 }
 
 impl Default for RpaApp {
@@ -92,6 +94,7 @@ impl Default for RpaApp {
             needs_repaint: true,
             is_interacting: false,
             quick_connect_start_pos: None,
+            recorder: None,
         }
     }
 }
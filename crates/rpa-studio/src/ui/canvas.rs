@@ -12,7 +12,12 @@ use egui::{
 };
 use egui_code_editor::{CodeEditor, ColorTheme, Syntax};
 
+use rpa_core::csv::{CsvDelimiter, CsvQuote};
+use rpa_core::desktop::ClickKind;
+use rpa_core::files::TextEncoding;
+use rpa_core::http::{HttpBodyKind, HttpMethod};
 use rpa_core::log::LogLevel;
+use rpa_core::mail::MailSecurity;
 use rpa_core::{Activity, ActivityMetadata, BranchType, Node, PropertyType, Scenario};
 use rust_i18n::t;
 use shared::NanoId;
@@ -1555,11 +1560,20 @@ pub fn render_node_properties(
                             ui.make_persistent_id(format!("{}_eval_expr_{}", node.id, prop_idx));
                         ui.add(egui::TextEdit::singleline(expression).id(expr_id));
                     }
-                    _ => {}
+                    activity => {
+                        if let Some(text) = file_text_property(activity, prop_idx) {
+                            let text_id = ui
+                                .make_persistent_id(format!("{}_file_text_{}", node.id, prop_idx));
+                            ui.add(egui::TextEdit::singleline(text).id(text_id));
+                        }
+                    }
                 }
             }
             PropertyType::TextMultiLine => {
-                ui.label(&label);
+                let label_widget = ui.label(&label);
+                if let Some(tooltip) = prop_def.tooltip_key {
+                    label_widget.on_hover_text(t!(tooltip).as_ref());
+                }
 
                 match &mut node.activity {
                     Activity::Log { level, message } => {
@@ -1578,11 +1592,236 @@ pub fn render_node_properties(
                         let note_id = ui.make_persistent_id(format!("{}_note_text", node.id));
                         ui.add(egui::TextEdit::multiline(text).id(note_id).desired_rows(8));
                     }
+                    Activity::WriteTextFile { content, .. } => {
+                        let content_id = ui.make_persistent_id(format!("{}_file_content", node.id));
+                        ui.add(egui::TextEdit::multiline(content).id(content_id));
+                    }
+                    Activity::SendEmail { body, .. } => {
+                        let body_id = ui.make_persistent_id(format!("{}_mail_body", node.id));
+                        ui.add(egui::TextEdit::multiline(body).id(body_id));
+                    }
+                    Activity::AppendCsvRow { values, .. } => {
+                        let values_id = ui.make_persistent_id(format!("{}_csv_values", node.id));
+                        ui.add(egui::TextEdit::multiline(values).id(values_id));
+                    }
+                    Activity::HttpRequest {
+                        query,
+                        headers,
+                        body,
+                        ..
+                    } => {
+                        let text = match prop_idx {
+                            2 => query,
+                            3 => headers,
+                            _ => body,
+                        };
+                        let text_id =
+                            ui.make_persistent_id(format!("{}_http_text_{}", node.id, prop_idx));
+                        ui.add(egui::TextEdit::multiline(text).id(text_id).desired_rows(3));
+                    }
+                    Activity::ExecuteQuery {
+                        sql, parameters, ..
+                    }
+                    | Activity::ExecuteNonQuery {
+                        sql, parameters, ..
+                    } => {
+                        let text = if prop_idx == 1 { sql } else { parameters };
+                        let text_id =
+                            ui.make_persistent_id(format!("{}_sql_text_{}", node.id, prop_idx));
+                        ui.add(
+                            egui::TextEdit::multiline(text)
+                                .id(text_id)
+                                .code_editor()
+                                .desired_rows(3),
+                        );
+                    }
                     _ => {}
                 }
             }
+            PropertyType::Combobox => match &mut node.activity {
+                Activity::ReadTextFile { encoding, .. }
+                | Activity::WriteTextFile { encoding, .. } => {
+                    ui.label(&label);
+                    egui::ComboBox::from_id_salt("file_encoding_combo")
+                        .selected_text(encoding.as_str())
+                        .show_ui(ui, |ui| {
+                            for option in TextEncoding::all() {
+                                ui.selectable_value(encoding, option, option.as_str());
+                            }
+                        });
+                }
+                Activity::SendEmail { security, .. } | Activity::ReadEmails { security, .. } => {
+                    let label_widget = ui.label(&label);
+                    if let Some(tooltip) = prop_def.tooltip_key {
+                        label_widget.on_hover_text(t!(tooltip).as_ref());
+                    }
+                    egui::ComboBox::from_id_salt("mail_security_combo")
+                        .selected_text(security.as_str())
+                        .show_ui(ui, |ui| {
+                            for option in MailSecurity::all() {
+                                ui.selectable_value(security, option, option.as_str());
+                            }
+                        });
+                }
+                Activity::ReadCsv {
+                    delimiter,
+                    quote,
+                    encoding,
+                    ..
+                }
+                | Activity::WriteCsv {
+                    delimiter,
+                    quote,
+                    encoding,
+                    ..
+                }
+                | Activity::AppendCsvRow {
+                    delimiter,
+                    quote,
+                    encoding,
+                    ..
+                } => {
+                    let label_widget = ui.label(&label);
+                    if let Some(tooltip) = prop_def.tooltip_key {
+                        label_widget.on_hover_text(t!(tooltip).as_ref());
+                    }
+                    match prop_def.label_key {
+                        "properties.delimiter" => {
+                            egui::ComboBox::from_id_salt("csv_delimiter_combo")
+                                .selected_text(delimiter.as_str())
+                                .show_ui(ui, |ui| {
+                                    for option in CsvDelimiter::all() {
+                                        ui.selectable_value(delimiter, option, option.as_str());
+                                    }
+                                });
+                        }
+                        "properties.quote" => {
+                            egui::ComboBox::from_id_salt("csv_quote_combo")
+                                .selected_text(quote.as_str())
+                                .show_ui(ui, |ui| {
+                                    for option in CsvQuote::all() {
+                                        ui.selectable_value(quote, option, option.as_str());
+                                    }
+                                });
+                        }
+                        _ => {
+                            egui::ComboBox::from_id_salt("csv_encoding_combo")
+                                .selected_text(encoding.as_str())
+                                .show_ui(ui, |ui| {
+                                    for option in TextEncoding::all() {
+                                        ui.selectable_value(encoding, option, option.as_str());
+                                    }
+                                });
+                        }
+                    }
+                }
+                Activity::HttpRequest {
+                    method, body_kind, ..
+                } => {
+                    let label_widget = ui.label(&label);
+                    if let Some(tooltip) = prop_def.tooltip_key {
+                        label_widget.on_hover_text(t!(tooltip).as_ref());
+                    }
+                    if prop_idx == 0 {
+                        egui::ComboBox::from_id_salt("http_method_combo")
+                            .selected_text(method.as_str())
+                            .show_ui(ui, |ui| {
+                                for option in HttpMethod::all() {
+                                    ui.selectable_value(method, option, option.as_str());
+                                }
+                            });
+                    } else {
+                        egui::ComboBox::from_id_salt("http_body_kind_combo")
+                            .selected_text(body_kind.as_str())
+                            .show_ui(ui, |ui| {
+                                for option in HttpBodyKind::all() {
+                                    ui.selectable_value(body_kind, option, option.as_str());
+                                }
+                            });
+                    }
+                }
+                Activity::Click { click, .. } | Activity::ClickImage { click, .. } => {
+                    ui.label(&label);
+                    egui::ComboBox::from_id_salt("click_kind_combo")
+                        .selected_text(click.as_str())
+                        .show_ui(ui, |ui| {
+                            for option in ClickKind::all() {
+                                ui.selectable_value(click, option, option.as_str());
+                            }
+                        });
+                }
+                _ => {}
+            },
+            PropertyType::Checkbox => match &mut node.activity {
+                Activity::SendEmail {
+                    allow_insecure_login,
+                    ..
+                }
+                | Activity::ReadEmails {
+                    allow_insecure_login,
+                    ..
+                } if prop_def.label_key == "properties.allow_insecure_login" => {
+                    let response = ui.checkbox(allow_insecure_login, label);
+                    if let Some(tooltip) = prop_def.tooltip_key {
+                        response.on_hover_text(t!(tooltip).as_ref());
+                    }
+                }
+                Activity::WriteTextFile { append: value, .. }
+                | Activity::CopyFile {
+                    overwrite: value, ..
+                }
+                | Activity::MoveFile {
+                    overwrite: value, ..
+                }
+                | Activity::ListFiles {
+                    recursive: value, ..
+                }
+                | Activity::ExcelReadRange {
+                    has_headers: value, ..
+                }
+                | Activity::ExcelWriteRange {
+                    write_headers: value,
+                    ..
+                }
+                | Activity::SendEmail { html: value, .. }
+                | Activity::ReadEmails {
+                    mark_read: value, ..
+                }
+                | Activity::SaveAttachments {
+                    include_inline: value,
+                    ..
+                }
+                | Activity::ReadCsv {
+                    has_headers: value, ..
+                }
+                | Activity::WriteCsv {
+                    write_headers: value,
+                    ..
+                }
+                | Activity::ToJson { pretty: value, .. } => {
+                    ui.checkbox(value, label);
+                }
+                Activity::TypeInto {
+                    simulate_typing, ..
+                } => {
+                    let response = ui.checkbox(simulate_typing, label);
+                    if let Some(tooltip) = prop_def.tooltip_key {
+                        response.on_hover_text(t!(tooltip).as_ref());
+                    }
+                }
+                _ => {}
+            },
             PropertyType::Slider => {
-                ui.label(&label);
+                let label_widget = ui.label(&label);
+                if let Some(tooltip) = prop_def.tooltip_key {
+                    label_widget.on_hover_text(t!(tooltip).as_ref());
+                }
+
+                if let Activity::ClickImage { confidence, .. }
+                | Activity::WaitForImage { confidence, .. } = &mut node.activity
+                {
+                    ui.add(egui::Slider::new(confidence, 0.0..=1.0).step_by(0.01));
+                }
             }
             PropertyType::CodeEditor => {
                 ui.label(&label);
@@ -1601,7 +1840,10 @@ pub fn render_node_properties(
                 }
             }
             PropertyType::DragInt => {
-                ui.label(&label);
+                let label_widget = ui.label(&label);
+                if let Some(tooltip) = prop_def.tooltip_key {
+                    label_widget.on_hover_text(t!(tooltip).as_ref());
+                }
 
                 match &mut node.activity {
                     Activity::Loop {
@@ -1625,6 +1867,54 @@ pub fn render_node_properties(
                                 .speed(25),
                         );
                     }
+                    Activity::WaitForFile { timeout_ms, .. } => {
+                        ui.add(
+                            egui::DragValue::new(timeout_ms)
+                                .range(0..=u64::MAX)
+                                .speed(100),
+                        );
+                    }
+                    Activity::SendEmail { port, .. } | Activity::ReadEmails { port, .. }
+                        if prop_idx == 1 =>
+                    {
+                        ui.add(egui::DragValue::new(port).range(1..=u16::MAX));
+                    }
+                    Activity::ReadEmails { limit, .. } => {
+                        ui.add(egui::DragValue::new(limit).range(0..=u64::MAX));
+                    }
+                    Activity::ReadCsv {
+                        skip_rows,
+                        max_rows,
+                        ..
+                    } => {
+                        let rows = if prop_idx == 5 { skip_rows } else { max_rows };
+                        ui.add(egui::DragValue::new(rows).range(0..=u64::MAX).speed(10));
+                    }
+                    Activity::HttpRequest { timeout_ms, .. } if prop_idx == 6 => {
+                        ui.add(
+                            egui::DragValue::new(timeout_ms)
+                                .range(0..=u64::MAX)
+                                .speed(100),
+                        );
+                    }
+                    Activity::HttpRequest { retries, .. } => {
+                        ui.add(egui::DragValue::new(retries).range(0..=10));
+                    }
+                    Activity::Click { timeout_ms, .. }
+                    | Activity::TypeInto { timeout_ms, .. }
+                    | Activity::GetText { timeout_ms, .. }
+                    | Activity::WaitForElement { timeout_ms, .. }
+                    | Activity::SendKeys { timeout_ms, .. }
+                    | Activity::CloseWindow { timeout_ms, .. }
+                    | Activity::ClickImage { timeout_ms, .. }
+                    | Activity::WaitForImage { timeout_ms, .. }
+                    | Activity::TakeScreenshot { timeout_ms, .. } => {
+                        ui.add(
+                            egui::DragValue::new(timeout_ms)
+                                .range(0..=u64::MAX)
+                                .speed(100),
+                        );
+                    }
                     _ => {}
                 }
             }
@@ -1760,3 +2050,120 @@ pub fn render_node_properties(
 
     (node.activity != original_activity, param_action)
 }
+
+/// Single-line text fields of the Files, Excel, Word, Mail, CSV, JSON/XML and HTTP activities, by property index.
+fn file_text_property(activity: &mut Activity, prop_idx: usize) -> Option<&mut String> {
+    match (activity, prop_idx) {
+        (Activity::ReadTextFile { path, .. }, 0)
+        | (Activity::WriteTextFile { path, .. }, 0)
+        | (Activity::DeleteFile { path }, 0)
+        | (Activity::CreateDirectory { path }, 0)
+        | (Activity::FileExists { path, .. }, 0)
+        | (Activity::WaitForFile { path, .. }, 0) => Some(path),
+        (Activity::CopyFile { source, .. }, 0) | (Activity::MoveFile { source, .. }, 0) => {
+            Some(source)
+        }
+        (Activity::CopyFile { destination, .. }, 1)
+        | (Activity::MoveFile { destination, .. }, 1) => Some(destination),
+        (Activity::ListFiles { directory, .. }, 0) => Some(directory),
+        (Activity::ListFiles { pattern, .. }, 1) => Some(pattern),
+        (Activity::ReadTextFile { output_var, .. }, 2)
+        | (Activity::ListFiles { output_var, .. }, 3)
+        | (Activity::FileExists { output_var, .. }, 1) => Some(output_var),
+        (Activity::ExcelReadRange { path, .. }, 0)
+        | (Activity::ExcelReadCell { path, .. }, 0)
+        | (Activity::ExcelWriteCell { path, .. }, 0)
+        | (Activity::ExcelWriteRange { path, .. }, 0)
+        | (Activity::ExcelAppendRows { path, .. }, 0)
+        | (Activity::ExcelListSheets { path, .. }, 0) => Some(path),
+        (Activity::ExcelReadRange { sheet, .. }, 1)
+        | (Activity::ExcelReadCell { sheet, .. }, 1)
+        | (Activity::ExcelWriteCell { sheet, .. }, 1)
+        | (Activity::ExcelWriteRange { sheet, .. }, 1)
+        | (Activity::ExcelAppendRows { sheet, .. }, 1) => Some(sheet),
+        (Activity::ExcelReadRange { range, .. }, 2) => Some(range),
+        (Activity::ExcelReadCell { cell, .. }, 2)
+        | (Activity::ExcelWriteCell { cell, .. }, 2)
+        | (Activity::ExcelWriteRange { cell, .. }, 2) => Some(cell),
+        (Activity::ExcelWriteCell { value, .. }, 3)
+        | (Activity::ExcelWriteRange { value, .. }, 3)
+        | (Activity::ExcelAppendRows { value, .. }, 2) => Some(value),
+        (Activity::ExcelReadRange { output_var, .. }, 4)
+        | (Activity::ExcelReadCell { output_var, .. }, 3)
+        | (Activity::ExcelListSheets { output_var, .. }, 1) => Some(output_var),
+        (Activity::FillWordTemplate { template_path, .. }, 0) => Some(template_path),
+        (Activity::FillWordTemplate { output_path, .. }, 1) => Some(output_path),
+        (Activity::FillWordTemplate { values, .. }, 2) => Some(values),
+        (Activity::SendEmail { server, .. }, 0) | (Activity::ReadEmails { server, .. }, 0) => {
+            Some(server)
+        }
+        (Activity::SendEmail { username, .. }, 3) | (Activity::ReadEmails { username, .. }, 3) => {
+            Some(username)
+        }
+        (Activity::SendEmail { password, .. }, 4) | (Activity::ReadEmails { password, .. }, 4) => {
+            Some(password)
+        }
+        (Activity::SendEmail { from, .. }, 5) => Some(from),
+        (Activity::SendEmail { to, .. }, 6) => Some(to),
+        (Activity::SendEmail { cc, .. }, 7) => Some(cc),
+        (Activity::SendEmail { subject, .. }, 8) => Some(subject),
+        (Activity::SendEmail { attachments, .. }, 11) => Some(attachments),
+        (Activity::ReadEmails { folder, .. }, 5) => Some(folder),
+        (Activity::ReadEmails { criteria, .. }, 6) => Some(criteria),
+        (
+            Activity::ReadEmails {
+                attachments_dir, ..
+            },
+            9,
+        ) => Some(attachments_dir),
+        (Activity::ReadEmails { output_var, .. }, 10) => Some(output_var),
+        (Activity::SendEmail { ca_bundle, .. }, 13)
+        | (Activity::ReadEmails { ca_bundle, .. }, 12) => Some(ca_bundle),
+        (Activity::ParseEmail { path, .. }, 0) | (Activity::SaveAttachments { path, .. }, 0) => {
+            Some(path)
+        }
+        (Activity::SaveAttachments { directory, .. }, 1) => Some(directory),
+        (Activity::ParseEmail { output_var, .. }, 1)
+        | (Activity::SaveAttachments { output_var, .. }, 3) => Some(output_var),
+        (Activity::ReadCsv { path, .. }, 0)
+        | (Activity::WriteCsv { path, .. }, 0)
+        | (Activity::AppendCsvRow { path, .. }, 0) => Some(path),
+        (Activity::WriteCsv { value, .. }, 1) => Some(value),
+        (Activity::ReadCsv { output_var, .. }, 7) => Some(output_var),
+        (Activity::ReadCsv { row_count_var, .. }, 8) => Some(row_count_var),
+        (Activity::ParseJson { text, .. }, 0) | (Activity::ParseXml { text, .. }, 0) => Some(text),
+        (Activity::ToJson { value, .. }, 0) => Some(value),
+        (Activity::ParseJson { output_var, .. }, 1)
+        | (Activity::ParseXml { output_var, .. }, 1)
+        | (Activity::ToJson { output_var, .. }, 2) => Some(output_var),
+        (Activity::HttpRequest { url, .. }, 1) => Some(url),
+        (Activity::HttpRequest { status_var, .. }, 8) => Some(status_var),
+        (Activity::HttpRequest { headers_var, .. }, 9) => Some(headers_var),
+        (Activity::HttpRequest { output_var, .. }, 10) => Some(output_var),
+        (Activity::ExecuteQuery { connection, .. }, 0)
+        | (Activity::ExecuteNonQuery { connection, .. }, 0)
+        | (Activity::BeginTransaction { connection }, 0)
+        | (Activity::CommitTransaction { connection }, 0)
+        | (Activity::RollbackTransaction { connection }, 0) => Some(connection),
+        (Activity::ExecuteQuery { output_var, .. }, 3)
+        | (Activity::ExecuteNonQuery { output_var, .. }, 3) => Some(output_var),
+        (Activity::Click { selector, .. }, 0)
+        | (Activity::TypeInto { selector, .. }, 0)
+        | (Activity::GetText { selector, .. }, 0)
+        | (Activity::WaitForElement { selector, .. }, 0)
+        | (Activity::SendKeys { selector, .. }, 0)
+        | (Activity::CloseWindow { selector, .. }, 0)
+        | (Activity::TakeScreenshot { selector, .. }, 0) => Some(selector),
+        (Activity::TypeInto { text, .. }, 1) => Some(text),
+        (Activity::SendKeys { keys, .. }, 1) => Some(keys),
+        (Activity::LaunchApplication { path, .. }, 0) => Some(path),
+        (Activity::LaunchApplication { arguments, .. }, 1) => Some(arguments),
+        (Activity::GetText { output_var, .. }, 2)
+        | (Activity::LaunchApplication { output_var, .. }, 2)
+        | (Activity::TakeScreenshot { output_var, .. }, 2) => Some(output_var),
+        (Activity::ClickImage { image_path, .. }, 0)
+        | (Activity::WaitForImage { image_path, .. }, 0) => Some(image_path),
+        (Activity::TakeScreenshot { path, .. }, 1) => Some(path),
+        _ => None,
+    }
+}
@@ -7,7 +7,10 @@ use arc_script::{Value, VariableType};
 use eframe::egui;
 use egui::{DragValue, Slider, Vec2};
 use egui_extras::{Column, TableBuilder};
+use rpa_core::database::{DatabaseConnection, DatabaseKind};
+use rpa_core::library::LibraryVersion;
 use rpa_core::log::{LogActivity, LogEntry, LogLevel};
+use rpa_core::secrets::SECRET_MASK;
 use rpa_core::{
     Activity, CoreConstants, ExecutionCommand, Project, Scenario, Variables,
     node_graph::VariableDirection, variables::VariableScope,
@@ -115,7 +118,7 @@ impl RpaApp {
 
                         let should_add_node = if matches!(*activity, Activity::CallScenario { .. })
                         {
-                            !self.project.scenarios.is_empty()
+                            self.project.callable_scenarios().next().is_some()
                         } else {
                             true
                         };
@@ -123,7 +126,12 @@ impl RpaApp {
                         if should_add_node {
                             let final_activity =
                                 if matches!(*activity, Activity::CallScenario { .. }) {
-                                    let scenario_id = self.project.scenarios[0].id.clone();
+                                    let scenario_id = self
+                                        .project
+                                        .callable_scenarios()
+                                        .next()
+                                        .map(|s| s.id.clone())
+                                        .unwrap_or_default();
                                     Activity::CallScenario {
                                         scenario_id,
                                         parameters: Vec::new(),
@@ -492,6 +500,119 @@ impl RpaApp {
             }
         }
 
+        if let Some(index) = self.dialogs.database_connection.connection_index {
+            let mut close_window = false;
+
+            if let Some(connection) = self.project.databases.get_mut(index) {
+                egui::Window::new(t!("database_dialog.title").as_ref())
+                    .id(egui::Id::new("database_connection_window"))
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        egui::Grid::new("database_connection_grid")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.label(t!("database_dialog.name").as_ref());
+                                ui.text_edit_singleline(&mut connection.name);
+                                ui.end_row();
+
+                                ui.label(t!("database_dialog.kind").as_ref());
+                                egui::ComboBox::from_id_salt("database_kind_combo")
+                                    .selected_text(connection.kind.as_str())
+                                    .show_ui(ui, |ui| {
+                                        for option in DatabaseKind::all() {
+                                            ui.selectable_value(
+                                                &mut connection.kind,
+                                                option,
+                                                option.as_str(),
+                                            );
+                                        }
+                                    });
+                                ui.end_row();
+
+                                ui.label(t!("database_dialog.target").as_ref())
+                                    .on_hover_text(t!("database_dialog.target_help").as_ref());
+                                ui.text_edit_singleline(&mut connection.target);
+                                ui.end_row();
+
+                                ui.label(t!("database_dialog.username").as_ref());
+                                ui.text_edit_singleline(&mut connection.username);
+                                ui.end_row();
+
+                                ui.label(t!("database_dialog.password").as_ref())
+                                    .on_hover_text(t!("database_dialog.password_help").as_ref());
+                                ui.text_edit_singleline(&mut connection.password);
+                                ui.end_row();
+                            });
+
+                        if ui.button(t!("database_dialog.ok").as_ref()).clicked() {
+                            close_window = true;
+                        }
+                    });
+            } else {
+                close_window = true;
+            }
+
+            if close_window {
+                self.dialogs.database_connection.connection_index = None;
+                self.undo_redo.add_undo(&self.project);
+            }
+        }
+
+        if self.dialogs.export_library.show {
+            let mut close_window = false;
+            let mut export = false;
+
+            egui::Window::new(t!("export_library_dialog.title").as_ref())
+                .id(egui::Id::new("export_library_window"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(t!("export_library_dialog.name").as_ref());
+                    ui.text_edit_singleline(&mut self.dialogs.export_library.name);
+
+                    ui.label(t!("export_library_dialog.version").as_ref());
+                    ui.text_edit_singleline(&mut self.dialogs.export_library.version);
+
+                    let version_valid =
+                        LibraryVersion::parse(&self.dialogs.export_library.version).is_ok();
+                    if !version_valid {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            t!("export_library_dialog.invalid_version").as_ref(),
+                        );
+                    }
+
+                    ui.horizontal(|ui| {
+                        let can_export =
+                            version_valid && !self.dialogs.export_library.name.trim().is_empty();
+                        if ui
+                            .add_enabled(
+                                can_export,
+                                egui::Button::new(t!("export_library_dialog.export").as_ref()),
+                            )
+                            .clicked()
+                        {
+                            export = true;
+                            close_window = true;
+                        }
+                        if ui
+                            .button(t!("export_library_dialog.cancel").as_ref())
+                            .clicked()
+                        {
+                            close_window = true;
+                        }
+                    });
+                });
+
+            if close_window {
+                self.dialogs.export_library.show = false;
+            }
+            if export {
+                self.export_library();
+            }
+        }
+
         if self.dialogs.add_variable.show {
             let mut close_window = false;
 
@@ -549,9 +670,19 @@ impl RpaApp {
                             );
                         });
 
-                    ui.label(t!("add_variable_dialog.value").as_ref());
+                    ui.checkbox(
+                        &mut self.dialogs.add_variable.is_secret,
+                        t!("add_variable_dialog.secret").as_ref(),
+                    );
+                    if self.dialogs.add_variable.is_secret {
+                        self.dialogs.add_variable.var_type = VariableType::String;
+                        self.dialogs.add_variable.value.clear();
+                    } else {
+                        ui.label(t!("add_variable_dialog.value").as_ref());
+                    }
 
                     let value_response = match self.dialogs.add_variable.var_type {
+                        _ if self.dialogs.add_variable.is_secret => name_response.clone(),
                         VariableType::String => {
                             ui.text_edit_singleline(&mut self.dialogs.add_variable.value)
                         }
@@ -602,7 +733,20 @@ impl RpaApp {
                             ) {
                                 Ok(value) => {
                                     let var_name = self.dialogs.add_variable.name.trim();
-                                    if self.dialogs.add_variable.is_global {
+                                    if self.dialogs.add_variable.is_secret {
+                                        if self.dialogs.add_variable.is_global {
+                                            self.global_variables
+                                                .create_secret(var_name, VariableScope::Global);
+                                        } else {
+                                            let scenario = match self.current_scenario_index {
+                                                None => &mut self.project.main_scenario,
+                                                Some(i) => &mut self.project.scenarios[i],
+                                            };
+                                            scenario
+                                                .variables
+                                                .create_secret(var_name, VariableScope::Scenario);
+                                        }
+                                    } else if self.dialogs.add_variable.is_global {
                                         self.global_variables.set(
                                             var_name,
                                             value,
@@ -624,6 +768,7 @@ impl RpaApp {
                                     self.dialogs.add_variable.value.clear();
                                     self.dialogs.add_variable.var_type = VariableType::String;
                                     self.dialogs.add_variable.is_global = false;
+                                    self.dialogs.add_variable.is_secret = false;
                                     close_window = true;
                                 }
                                 Err(err) => {
@@ -649,6 +794,7 @@ impl RpaApp {
                             self.dialogs.add_variable.value.clear();
                             self.dialogs.add_variable.var_type = VariableType::String;
                             self.dialogs.add_variable.is_global = false;
+                            self.dialogs.add_variable.is_secret = false;
                             close_window = true;
                         }
                     });
@@ -840,24 +986,28 @@ impl RpaApp {
             };
 
             if let Some((scenario_id, mut parameters)) = scenario_id_and_params
-                && let Some(called_scenario) = self
+                && self.project.find_scenario(&scenario_id).is_some()
+            {
+                // Imported library scenarios are read-only, only local ones get new parameters
+                if let Some(called_scenario) = self
                     .project
                     .scenarios
                     .iter_mut()
                     .find(|s| s.id == scenario_id)
-            {
-                let target_var_exists = called_scenario
-                    .parameters
-                    .iter()
-                    .any(|p| p.var_name == dialog_state.target_var_name);
-
-                if !target_var_exists {
-                    called_scenario
+                {
+                    let target_var_exists = called_scenario
                         .parameters
-                        .push(rpa_core::node_graph::ScenarioParameter {
-                            var_name: dialog_state.target_var_name.clone(),
-                            direction: dialog_state.direction,
-                        });
+                        .iter()
+                        .any(|p| p.var_name == dialog_state.target_var_name);
+
+                    if !target_var_exists {
+                        called_scenario
+                            .parameters
+                            .push(rpa_core::node_graph::ScenarioParameter {
+                                var_name: dialog_state.target_var_name.clone(),
+                                direction: dialog_state.direction,
+                            });
+                    }
                 }
 
                 let binding = rpa_core::node_graph::VariablesBinding {
@@ -913,13 +1063,37 @@ impl RpaApp {
             });
         self.dialogs.ui_explorer.show = open;
 
+        if let Some(selector) = self.dialogs.ui_explorer.picker.take_new_selector() {
+            self.apply_picked_selector(selector);
+        }
+
         if !open {
+            self.dialogs.ui_explorer.picker.cancel();
             self.dialogs.ui_explorer.root_node = None;
             self.dialogs.ui_explorer.expanded_nodes.clear();
             self.dialogs.ui_explorer.selected_element = None;
         }
     }
 
+    /// Puts a selector captured in the UI Explorer into the selected node,
+    /// when that node targets a UI element.
+    fn apply_picked_selector(&mut self, selector: String) {
+        let Some(node_id) = self.selected_nodes.iter().next().cloned() else {
+            return;
+        };
+
+        let scenario = self.get_current_scenario_mut();
+        let Some(target) = scenario
+            .get_node_mut(node_id)
+            .and_then(|node| node.activity.selector_mut())
+        else {
+            return;
+        };
+
+        *target = selector;
+        self.undo_redo.add_undo(&self.project);
+    }
+
     pub fn render_menu_bar(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
@@ -948,6 +1122,20 @@ impl RpaApp {
                         self.save_project_as();
                         ui.close();
                     }
+                    if ui.button(t!("menu.save_as_directory").as_ref()).clicked() {
+                        self.save_project_as_directory();
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui.button(t!("menu.import_library").as_ref()).clicked() {
+                        self.import_library();
+                        ui.close();
+                    }
+                    if ui.button(t!("menu.export_library").as_ref()).clicked() {
+                        self.dialogs.export_library.name = self.project.name.clone();
+                        self.dialogs.export_library.show = true;
+                        ui.close();
+                    }
                     ui.separator();
                     if ui.button(t!("menu.exit").as_ref()).clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
@@ -980,10 +1168,28 @@ impl RpaApp {
                         self.dialogs.ui_explorer.show = true;
                         ui.close();
                     }
+                    if ui
+                        .add_enabled(
+                            !self.is_executing && self.recorder.is_none(),
+                            egui::Button::new("⏺ Record actions"),
+                        )
+                        .clicked()
+                    {
+                        self.start_recording();
+                        ui.close();
+                    }
                 });
 
                 ui.separator();
 
+                if self.recorder.is_some() {
+                    if ui.button("⏹ Stop recording").clicked() {
+                        self.stop_recording();
+                    }
+                    ui.colored_label(egui::Color32::RED, "● Recording");
+                    ui.separator();
+                }
+
                 if self.is_executing {
                     ui.ctx().set_cursor_icon(egui::CursorIcon::Wait);
                     if ui.button(t!("toolbar.stop").as_ref()).clicked() {
@@ -1097,6 +1303,50 @@ impl RpaApp {
 
             ui.add_space(ui.available_width() - 30.0);
 
+            if !self.project.imports.is_empty() {
+                ui.separator();
+                ui.label(t!("sidebar.libraries").as_ref());
+
+                let mut to_remove: Option<usize> = None;
+                for (i, import) in self.project.imports.iter().enumerate() {
+                    let library = self
+                        .project
+                        .libraries
+                        .iter()
+                        .find(|l| l.source_path == import.path);
+
+                    ui.horizontal(|ui| {
+                        match library {
+                            Some(library) => {
+                                ui.label(format!("📚 {} {}", library.name, library.version))
+                                    .on_hover_text(&import.path);
+                            }
+                            None => {
+                                ui.colored_label(egui::Color32::RED, format!("📚 {}", import.path))
+                                    .on_hover_text(t!("sidebar.library_not_loaded").as_ref());
+                            }
+                        }
+
+                        if ui.small_button("🗑").clicked() {
+                            to_remove = Some(i);
+                        }
+                    });
+
+                    if let Some(library) = library {
+                        ui.indent(("library_scenarios", i), |ui| {
+                            for scenario in &library.scenarios {
+                                ui.label(format!("📁 {}", scenario.name));
+                            }
+                        });
+                    }
+                }
+
+                if let Some(i) = to_remove {
+                    self.remove_library_import(i);
+                    self.undo_redo.add_undo(&self.project);
+                }
+            }
+
             if ui.button(t!("sidebar.new_scenario").as_ref()).clicked() {
                 let new_tab_idx = self.project.scenarios.len();
                 let name = t!(
@@ -1185,6 +1435,93 @@ impl RpaApp {
                 }
             });
 
+        if !self.project.imports.is_empty() {
+            ui.separator();
+            ui.label(t!("sidebar.libraries").as_ref());
+
+            let mut to_remove: Option<usize> = None;
+            for (i, import) in self.project.imports.iter().enumerate() {
+                let library = self
+                    .project
+                    .libraries
+                    .iter()
+                    .find(|l| l.source_path == import.path);
+
+                ui.horizontal(|ui| {
+                    match library {
+                        Some(library) => {
+                            ui.label(format!("📚 {} {}", library.name, library.version))
+                                .on_hover_text(&import.path);
+                        }
+                        None => {
+                            ui.colored_label(egui::Color32::RED, format!("📚 {}", import.path))
+                                .on_hover_text(t!("sidebar.library_not_loaded").as_ref());
+                        }
+                    }
+
+                    if ui.small_button("🗑").clicked() {
+                        to_remove = Some(i);
+                    }
+                });
+
+                if let Some(library) = library {
+                    ui.indent(("library_scenarios", i), |ui| {
+                        for scenario in &library.scenarios {
+                            ui.label(format!("📁 {}", scenario.name));
+                        }
+                    });
+                }
+            }
+
+            if let Some(i) = to_remove {
+                self.remove_library_import(i);
+                self.undo_redo.add_undo(&self.project);
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(t!("sidebar.databases").as_ref());
+
+            if ui
+                .small_button("➕")
+                .on_hover_text(t!("sidebar.new_database").as_ref())
+                .clicked()
+            {
+                let name = t!(
+                    "default_values.database_name",
+                    number = self.project.databases.len() + 1
+                )
+                .to_string();
+                self.project.databases.push(DatabaseConnection::new(&name));
+                self.undo_redo.add_undo(&self.project);
+                self.dialogs.database_connection.connection_index =
+                    Some(self.project.databases.len() - 1);
+            }
+        });
+
+        let mut to_remove: Option<usize> = None;
+        for (i, connection) in self.project.databases.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("🗄 {}", connection.name))
+                    .on_hover_text(connection.kind.as_str());
+
+                if ui.small_button("✏").clicked() {
+                    self.dialogs.database_connection.connection_index = Some(i);
+                }
+
+                if ui.small_button("🗑").clicked() {
+                    to_remove = Some(i);
+                }
+            });
+        }
+
+        if let Some(i) = to_remove {
+            self.project.databases.remove(i);
+            self.undo_redo.add_undo(&self.project);
+            self.dialogs.database_connection.connection_index = None;
+        }
+
         if ui.button(t!("sidebar.new_scenario").as_ref()).clicked() {
             let name = t!(
                 "default_values.scenario_name",
@@ -1217,7 +1554,7 @@ impl RpaApp {
                             for (metadata, activity) in activities {
                                 let should_disable =
                                     matches!(activity, Activity::CallScenario { .. })
-                                        && self.project.scenarios.is_empty();
+                                        && self.project.callable_scenarios().next().is_none();
 
                                 let was_clicked = if should_disable {
                                     let btn_response = ui
@@ -1245,8 +1582,10 @@ impl RpaApp {
 
                                 if was_clicked {
                                     if matches!(activity, Activity::CallScenario { .. }) {
-                                        if !self.project.scenarios.is_empty() {
-                                            let scenario_id = self.project.scenarios[0].id.clone();
+                                        if let Some(scenario) =
+                                            self.project.callable_scenarios().next()
+                                        {
+                                            let scenario_id = scenario.id.clone();
                                             node_to_add = Some(Activity::CallScenario {
                                                 scenario_id,
                                                 parameters: Vec::new(),
@@ -1314,7 +1653,8 @@ impl RpaApp {
                     .auto_shrink([false; 2])
                     .show(ui, |ui| {
                         if let Some(node_id) = self.selected_nodes.iter().next().cloned() {
-                            let scenarios = self.project.scenarios.clone();
+                            let scenarios: Vec<Scenario> =
+                                self.project.callable_scenarios().cloned().collect();
                             let (changed, param_action, activity) = {
                                 let scenario = self.get_current_scenario_mut();
                                 if let Some(node) = scenario.get_node_mut(node_id.clone()) {
@@ -1499,7 +1839,11 @@ impl RpaApp {
                                 });
                                 ui.label(name);
                                 ui.label(value.get_type().as_str());
-                                let value_str = value.to_string();
+                                let value_str = if variables.is_secret(name) {
+                                    format!("🔒 {}", SECRET_MASK)
+                                } else {
+                                    value.to_string()
+                                };
                                 let display_value = if value_str.len() > 20 {
                                     format!("{}...", &value_str[..20])
                                 } else {
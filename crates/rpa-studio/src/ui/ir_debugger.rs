@@ -181,6 +181,330 @@ fn format_instruction(instruction: &Instruction) -> (String, String) {
             format!("{} with {} params", scenario_id, parameters.len()),
         ),
         Instruction::RunPowershell { code } => ("RunPowershell".to_string(), truncate(code, 60)),
+        Instruction::ReadTextFile {
+            path,
+            encoding,
+            output_var,
+        } => (
+            "ReadTextFile".to_string(),
+            format!("{:?} ({}) → {}", path, encoding.as_str(), output_var),
+        ),
+        Instruction::WriteTextFile {
+            path,
+            encoding,
+            append,
+            ..
+        } => (
+            "WriteTextFile".to_string(),
+            format!("{:?} ({}, append: {})", path, encoding.as_str(), append),
+        ),
+        Instruction::CopyFile {
+            source,
+            destination,
+            overwrite,
+            remove_source,
+        } => (
+            if *remove_source {
+                "MoveFile"
+            } else {
+                "CopyFile"
+            }
+            .to_string(),
+            format!(
+                "{:?} → {:?} (overwrite: {})",
+                source, destination, overwrite
+            ),
+        ),
+        Instruction::DeleteFile { path } => ("DeleteFile".to_string(), format!("{:?}", path)),
+        Instruction::CreateDirectory { path } => {
+            ("CreateDirectory".to_string(), format!("{:?}", path))
+        }
+        Instruction::ListFiles {
+            directory,
+            pattern,
+            recursive,
+            output_var,
+        } => (
+            "ListFiles".to_string(),
+            format!(
+                "{:?} {:?} (recursive: {}) → {}",
+                directory, pattern, recursive, output_var
+            ),
+        ),
+        Instruction::FileExists { path, output_var } => (
+            "FileExists".to_string(),
+            format!("{:?} → {}", path, output_var),
+        ),
+        Instruction::WaitForFile { path, timeout_ms } => (
+            "WaitForFile".to_string(),
+            format!("{:?} ({}ms)", path, timeout_ms),
+        ),
+        Instruction::ExcelReadRange {
+            path,
+            sheet,
+            range,
+            has_headers,
+            output_var,
+        } => (
+            "ExcelReadRange".to_string(),
+            format!(
+                "{:?} [{:?}] {:?} (headers: {}) → {}",
+                path, sheet, range, has_headers, output_var
+            ),
+        ),
+        Instruction::ExcelReadCell {
+            path,
+            sheet,
+            cell,
+            output_var,
+        } => (
+            "ExcelReadCell".to_string(),
+            format!("{:?} [{:?}] {:?} → {}", path, sheet, cell, output_var),
+        ),
+        Instruction::ExcelWrite {
+            path,
+            sheet,
+            cell,
+            write_headers,
+            ..
+        } => (
+            "ExcelWrite".to_string(),
+            format!(
+                "{:?} [{:?}] {:?} (headers: {})",
+                path, sheet, cell, write_headers
+            ),
+        ),
+        Instruction::ExcelAppendRows { path, sheet, .. } => (
+            "ExcelAppendRows".to_string(),
+            format!("{:?} [{:?}]", path, sheet),
+        ),
+        Instruction::ExcelListSheets { path, output_var } => (
+            "ExcelListSheets".to_string(),
+            format!("{:?} → {}", path, output_var),
+        ),
+        Instruction::FillWordTemplate {
+            template_path,
+            output_path,
+            values,
+        } => (
+            "FillWordTemplate".to_string(),
+            format!("{:?} → {:?} ({:?})", template_path, output_path, values),
+        ),
+        Instruction::SendEmail {
+            account,
+            to,
+            subject,
+            ..
+        } => (
+            "SendEmail".to_string(),
+            format!(
+                "{:?}:{} {:?} → {:?}",
+                account.server, account.port, subject, to
+            ),
+        ),
+        Instruction::ReadEmails {
+            account,
+            folder,
+            criteria,
+            output_var,
+            ..
+        } => (
+            "ReadEmails".to_string(),
+            format!(
+                "{:?}:{} [{:?}] {:?} → {}",
+                account.server, account.port, folder, criteria, output_var
+            ),
+        ),
+        Instruction::ParseEmail { path, output_var } => (
+            "ParseEmail".to_string(),
+            format!("{:?} → {}", path, output_var),
+        ),
+        Instruction::SaveAttachments {
+            path,
+            directory,
+            output_var,
+            ..
+        } => (
+            "SaveAttachments".to_string(),
+            format!("{:?} → {:?} → {}", path, directory, output_var),
+        ),
+        Instruction::ReadCsv {
+            path,
+            range,
+            output_var,
+            ..
+        } => (
+            "ReadCsv".to_string(),
+            format!(
+                "{:?} [skip {}, max {}] → {}",
+                path, range.skip_rows, range.max_rows, output_var
+            ),
+        ),
+        Instruction::WriteCsv { path, value, .. } => {
+            ("WriteCsv".to_string(), format!("{:?} → {:?}", value, path))
+        }
+        Instruction::AppendCsvRow { path, values, .. } => (
+            "AppendCsvRow".to_string(),
+            format!("{:?} → {:?}", values, path),
+        ),
+        Instruction::ParseJson { text, output_var } => (
+            "ParseJson".to_string(),
+            format!("{:?} → {}", text, output_var),
+        ),
+        Instruction::ToJson {
+            value,
+            pretty,
+            output_var,
+        } => (
+            "ToJson".to_string(),
+            format!("{:?} [pretty {}] → {}", value, pretty, output_var),
+        ),
+        Instruction::ParseXml { text, output_var } => (
+            "ParseXml".to_string(),
+            format!("{:?} → {}", text, output_var),
+        ),
+        Instruction::HttpRequest {
+            method,
+            url,
+            retries,
+            status_var,
+            output_var,
+            ..
+        } => (
+            "HttpRequest".to_string(),
+            format!(
+                "{} {:?} [retries {}] → {}, {}",
+                method.as_str(),
+                url,
+                retries,
+                status_var,
+                output_var
+            ),
+        ),
+        Instruction::ExecuteQuery {
+            connection,
+            sql,
+            parameters,
+            output_var,
+        } => (
+            "ExecuteQuery".to_string(),
+            format!(
+                "'{}' {:?} {:?} → {}",
+                connection, sql, parameters, output_var
+            ),
+        ),
+        Instruction::ExecuteNonQuery {
+            connection,
+            sql,
+            parameters,
+            output_var,
+        } => (
+            "ExecuteNonQuery".to_string(),
+            format!(
+                "'{}' {:?} {:?} → {}",
+                connection, sql, parameters, output_var
+            ),
+        ),
+        Instruction::BeginTransaction { connection } => {
+            ("BeginTransaction".to_string(), format!("'{}'", connection))
+        }
+        Instruction::CommitTransaction { connection } => {
+            ("CommitTransaction".to_string(), format!("'{}'", connection))
+        }
+        Instruction::RollbackTransaction { connection } => (
+            "RollbackTransaction".to_string(),
+            format!("'{}'", connection),
+        ),
+        Instruction::Click {
+            selector,
+            click,
+            timeout_ms,
+        } => (
+            "Click".to_string(),
+            format!(
+                "{} {:?} [timeout {} ms]",
+                click.as_str(),
+                selector,
+                timeout_ms
+            ),
+        ),
+        Instruction::TypeInto {
+            selector,
+            text,
+            simulate_typing,
+            ..
+        } => (
+            "TypeInto".to_string(),
+            format!(
+                "{:?} → {:?} [keystrokes {}]",
+                text, selector, simulate_typing
+            ),
+        ),
+        Instruction::GetText {
+            selector,
+            output_var,
+            ..
+        } => (
+            "GetText".to_string(),
+            format!("{:?} → {}", selector, output_var),
+        ),
+        Instruction::WaitForElement {
+            selector,
+            timeout_ms,
+        } => (
+            "WaitForElement".to_string(),
+            format!("{:?} [timeout {} ms]", selector, timeout_ms),
+        ),
+        Instruction::SendKeys { selector, keys, .. } => (
+            "SendKeys".to_string(),
+            format!("{:?} → {:?}", keys, selector),
+        ),
+        Instruction::LaunchApplication {
+            path,
+            arguments,
+            output_var,
+        } => (
+            "LaunchApplication".to_string(),
+            format!("{:?} {:?} → {:?}", path, arguments, output_var),
+        ),
+        Instruction::CloseWindow { selector, .. } => {
+            ("CloseWindow".to_string(), format!("{:?}", selector))
+        }
+        Instruction::ClickImage {
+            image_path,
+            confidence,
+            click,
+            timeout_ms,
+        } => (
+            "ClickImage".to_string(),
+            format!(
+                "{} {:?} [confidence {:.2}, timeout {} ms]",
+                click.as_str(),
+                image_path,
+                confidence,
+                timeout_ms
+            ),
+        ),
+        Instruction::WaitForImage {
+            image_path,
+            confidence,
+            timeout_ms,
+        } => (
+            "WaitForImage".to_string(),
+            format!(
+                "{:?} [confidence {:.2}, timeout {} ms]",
+                image_path, confidence, timeout_ms
+            ),
+        ),
+        Instruction::TakeScreenshot {
+            selector,
+            path,
+            output_var,
+            ..
+        } => (
+            "TakeScreenshot".to_string(),
+            format!("{:?} → {:?} → {:?}", selector, path, output_var),
+        ),
         Instruction::DebugMarker {
             node_id,
             description,
@@ -7,3 +7,21 @@ pub use win32::*;
 pub mod linux;
 #[cfg(target_os = "linux")]
 pub use linux::*;
+
+pub mod recorder;
+pub use recorder::{
+    ClickTarget, HeldKey, InputState, MouseButton, RecordedAction, RecordedEvent, Recorder,
+};
+
+pub mod screenshot;
+pub use screenshot::{RgbaImage, capture_element, capture_screen, load_image, save_png};
+
+pub mod selector;
+pub use selector::{
+    Combinator, FuzzyMatch, MatchMode, MatchType, Selector, SelectorCriteria, SelectorElement,
+    SelectorPath, control_to_selector, escape_dsl_value, match_string, robust_selector,
+    window_to_selector,
+};
+
+pub mod template_match;
+pub use template_match::{DEFAULT_CONFIDENCE, ImageMatch, find_image_on_screen, find_template};
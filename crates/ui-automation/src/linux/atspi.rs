@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+use zbus::export::serde::Serialize;
+use zbus::zvariant::{DynamicDeserialize, DynamicType, OwnedObjectPath, OwnedValue};
+
+use super::automation::{AutomationError, Rect};
+
+const REGISTRY_NAME: &str = "org.a11y.atspi.Registry";
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+
+const ACCESSIBLE: &str = "org.a11y.atspi.Accessible";
+const ACTION: &str = "org.a11y.atspi.Action";
+const COMPONENT: &str = "org.a11y.atspi.Component";
+const EDITABLE_TEXT: &str = "org.a11y.atspi.EditableText";
+const TEXT: &str = "org.a11y.atspi.Text";
+
+/// `ATSPI_COORD_TYPE_SCREEN`: extents relative to the screen origin.
+const COORD_TYPE_SCREEN: u32 = 0;
+
+/// Action names that trigger a control's primary behaviour, in order of
+/// preference; toolkits differ in what they call it.
+const PRIMARY_ACTIONS: &[&str] = &["click", "press", "activate", "jump", "toggle"];
+
+/// `AtspiStateType` values the backend reads.
+pub mod state {
+    pub const CHECKED: u32 = 4;
+    pub const ENABLED: u32 = 8;
+    pub const FOCUSED: u32 = 12;
+    pub const SENSITIVE: u32 = 24;
+    pub const SHOWING: u32 = 25;
+}
+
+/// An accessible object: the bus name of the application exposing it and its
+/// object path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccessibleRef {
+    pub bus_name: String,
+    pub path: OwnedObjectPath,
+}
+
+/// Accessibles are handed out as negative [`ElementId`](super::ElementId)s so
+/// they never collide with X11 window ids.
+#[derive(Default)]
+struct Handles {
+    refs: Vec<AccessibleRef>,
+    ids: HashMap<AccessibleRef, isize>,
+}
+
+static HANDLES: Mutex<Option<Handles>> = Mutex::new(None);
+static BUS: Mutex<Option<Connection>> = Mutex::new(None);
+
+pub fn handle_for(accessible: &AccessibleRef) -> isize {
+    let mut guard = HANDLES.lock().unwrap_or_else(PoisonError::into_inner);
+    let handles = guard.get_or_insert_with(Handles::default);
+    if let Some(&id) = handles.ids.get(accessible) {
+        return id;
+    }
+    handles.refs.push(accessible.clone());
+    let id = -isize::try_from(handles.refs.len()).unwrap_or(isize::MAX);
+    handles.ids.insert(accessible.clone(), id);
+    drop(guard);
+    id
+}
+
+pub fn resolve_handle(id: isize) -> Option<AccessibleRef> {
+    let index = usize::try_from(id.checked_neg()?).ok()?.checked_sub(1)?;
+    HANDLES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()?
+        .refs
+        .get(index)
+        .cloned()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StateSet(u64);
+
+impl StateSet {
+    pub const fn contains(self, state: u32) -> bool {
+        state < 64 && self.0 & (1 << state) != 0
+    }
+}
+
+fn unavailable(error: impl std::fmt::Display) -> AutomationError {
+    AutomationError::NotSupported(format!(
+        "AT-SPI accessibility bus is not available: {error}"
+    ))
+}
+
+fn call_failed(error: impl std::fmt::Display) -> AutomationError {
+    AutomationError::Other(format!("AT-SPI call failed: {error}"))
+}
+
+/// The accessibility bus address: `AT_SPI_BUS_ADDRESS` when set, otherwise
+/// whatever `org.a11y.Bus` on the session bus reports.
+fn bus_address() -> Result<String, AutomationError> {
+    if let Ok(address) = std::env::var("AT_SPI_BUS_ADDRESS")
+        && !address.is_empty()
+    {
+        return Ok(address);
+    }
+
+    let session = Connection::session().map_err(unavailable)?;
+    session
+        .call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.a11y.Bus"),
+            "GetAddress",
+            &(),
+        )
+        .and_then(|reply| reply.body().deserialize::<String>())
+        .map_err(unavailable)
+}
+
+/// A connection to the AT-SPI accessibility bus, shared across calls.
+pub struct Atspi {
+    conn: Connection,
+}
+
+impl Atspi {
+    pub fn connect() -> Result<Self, AutomationError> {
+        let mut bus = BUS.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(conn) = bus.as_ref() {
+            return Ok(Self { conn: conn.clone() });
+        }
+
+        let conn = Builder::address(bus_address()?.as_str())
+            .and_then(Builder::build)
+            .map_err(unavailable)?;
+        *bus = Some(conn.clone());
+        drop(bus);
+        Ok(Self { conn })
+    }
+
+    fn call<B, R>(
+        &self,
+        target: &AccessibleRef,
+        interface: &str,
+        method: &str,
+        body: &B,
+    ) -> Result<R, AutomationError>
+    where
+        B: Serialize + DynamicType,
+        R: for<'d> DynamicDeserialize<'d>,
+    {
+        self.conn
+            .call_method(
+                Some(target.bus_name.as_str()),
+                target.path.as_str(),
+                Some(interface),
+                method,
+                body,
+            )
+            .and_then(|reply| reply.body().deserialize::<R>())
+            .map_err(call_failed)
+    }
+
+    fn property<T>(
+        &self,
+        target: &AccessibleRef,
+        interface: &str,
+        name: &str,
+    ) -> Result<T, AutomationError>
+    where
+        T: TryFrom<OwnedValue>,
+        T::Error: std::fmt::Display,
+    {
+        let value: OwnedValue = self.call(
+            target,
+            "org.freedesktop.DBus.Properties",
+            "Get",
+            &(interface, name),
+        )?;
+        T::try_from(value).map_err(call_failed)
+    }
+
+    pub fn applications(&self) -> Result<Vec<AccessibleRef>, AutomationError> {
+        let root = AccessibleRef {
+            bus_name: REGISTRY_NAME.to_string(),
+            path: OwnedObjectPath::try_from(ROOT_PATH).map_err(call_failed)?,
+        };
+        self.children(&root)
+    }
+
+    pub fn children(&self, target: &AccessibleRef) -> Result<Vec<AccessibleRef>, AutomationError> {
+        let children: Vec<(String, OwnedObjectPath)> =
+            self.call(target, ACCESSIBLE, "GetChildren", &())?;
+        Ok(children
+            .into_iter()
+            .filter(|(_, path)| path.as_str() != NULL_PATH)
+            .map(|(bus_name, path)| AccessibleRef { bus_name, path })
+            .collect())
+    }
+
+    pub fn name(&self, target: &AccessibleRef) -> Result<String, AutomationError> {
+        self.property(target, ACCESSIBLE, "Name")
+    }
+
+    /// The toolkit-assigned id of the accessible; empty when it has none.
+    pub fn accessible_id(&self, target: &AccessibleRef) -> Result<String, AutomationError> {
+        self.property(target, ACCESSIBLE, "AccessibleId")
+    }
+
+    pub fn role_name(&self, target: &AccessibleRef) -> Result<String, AutomationError> {
+        self.call(target, ACCESSIBLE, "GetRoleName", &())
+    }
+
+    pub fn states(&self, target: &AccessibleRef) -> Result<StateSet, AutomationError> {
+        let words: Vec<u32> = self.call(target, ACCESSIBLE, "GetState", &())?;
+        let low = u64::from(words.first().copied().unwrap_or(0));
+        let high = u64::from(words.get(1).copied().unwrap_or(0));
+        Ok(StateSet(low | (high << 32)))
+    }
+
+    pub fn has_interface(&self, target: &AccessibleRef, interface: &str) -> bool {
+        self.call::<_, Vec<String>>(target, ACCESSIBLE, "GetInterfaces", &())
+            .is_ok_and(|interfaces| interfaces.iter().any(|i| i == interface))
+    }
+
+    pub fn has_text(&self, target: &AccessibleRef) -> bool {
+        self.has_interface(target, TEXT)
+    }
+
+    /// On-screen extents, or an empty rectangle for accessibles without a
+    /// Component interface.
+    pub fn extents(&self, target: &AccessibleRef) -> Rect {
+        self.call::<_, (i32, i32, i32, i32)>(target, COMPONENT, "GetExtents", &COORD_TYPE_SCREEN)
+            .map_or_else(
+                |_| Rect::empty(),
+                |(left, top, width, height)| Rect {
+                    left,
+                    top,
+                    width,
+                    height,
+                },
+            )
+    }
+
+    pub fn process_id(&self, bus_name: &str) -> Option<u32> {
+        self.conn
+            .call_method(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                Some("org.freedesktop.DBus"),
+                "GetConnectionUnixProcessID",
+                &bus_name,
+            )
+            .and_then(|reply| reply.body().deserialize::<u32>())
+            .ok()
+    }
+
+    /// The top-level accessible (frame, dialog...) of process `pid` named
+    /// `title`, or its only top-level accessible when none matches by name.
+    pub fn window_for(
+        &self,
+        pid: u32,
+        title: &str,
+    ) -> Result<Option<AccessibleRef>, AutomationError> {
+        for application in self.applications()? {
+            if self.process_id(&application.bus_name) != Some(pid) {
+                continue;
+            }
+            let windows = self.children(&application)?;
+            if let Some(window) = windows
+                .iter()
+                .find(|window| self.name(window).is_ok_and(|name| name == title))
+            {
+                return Ok(Some(window.clone()));
+            }
+            if let [window] = windows.as_slice() {
+                return Ok(Some(window.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn text(&self, target: &AccessibleRef) -> Result<String, AutomationError> {
+        self.call(target, TEXT, "GetText", &(0_i32, -1_i32))
+    }
+
+    pub fn character_count(&self, target: &AccessibleRef) -> Result<i32, AutomationError> {
+        self.property(target, TEXT, "CharacterCount")
+    }
+
+    pub fn set_text(&self, target: &AccessibleRef, text: &str) -> Result<(), AutomationError> {
+        if !self.has_interface(target, EDITABLE_TEXT) {
+            return Err(AutomationError::Other(
+                "Control does not accept text input".to_string(),
+            ));
+        }
+        let accepted: bool = self.call(target, EDITABLE_TEXT, "SetTextContents", &text)?;
+        accepted
+            .then_some(())
+            .ok_or_else(|| AutomationError::Other("Control rejected the text".to_string()))
+    }
+
+    /// The current selection, or an empty range at the caret.
+    pub fn selection(&self, target: &AccessibleRef) -> Result<(i32, i32), AutomationError> {
+        let selections: i32 = self.call(target, TEXT, "GetNSelections", &())?;
+        if selections > 0 {
+            return self.call(target, TEXT, "GetSelection", &0_i32);
+        }
+        let caret: i32 = self.property(target, TEXT, "CaretOffset")?;
+        Ok((caret, caret))
+    }
+
+    pub fn select(
+        &self,
+        target: &AccessibleRef,
+        start: i32,
+        end: i32,
+    ) -> Result<(), AutomationError> {
+        let selections: i32 = self.call(target, TEXT, "GetNSelections", &())?;
+        let selected: bool = if selections > 0 {
+            self.call(target, TEXT, "SetSelection", &(0_i32, start, end))?
+        } else {
+            self.call(target, TEXT, "AddSelection", &(start, end))?
+        };
+        selected
+            .then_some(())
+            .ok_or_else(|| AutomationError::Other("Control rejected the selection".to_string()))
+    }
+
+    pub fn grab_focus(&self, target: &AccessibleRef) -> Result<(), AutomationError> {
+        let focused: bool = self.call(target, COMPONENT, "GrabFocus", &())?;
+        focused
+            .then_some(())
+            .ok_or_else(|| AutomationError::Other("Control cannot take focus".to_string()))
+    }
+
+    /// Runs the control's primary action (see [`PRIMARY_ACTIONS`]), falling
+    /// back to its first action. Returns `false` when it has no actions.
+    pub fn do_primary_action(&self, target: &AccessibleRef) -> Result<bool, AutomationError> {
+        let Ok(actions) =
+            self.call::<_, Vec<(String, String, String)>>(target, ACTION, "GetActions", &())
+        else {
+            return Ok(false);
+        };
+        if actions.is_empty() {
+            return Ok(false);
+        }
+
+        let index = PRIMARY_ACTIONS
+            .iter()
+            .find_map(|wanted| {
+                actions
+                    .iter()
+                    .position(|(name, _, _)| name.eq_ignore_ascii_case(wanted))
+            })
+            .unwrap_or(0);
+        let done: bool = self.call(
+            target,
+            ACTION,
+            "DoAction",
+            &i32::try_from(index).unwrap_or(0),
+        )?;
+        done.then_some(true)
+            .ok_or_else(|| AutomationError::Other("Control action failed".to_string()))
+    }
+}
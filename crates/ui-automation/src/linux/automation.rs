@@ -1,6 +1,60 @@
 use std::fmt::{self, Display, Formatter};
-
-use super::selector::Selector;
+use std::fs;
+use std::io::ErrorKind;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant};
+
+use image::RgbaImage;
+use x11rb::protocol::xproto::{ConfigureWindowAux, KeyButMask, Window};
+
+use super::atspi::{self, AccessibleRef, Atspi, state};
+use super::x11::{
+    BUTTON_LEFT, BUTTON_RIGHT, BUTTON_WHEEL_DOWN, BUTTON_WHEEL_LEFT, BUTTON_WHEEL_RIGHT,
+    BUTTON_WHEEL_UP, X11, XK_SHIFT_L,
+};
+use crate::recorder::{HeldKey, InputState};
+use crate::selector::{self, MatchMode, Selector, SelectorElement};
+
+const XK_BACKSPACE: u32 = 0xff08;
+const XK_TAB: u32 = 0xff09;
+const XK_RETURN: u32 = 0xff0d;
+const XK_PAUSE: u32 = 0xff13;
+const XK_ESCAPE: u32 = 0xff1b;
+const XK_HOME: u32 = 0xff50;
+const XK_LEFT: u32 = 0xff51;
+const XK_UP: u32 = 0xff52;
+const XK_RIGHT: u32 = 0xff53;
+const XK_DOWN: u32 = 0xff54;
+const XK_PAGE_UP: u32 = 0xff55;
+const XK_PAGE_DOWN: u32 = 0xff56;
+const XK_END: u32 = 0xff57;
+const XK_INSERT: u32 = 0xff63;
+const XK_F1: u32 = 0xffbe;
+const XK_F12: u32 = 0xffc9;
+const XK_SHIFT_R: u32 = 0xffe2;
+const XK_CONTROL_L: u32 = 0xffe3;
+const XK_CONTROL_R: u32 = 0xffe4;
+const XK_CAPS_LOCK: u32 = 0xffe5;
+const XK_ALT_L: u32 = 0xffe9;
+const XK_ALT_R: u32 = 0xffea;
+const XK_SUPER_L: u32 = 0xffeb;
+const XK_SUPER_R: u32 = 0xffec;
+const XK_DELETE: u32 = 0xffff;
+/// Keysyms for Unicode characters outside Latin-1 are the code point with
+/// this flag set.
+const XK_UNICODE_FLAG: u32 = 0x0100_0000;
+
+const VK_F1: u16 = 0x70;
+
+const OVERLAY_COLOR_DEFAULT: (u8, u8, u8) = (0, 255, 0);
+const OVERLAY_DURATION_DEFAULT_MS: u32 = 2000;
+const OVERLAY_BORDER_WIDTH_DEFAULT: i32 = 4;
+
+const PROCESS_POLL_INTERVAL_MS: u64 = 50;
+const SELECTOR_POLL_INTERVAL_MS: u32 = 100;
 
 #[derive(Debug)]
 pub enum AutomationError {
@@ -44,8 +98,11 @@ impl Display for AutomationError {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ApplicationId(pub u32);
 
+/// A process launched by or attached to the automation. Launched processes
+/// keep their [`Child`] handle so the exit code can be collected.
 pub struct Application {
     pid: u32,
+    child: Option<Mutex<Child>>,
 }
 
 impl Application {
@@ -61,37 +118,176 @@ impl Application {
 
     #[must_use]
     pub fn is_running(&self) -> bool {
-        false
+        matches!(self.exit_code(), Ok(None))
     }
 
     pub fn close(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Application.close".to_string(),
-        ))
+        if !self.is_running() {
+            return Err(AutomationError::ProcessTerminated { pid: self.pid });
+        }
+
+        let pid = libc::pid_t::try_from(self.pid)
+            .map_err(|_| AutomationError::ProcessTerminated { pid: self.pid })?;
+        // SAFETY: kill(2) only sends a signal to the process with this id.
+        if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+            return Ok(());
+        }
+
+        match std::io::Error::last_os_error().raw_os_error() {
+            Some(libc::EPERM) => Err(AutomationError::AccessDenied {
+                operation: format!("terminate process {}", self.pid),
+            }),
+            _ => Err(AutomationError::ProcessTerminated { pid: self.pid }),
+        }
     }
 
-    pub fn wait_for_exit(&self, _timeout_ms: Option<u32>) -> Result<u32, AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Application.wait_for_exit".to_string(),
-        ))
+    pub fn wait_for_exit(&self, timeout_ms: Option<u32>) -> Result<u32, AutomationError> {
+        let deadline = timeout_ms.map(|ms| Instant::now() + Duration::from_millis(u64::from(ms)));
+
+        loop {
+            if let Some(code) = self.exit_code()? {
+                return Ok(code);
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(AutomationError::Other("Wait timeout".to_string()));
+            }
+            sleep(Duration::from_millis(PROCESS_POLL_INTERVAL_MS));
+        }
+    }
+
+    /// `Some(code)` once the process has exited. Attached processes are not
+    /// our children, so their exit code cannot be collected and reads as 0.
+    fn exit_code(&self) -> Result<Option<u32>, AutomationError> {
+        let Some(child) = &self.child else {
+            return Ok((!process_alive(self.pid)).then_some(0));
+        };
+
+        let status = child
+            .lock()
+            .map_err(|_| AutomationError::Other("Process handle is poisoned".to_string()))?
+            .try_wait()
+            .map_err(|e| {
+                AutomationError::Other(format!("Failed to query process {}: {e}", self.pid))
+            })?;
+
+        Ok(status.map(|status| {
+            status
+                .code()
+                .or_else(|| status.signal().map(|signal| 128 + signal))
+                .map_or(1, i32::cast_unsigned)
+        }))
     }
 }
 
-pub fn launch_application(_exe: &str, _args: &str) -> Result<Application, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "launch_application".to_string(),
-    ))
+/// Splits a command line into arguments on whitespace. Single and double
+/// quotes group words, and a backslash outside single quotes escapes the
+/// next character.
+fn split_arguments(args: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut quote: Option<char> = None;
+    let mut chars = args.chars();
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('\''), _) => current.push(ch),
+            (_, '\\') => {
+                in_argument = true;
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            (None, '\'' | '"') => {
+                in_argument = true;
+                quote = Some(ch);
+            }
+            (None, _) if ch.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            _ => {
+                in_argument = true;
+                current.push(ch);
+            }
+        }
+    }
+
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
 }
 
+pub fn launch_application(exe: &str, args: &str) -> Result<Application, AutomationError> {
+    let child = Command::new(exe)
+        .args(split_arguments(args))
+        .stdin(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => AutomationError::Other(format!("Executable '{exe}' not found")),
+            ErrorKind::PermissionDenied => AutomationError::AccessDenied {
+                operation: format!("launch {exe}"),
+            },
+            _ => AutomationError::Other(format!("Failed to launch {exe}: {e}")),
+        })?;
+
+    Ok(Application {
+        pid: child.id(),
+        child: Some(Mutex::new(child)),
+    })
+}
+
+/// Frames `rect` with four borderless, unmanaged windows for `duration_ms`.
+/// Returns once the frame is requested; it is removed on a worker thread.
 pub fn show_overlay_on_rect(
-    _rect: Rect,
-    _color_rgb: (u8, u8, u8),
-    _duration_ms: u32,
-    _border_width: i32,
+    rect: Rect,
+    color_rgb: (u8, u8, u8),
+    duration_ms: u32,
+    border_width: i32,
 ) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported(
-        "show_overlay_on_rect".to_string(),
-    ))
+    let x11 = X11::connect()?;
+    let border = border_width.max(1);
+    let edges = [
+        Rect {
+            height: border,
+            ..rect
+        },
+        Rect {
+            top: rect.top + rect.height - border,
+            height: border,
+            ..rect
+        },
+        Rect {
+            width: border,
+            ..rect
+        },
+        Rect {
+            left: rect.left + rect.width - border,
+            width: border,
+            ..rect
+        },
+    ];
+
+    let windows = edges
+        .into_iter()
+        .map(|edge| x11.create_overlay_window(edge, color_rgb))
+        .collect::<Result<Vec<_>, _>>()?;
+    x11.sync()?;
+
+    let _ = thread::spawn(move || {
+        sleep(Duration::from_millis(u64::from(duration_ms)));
+        for window in windows {
+            if let Err(e) = x11.destroy_window(window) {
+                log::warn!("Failed to remove overlay: {e}");
+            }
+        }
+    });
+
+    Ok(())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -100,6 +296,8 @@ pub enum ElementType {
     Control,
 }
 
+/// Identifies an element: an X11 window id, or a negative handle for an
+/// AT-SPI accessible.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ElementId(pub isize);
 
@@ -108,6 +306,24 @@ impl ElementId {
     pub const fn as_hwnd(&self) -> ElementId {
         *self
     }
+
+    #[allow(clippy::cast_possible_wrap)]
+    const fn from_window(window: Window) -> Self {
+        Self(window as isize)
+    }
+
+    fn window(self) -> Result<Window, AutomationError> {
+        Window::try_from(self.0).map_err(|_| {
+            AutomationError::NotSupported(format!(
+                "element {} is an accessible, not an X11 window",
+                self.0
+            ))
+        })
+    }
+
+    fn accessible(self) -> Option<AccessibleRef> {
+        atspi::resolve_handle(self.0)
+    }
 }
 
 #[derive(Debug)]
@@ -139,9 +355,30 @@ impl Rect {
             height: 0,
         }
     }
+
+    /// Whether the screen point lies inside the rectangle
+    #[must_use]
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.left && x < self.left + self.width && y >= self.top && y < self.top + self.height
+    }
+
+    fn area(&self) -> i64 {
+        i64::from(self.width) * i64::from(self.height)
+    }
 }
 
 impl Element {
+    fn window(&self) -> Result<Window, AutomationError> {
+        self.id.window()
+    }
+
+    const fn center(&self) -> (i32, i32) {
+        (
+            self.bounds.left + self.bounds.width / 2,
+            self.bounds.top + self.bounds.height / 2,
+        )
+    }
+
     fn require_window(&self, operation: &str) -> Result<(), AutomationError> {
         if self.element_type != ElementType::Window {
             return Err(AutomationError::Other(format!(
@@ -167,168 +404,308 @@ impl Element {
         &self.text
     }
 
+    /// The text contents of a text control, the name of other accessibles,
+    /// or the title of a window.
     pub fn get_text_live(&mut self) -> Result<String, AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.get_text_live".to_string(),
-        ))
+        self.text = match self.id.accessible() {
+            Some(accessible) => {
+                let atspi = Atspi::connect()?;
+                if atspi.has_text(&accessible) {
+                    atspi.text(&accessible)?
+                } else {
+                    atspi.name(&accessible)?
+                }
+            }
+            None => X11::connect()?.title(self.window()?),
+        };
+        Ok(self.text.clone())
     }
 
-    pub fn set_text(&self, _text: &str) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.set_text".to_string(),
-        ))
+    /// Replaces the contents of an editable control, or sets the title
+    /// (`_NET_WM_NAME` and `WM_NAME`) of a window.
+    pub fn set_text(&self, text: &str) -> Result<(), AutomationError> {
+        match self.id.accessible() {
+            Some(accessible) => Atspi::connect()?.set_text(&accessible, text),
+            None => X11::connect()?.set_title(self.window()?, text),
+        }
     }
 
+    /// Runs the primary accessible action of a control (press, toggle...),
+    /// falling back to a mouse click at the element's centre.
     pub fn click(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported("Element.click".to_string()))
+        if let Some(accessible) = self.id.accessible()
+            && Atspi::connect()?.do_primary_action(&accessible)?
+        {
+            return Ok(());
+        }
+        let (x, y) = self.center();
+        click(x, y)
     }
 
     pub fn right_click(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.right_click".to_string(),
-        ))
+        let (x, y) = self.center();
+        right_click(x, y)
     }
 
     pub fn double_click(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.double_click".to_string(),
-        ))
+        let (x, y) = self.center();
+        double_click(x, y)
     }
 
     #[must_use]
     pub fn is_focused(&self) -> bool {
-        false
+        if let Some(accessible) = self.id.accessible() {
+            return Atspi::connect()
+                .and_then(|atspi| atspi.states(&accessible))
+                .is_ok_and(|states| states.contains(state::FOCUSED));
+        }
+        X11::connect()
+            .ok()
+            .and_then(|x11| x11.active_window())
+            .is_some_and(|active| self.window().is_ok_and(|window| window == active))
     }
 
     pub fn focus(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported("Element.focus".to_string()))
+        match self.id.accessible() {
+            Some(accessible) => Atspi::connect()?.grab_focus(&accessible),
+            None => X11::connect()?.activate(self.window()?),
+        }
     }
 
     #[must_use]
     pub fn is_visible(&self) -> bool {
-        self.visible
+        if let Some(accessible) = self.id.accessible() {
+            return Atspi::connect()
+                .and_then(|atspi| atspi.states(&accessible))
+                .map_or(self.visible, |states| states.contains(state::SHOWING));
+        }
+        match (X11::connect(), self.window()) {
+            (Ok(x11), Ok(window)) => x11.is_viewable(window),
+            _ => self.visible,
+        }
     }
 
     pub fn refresh(&mut self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported("Element.refresh".to_string()))
+        let updated = match self.id.accessible() {
+            Some(accessible) => {
+                accessible_element(&Atspi::connect()?, &accessible, self.element_type)?
+            }
+            None => window_element(&X11::connect()?, self.window()?, self.element_type)?,
+        };
+        self.class_name = updated.class_name;
+        self.text = updated.text;
+        self.bounds = updated.bounds;
+        self.visible = updated.visible;
+        self.enabled = updated.enabled;
+        Ok(())
     }
 
+    /// The process exposing the accessible, or the `_NET_WM_PID` of the
+    /// window; 0 when unknown.
     #[must_use]
     pub fn get_process_id(&self) -> u32 {
-        0
+        if let Some(accessible) = self.id.accessible() {
+            return Atspi::connect()
+                .ok()
+                .and_then(|atspi| atspi.process_id(&accessible.bus_name))
+                .unwrap_or(0);
+        }
+        match (X11::connect(), self.window()) {
+            (Ok(x11), Ok(window)) => x11.pid(window).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// The executable name of the owning process, or empty when unknown.
+    #[must_use]
+    pub fn get_process_name(&self) -> String {
+        match self.get_process_id() {
+            0 => String::new(),
+            pid => process_names(pid).pop().unwrap_or_default(),
+        }
+    }
+
+    /// The toolkit-assigned id (`AccessibleId`) of an accessible; empty for
+    /// X11 windows.
+    #[must_use]
+    pub fn get_automation_id(&self) -> String {
+        self.id
+            .accessible()
+            .and_then(|accessible| Atspi::connect().ok()?.accessible_id(&accessible).ok())
+            .unwrap_or_default()
+    }
+
+    /// The AT-SPI role name ("push button", "check box", "text"...); X11
+    /// windows are frames.
+    #[must_use]
+    pub fn get_role(&self) -> String {
+        let Some(accessible) = self.id.accessible() else {
+            return "frame".to_string();
+        };
+        Atspi::connect()
+            .and_then(|atspi| atspi.role_name(&accessible))
+            .unwrap_or_default()
     }
 
     pub fn show_overlay(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.show_overlay".to_string(),
-        ))
+        show_overlay_on_rect(
+            self.bounds,
+            OVERLAY_COLOR_DEFAULT,
+            OVERLAY_DURATION_DEFAULT_MS,
+            OVERLAY_BORDER_WIDTH_DEFAULT,
+        )
     }
 
     pub fn show_overlay_custom(
         &self,
-        _color_rgb: (u8, u8, u8),
-        _duration_ms: u32,
-        _border_width: i32,
+        color_rgb: (u8, u8, u8),
+        duration_ms: u32,
+        border_width: i32,
     ) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.show_overlay_custom".to_string(),
-        ))
+        show_overlay_on_rect(self.bounds, color_rgb, duration_ms, border_width)
     }
 
     pub fn close(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::NotSupported("Element.close".to_string()))
+        self.require_window("close")?;
+        let window = self.window()?;
+        X11::connect()?.close(window)
     }
 
     pub fn activate(&self) -> Result<(), AutomationError> {
         self.require_window("activate")?;
-        Err(AutomationError::NotSupported(
-            "Element.activate".to_string(),
-        ))
+        let window = self.window()?;
+        X11::connect()?.activate(window)
     }
 
     pub fn minimize(&self) -> Result<(), AutomationError> {
         self.require_window("minimize")?;
-        Err(AutomationError::NotSupported(
-            "Element.minimize".to_string(),
-        ))
+        let window = self.window()?;
+        X11::connect()?.minimize(window)
     }
 
     pub fn maximize(&self) -> Result<(), AutomationError> {
         self.require_window("maximize")?;
-        Err(AutomationError::NotSupported(
-            "Element.maximize".to_string(),
-        ))
+        let window = self.window()?;
+        X11::connect()?.maximize(window)
     }
 
     pub fn restore(&self) -> Result<(), AutomationError> {
         self.require_window("restore")?;
-        Err(AutomationError::NotSupported("Element.restore".to_string()))
+        let window = self.window()?;
+        X11::connect()?.restore(window)
     }
 
     pub fn show(&self) -> Result<(), AutomationError> {
         self.require_window("show")?;
-        Err(AutomationError::NotSupported("Element.show".to_string()))
+        let window = self.window()?;
+        X11::connect()?.show(window)
     }
 
-    pub fn resize(&self, _width: i32, _height: i32) -> Result<(), AutomationError> {
+    pub fn resize(&self, width: i32, height: i32) -> Result<(), AutomationError> {
         self.require_window("resize")?;
-        Err(AutomationError::NotSupported("Element.resize".to_string()))
+        let (Ok(width), Ok(height)) = (u32::try_from(width), u32::try_from(height)) else {
+            return Err(AutomationError::Other(format!(
+                "Invalid window size {width}x{height}"
+            )));
+        };
+        let window = self.window()?;
+        X11::connect()?.configure(
+            window,
+            ConfigureWindowAux::new().width(width).height(height),
+        )
     }
 
-    pub fn move_to(&self, _x: i32, _y: i32) -> Result<(), AutomationError> {
+    pub fn move_to(&self, x: i32, y: i32) -> Result<(), AutomationError> {
         self.require_window("move_to")?;
-        Err(AutomationError::NotSupported("Element.move_to".to_string()))
+        let window = self.window()?;
+        X11::connect()?.configure(window, ConfigureWindowAux::new().x(x).y(y))
     }
 
     #[must_use]
     pub fn is_minimized(&self) -> bool {
-        false
+        self.element_type == ElementType::Window
+            && matches!(
+                (X11::connect(), self.window()),
+                (Ok(x11), Ok(window)) if x11.is_minimized(window)
+            )
     }
 
     #[must_use]
     pub fn is_maximized(&self) -> bool {
-        false
+        self.element_type == ElementType::Window
+            && matches!(
+                (X11::connect(), self.window()),
+                (Ok(x11), Ok(window)) if x11.is_maximized(window)
+            )
     }
 
     pub fn find_child_elements(&self) -> Result<Vec<Element>, AutomationError> {
-        Err(AutomationError::NotSupported(
-            "Element.find_child_elements".to_string(),
-        ))
+        find_child_elements(self.id)
+    }
+
+    fn require_accessible(&self, operation: &str) -> Result<AccessibleRef, AutomationError> {
+        self.require_control(operation)?;
+        self.id.accessible().ok_or_else(|| {
+            AutomationError::NotSupported(format!("{operation} needs an accessible control"))
+        })
+    }
+
+    fn set_checked(&self, checked: bool, operation: &str) -> Result<(), AutomationError> {
+        let accessible = self.require_accessible(operation)?;
+        let atspi = Atspi::connect()?;
+        if atspi.states(&accessible)?.contains(state::CHECKED) == checked {
+            return Ok(());
+        }
+        if atspi.do_primary_action(&accessible)? {
+            Ok(())
+        } else {
+            Err(AutomationError::Other(format!(
+                "Control has no action to {operation}"
+            )))
+        }
     }
 
     pub fn check(&self) -> Result<(), AutomationError> {
-        self.require_control("check")?;
-        Err(AutomationError::NotSupported("Element.check".to_string()))
+        self.set_checked(true, "check")
     }
 
     pub fn uncheck(&self) -> Result<(), AutomationError> {
-        self.require_control("uncheck")?;
-        Err(AutomationError::NotSupported("Element.uncheck".to_string()))
+        self.set_checked(false, "uncheck")
     }
 
     #[must_use]
     pub fn is_checked(&self) -> bool {
-        false
+        self.id.accessible().is_some_and(|accessible| {
+            Atspi::connect()
+                .and_then(|atspi| atspi.states(&accessible))
+                .is_ok_and(|states| states.contains(state::CHECKED))
+        })
     }
 
-    pub fn select_text(&self, _start: usize, _end: usize) -> Result<(), AutomationError> {
-        self.require_control("select_text")?;
-        Err(AutomationError::NotSupported(
-            "Element.select_text".to_string(),
-        ))
+    /// Selects the characters from `start` up to (not including) `end`.
+    pub fn select_text(&self, start: usize, end: usize) -> Result<(), AutomationError> {
+        let accessible = self.require_accessible("select_text")?;
+        let (Ok(start), Ok(end)) = (i32::try_from(start), i32::try_from(end)) else {
+            return Err(AutomationError::Other(format!(
+                "Invalid selection {start}..{end}"
+            )));
+        };
+        Atspi::connect()?.select(&accessible, start, end)
     }
 
     pub fn select_all(&self) -> Result<(), AutomationError> {
-        self.require_control("select_all")?;
-        Err(AutomationError::NotSupported(
-            "Element.select_all".to_string(),
-        ))
+        let accessible = self.require_accessible("select_all")?;
+        let atspi = Atspi::connect()?;
+        let count = atspi.character_count(&accessible)?;
+        atspi.select(&accessible, 0, count)
     }
 
     pub fn get_selected_range(&self) -> Result<(usize, usize), AutomationError> {
-        self.require_control("get_selected_range")?;
-        Err(AutomationError::NotSupported(
-            "Element.get_selected_range".to_string(),
+        let accessible = self.require_accessible("get_selected_range")?;
+        let (start, end) = Atspi::connect()?.selection(&accessible)?;
+        Ok((
+            usize::try_from(start).unwrap_or(0),
+            usize::try_from(end).unwrap_or(0),
         ))
     }
 
@@ -339,184 +716,887 @@ impl Element {
     }
 }
 
-pub fn find_processes_by_name(_name: &str) -> Result<Vec<Application>, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "find_processes_by_name".to_string(),
-    ))
+/// A live process is one with a `/proc` entry that is not a zombie.
+fn process_alive(pid: u32) -> bool {
+    fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| {
+        stat.rsplit_once(')')
+            .is_some_and(|(_, rest)| !rest.trim_start().starts_with('Z'))
+    })
+}
+
+fn process_names(pid: u32) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(comm) = fs::read_to_string(format!("/proc/{pid}/comm")) {
+        names.push(comm.trim_end().to_string());
+    }
+    if let Ok(exe) = fs::read_link(format!("/proc/{pid}/exe"))
+        && let Some(file_name) = exe.file_name()
+    {
+        names.push(file_name.to_string_lossy().into_owned());
+    }
+    names
+}
+
+pub fn find_processes_by_name(name: &str) -> Result<Vec<Application>, AutomationError> {
+    let name_lower = name.to_lowercase();
+    let entries = fs::read_dir("/proc")
+        .map_err(|e| AutomationError::Other(format!("Cannot list processes: {e}")))?;
+
+    let mut pids: Vec<u32> = entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .filter(|&pid| {
+            process_names(pid)
+                .iter()
+                .any(|process_name| process_name.to_lowercase().contains(&name_lower))
+        })
+        .collect();
+    pids.sort_unstable();
+
+    Ok(pids
+        .into_iter()
+        .filter_map(|pid| attach_to_process_by_pid(pid).ok())
+        .collect())
+}
+
+pub fn attach_to_process_by_pid(pid: u32) -> Result<Application, AutomationError> {
+    if !process_alive(pid) {
+        return Err(AutomationError::ProcessNotFound {
+            name: pid.to_string(),
+        });
+    }
+    Ok(Application { pid, child: None })
 }
 
-pub fn attach_to_process_by_pid(_pid: u32) -> Result<Application, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "attach_to_process_by_pid".to_string(),
-    ))
+pub fn attach_to_process_by_name(name: &str) -> Result<Application, AutomationError> {
+    find_processes_by_name(name)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AutomationError::ProcessNotFound {
+            name: name.to_string(),
+        })
 }
 
-pub fn attach_to_process_by_name(_name: &str) -> Result<Application, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "attach_to_process_by_name".to_string(),
-    ))
+fn window_element(
+    x11: &X11,
+    window: Window,
+    element_type: ElementType,
+) -> Result<Element, AutomationError> {
+    Ok(Element {
+        id: ElementId::from_window(window),
+        element_type,
+        class_name: x11.class(window),
+        text: x11.title(window),
+        bounds: x11.bounds(window)?,
+        visible: x11.is_viewable(window),
+        enabled: true,
+    })
 }
 
 pub fn find_windows() -> Result<Vec<Element>, AutomationError> {
-    Err(AutomationError::NotSupported("find_windows".to_string()))
+    let x11 = X11::connect()?;
+    Ok(x11
+        .top_level_windows()?
+        .into_iter()
+        .filter_map(|window| window_element(&x11, window, ElementType::Window).ok())
+        .collect())
 }
 
 pub fn get_foreground_window() -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "get_foreground_window".to_string(),
-    ))
+    let x11 = X11::connect()?;
+    let window = x11
+        .active_window()
+        .ok_or_else(|| AutomationError::WindowNotFound {
+            title: "No foreground window".to_string(),
+        })?;
+    window_element(&x11, window, ElementType::Window)
+}
+
+/// The topmost visible top-level window under the screen point
+pub fn window_from_point(x: i32, y: i32) -> Result<Element, AutomationError> {
+    let x11 = X11::connect()?;
+    x11.stacked_windows()?
+        .into_iter()
+        .rev()
+        .filter_map(|window| window_element(&x11, window, ElementType::Window).ok())
+        .find(|window| window.visible && window.bounds.contains(x, y))
+        .ok_or_else(|| AutomationError::WindowNotFound {
+            title: format!("No window at ({x}, {y})"),
+        })
+}
+
+/// The innermost visible control of `window` under the screen point
+pub fn control_from_point(
+    window: &Element,
+    x: i32,
+    y: i32,
+) -> Result<Option<Element>, AutomationError> {
+    let mut found = None;
+    let mut children = find_child_elements(window.id)?;
+    while let Some(control) = children
+        .into_iter()
+        .filter(|child| child.visible && child.bounds.contains(x, y))
+        .min_by_key(|child| child.bounds.area())
+    {
+        children = find_child_elements(control.id)?;
+        found = Some(control);
+    }
+    Ok(found)
+}
+
+fn accessible_element(
+    atspi: &Atspi,
+    accessible: &AccessibleRef,
+    element_type: ElementType,
+) -> Result<Element, AutomationError> {
+    let name = atspi.name(accessible)?;
+    let text = if name.is_empty() && atspi.has_text(accessible) {
+        atspi.text(accessible).unwrap_or_default()
+    } else {
+        name
+    };
+    let states = atspi.states(accessible)?;
+
+    Ok(Element {
+        id: ElementId(atspi::handle_for(accessible)),
+        element_type,
+        class_name: atspi.role_name(accessible)?,
+        text,
+        bounds: atspi.extents(accessible),
+        visible: states.contains(state::SHOWING),
+        enabled: states.contains(state::ENABLED) || states.contains(state::SENSITIVE),
+    })
+}
+
+fn accessible_children(
+    atspi: &Atspi,
+    parent: &AccessibleRef,
+) -> Result<Vec<Element>, AutomationError> {
+    Ok(atspi
+        .children(parent)?
+        .iter()
+        .filter_map(|child| accessible_element(atspi, child, ElementType::Control).ok())
+        .collect())
 }
 
-pub fn find_child_elements(_parent_id: ElementId) -> Result<Vec<Element>, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "find_child_elements".to_string(),
-    ))
+/// Controls come from the AT-SPI accessibility tree. A window's controls are
+/// the children of the top-level accessible its process exposes under the
+/// window's title; windows without one have no children.
+pub fn find_child_elements(parent_id: ElementId) -> Result<Vec<Element>, AutomationError> {
+    if let Some(parent) = parent_id.accessible() {
+        return accessible_children(&Atspi::connect()?, &parent);
+    }
+
+    let x11 = X11::connect()?;
+    let window = parent_id.window()?;
+    let Some(pid) = x11.pid(window) else {
+        return Ok(Vec::new());
+    };
+
+    let atspi = Atspi::connect()?;
+    atspi.window_for(pid, &x11.title(window))?.map_or_else(
+        || Ok(Vec::new()),
+        |frame| accessible_children(&atspi, &frame),
+    )
+}
+
+/// The top-level accessibles (frames, dialogs...) exposed by process `pid`.
+/// They work without an X11 window, but window management (move, minimize...)
+/// is not available on them.
+pub fn find_application_windows(pid: u32) -> Result<Vec<Element>, AutomationError> {
+    let atspi = Atspi::connect()?;
+    let mut windows = Vec::new();
+    for application in atspi.applications()? {
+        if atspi.process_id(&application.bus_name) != Some(pid) {
+            continue;
+        }
+        windows.extend(
+            atspi
+                .children(&application)?
+                .iter()
+                .filter_map(|window| accessible_element(&atspi, window, ElementType::Window).ok()),
+        );
+    }
+    Ok(windows)
+}
+
+impl SelectorElement for Element {
+    fn key(&self) -> isize {
+        self.id.0
+    }
+
+    fn is_window(&self) -> bool {
+        self.element_type == ElementType::Window
+    }
+
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn process_id(&self) -> u32 {
+        self.get_process_id()
+    }
+
+    fn process_name(&self) -> String {
+        self.get_process_name()
+    }
+
+    fn automation_id(&self) -> String {
+        self.get_automation_id()
+    }
+
+    fn role(&self) -> String {
+        self.get_role()
+    }
+
+    fn children(&self) -> Result<Vec<Self>, AutomationError> {
+        find_child_elements(self.id.as_hwnd())
+    }
 }
 
-pub fn find_element_by_selector(_dsl: &str) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "find_element_by_selector".to_string(),
-    ))
+/// Resolve the selector against the current element tree, without waiting
+fn find_element_once(selector: &Selector, mode: MatchMode) -> Result<Element, AutomationError> {
+    selector::resolve_with(selector, find_windows()?, mode)
 }
 
+pub fn find_element_by_selector(dsl: &str) -> Result<Element, AutomationError> {
+    let selector = Selector::parse(dsl)?;
+    find_element_by_selector_obj(&selector, None)
+}
+
+/// Resolves `selector` exactly, retrying until `timeout_ms` runs out when given.
 pub fn find_element_by_selector_obj(
-    _selector: &Selector,
-    _timeout_ms: Option<u32>,
+    selector: &Selector,
+    timeout_ms: Option<u32>,
+) -> Result<Element, AutomationError> {
+    find_element_by_selector_obj_with_mode(selector, timeout_ms, MatchMode::Strict)
+}
+
+/// [`find_element_by_selector_obj`] in the given mode. In fuzzy mode the best
+/// scoring element is taken and the criteria it failed are logged.
+pub fn find_element_by_selector_obj_with_mode(
+    selector: &Selector,
+    timeout_ms: Option<u32>,
+    mode: MatchMode,
 ) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "find_element_by_selector_obj".to_string(),
-    ))
+    let Some(timeout_ms) = timeout_ms else {
+        return find_element_once(selector, mode);
+    };
+    poll_until(timeout_ms, SELECTOR_POLL_INTERVAL_MS, || {
+        find_element_once(selector, mode).ok()
+    })
+}
+
+pub fn find_window_by_selector(dsl: &str) -> Result<Element, AutomationError> {
+    let element = find_element_by_selector(dsl)?;
+    if element.element_type != ElementType::Window {
+        return Err(AutomationError::Other(
+            "Selector did not return a window".to_string(),
+        ));
+    }
+    Ok(element)
+}
+
+pub fn find_control_by_selector(dsl: &str) -> Result<Element, AutomationError> {
+    let element = find_element_by_selector(dsl)?;
+    if element.element_type != ElementType::Control {
+        return Err(AutomationError::Other(
+            "Selector did not return a control".to_string(),
+        ));
+    }
+    Ok(element)
+}
+
+fn click_button(x: i32, y: i32, button: u8) -> Result<(), AutomationError> {
+    let x11 = X11::connect()?;
+    x11.move_pointer(x, y)?;
+    x11.button(button, true)?;
+    sleep(Duration::from_millis(50));
+    x11.button(button, false)
+}
+
+pub fn click(x: i32, y: i32) -> Result<(), AutomationError> {
+    click_button(x, y, BUTTON_LEFT)
 }
 
-pub fn find_window_by_selector(_dsl: &str) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "find_window_by_selector".to_string(),
-    ))
+pub fn right_click(x: i32, y: i32) -> Result<(), AutomationError> {
+    click_button(x, y, BUTTON_RIGHT)
 }
 
-pub fn find_control_by_selector(_dsl: &str) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "find_control_by_selector".to_string(),
-    ))
+pub fn double_click(x: i32, y: i32) -> Result<(), AutomationError> {
+    click(x, y)?;
+    sleep(Duration::from_millis(100));
+    click(x, y)?;
+    Ok(())
 }
 
-pub fn click(_x: i32, _y: i32) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("click".to_string()))
+pub fn get_cursor_position() -> Result<(i32, i32), AutomationError> {
+    let (x, y, _) = X11::connect()?.pointer()?;
+    Ok((x, y))
 }
 
-pub fn right_click(_x: i32, _y: i32) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("right_click".to_string()))
+/// Whether the left mouse button is held down, wherever the pointer is
+#[must_use]
+pub fn is_left_button_down() -> bool {
+    X11::connect()
+        .and_then(|x11| x11.pointer())
+        .is_ok_and(|(_, _, mask)| mask.contains(KeyButMask::BUTTON1))
 }
 
-pub fn double_click(_x: i32, _y: i32) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("double_click".to_string()))
+/// Pointer position, mouse buttons and keys currently held, for the recorder
+pub fn input_state() -> Result<InputState, AutomationError> {
+    let x11 = X11::connect()?;
+    let (x, y, mask) = x11.pointer()?;
+    let held = x11.held_keysyms()?;
+    let shift = held
+        .iter()
+        .any(|&(keysym, _)| matches!(keysym, XK_SHIFT_L | XK_SHIFT_R));
+
+    let keys = held
+        .into_iter()
+        .filter_map(|(plain, shifted)| {
+            let name = key_name_for_keysym(plain)?;
+            let text = if !shift {
+                char_for_keysym(plain)
+            } else if shifted == 0 {
+                // Keys without a shifted keysym shift like letters do
+                char_for_keysym(plain).map(|ch| ch.to_ascii_uppercase())
+            } else {
+                char_for_keysym(shifted)
+            };
+            Some(HeldKey { name, text })
+        })
+        .collect();
+
+    Ok(InputState {
+        cursor: (x, y),
+        left_button: mask.contains(KeyButMask::BUTTON1),
+        right_button: mask.contains(KeyButMask::BUTTON3),
+        keys,
+    })
 }
 
-pub fn move_mouse(_x: i32, _y: i32) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("move_mouse".to_string()))
+/// The area of the whole screen, in screen coordinates
+pub fn screen_rect() -> Result<Rect, AutomationError> {
+    Ok(X11::connect()?.screen_rect())
 }
 
-pub fn type_text(_text: &str) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("type_text".to_string()))
+/// Captures what is shown on screen inside `rect`, clipped to the screen
+pub fn capture_rect(rect: Rect) -> Result<RgbaImage, AutomationError> {
+    X11::connect()?.capture(rect)
 }
 
-pub fn key_down(_key_code: u16) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("key_down".to_string()))
+pub fn move_mouse(x: i32, y: i32) -> Result<(), AutomationError> {
+    X11::connect()?.move_pointer(x, y)
 }
 
-pub fn key_up(_key_code: u16) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("key_up".to_string()))
+fn keysym_for_char(ch: char) -> u32 {
+    match ch {
+        '\n' | '\r' => XK_RETURN,
+        '\t' => XK_TAB,
+        '\u{8}' => XK_BACKSPACE,
+        ' '..='~' | '\u{a0}'..='\u{ff}' => u32::from(ch),
+        _ => XK_UNICODE_FLAG | u32::from(ch),
+    }
 }
 
-pub fn press_key_code(_key_code: u16) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("press_key_code".to_string()))
+/// The character typed by a keysym, the inverse of `keysym_for_char`
+const fn char_for_keysym(keysym: u32) -> Option<char> {
+    match keysym {
+        0x20..=0x7e | 0xa0..=0xff => char::from_u32(keysym),
+        _ if keysym & 0xff00_0000 == XK_UNICODE_FLAG => char::from_u32(keysym & 0x00ff_ffff),
+        _ => None,
+    }
 }
 
-pub fn press_key(_key: char) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("press_key".to_string()))
+/// The `key_sequence` name of a keysym, the inverse of `keysym_for_key_name`
+fn key_name_for_keysym(keysym: u32) -> Option<String> {
+    let name = match keysym {
+        XK_BACKSPACE => "backspace",
+        XK_TAB => "tab",
+        XK_RETURN => "enter",
+        XK_ESCAPE => "escape",
+        0x20 => "space",
+        XK_DELETE => "delete",
+        XK_INSERT => "insert",
+        XK_HOME => "home",
+        XK_END => "end",
+        XK_PAGE_UP => "pageup",
+        XK_PAGE_DOWN => "pagedown",
+        XK_UP => "up",
+        XK_DOWN => "down",
+        XK_LEFT => "left",
+        XK_RIGHT => "right",
+        XK_SHIFT_L | XK_SHIFT_R => "shift",
+        XK_CONTROL_L | XK_CONTROL_R => "ctrl",
+        XK_ALT_L | XK_ALT_R => "alt",
+        XK_SUPER_L | XK_SUPER_R => "super",
+        XK_F1..=XK_F12 => return Some(format!("f{}", keysym - XK_F1 + 1)),
+        _ => {
+            return char_for_keysym(keysym)
+                .filter(|ch| !ch.is_whitespace())
+                .map(|ch| ch.to_lowercase().to_string());
+        }
+    };
+    Some(name.to_string())
 }
 
-pub fn key_combination(_modifier: &str, _key_code: u16) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("key_combination".to_string()))
+/// Maps the Windows virtual-key codes accepted by `key_down`/`key_up` to X
+/// keysyms so callers can stay platform-neutral.
+const fn keysym_for_virtual_key(key_code: u16) -> Option<u32> {
+    Some(match key_code {
+        0x08 => XK_BACKSPACE,
+        0x09 => XK_TAB,
+        0x0D => XK_RETURN,
+        0x10 | 0xA0 => XK_SHIFT_L,
+        0xA1 => XK_SHIFT_R,
+        0x11 | 0xA2 => XK_CONTROL_L,
+        0xA3 => XK_CONTROL_R,
+        0x12 | 0xA4 => XK_ALT_L,
+        0xA5 => XK_ALT_R,
+        0x13 => XK_PAUSE,
+        0x14 => XK_CAPS_LOCK,
+        0x1B => XK_ESCAPE,
+        0x20 => 0x20,
+        0x21 => XK_PAGE_UP,
+        0x22 => XK_PAGE_DOWN,
+        0x23 => XK_END,
+        0x24 => XK_HOME,
+        0x25 => XK_LEFT,
+        0x26 => XK_UP,
+        0x27 => XK_RIGHT,
+        0x28 => XK_DOWN,
+        0x2D => XK_INSERT,
+        0x2E => XK_DELETE,
+        0x30..=0x39 => key_code as u32,
+        // Letter keys produce their lowercase keysym, like an unshifted press.
+        0x41..=0x5A => key_code as u32 + 0x20,
+        0x5B => XK_SUPER_L,
+        0x5C => XK_SUPER_R,
+        0x70..=0x7B => XK_F1 + (key_code - VK_F1) as u32,
+        _ => return None,
+    })
 }
 
-pub fn press_f_key(_n: u8) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("press_f_key".to_string()))
+fn keysym_for_key_name(name: &str) -> Result<u32, AutomationError> {
+    let name_lower = name.to_lowercase();
+
+    let mut chars = name_lower.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Ok(keysym_for_char(ch));
+    }
+
+    Ok(match name_lower.as_str() {
+        "backspace" => XK_BACKSPACE,
+        "tab" => XK_TAB,
+        "enter" | "return" => XK_RETURN,
+        "escape" | "esc" => XK_ESCAPE,
+        "space" => u32::from(' '),
+        "delete" | "del" => XK_DELETE,
+        "insert" | "ins" => XK_INSERT,
+        "home" => XK_HOME,
+        "end" => XK_END,
+        "pageup" | "page_up" => XK_PAGE_UP,
+        "pagedown" | "page_down" => XK_PAGE_DOWN,
+        "arrowup" | "arrow_up" | "up" => XK_UP,
+        "arrowdown" | "arrow_down" | "down" => XK_DOWN,
+        "arrowleft" | "arrow_left" | "left" => XK_LEFT,
+        "arrowright" | "arrow_right" | "right" => XK_RIGHT,
+        "shift" => XK_SHIFT_L,
+        "control" | "ctrl" => XK_CONTROL_L,
+        "alt" => XK_ALT_L,
+        "super" | "win" | "meta" => XK_SUPER_L,
+        _ if name_lower.starts_with('f') && name_lower.len() <= 3 => {
+            match name_lower[1..].parse::<u8>() {
+                Ok(n) if (1..=12).contains(&n) => XK_F1 + u32::from(n - 1),
+                Ok(_) => {
+                    return Err(AutomationError::Other(format!(
+                        "F key must be F1-F12, got {name}"
+                    )));
+                }
+                Err(_) => {
+                    return Err(AutomationError::Other(format!("Invalid F key: {name}")));
+                }
+            }
+        }
+        _ => {
+            return Err(AutomationError::Other(format!("Unknown key name: {name}")));
+        }
+    })
+}
+
+fn modifier_keysym(modifier: &str) -> Option<u32> {
+    match modifier.to_lowercase().as_str() {
+        "ctrl" | "control" => Some(XK_CONTROL_L),
+        "shift" => Some(XK_SHIFT_L),
+        "alt" => Some(XK_ALT_L),
+        "super" | "win" | "meta" => Some(XK_SUPER_L),
+        _ => None,
+    }
 }
 
-pub fn press_key_by_name(_name: &str) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported(
-        "press_key_by_name".to_string(),
-    ))
+fn press_keysym(x11: &X11, keysym: u32) -> Result<(), AutomationError> {
+    x11.keysym_event(keysym, true)?;
+    sleep(Duration::from_millis(50));
+    x11.keysym_event(keysym, false)
+}
+
+pub fn type_text(text: &str) -> Result<(), AutomationError> {
+    let x11 = X11::connect()?;
+    for ch in text.chars() {
+        x11.type_keysym(keysym_for_char(ch))?;
+        sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+fn virtual_key_keysym(key_code: u16) -> Result<u32, AutomationError> {
+    keysym_for_virtual_key(key_code).ok_or_else(|| {
+        AutomationError::Other(format!("Unsupported virtual key code: 0x{key_code:02X}"))
+    })
+}
+
+pub fn key_down(key_code: u16) -> Result<(), AutomationError> {
+    X11::connect()?.keysym_event(virtual_key_keysym(key_code)?, true)
+}
+
+pub fn key_up(key_code: u16) -> Result<(), AutomationError> {
+    X11::connect()?.keysym_event(virtual_key_keysym(key_code)?, false)
+}
+
+pub fn press_key_code(key_code: u16) -> Result<(), AutomationError> {
+    key_down(key_code)?;
+    sleep(Duration::from_millis(50));
+    key_up(key_code)?;
+    Ok(())
+}
+
+pub fn press_key(key: char) -> Result<(), AutomationError> {
+    let c = key
+        .to_lowercase()
+        .next()
+        .ok_or_else(|| AutomationError::Other("Empty key".to_string()))?;
+    press_keysym(&X11::connect()?, keysym_for_char(c))
+}
+
+pub fn key_combination(modifier: &str, key_code: u16) -> Result<(), AutomationError> {
+    let modifier_keysym = modifier_keysym(modifier)
+        .ok_or_else(|| AutomationError::Other(format!("Unsupported modifier: {modifier}")))?;
+    let keysym = virtual_key_keysym(key_code)?;
+
+    let x11 = X11::connect()?;
+    x11.keysym_event(modifier_keysym, true)?;
+    sleep(Duration::from_millis(50));
+    let pressed = press_keysym(&x11, keysym);
+    sleep(Duration::from_millis(50));
+    x11.keysym_event(modifier_keysym, false)?;
+    pressed
+}
+
+pub fn press_f_key(n: u8) -> Result<(), AutomationError> {
+    if !(1..=12).contains(&n) {
+        return Err(AutomationError::Other(format!(
+            "F key must be F1-F12, got F{n}"
+        )));
+    }
+    press_keysym(&X11::connect()?, XK_F1 + u32::from(n - 1))
+}
+
+pub fn press_key_by_name(name: &str) -> Result<(), AutomationError> {
+    let keysym = keysym_for_key_name(name)?;
+    press_keysym(&X11::connect()?, keysym)
+}
+
+fn poll_until<F, T>(
+    timeout_ms: u32,
+    poll_interval_ms: u32,
+    mut predicate: F,
+) -> Result<T, AutomationError>
+where
+    F: FnMut() -> Option<T>,
+{
+    let start = Instant::now();
+    let timeout = Duration::from_millis(u64::from(timeout_ms));
+    let poll_interval = Duration::from_millis(u64::from(poll_interval_ms));
+
+    loop {
+        if let Some(result) = predicate() {
+            return Ok(result);
+        }
+
+        if start.elapsed() >= timeout {
+            return Err(AutomationError::Other(format!(
+                "Timeout after {timeout_ms}ms waiting for condition"
+            )));
+        }
+
+        sleep(poll_interval);
+    }
 }
 
 pub fn wait_for_window(
-    _dsl: &str,
-    _timeout_ms: u32,
-    _poll_interval_ms: u32,
+    dsl: &str,
+    timeout_ms: u32,
+    poll_interval_ms: u32,
 ) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported("wait_for_window".to_string()))
+    poll_until(timeout_ms, poll_interval_ms, || {
+        find_window_by_selector(dsl).ok()
+    })
 }
 
 pub fn wait_for_control(
-    _dsl: &str,
-    _timeout_ms: u32,
-    _poll_interval_ms: u32,
+    dsl: &str,
+    timeout_ms: u32,
+    poll_interval_ms: u32,
 ) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "wait_for_control".to_string(),
-    ))
+    poll_until(timeout_ms, poll_interval_ms, || {
+        find_control_by_selector(dsl).ok()
+    })
 }
 
 pub fn wait_for_control_text(
-    _dsl: &str,
-    _expected_text: &str,
-    _timeout_ms: u32,
-    _poll_interval_ms: u32,
+    dsl: &str,
+    expected_text: &str,
+    timeout_ms: u32,
+    poll_interval_ms: u32,
 ) -> Result<Element, AutomationError> {
-    Err(AutomationError::NotSupported(
-        "wait_for_control_text".to_string(),
-    ))
+    let expected = expected_text.to_lowercase();
+    poll_until(timeout_ms, poll_interval_ms, || {
+        find_control_by_selector(dsl)
+            .ok()
+            .filter(|control| control.text.to_lowercase().contains(&expected))
+    })
 }
 
-pub fn key_sequence(_sequence: &str) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("key_sequence".to_string()))
+/// Presses a `modifier+...+key` combination such as `ctrl+shift+s`, holding
+/// the modifiers around the final key.
+pub fn key_sequence(sequence: &str) -> Result<(), AutomationError> {
+    let parts: Vec<&str> = sequence.split('+').map(str::trim).collect();
+    let Some((final_key, modifiers)) = parts.split_last() else {
+        return Err(AutomationError::Other("Empty key sequence".to_string()));
+    };
+
+    let modifiers = modifiers
+        .iter()
+        .map(|modifier| {
+            modifier_keysym(modifier).ok_or_else(|| {
+                AutomationError::Other(format!("Unknown modifier: {}", modifier.to_lowercase()))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let keysym = keysym_for_key_name(final_key)?;
+
+    let x11 = X11::connect()?;
+    for &modifier in &modifiers {
+        x11.keysym_event(modifier, true)?;
+        sleep(Duration::from_millis(50));
+    }
+
+    let pressed = press_keysym(&x11, keysym);
+
+    for &modifier in modifiers.iter().rev() {
+        sleep(Duration::from_millis(50));
+        x11.keysym_event(modifier, false)?;
+    }
+
+    pressed
 }
 
+/// Turns the wheel `delta` notches at (`x`, `y`); positive values scroll up,
+/// or right when `horizontal`.
 pub fn scroll_wheel_at(
-    _x: i32,
-    _y: i32,
-    _delta: i32,
-    _horizontal: bool,
+    x: i32,
+    y: i32,
+    delta: i32,
+    horizontal: bool,
 ) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("scroll_wheel_at".to_string()))
+    if delta == 0 {
+        return Err(AutomationError::Other(
+            "Scroll delta must not be zero".to_string(),
+        ));
+    }
+
+    let button = match (horizontal, delta > 0) {
+        (false, true) => BUTTON_WHEEL_UP,
+        (false, false) => BUTTON_WHEEL_DOWN,
+        (true, true) => BUTTON_WHEEL_RIGHT,
+        (true, false) => BUTTON_WHEEL_LEFT,
+    };
+
+    let x11 = X11::connect()?;
+    x11.move_pointer(x, y)?;
+    sleep(Duration::from_millis(50));
+
+    for _ in 0..delta.unsigned_abs() {
+        x11.button(button, true)?;
+        x11.button(button, false)?;
+        sleep(Duration::from_millis(50));
+    }
+
+    Ok(())
 }
 
 pub fn scroll_in_window(
-    _element: &Element,
-    _direction: &str,
-    _times: usize,
+    element: &Element,
+    direction: &str,
+    times: usize,
 ) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported(
-        "scroll_in_window".to_string(),
-    ))
+    let Ok(amount) = i32::try_from(times) else {
+        return Err(AutomationError::Other(format!(
+            "Scroll amount {times} is too large"
+        )));
+    };
+    if amount == 0 {
+        return Err(AutomationError::Other(
+            "Scroll amount must be greater than 0".to_string(),
+        ));
+    }
+
+    let (delta, horizontal) = match direction.to_lowercase().as_str() {
+        "up" => (amount, false),
+        "down" => (-amount, false),
+        "left" => (-amount, true),
+        "right" => (amount, true),
+        _ => {
+            return Err(AutomationError::Other(format!(
+                "Invalid scroll direction: {direction}. Use 'up', 'down', 'left', or 'right'"
+            )));
+        }
+    };
+
+    let (x, y) = element.center();
+    scroll_wheel_at(x, y, delta, horizontal)
+}
+
+fn linspace(from: (i32, i32), to: (i32, i32), steps: u32) -> Vec<(f64, f64)> {
+    if steps == 0 {
+        return vec![(f64::from(from.0), f64::from(from.1))];
+    }
+
+    (0..=steps)
+        .map(|i| {
+            let t = f64::from(i) / f64::from(steps);
+            (
+                f64::from(to.0 - from.0).mul_add(t, f64::from(from.0)),
+                f64::from(to.1 - from.1).mul_add(t, f64::from(from.1)),
+            )
+        })
+        .collect()
 }
 
 pub fn drag_mouse(
-    _start_x: i32,
-    _start_y: i32,
-    _end_x: i32,
-    _end_y: i32,
-    _duration_ms: u32,
+    start_x: i32,
+    start_y: i32,
+    end_x: i32,
+    end_y: i32,
+    duration_ms: u32,
 ) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("drag_mouse".to_string()))
+    if duration_ms < 50 {
+        return Err(AutomationError::Other(
+            "Drag duration must be at least 50ms".to_string(),
+        ));
+    }
+
+    let x11 = X11::connect()?;
+    x11.move_pointer(start_x, start_y)?;
+    sleep(Duration::from_millis(50));
+    x11.button(BUTTON_LEFT, true)?;
+    sleep(Duration::from_millis(50));
+
+    let step_count = (duration_ms / 50).max(1);
+    for (x, y) in linspace((start_x, start_y), (end_x, end_y), step_count) {
+        #[allow(clippy::cast_possible_truncation)]
+        x11.move_pointer(x as i32, y as i32)?;
+        sleep(Duration::from_millis(50));
+    }
+
+    x11.button(BUTTON_LEFT, false)
 }
 
 pub fn drag_control(
-    _element: &Element,
-    _target_x: i32,
-    _target_y: i32,
-    _duration_ms: u32,
+    element: &Element,
+    target_x: i32,
+    target_y: i32,
+    duration_ms: u32,
 ) -> Result<(), AutomationError> {
-    Err(AutomationError::NotSupported("drag_control".to_string()))
+    let (x, y) = element.center();
+    drag_mouse(x, y, target_x, target_y, duration_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_split_like_a_shell() {
+        assert_eq!(
+            split_arguments(r#"-n  "two words" 'it''s' a\ b """#),
+            ["-n", "two words", "its", "a b", ""]
+        );
+        assert!(split_arguments("   ").is_empty());
+    }
+
+    #[test]
+    fn keys_map_to_keysyms() {
+        assert_eq!(keysym_for_char('a'), 0x61);
+        assert_eq!(keysym_for_char('é'), 0xe9);
+        assert_eq!(keysym_for_char('ж'), 0x0100_0436);
+        assert_eq!(keysym_for_char('\n'), XK_RETURN);
+        assert_eq!(keysym_for_virtual_key(0x41), Some(0x61));
+        assert_eq!(keysym_for_virtual_key(0x7B), Some(XK_F1 + 11));
+        assert_eq!(keysym_for_virtual_key(0xFF), None);
+        assert_eq!(keysym_for_key_name("PageDown").unwrap(), XK_PAGE_DOWN);
+        assert_eq!(keysym_for_key_name("S").unwrap(), 0x73);
+        assert!(keysym_for_key_name("F13").is_err());
+        assert!(key_sequence("hyper+a").is_err());
+    }
+
+    #[test]
+    fn keysyms_map_back_to_key_names() {
+        for name in ["enter", "pagedown", "shift", "f12", "a", "7", "."] {
+            let keysym = keysym_for_key_name(name).unwrap();
+            assert_eq!(key_name_for_keysym(keysym).as_deref(), Some(name));
+        }
+        assert_eq!(key_name_for_keysym(XK_CONTROL_R).as_deref(), Some("ctrl"));
+        assert_eq!(key_name_for_keysym(u32::from('A')).as_deref(), Some("a"));
+        assert_eq!(key_name_for_keysym(XK_CAPS_LOCK), None);
+
+        assert_eq!(char_for_keysym(keysym_for_char('ж')), Some('ж'));
+        assert_eq!(char_for_keysym(u32::from(' ')), Some(' '));
+        assert_eq!(char_for_keysym(XK_RETURN), None);
+    }
+
+    #[test]
+    fn processes_are_found_through_proc() {
+        let app = launch_application("sleep", "5").unwrap();
+        assert!(app.is_running());
+        assert!(
+            find_processes_by_name("sleep")
+                .unwrap()
+                .iter()
+                .any(|found| found.pid() == app.pid())
+        );
+
+        let attached = attach_to_process_by_pid(app.pid()).unwrap();
+        attached.close().unwrap();
+        assert_eq!(app.wait_for_exit(Some(5000)).unwrap(), 128 + 15);
+        assert!(!app.is_running());
+        assert!(matches!(
+            attach_to_process_by_pid(app.pid()),
+            Err(AutomationError::ProcessNotFound { .. })
+        ));
+
+        assert!(launch_application("/nonexistent/app", "").is_err());
+    }
 }
@@ -1,5 +1,5 @@
+mod atspi;
 pub mod automation;
-pub mod selector;
+mod x11;
 
 pub use automation::*;
-pub use selector::*;
@@ -0,0 +1,738 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use image::{Rgba, RgbaImage};
+use x11rb::atom_manager;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{
+    AtomEnum, BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT, ClientMessageEvent, ConfigureWindowAux,
+    ConnectionExt as _, CreateWindowAux, EventMask, ImageFormat, ImageOrder, InputFocus,
+    KEY_PRESS_EVENT, KEY_RELEASE_EVENT, KeyButMask, MOTION_NOTIFY_EVENT, MapState, PropMode,
+    StackMode, Window, WindowClass,
+};
+use x11rb::protocol::xtest::{self, ConnectionExt as _};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{CURRENT_TIME, NONE};
+
+use super::automation::{AutomationError, Rect};
+
+pub const BUTTON_LEFT: u8 = 1;
+pub const BUTTON_RIGHT: u8 = 3;
+pub const BUTTON_WHEEL_UP: u8 = 4;
+pub const BUTTON_WHEEL_DOWN: u8 = 5;
+pub const BUTTON_WHEEL_LEFT: u8 = 6;
+pub const BUTTON_WHEEL_RIGHT: u8 = 7;
+
+pub const XK_SHIFT_L: u32 = 0xffe1;
+
+/// ICCCM `WM_STATE` value of an iconified (minimized) window.
+const ICONIC_STATE: u32 = 3;
+/// EWMH source indication for requests sent on behalf of a pager or tool.
+const SOURCE_PAGER: u32 = 2;
+const NET_WM_STATE_REMOVE: u32 = 0;
+const NET_WM_STATE_ADD: u32 = 1;
+
+/// Time given to the target client to read a key event before a temporarily
+/// remapped keycode is restored.
+const REMAP_SETTLE_MS: u64 = 20;
+
+atom_manager! {
+    pub Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_CLOSE_WINDOW,
+        _NET_SUPPORTING_WM_CHECK,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        UTF8_STRING,
+        WM_CHANGE_STATE,
+        WM_DELETE_WINDOW,
+        WM_PROTOCOLS,
+        WM_STATE,
+    }
+}
+
+fn request_failed(error: impl std::fmt::Display) -> AutomationError {
+    AutomationError::Other(format!("X11 request failed: {error}"))
+}
+
+/// A connection to the X server named by `DISPLAY`, with the EWMH/ICCCM atoms
+/// the backend needs already interned.
+pub struct X11 {
+    conn: RustConnection,
+    root: Window,
+    root_depth: u8,
+    root_visual: u32,
+    screen_width: u16,
+    screen_height: u16,
+    atoms: Atoms,
+}
+
+impl X11 {
+    pub fn connect() -> Result<Self, AutomationError> {
+        let (conn, screen_num) = RustConnection::connect(None)
+            .map_err(|e| AutomationError::Other(format!("Cannot connect to X display: {e}")))?;
+        let screen = &conn.setup().roots[screen_num];
+        let (root, root_depth, root_visual) = (screen.root, screen.root_depth, screen.root_visual);
+        let (screen_width, screen_height) = (screen.width_in_pixels, screen.height_in_pixels);
+        let atoms = Atoms::new(&conn)
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?;
+
+        Ok(Self {
+            conn,
+            root,
+            root_depth,
+            root_visual,
+            screen_width,
+            screen_height,
+            atoms,
+        })
+    }
+
+    fn property32(&self, window: Window, property: u32, kind: impl Into<u32>) -> Vec<u32> {
+        self.conn
+            .get_property(false, window, property, kind.into(), 0, u32::MAX / 4)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(Iterator::collect))
+            .unwrap_or_default()
+    }
+
+    fn property_bytes(&self, window: Window, property: u32, kind: impl Into<u32>) -> Vec<u8> {
+        self.conn
+            .get_property(false, window, property, kind.into(), 0, u32::MAX / 4)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.value)
+            .unwrap_or_default()
+    }
+
+    /// Whether an EWMH-compliant window manager is running.
+    pub fn has_window_manager(&self) -> bool {
+        !self
+            .property32(
+                self.root,
+                self.atoms._NET_SUPPORTING_WM_CHECK,
+                AtomEnum::WINDOW,
+            )
+            .is_empty()
+    }
+
+    /// Top-level client windows: `_NET_CLIENT_LIST` when a window manager
+    /// maintains it, otherwise the root's children that carry a title or class.
+    pub fn top_level_windows(&self) -> Result<Vec<Window>, AutomationError> {
+        let clients = self.property32(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW);
+        if !clients.is_empty() {
+            return Ok(clients);
+        }
+
+        let tree = self
+            .conn
+            .query_tree(self.root)
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?;
+
+        Ok(tree
+            .children
+            .into_iter()
+            .filter(|&window| {
+                let override_redirect = self
+                    .conn
+                    .get_window_attributes(window)
+                    .ok()
+                    .and_then(|cookie| cookie.reply().ok())
+                    .is_none_or(|attributes| attributes.override_redirect);
+                !override_redirect
+                    && (!self.title(window).is_empty() || !self.class(window).is_empty())
+            })
+            .collect())
+    }
+
+    /// Top-level windows from the bottom to the top of the stacking order.
+    /// Without `_NET_CLIENT_LIST_STACKING` this is the order of
+    /// [`Self::top_level_windows`].
+    pub fn stacked_windows(&self) -> Result<Vec<Window>, AutomationError> {
+        let stacking = self.property32(
+            self.root,
+            self.atoms._NET_CLIENT_LIST_STACKING,
+            AtomEnum::WINDOW,
+        );
+        if stacking.is_empty() {
+            self.top_level_windows()
+        } else {
+            Ok(stacking)
+        }
+    }
+
+    pub fn title(&self, window: Window) -> String {
+        let name = self.property_bytes(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING);
+        let name = if name.is_empty() {
+            self.property_bytes(window, AtomEnum::WM_NAME.into(), AtomEnum::ANY)
+        } else {
+            name
+        };
+        String::from_utf8_lossy(&name).into_owned()
+    }
+
+    pub fn set_title(&self, window: Window, title: &str) -> Result<(), AutomationError> {
+        self.conn
+            .change_property8(
+                PropMode::REPLACE,
+                window,
+                self.atoms._NET_WM_NAME,
+                self.atoms.UTF8_STRING,
+                title.as_bytes(),
+            )
+            .map_err(request_failed)?;
+        self.conn
+            .change_property8(
+                PropMode::REPLACE,
+                window,
+                AtomEnum::WM_NAME,
+                self.atoms.UTF8_STRING,
+                title.as_bytes(),
+            )
+            .map_err(request_failed)?;
+        self.sync()
+    }
+
+    /// The class part of `WM_CLASS` (`"instance\0Class\0"`), falling back to
+    /// the instance name.
+    pub fn class(&self, window: Window) -> String {
+        let bytes = self.property_bytes(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING);
+        let mut parts = bytes
+            .split(|&b| b == 0)
+            .filter(|part| !part.is_empty())
+            .map(|part| String::from_utf8_lossy(part).into_owned());
+        let instance = parts.next().unwrap_or_default();
+        parts.next().unwrap_or(instance)
+    }
+
+    pub fn pid(&self, window: Window) -> Option<u32> {
+        self.property32(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL)
+            .first()
+            .copied()
+    }
+
+    /// Window geometry in root (screen) coordinates.
+    pub fn bounds(&self, window: Window) -> Result<Rect, AutomationError> {
+        let geometry = self
+            .conn
+            .get_geometry(window)
+            .map_err(request_failed)?
+            .reply()
+            .map_err(|_| AutomationError::WindowNotFound {
+                title: format!("0x{window:x}"),
+            })?;
+        let origin = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?;
+
+        Ok(Rect {
+            left: i32::from(origin.dst_x),
+            top: i32::from(origin.dst_y),
+            width: i32::from(geometry.width),
+            height: i32::from(geometry.height),
+        })
+    }
+
+    pub fn is_viewable(&self, window: Window) -> bool {
+        self.conn
+            .get_window_attributes(window)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|attributes| attributes.map_state == MapState::VIEWABLE)
+    }
+
+    fn net_wm_state(&self, window: Window) -> Vec<u32> {
+        self.property32(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM)
+    }
+
+    pub fn is_minimized(&self, window: Window) -> bool {
+        if self
+            .net_wm_state(window)
+            .contains(&self.atoms._NET_WM_STATE_HIDDEN)
+            || self
+                .property32(window, self.atoms.WM_STATE, self.atoms.WM_STATE)
+                .first()
+                == Some(&ICONIC_STATE)
+        {
+            return true;
+        }
+        !self.has_window_manager() && !self.is_viewable(window)
+    }
+
+    pub fn is_maximized(&self, window: Window) -> bool {
+        if self.has_window_manager() {
+            let state = self.net_wm_state(window);
+            return state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ)
+                && state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT);
+        }
+        self.bounds(window).is_ok_and(|bounds| {
+            bounds.left == 0
+                && bounds.top == 0
+                && bounds.width == i32::from(self.screen_width)
+                && bounds.height == i32::from(self.screen_height)
+        })
+    }
+
+    /// The active top-level window, from `_NET_ACTIVE_WINDOW` or else the
+    /// top-level ancestor of the input focus.
+    pub fn active_window(&self) -> Option<Window> {
+        if let Some(&window) = self
+            .property32(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW)
+            .first()
+            && window != NONE
+        {
+            return Some(window);
+        }
+
+        let mut window = self.conn.get_input_focus().ok()?.reply().ok()?.focus;
+        loop {
+            if window == NONE
+                || window == self.root
+                || window == u32::from(InputFocus::POINTER_ROOT)
+            {
+                return None;
+            }
+            let parent = self.conn.query_tree(window).ok()?.reply().ok()?.parent;
+            if parent == self.root {
+                return Some(window);
+            }
+            window = parent;
+        }
+    }
+
+    fn send_root_message(
+        &self,
+        window: Window,
+        kind: u32,
+        data: [u32; 5],
+    ) -> Result<(), AutomationError> {
+        let event = ClientMessageEvent::new(32, window, kind, data);
+        self.conn
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .map_err(request_failed)?;
+        self.sync()
+    }
+
+    pub fn activate(&self, window: Window) -> Result<(), AutomationError> {
+        self.conn.map_window(window).map_err(request_failed)?;
+        if self.has_window_manager() {
+            return self.send_root_message(
+                window,
+                self.atoms._NET_ACTIVE_WINDOW,
+                [SOURCE_PAGER, CURRENT_TIME, 0, 0, 0],
+            );
+        }
+
+        self.conn
+            .configure_window(
+                window,
+                &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
+            )
+            .map_err(request_failed)?;
+        self.sync()?;
+        self.conn
+            .set_input_focus(InputFocus::PARENT, window, CURRENT_TIME)
+            .map_err(request_failed)?;
+        self.sync()
+    }
+
+    pub fn minimize(&self, window: Window) -> Result<(), AutomationError> {
+        if self.has_window_manager() {
+            return self.send_root_message(
+                window,
+                self.atoms.WM_CHANGE_STATE,
+                [ICONIC_STATE, 0, 0, 0, 0],
+            );
+        }
+        // Without a window manager there is no iconic state; unmapping is the
+        // closest equivalent and `restore` maps the window back.
+        self.conn.unmap_window(window).map_err(request_failed)?;
+        self.sync()
+    }
+
+    pub fn maximize(&self, window: Window) -> Result<(), AutomationError> {
+        if self.has_window_manager() {
+            return self.send_root_message(
+                window,
+                self.atoms._NET_WM_STATE,
+                [
+                    NET_WM_STATE_ADD,
+                    self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                    self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                    SOURCE_PAGER,
+                    0,
+                ],
+            );
+        }
+        self.configure(
+            window,
+            ConfigureWindowAux::new()
+                .x(0)
+                .y(0)
+                .width(u32::from(self.screen_width))
+                .height(u32::from(self.screen_height)),
+        )
+    }
+
+    pub fn restore(&self, window: Window) -> Result<(), AutomationError> {
+        if self.has_window_manager() {
+            self.send_root_message(
+                window,
+                self.atoms._NET_WM_STATE,
+                [
+                    NET_WM_STATE_REMOVE,
+                    self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                    self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                    SOURCE_PAGER,
+                    0,
+                ],
+            )?;
+        }
+        self.show(window)
+    }
+
+    pub fn show(&self, window: Window) -> Result<(), AutomationError> {
+        self.conn.map_window(window).map_err(request_failed)?;
+        self.sync()
+    }
+
+    pub fn configure(
+        &self,
+        window: Window,
+        aux: ConfigureWindowAux,
+    ) -> Result<(), AutomationError> {
+        self.conn
+            .configure_window(window, &aux)
+            .map_err(request_failed)?;
+        self.sync()
+    }
+
+    /// Asks the window to close through `WM_DELETE_WINDOW` when it supports the
+    /// protocol, otherwise through the window manager, otherwise by
+    /// disconnecting its client.
+    pub fn close(&self, window: Window) -> Result<(), AutomationError> {
+        let protocols = self.property32(window, self.atoms.WM_PROTOCOLS, AtomEnum::ATOM);
+        if protocols.contains(&self.atoms.WM_DELETE_WINDOW) {
+            let event = ClientMessageEvent::new(
+                32,
+                window,
+                self.atoms.WM_PROTOCOLS,
+                [self.atoms.WM_DELETE_WINDOW, CURRENT_TIME, 0, 0, 0],
+            );
+            self.conn
+                .send_event(false, window, EventMask::NO_EVENT, event)
+                .map_err(request_failed)?;
+            return self.sync();
+        }
+
+        if self.has_window_manager() {
+            return self.send_root_message(
+                window,
+                self.atoms._NET_CLOSE_WINDOW,
+                [CURRENT_TIME, SOURCE_PAGER, 0, 0, 0],
+            );
+        }
+
+        self.conn.kill_client(window).map_err(request_failed)?;
+        self.sync()
+    }
+
+    /// Creates an unmanaged, solid-coloured window over `rect`; used for the
+    /// edges of highlight overlays.
+    pub fn create_overlay_window(
+        &self,
+        rect: Rect,
+        rgb: (u8, u8, u8),
+    ) -> Result<Window, AutomationError> {
+        let window = self.conn.generate_id().map_err(request_failed)?;
+        let pixel = (u32::from(rgb.0) << 16) | (u32::from(rgb.1) << 8) | u32::from(rgb.2);
+        self.conn
+            .create_window(
+                self.root_depth,
+                window,
+                self.root,
+                i16::try_from(rect.left).unwrap_or(i16::MAX),
+                i16::try_from(rect.top).unwrap_or(i16::MAX),
+                u16::try_from(rect.width.max(1)).unwrap_or(u16::MAX),
+                u16::try_from(rect.height.max(1)).unwrap_or(u16::MAX),
+                0,
+                WindowClass::INPUT_OUTPUT,
+                self.root_visual,
+                &CreateWindowAux::new()
+                    .background_pixel(pixel)
+                    .override_redirect(1),
+            )
+            .map_err(request_failed)?;
+        self.conn.map_window(window).map_err(request_failed)?;
+        Ok(window)
+    }
+
+    /// The whole screen in root coordinates.
+    pub fn screen_rect(&self) -> Rect {
+        Rect {
+            left: 0,
+            top: 0,
+            width: i32::from(self.screen_width),
+            height: i32::from(self.screen_height),
+        }
+    }
+
+    /// Reads the pixels of `rect`, clipped to the screen, from the root
+    /// window; what is shown on screen, including other windows on top.
+    pub fn capture(&self, rect: Rect) -> Result<RgbaImage, AutomationError> {
+        let screen = self.screen_rect();
+        let left = rect.left.clamp(0, screen.width);
+        let top = rect.top.clamp(0, screen.height);
+        let right = (rect.left.saturating_add(rect.width)).clamp(left, screen.width);
+        let bottom = (rect.top.saturating_add(rect.height)).clamp(top, screen.height);
+        let (width, height) = (right - left, bottom - top);
+        if width == 0 || height == 0 {
+            return Err(AutomationError::Other(format!(
+                "Area {}x{} at ({}, {}) is outside the screen",
+                rect.width, rect.height, rect.left, rect.top
+            )));
+        }
+
+        let setup = self.conn.setup();
+        let bits_per_pixel = setup
+            .pixmap_formats
+            .iter()
+            .find(|format| format.depth == self.root_depth)
+            .map_or(0, |format| format.bits_per_pixel);
+        if bits_per_pixel != 32 {
+            return Err(AutomationError::NotSupported(format!(
+                "screen capture at {bits_per_pixel} bits per pixel"
+            )));
+        }
+        let msb_first = setup.image_byte_order == ImageOrder::MSB_FIRST;
+
+        // Clamped to the screen, whose size fits a u16
+        let (Ok(src_x), Ok(src_y), Ok(width), Ok(height)) = (
+            i16::try_from(left),
+            i16::try_from(top),
+            u16::try_from(width),
+            u16::try_from(height),
+        ) else {
+            return Err(AutomationError::Other(
+                "Capture area is too large".to_string(),
+            ));
+        };
+        let reply = self
+            .conn
+            .get_image(
+                ImageFormat::Z_PIXMAP,
+                self.root,
+                src_x,
+                src_y,
+                width,
+                height,
+                !0,
+            )
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?;
+
+        let stride = reply.data.len() / usize::from(height);
+        let mut image = RgbaImage::new(u32::from(width), u32::from(height));
+        for (row, pixels) in reply.data.chunks_exact(stride).zip(image.rows_mut()) {
+            for (bytes, pixel) in row.chunks_exact(4).zip(pixels) {
+                let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+                let value = if msb_first {
+                    u32::from_be_bytes(bytes)
+                } else {
+                    u32::from_le_bytes(bytes)
+                };
+                let [_, red, green, blue] = value.to_be_bytes();
+                *pixel = Rgba([red, green, blue, 255]);
+            }
+        }
+        Ok(image)
+    }
+
+    pub fn destroy_window(&self, window: Window) -> Result<(), AutomationError> {
+        self.conn.destroy_window(window).map_err(request_failed)?;
+        self.sync()
+    }
+
+    pub fn sync(&self) -> Result<(), AutomationError> {
+        self.conn.sync().map_err(request_failed)
+    }
+
+    fn require_xtest(&self) -> Result<(), AutomationError> {
+        match self.conn.extension_information(xtest::X11_EXTENSION_NAME) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(AutomationError::NotSupported(
+                "XTest extension is not available on this display".to_string(),
+            )),
+            Err(e) => Err(request_failed(e)),
+        }
+    }
+
+    fn fake_input(&self, kind: u8, detail: u8, x: i16, y: i16) -> Result<(), AutomationError> {
+        self.require_xtest()?;
+        self.conn
+            .xtest_fake_input(kind, detail, CURRENT_TIME, self.root, x, y, 0)
+            .map_err(request_failed)?;
+        self.sync()
+    }
+
+    /// Pointer position on the screen and the buttons and modifiers held.
+    pub fn pointer(&self) -> Result<(i32, i32, KeyButMask), AutomationError> {
+        let reply = self
+            .conn
+            .query_pointer(self.root)
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?;
+        Ok((i32::from(reply.root_x), i32::from(reply.root_y), reply.mask))
+    }
+
+    /// The unshifted and shifted keysym of every key held down.
+    pub fn held_keysyms(&self) -> Result<Vec<(u32, u32)>, AutomationError> {
+        let keymap = self
+            .conn
+            .query_keymap()
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?
+            .keys;
+        let (min, per_keycode, keysyms) = self.keyboard_mapping()?;
+        let per_keycode = usize::from(per_keycode.max(1));
+
+        Ok((0..keymap.len() * 8)
+            .filter(|&keycode| keymap[keycode / 8] & (1 << (keycode % 8)) != 0)
+            .filter_map(|keycode| {
+                let syms = keysyms
+                    .chunks(per_keycode)
+                    .nth(keycode.checked_sub(usize::from(min))?)?;
+                Some((syms[0], syms.get(1).copied().unwrap_or(0)))
+            })
+            .collect())
+    }
+
+    pub fn move_pointer(&self, x: i32, y: i32) -> Result<(), AutomationError> {
+        let clamp = |v: i32| i16::try_from(v).unwrap_or(if v < 0 { i16::MIN } else { i16::MAX });
+        self.fake_input(MOTION_NOTIFY_EVENT, 0, clamp(x), clamp(y))
+    }
+
+    pub fn button(&self, button: u8, pressed: bool) -> Result<(), AutomationError> {
+        let kind = if pressed {
+            BUTTON_PRESS_EVENT
+        } else {
+            BUTTON_RELEASE_EVENT
+        };
+        self.fake_input(kind, button, 0, 0)
+    }
+
+    fn key(&self, keycode: u8, pressed: bool) -> Result<(), AutomationError> {
+        let kind = if pressed {
+            KEY_PRESS_EVENT
+        } else {
+            KEY_RELEASE_EVENT
+        };
+        self.fake_input(kind, keycode, 0, 0)
+    }
+
+    fn keyboard_mapping(&self) -> Result<(u8, u8, Vec<u32>), AutomationError> {
+        let setup = self.conn.setup();
+        let (min, max) = (setup.min_keycode, setup.max_keycode);
+        let mapping = self
+            .conn
+            .get_keyboard_mapping(min, max - min + 1)
+            .map_err(request_failed)?
+            .reply()
+            .map_err(request_failed)?;
+        Ok((min, mapping.keysyms_per_keycode, mapping.keysyms))
+    }
+
+    /// The keycode producing `keysym`, and whether Shift must be held for it.
+    fn keycode_for(&self, keysym: u32) -> Result<Option<(u8, bool)>, AutomationError> {
+        let (min, per_keycode, keysyms) = self.keyboard_mapping()?;
+        let per_keycode = usize::from(per_keycode.max(1));
+        for level in 0..per_keycode.min(2) {
+            if let Some(index) = keysyms
+                .chunks(per_keycode)
+                .position(|syms| syms.get(level) == Some(&keysym))
+            {
+                let keycode = u8::try_from(usize::from(min) + index).map_err(request_failed)?;
+                return Ok(Some((keycode, level == 1)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Presses or releases the key for `keysym` without touching modifiers.
+    pub fn keysym_event(&self, keysym: u32, pressed: bool) -> Result<(), AutomationError> {
+        let (keycode, _) = self.keycode_for(keysym)?.ok_or_else(|| {
+            AutomationError::Other(format!("No key produces keysym 0x{keysym:x}"))
+        })?;
+        self.key(keycode, pressed)
+    }
+
+    /// Types `keysym`, holding Shift when the layout needs it. Keysyms missing
+    /// from the layout are bound to a spare keycode for the duration of the
+    /// key press, as xdotool does.
+    pub fn type_keysym(&self, keysym: u32) -> Result<(), AutomationError> {
+        if let Some((keycode, shift)) = self.keycode_for(keysym)? {
+            let shift_keycode = if shift {
+                self.keycode_for(XK_SHIFT_L)?.map(|(code, _)| code)
+            } else {
+                None
+            };
+            if let Some(code) = shift_keycode {
+                self.key(code, true)?;
+            }
+            self.key(keycode, true)?;
+            self.key(keycode, false)?;
+            if let Some(code) = shift_keycode {
+                self.key(code, false)?;
+            }
+            return Ok(());
+        }
+
+        let (min, per_keycode, keysyms) = self.keyboard_mapping()?;
+        let per_keycode_usize = usize::from(per_keycode.max(1));
+        let spare = keysyms
+            .chunks(per_keycode_usize)
+            .rposition(|syms| syms.iter().all(|&sym| sym == 0))
+            .ok_or_else(|| {
+                AutomationError::Other(format!("No spare keycode to type keysym 0x{keysym:x}"))
+            })?;
+        let keycode = u8::try_from(usize::from(min) + spare).map_err(request_failed)?;
+
+        let remap = |sym: u32| {
+            self.conn
+                .change_keyboard_mapping(1, keycode, per_keycode, &vec![sym; per_keycode_usize])
+                .map_err(request_failed)?;
+            self.sync()
+        };
+        remap(keysym)?;
+        sleep(Duration::from_millis(REMAP_SETTLE_MS));
+        let typed = self
+            .key(keycode, true)
+            .and_then(|()| self.key(keycode, false));
+        sleep(Duration::from_millis(REMAP_SETTLE_MS));
+        remap(0)?;
+        typed
+    }
+}
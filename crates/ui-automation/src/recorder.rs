@@ -0,0 +1,317 @@
+//! Recording of user input for turning it into a workflow.
+//!
+//! The backends only report which buttons and keys are held right now
+//! ([`input_state`]); the recorder polls that state and turns the changes
+//! into clicks and key presses, capturing a selector for the element under
+//! the cursor at each click.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread::{self, JoinHandle, sleep};
+use std::time::{Duration, Instant};
+
+use crate::{
+    AutomationError, Element, control_from_point, find_windows, get_foreground_window, input_state,
+    robust_selector, window_from_point,
+};
+
+/// Modifier key names, in the order they are written in a combination
+pub const MODIFIER_KEYS: [&str; 4] = ["ctrl", "alt", "shift", "super"];
+
+const POLL_INTERVAL: Duration = Duration::from_millis(15);
+
+/// A key held down
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeldKey {
+    /// Name as `key_sequence` accepts it, e.g. `enter`, `a` or `ctrl`
+    pub name: String,
+    /// The character the key types with the current Shift state
+    pub text: Option<char>,
+}
+
+impl HeldKey {
+    fn is_modifier(&self) -> bool {
+        MODIFIER_KEYS.contains(&self.name.as_str())
+    }
+}
+
+/// Pointer position and the buttons and keys held at one moment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputState {
+    pub cursor: (i32, i32),
+    pub left_button: bool,
+    pub right_button: bool,
+    pub keys: Vec<HeldKey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+}
+
+/// The element a recorded click landed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClickTarget {
+    /// Selector of the clicked element, see [`robust_selector`]
+    pub selector: String,
+    /// Selector of its top-level window
+    pub window: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordedAction {
+    /// `target` is `None` when the clicked element could not be identified
+    Click {
+        button: MouseButton,
+        target: Option<ClickTarget>,
+    },
+    /// A key pressed with the modifiers held at that moment, e.g. `ctrl+s`;
+    /// `text` is what it typed, unless a modifier other than Shift was held
+    Key {
+        combination: String,
+        text: Option<char>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent {
+    pub action: RecordedAction,
+    /// Time since the recording started
+    pub at: Duration,
+}
+
+/// The mouse buttons that went down between two polls
+#[must_use]
+pub fn pressed_buttons(previous: &InputState, current: &InputState) -> Vec<MouseButton> {
+    let mut buttons = Vec::new();
+    if current.left_button && !previous.left_button {
+        buttons.push(MouseButton::Left);
+    }
+    if current.right_button && !previous.right_button {
+        buttons.push(MouseButton::Right);
+    }
+    buttons
+}
+
+/// The keys that went down between two polls, as [`RecordedAction::Key`].
+/// Modifiers alone are not reported; they are part of the combination of
+/// the key pressed while they are held.
+#[must_use]
+pub fn pressed_keys(previous: &InputState, current: &InputState) -> Vec<RecordedAction> {
+    let modifiers: Vec<&str> = MODIFIER_KEYS
+        .into_iter()
+        .filter(|&modifier| current.keys.iter().any(|key| key.name == modifier))
+        .collect();
+    let plain_text = modifiers.iter().all(|&modifier| modifier == "shift");
+
+    current
+        .keys
+        .iter()
+        .filter(|key| !key.is_modifier())
+        .filter(|key| !previous.keys.iter().any(|held| held.name == key.name))
+        .map(|key| {
+            let combination = modifiers
+                .iter()
+                .copied()
+                .chain([key.name.as_str()])
+                .collect::<Vec<_>>()
+                .join("+");
+            RecordedAction::Key {
+                combination,
+                text: key.text.filter(|_| plain_text),
+            }
+        })
+        .collect()
+}
+
+/// Records clicks and key presses in a background thread until stopped.
+///
+/// Input aimed at this process's own windows is left out, so the UI that
+/// controls the recording stays usable while it runs.
+pub struct Recorder {
+    stop: Arc<AtomicBool>,
+    events: Receiver<RecordedEvent>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Recorder {
+    pub fn start() -> Result<Self, AutomationError> {
+        let initial = input_state()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, events) = channel();
+
+        let thread = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || record(initial, &stop, &sender))
+        };
+
+        Ok(Self {
+            stop,
+            events,
+            thread: Some(thread),
+        })
+    }
+
+    /// Stops recording and returns everything recorded
+    #[must_use]
+    pub fn stop(mut self) -> Vec<RecordedEvent> {
+        self.join();
+        self.events.try_iter().collect()
+    }
+
+    fn join(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+fn record(mut previous: InputState, stop: &AtomicBool, sender: &Sender<RecordedEvent>) {
+    let started = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        sleep(POLL_INTERVAL);
+        let Ok(current) = input_state() else {
+            continue;
+        };
+
+        let mut actions = Vec::new();
+        for button in pressed_buttons(&previous, &current) {
+            let (x, y) = current.cursor;
+            let window = window_from_point(x, y).ok();
+            if window.as_ref().is_some_and(is_own_window) {
+                continue;
+            }
+            let target = window.and_then(|window| click_target(&window, x, y).ok());
+            actions.push(RecordedAction::Click { button, target });
+        }
+
+        let keys = pressed_keys(&previous, &current);
+        let own_focus = get_foreground_window().is_ok_and(|window| is_own_window(&window));
+        if !own_focus {
+            actions.extend(keys);
+        }
+
+        for action in actions {
+            let event = RecordedEvent {
+                action,
+                at: started.elapsed(),
+            };
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+        previous = current;
+    }
+}
+
+fn is_own_window(window: &Element) -> bool {
+    window.get_process_id() == std::process::id()
+}
+
+fn click_target(window: &Element, x: i32, y: i32) -> Result<ClickTarget, AutomationError> {
+    let control = control_from_point(window, x, y)?;
+    let windows = find_windows()?;
+    Ok(ClickTarget {
+        selector: robust_selector(control.as_ref().unwrap_or(window), window, &windows)?,
+        window: robust_selector(window, window, &windows)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str, text: Option<char>) -> HeldKey {
+        HeldKey {
+            name: name.to_string(),
+            text,
+        }
+    }
+
+    fn holding(keys: &[HeldKey]) -> InputState {
+        InputState {
+            keys: keys.to_vec(),
+            ..InputState::default()
+        }
+    }
+
+    fn pressed(action: &RecordedAction) -> (&str, Option<char>) {
+        match action {
+            RecordedAction::Key { combination, text } => (combination.as_str(), *text),
+            RecordedAction::Click { .. } => panic!("not a key: {action:?}"),
+        }
+    }
+
+    #[test]
+    fn buttons_are_reported_when_they_go_down() {
+        let up = InputState::default();
+        let left = InputState {
+            left_button: true,
+            ..InputState::default()
+        };
+        let both = InputState {
+            left_button: true,
+            right_button: true,
+            ..InputState::default()
+        };
+
+        assert_eq!(pressed_buttons(&up, &left), [MouseButton::Left]);
+        assert_eq!(pressed_buttons(&left, &both), [MouseButton::Right]);
+        assert!(pressed_buttons(&both, &both).is_empty());
+        assert!(pressed_buttons(&both, &up).is_empty());
+    }
+
+    #[test]
+    fn keys_carry_their_modifiers_and_text() {
+        let ctrl = key("ctrl", None);
+        let shift = key("shift", None);
+
+        let typed = pressed_keys(&holding(&[]), &holding(&[key("a", Some('a'))]));
+        assert_eq!(pressed(&typed[0]), ("a", Some('a')));
+
+        let shifted = pressed_keys(
+            &holding(std::slice::from_ref(&shift)),
+            &holding(&[shift.clone(), key("a", Some('A'))]),
+        );
+        assert_eq!(pressed(&shifted[0]), ("shift+a", Some('A')));
+
+        let combination = pressed_keys(
+            &holding(&[shift.clone(), ctrl.clone()]),
+            &holding(&[shift, ctrl, key("s", Some('S'))]),
+        );
+        assert_eq!(pressed(&combination[0]), ("ctrl+shift+s", None));
+
+        let enter = pressed_keys(&holding(&[]), &holding(&[key("enter", None)]));
+        assert_eq!(pressed(&enter[0]), ("enter", None));
+    }
+
+    #[test]
+    fn held_keys_and_lone_modifiers_are_not_repeated() {
+        let a = || key("a", Some('a'));
+
+        assert!(pressed_keys(&holding(&[a()]), &holding(&[a()])).is_empty());
+        assert!(pressed_keys(&holding(&[]), &holding(&[key("ctrl", None)])).is_empty());
+        // Shift changing the text of a key already held is not a new press
+        assert!(
+            pressed_keys(
+                &holding(&[a()]),
+                &holding(&[key("shift", None), key("a", Some('A'))])
+            )
+            .is_empty()
+        );
+
+        let both = pressed_keys(&holding(&[a()]), &holding(&[a(), key("b", Some('b'))]));
+        assert_eq!(both.len(), 1);
+        assert_eq!(pressed(&both[0]), ("b", Some('b')));
+    }
+}
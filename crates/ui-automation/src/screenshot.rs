@@ -0,0 +1,38 @@
+//! Screen capture, and reading and writing the PNG files it produces.
+//!
+//! The backends capture a rectangle of the screen ([`capture_rect`]); this
+//! module builds the screen, window and element captures on top of it.
+
+use std::path::Path;
+
+pub use image::RgbaImage;
+
+use crate::{AutomationError, Element, capture_rect, screen_rect};
+
+/// Captures the whole screen
+pub fn capture_screen() -> Result<RgbaImage, AutomationError> {
+    capture_rect(screen_rect()?)
+}
+
+/// Captures the part of the screen a window or control covers, including
+/// anything shown on top of it
+pub fn capture_element(element: &Element) -> Result<RgbaImage, AutomationError> {
+    capture_rect(element.bounds)
+}
+
+pub fn save_png(image: &RgbaImage, path: &Path) -> Result<(), AutomationError> {
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| {
+            AutomationError::Other(format!("Failed to save image '{}': {e}", path.display()))
+        })
+}
+
+/// Loads a PNG, or any other format the `image` crate reads, as RGBA
+pub fn load_image(path: &Path) -> Result<RgbaImage, AutomationError> {
+    image::open(path)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| {
+            AutomationError::Other(format!("Failed to load image '{}': {e}", path.display()))
+        })
+}
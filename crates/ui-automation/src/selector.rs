@@ -0,0 +1,2012 @@
+//! Selector DSL shared by all backends: parsing, matching, resolving a
+//! selector against an element tree and generating selectors from elements.
+//! Backends only supply the tree, through [`SelectorElement`].
+
+use crate::automation::AutomationError;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+/// Attributes matched as strings; these accept every operator and `regex:`.
+const STRING_ATTRIBUTES: &[&str] = &["title", "class", "text", "process", "automationid", "role"];
+const FLAG_ATTRIBUTES: &[&str] = &["enabled", "visible"];
+const VALID_ATTRIBUTES: &str =
+    "title, class, text, index, pid, process, automationid, role, enabled, visible";
+
+/// The view of a UI element the selector engine works on. Backends implement
+/// it for their `Element`; tests implement it for an in-memory tree.
+pub trait SelectorElement: Sized {
+    /// Identifies the element within its window
+    fn key(&self) -> isize;
+    fn is_window(&self) -> bool;
+    /// Window title or control text
+    fn text(&self) -> &str;
+    fn class_name(&self) -> &str;
+    fn is_enabled(&self) -> bool;
+    fn is_visible(&self) -> bool;
+    fn process_id(&self) -> u32;
+    fn process_name(&self) -> String;
+    fn automation_id(&self) -> String;
+    fn role(&self) -> String;
+    /// Elements one level down; backends may list deeper descendants too
+    fn children(&self) -> Result<Vec<Self>, AutomationError>;
+}
+
+/// How to match attribute values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchType {
+    Exact,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Regex,
+}
+
+impl MatchType {
+    /// Parse match type from the operator suffix after `~`: "", "=", "*", "$"
+    fn from_operator(op: &str) -> Self {
+        match op {
+            "=" => Self::Exact,
+            "*" => Self::StartsWith,
+            "$" => Self::EndsWith,
+            _ => Self::Contains,
+        }
+    }
+}
+
+/// How a path step relates to the element matched by the step before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combinator {
+    /// `>`: a direct child
+    Child,
+    /// `>>`: a descendant at any depth
+    Descendant,
+}
+
+/// Single matching criterion (attribute + value + match type)
+#[derive(Debug, Clone)]
+pub struct SelectorCriteria {
+    pub attribute: String,
+    pub value: String,
+    pub match_type: MatchType,
+    pub regex_obj: Option<Regex>,
+    /// Written `attr!~value`: the criterion holds when the value does not match
+    pub negated: bool,
+}
+
+impl SelectorCriteria {
+    /// Check if the element satisfies this criterion
+    #[must_use]
+    pub fn matches(&self, element: &impl SelectorElement) -> bool {
+        let matched = match self.attribute.as_str() {
+            "pid" => self
+                .value
+                .parse::<u32>()
+                .is_ok_and(|pid| element.process_id() == pid),
+            "enabled" => element.is_enabled() == (self.value == "true"),
+            "visible" => element.is_visible() == (self.value == "true"),
+            attribute => match_string(
+                &string_attribute(element, attribute),
+                &self.value,
+                self.match_type,
+                self.regex_obj.as_ref(),
+            ),
+        };
+        matched != self.negated
+    }
+}
+
+impl Display for SelectorCriteria {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let negation = if self.negated { "!" } else { "" };
+        let operator = match self.match_type {
+            MatchType::Exact => "=",
+            MatchType::StartsWith => "*",
+            MatchType::EndsWith => "$",
+            MatchType::Contains | MatchType::Regex => "",
+        };
+        write!(
+            f,
+            "{}{negation}~{operator}{}",
+            self.attribute,
+            escape_dsl_value(&self.value)
+        )
+    }
+}
+
+fn string_attribute<'a>(element: &'a impl SelectorElement, attribute: &str) -> Cow<'a, str> {
+    match attribute {
+        "title" | "text" => Cow::Borrowed(element.text()),
+        "class" => Cow::Borrowed(element.class_name()),
+        "process" => Cow::Owned(element.process_name()),
+        "automationid" => Cow::Owned(element.automation_id()),
+        "role" => Cow::Owned(element.role()),
+        _ => Cow::Borrowed(""),
+    }
+}
+
+/// One level in selector path (Window or Control)
+#[derive(Debug, Clone)]
+pub struct SelectorPath {
+    pub element_type: String, // "Window" or "Control"
+    pub combinator: Combinator,
+    pub criteria: Vec<SelectorCriteria>,
+    /// `index~N`: take the N-th (0-based) element matching the criteria
+    pub index: Option<usize>,
+}
+
+impl SelectorPath {
+    /// Check if the element satisfies every criterion of this step
+    #[must_use]
+    pub fn matches(&self, element: &impl SelectorElement) -> bool {
+        self.criteria.iter().all(|c| c.matches(element))
+    }
+}
+
+/// How a selector is resolved against the element tree
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MatchMode {
+    /// Every criterion of every step has to hold
+    #[default]
+    Strict,
+    /// Fall back to the best scoring element; see [`resolve_fuzzy`]
+    Fuzzy { threshold: f32 },
+}
+
+impl MatchMode {
+    /// Score a fuzzy match needs by default
+    pub const DEFAULT_FUZZY_THRESHOLD: f32 = 0.6;
+
+    #[must_use]
+    pub const fn fuzzy() -> Self {
+        Self::Fuzzy {
+            threshold: Self::DEFAULT_FUZZY_THRESHOLD,
+        }
+    }
+}
+
+/// Element found by fuzzy resolution
+#[derive(Debug)]
+pub struct FuzzyMatch<E> {
+    pub element: E,
+    /// Share of each step's criteria weight matched, averaged over the steps, 0.0-1.0
+    pub score: f32,
+    /// Criteria the element failed, as `path N: attr~value`
+    pub failed: Vec<String>,
+}
+
+/// Parsed selector: sequence of paths from Window → Control → ...
+#[derive(Debug, Clone)]
+pub struct Selector {
+    pub path: Vec<SelectorPath>,
+    pub original: String,
+}
+
+impl Selector {
+    /// Parse DSL string into Selector
+    /// Format: "Window>attr~val;attr~val>Control>attr~val;attr~val"
+    /// `>>` before an element type matches descendants at any depth.
+    /// Special characters in values are escaped: \>, \;, \\
+    pub fn parse(dsl: &str) -> Result<Self, AutomationError> {
+        let trimmed = dsl.trim();
+        if trimmed.is_empty() {
+            return Err(AutomationError::Other(
+                "Selector DSL cannot be empty".to_string(),
+            ));
+        }
+
+        let mut path = Vec::new();
+        let mut current: Option<SelectorPath> = None;
+        let mut descendant = false;
+
+        // Split by unescaped '>' to get element types and criteria; the empty
+        // part between the two characters of '>>' marks a descendant step
+        for part in split_by_unescaped(trimmed, '>') {
+            let part = part.trim();
+
+            if part == "Window" || part == "Control" {
+                if let Some(previous) = current.take() {
+                    path.push(finish_path(previous)?);
+                }
+                current = Some(SelectorPath {
+                    element_type: part.to_string(),
+                    combinator: if descendant {
+                        Combinator::Descendant
+                    } else {
+                        Combinator::Child
+                    },
+                    criteria: Vec::new(),
+                    index: None,
+                });
+                descendant = false;
+            } else if part.is_empty() {
+                if descendant || current.is_none() {
+                    return Err(AutomationError::Other(format!(
+                        "Misplaced '>' in selector: {trimmed}"
+                    )));
+                }
+                descendant = true;
+            } else {
+                let Some(step) = current.as_mut().filter(|_| !descendant) else {
+                    return Err(AutomationError::Other(format!(
+                        "Criteria must follow Window or Control: {part}"
+                    )));
+                };
+
+                // Split by unescaped ';' for multiple criteria
+                for criteria_part in split_by_unescaped(part, ';') {
+                    let criteria = parse_criteria(criteria_part)?;
+                    if criteria.attribute == "index" {
+                        if step.index.is_some() {
+                            return Err(AutomationError::Other(format!(
+                                "Element '{}' has more than one index",
+                                step.element_type
+                            )));
+                        }
+                        step.index = criteria.value.parse().ok();
+                    } else {
+                        step.criteria.push(criteria);
+                    }
+                }
+            }
+        }
+
+        if descendant {
+            return Err(AutomationError::Other(
+                "Selector cannot end with '>'".to_string(),
+            ));
+        }
+
+        // Save final element
+        let Some(last) = current else {
+            return Err(AutomationError::Other(
+                "No valid element types found in selector".to_string(),
+            ));
+        };
+        path.push(finish_path(last)?);
+
+        Ok(Self {
+            path,
+            original: trimmed.to_string(),
+        })
+    }
+
+    /// Convert selector back to DSL string
+    #[must_use]
+    pub fn to_dsl(&self) -> String {
+        self.original.clone()
+    }
+
+    /// Load selector from file (reads first line as DSL)
+    pub fn from_file(path: &str) -> Result<Self, AutomationError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| AutomationError::Other(format!("Failed to read selector file: {e}")))?;
+
+        let dsl = content.lines().next().unwrap_or("").trim();
+        if dsl.is_empty() {
+            return Err(AutomationError::Other(
+                "Selector file is empty or contains no DSL".to_string(),
+            ));
+        }
+
+        Self::parse(dsl)
+    }
+
+    /// Save selector to file
+    pub fn to_file(&self, path: &str) -> Result<(), AutomationError> {
+        // Create parent directories if needed
+        if let Some(parent) = Path::new(path).parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)
+                .map_err(|e| AutomationError::Other(format!("Failed to create directory: {e}")))?;
+        }
+
+        fs::write(path, &self.original)
+            .map_err(|e| AutomationError::Other(format!("Failed to write selector file: {e}")))?;
+
+        Ok(())
+    }
+}
+
+/// A step needs at least one criterion or an index
+fn finish_path(step: SelectorPath) -> Result<SelectorPath, AutomationError> {
+    if step.criteria.is_empty() && step.index.is_none() {
+        return Err(AutomationError::Other(format!(
+            "Element '{}' has no criteria",
+            step.element_type
+        )));
+    }
+    Ok(step)
+}
+
+/// Split string by delimiter character, respecting escape sequences (backslash)
+fn split_by_unescaped(s: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (i, ch) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == delimiter {
+            parts.push(&s[start..i]);
+            start = i + ch.len_utf8();
+        }
+    }
+
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Escape special DSL characters in values: >, ;, \
+/// Used when generating selectors from element properties
+#[must_use]
+pub fn escape_dsl_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('>', "\\>")
+        .replace(';', "\\;")
+}
+
+/// Unescape special DSL characters in values
+/// Used when parsing selectors to recover original values
+fn unescape_dsl_value(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            if let Some(&next) = chars.peek() {
+                match next {
+                    '>' | ';' | '\\' => {
+                        chars.next();
+                        result.push(next);
+                    }
+                    _ => result.push(ch),
+                }
+            } else {
+                result.push(ch);
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+
+    result
+}
+
+/// Parse single criteria string: "attr~val", "attr~=val", "attr~*val",
+/// "attr~$val" or "attr~regex:pattern", optionally negated as "attr!~val"
+fn parse_criteria(criteria_str: &str) -> Result<SelectorCriteria, AutomationError> {
+    let trimmed = criteria_str.trim();
+
+    let Some(pos) = trimmed.find('~') else {
+        return Err(AutomationError::Other(format!(
+            "Invalid criteria format (missing ~): {trimmed}"
+        )));
+    };
+    let name = trimmed[..pos].trim();
+    let (attribute, negated) = name
+        .strip_suffix('!')
+        .map_or((name, false), |attribute| (attribute.trim_end(), true));
+    let rest = &trimmed[pos + 1..];
+    let operator = rest
+        .get(..1)
+        .filter(|op| matches!(*op, "=" | "*" | "$"))
+        .unwrap_or("");
+    let value = rest[operator.len()..].trim();
+
+    if attribute.is_empty() || value.is_empty() {
+        return Err(AutomationError::Other(format!(
+            "Criteria has empty attribute or value: {trimmed}"
+        )));
+    }
+
+    let attr_lower = attribute.to_lowercase();
+    let match_type = MatchType::from_operator(operator);
+
+    if STRING_ATTRIBUTES.contains(&attr_lower.as_str()) {
+        // Check if value uses regex syntax
+        let (match_type, regex_obj) = if let Some(pattern) = value.strip_prefix("regex:") {
+            if pattern.is_empty() {
+                return Err(AutomationError::Other(
+                    "Regex pattern cannot be empty".to_string(),
+                ));
+            }
+
+            // Compile regex with case-insensitive flag
+            let regex = Regex::new(&format!("(?i){pattern}"))
+                .map_err(|e| AutomationError::Other(format!("Invalid regex pattern: {e}")))?;
+            (MatchType::Regex, Some(regex))
+        } else {
+            (match_type, None)
+        };
+
+        return Ok(SelectorCriteria {
+            attribute: attr_lower,
+            value: unescape_dsl_value(value),
+            match_type,
+            regex_obj,
+            negated,
+        });
+    }
+
+    let is_index = attr_lower == "index";
+    let value = if is_index || attr_lower == "pid" {
+        value
+            .parse::<u32>()
+            .map_err(|_| {
+                AutomationError::Other(format!("Attribute '{attr_lower}' needs a number: {value}"))
+            })?
+            .to_string()
+    } else if FLAG_ATTRIBUTES.contains(&attr_lower.as_str()) {
+        parse_flag(value)
+            .ok_or_else(|| {
+                AutomationError::Other(format!(
+                    "Attribute '{attr_lower}' needs true or false: {value}"
+                ))
+            })?
+            .to_string()
+    } else {
+        return Err(AutomationError::Other(format!(
+            "Unknown attribute: {attribute}. Valid: {VALID_ATTRIBUTES}"
+        )));
+    };
+
+    if !matches!(match_type, MatchType::Contains | MatchType::Exact) {
+        return Err(AutomationError::Other(format!(
+            "Attribute '{attr_lower}' only supports ~ and ~="
+        )));
+    }
+    if is_index && negated {
+        return Err(AutomationError::Other(
+            "Attribute 'index' cannot be negated".to_string(),
+        ));
+    }
+
+    Ok(SelectorCriteria {
+        attribute: attr_lower,
+        value,
+        match_type: MatchType::Exact,
+        regex_obj: None,
+        negated,
+    })
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Evaluate if string matches criteria
+#[must_use]
+pub fn match_string(
+    haystack: &str,
+    needle: &str,
+    match_type: MatchType,
+    regex_obj: Option<&Regex>,
+) -> bool {
+    if match_type == MatchType::Regex {
+        regex_obj.is_some_and(|re| re.is_match(haystack))
+    } else {
+        let h = haystack.to_lowercase();
+        let n = needle.to_lowercase();
+
+        match match_type {
+            MatchType::Exact => h == n,
+            MatchType::Contains => h.contains(&n),
+            MatchType::StartsWith => h.starts_with(&n),
+            MatchType::EndsWith => h.ends_with(&n),
+            MatchType::Regex => unreachable!(),
+        }
+    }
+}
+
+/// Resolve the selector against the tree below the given top-level windows
+pub fn resolve<E: SelectorElement>(
+    selector: &Selector,
+    windows: Vec<E>,
+) -> Result<E, AutomationError> {
+    check_element_types(selector)?;
+
+    let first_path = &selector.path[0];
+    let Some(mut current_element) = pick_match(windows, first_path, 0) else {
+        return Err(AutomationError::WindowNotFound {
+            title: format!("No window matches selector: {}", selector.original),
+        });
+    };
+
+    for (path_idx, path) in selector.path.iter().enumerate().skip(1) {
+        let candidates = match path.combinator {
+            Combinator::Child => current_element.children()?,
+            Combinator::Descendant => descendants(&current_element)?,
+        };
+        let Some(element) = pick_match(candidates, path, path_idx) else {
+            return Err(AutomationError::WindowNotFound {
+                title: format!(
+                    "No control matches selector at path {}: {}",
+                    path_idx, selector.original
+                ),
+            });
+        };
+        current_element = element;
+    }
+
+    Ok(current_element)
+}
+
+/// A selector starts at a Window and continues with Controls
+fn check_element_types(selector: &Selector) -> Result<(), AutomationError> {
+    let Some(first_path) = selector.path.first() else {
+        return Err(AutomationError::Other("Selector has no paths".to_string()));
+    };
+
+    if first_path.element_type != "Window" {
+        return Err(AutomationError::Other(
+            "First selector path must be Window".to_string(),
+        ));
+    }
+
+    for (path_idx, path) in selector.path.iter().enumerate().skip(1) {
+        if path.element_type != "Control" {
+            return Err(AutomationError::Other(format!(
+                "Selector path {} must be Control, got {}",
+                path_idx, path.element_type
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the selector in the given mode, logging the failed criteria of a
+/// fuzzy match so the selector can be repaired
+pub fn resolve_with<E: SelectorElement>(
+    selector: &Selector,
+    windows: Vec<E>,
+    mode: MatchMode,
+) -> Result<E, AutomationError> {
+    let MatchMode::Fuzzy { threshold } = mode else {
+        return resolve(selector, windows);
+    };
+
+    let found = resolve_fuzzy(selector, windows, threshold)?;
+    if !found.failed.is_empty() {
+        log::warn!(
+            "Selector matched fuzzily (score {:.2}), failed criteria: {}; selector: {}",
+            found.score,
+            found.failed.join(", "),
+            selector.original
+        );
+    }
+    Ok(found.element)
+}
+
+/// Resolve the selector to the element with the highest fuzzy score.
+///
+/// The score is the share of each step's criteria weight the element matches,
+/// averaged over the steps. Scoring per step keeps a drifted window title from
+/// outweighing the criteria of the control the selector is after.
+///
+/// The score has to reach `threshold` (0.0-1.0) and every step has to match
+/// at least one criterion. An exact match always wins.
+pub fn resolve_fuzzy<E: SelectorElement>(
+    selector: &Selector,
+    windows: Vec<E>,
+    threshold: f32,
+) -> Result<FuzzyMatch<E>, AutomationError> {
+    check_element_types(selector)?;
+
+    // Steps after each step, which can at best match in full, to skip
+    // branches that cannot reach the threshold any more
+    let mut remaining_steps = vec![0.0; selector.path.len()];
+    for path_idx in (1..selector.path.len()).rev() {
+        remaining_steps[path_idx - 1] = remaining_steps[path_idx] + 1.0;
+    }
+    let search = FuzzySearch {
+        selector,
+        threshold,
+        steps: remaining_steps[0] + 1.0,
+        remaining_steps,
+    };
+    let mut best = None;
+    search.step(0, windows, 0.0, &[], &mut best)?;
+
+    best.ok_or_else(|| AutomationError::WindowNotFound {
+        title: format!(
+            "No element scores {threshold:.2} on selector: {}",
+            selector.original
+        ),
+    })
+}
+
+/// Weight of a criterion in fuzzy scoring: identifiers set by developers
+/// count most, state and per-run values least
+fn attribute_weight(attribute: &str) -> f32 {
+    match attribute {
+        "automationid" => 4.0,
+        "title" | "text" => 3.0,
+        "class" | "role" | "process" => 2.0,
+        _ => 1.0,
+    }
+}
+
+fn step_weight(step: &SelectorPath) -> f32 {
+    let criteria: f32 = step
+        .criteria
+        .iter()
+        .map(|c| attribute_weight(&c.attribute))
+        .sum();
+    criteria + step.index.map_or(0.0, |_| attribute_weight("index"))
+}
+
+struct FuzzySearch<'a> {
+    selector: &'a Selector,
+    threshold: f32,
+    steps: f32,
+    remaining_steps: Vec<f32>,
+}
+
+impl FuzzySearch<'_> {
+    /// Score the candidates for step `path_idx` and descend into those that
+    /// can still reach the threshold, keeping the best complete match in `best`.
+    /// `matched` is the sum of the matched shares of the steps before.
+    fn step<E: SelectorElement>(
+        &self,
+        path_idx: usize,
+        candidates: Vec<E>,
+        matched: f32,
+        failed: &[String],
+        best: &mut Option<FuzzyMatch<E>>,
+    ) -> Result<(), AutomationError> {
+        let path = &self.selector.path[path_idx];
+        // The index counts among candidates meeting every criterion, as it
+        // does in strict resolution
+        let mut exact_position = 0;
+
+        for candidate in candidates {
+            if best.as_ref().is_some_and(|b| b.failed.is_empty()) {
+                return Ok(());
+            }
+
+            let mut step_matched = 0.0;
+            let mut step_failed = failed.to_vec();
+            for criteria in &path.criteria {
+                if criteria.matches(&candidate) {
+                    step_matched += attribute_weight(&criteria.attribute);
+                } else {
+                    step_failed.push(format!("path {path_idx}: {criteria}"));
+                }
+            }
+            if let Some(index) = path.index {
+                let all_matched = step_failed.len() == failed.len();
+                if all_matched && exact_position == index {
+                    step_matched += attribute_weight("index");
+                } else {
+                    step_failed.push(format!("path {path_idx}: index~{index}"));
+                }
+                if all_matched {
+                    exact_position += 1;
+                }
+            }
+
+            let matched = matched + step_matched / step_weight(path);
+            let reachable = matched + self.remaining_steps[path_idx];
+            if step_matched <= 0.0 || reachable < self.threshold * self.steps {
+                continue;
+            }
+
+            let Some(next) = self.selector.path.get(path_idx + 1) else {
+                let score = matched / self.steps;
+                if best.as_ref().is_none_or(|b| score > b.score) {
+                    *best = Some(FuzzyMatch {
+                        element: candidate,
+                        score,
+                        failed: step_failed,
+                    });
+                }
+                continue;
+            };
+
+            let children = match next.combinator {
+                Combinator::Child => candidate.children()?,
+                Combinator::Descendant => descendants(&candidate)?,
+            };
+            self.step(path_idx + 1, children, matched, &step_failed, best)?;
+        }
+        Ok(())
+    }
+}
+
+/// The element at the step's index among the candidates matching it
+fn pick_match<E: SelectorElement>(
+    candidates: Vec<E>,
+    path: &SelectorPath,
+    path_idx: usize,
+) -> Option<E> {
+    let mut matches = candidates
+        .into_iter()
+        .filter(|c| path.matches(c))
+        .collect::<Vec<_>>();
+
+    let Some(index) = path.index else {
+        if matches.len() > 1 {
+            log::warn!(
+                "Selector path {} matched {} elements, using first",
+                path_idx,
+                matches.len()
+            );
+        }
+        return (!matches.is_empty()).then(|| matches.remove(0));
+    };
+    (index < matches.len()).then(|| matches.swap_remove(index))
+}
+
+/// All elements below `root`, depth first in tree order
+fn descendants<E: SelectorElement>(root: &E) -> Result<Vec<E>, AutomationError> {
+    let mut seen = HashSet::from([root.key()]);
+    let mut descendants = Vec::new();
+    collect_descendants(root, &mut seen, &mut descendants)?;
+    Ok(descendants)
+}
+
+fn collect_descendants<E: SelectorElement>(
+    parent: &E,
+    seen: &mut HashSet<isize>,
+    out: &mut Vec<E>,
+) -> Result<(), AutomationError> {
+    for child in parent.children()? {
+        // Backends that already list nested controls as children would
+        // otherwise yield them once per ancestor
+        if seen.insert(child.key()) {
+            let mut nested = Vec::new();
+            collect_descendants(&child, seen, &mut nested)?;
+            out.push(child);
+            out.append(&mut nested);
+        }
+    }
+    Ok(())
+}
+
+/// Generate a selector DSL string from a Window
+///
+/// Generates a selector with both title and class criteria for robustness.
+/// Special characters (>, ;, \) in values are automatically escaped.
+///
+/// Returns error if both title and class are empty.
+pub fn window_to_selector(element: &impl SelectorElement) -> Result<String, AutomationError> {
+    if !element.is_window() {
+        return Err(AutomationError::Other(
+            "Element must be a window to generate window selector".to_string(),
+        ));
+    }
+
+    let title_empty = element.text().is_empty();
+    let class_empty = element.class_name().is_empty();
+
+    if title_empty && class_empty {
+        return Err(AutomationError::Other(
+            "Cannot generate window selector: both title and class are empty".to_string(),
+        ));
+    }
+
+    let mut criteria = Vec::new();
+
+    if !title_empty {
+        let escaped_title = escape_dsl_value(element.text());
+        criteria.push(format!("title~{escaped_title}"));
+    }
+
+    if !class_empty {
+        let escaped_class = escape_dsl_value(element.class_name());
+        criteria.push(format!("class~{escaped_class}"));
+    }
+
+    let criteria_str = criteria.join(";");
+    Ok(format!("Window>{criteria_str}"))
+}
+
+/// Generate a selector DSL string for a control anywhere inside `parent`
+///
+/// Uses class and text criteria, plus an index when earlier controls in the
+/// window match them too.
+pub fn control_to_selector<E: SelectorElement>(
+    element: &E,
+    parent: &E,
+) -> Result<String, AutomationError> {
+    if element.is_window() {
+        return Err(AutomationError::Other(
+            "Element must be a control to generate control selector".to_string(),
+        ));
+    }
+
+    if !parent.is_window() {
+        return Err(AutomationError::Other(
+            "Parent must be a window".to_string(),
+        ));
+    }
+
+    let text_empty = element.text().is_empty();
+    let class_empty = element.class_name().is_empty();
+
+    if text_empty && class_empty {
+        return Err(AutomationError::Other(
+            "Cannot generate control selector: both class and text are empty".to_string(),
+        ));
+    }
+
+    // Generate parent window selector
+    let window_dsl = window_to_selector(parent)?;
+
+    let mut criteria = Vec::new();
+
+    if !class_empty {
+        let escaped_class = escape_dsl_value(element.class_name());
+        criteria.push(format!("class~{escaped_class}"));
+    }
+
+    if !text_empty {
+        let escaped_text = escape_dsl_value(element.text());
+        criteria.push(format!("text~{escaped_text}"));
+    }
+
+    // Index among the controls the criteria alone would match, so the
+    // selector resolves to this control rather than the first match
+    let step = Selector::parse(&format!("Control>{}", criteria.join(";")))?;
+    if let Ok(index) = control_index(element, parent, &step.path[0]) {
+        // Include index if earlier controls match too (i.e., index > 0)
+        if index > 0 {
+            criteria.push(format!("index~{index}"));
+        }
+    } else {
+        // If we can't calculate index, continue without it
+        // This can happen if the control is not found, but we still generate the selector
+    }
+
+    let criteria_str = criteria.join(";");
+    Ok(format!("{window_dsl}>>Control>{criteria_str}"))
+}
+
+/// Roles whose text is what the user typed rather than a label
+const EDITABLE_ROLES: &[&str] = &["text", "password text", "entry", "editbar", "terminal"];
+
+/// Generate the selector that picks `element` out of `window` by its most
+/// stable attributes.
+///
+/// An index is added only when no combination of them is unique. `windows`
+/// are the top-level windows to tell `window` apart from; pass the window
+/// itself as `element` for a window selector.
+pub fn robust_selector<E: SelectorElement>(
+    element: &E,
+    window: &E,
+    windows: &[E],
+) -> Result<String, AutomationError> {
+    if !window.is_window() {
+        return Err(AutomationError::Other(
+            "Parent must be a window".to_string(),
+        ));
+    }
+
+    let window_step = unique_step("Window", window, &window_candidates(window), windows)?;
+    if element.key() == window.key() {
+        return Ok(format!("Window>{window_step}"));
+    }
+
+    let controls = descendants(window)?;
+    let control_step = unique_step("Control", element, &control_candidates(element), &controls)?;
+    Ok(format!("Window>{window_step}>>Control>{control_step}"))
+}
+
+/// Exact-match criteria for the attributes that have a value
+fn exact_criteria(attributes: &[(&str, &str)]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(attribute, value)| format!("{attribute}~={}", escape_dsl_value(value)))
+        .collect()
+}
+
+/// Process and class survive document changes, the title is added only to
+/// tell windows of the same application apart
+fn window_candidates(window: &impl SelectorElement) -> Vec<Vec<String>> {
+    let process = window.process_name();
+    vec![
+        exact_criteria(&[("process", &process), ("class", window.class_name())]),
+        exact_criteria(&[
+            ("process", &process),
+            ("class", window.class_name()),
+            ("title", window.text()),
+        ]),
+    ]
+}
+
+/// Most stable first: the developer-assigned id, then the role or class
+/// with the label, then the role or class alone
+fn control_candidates(control: &impl SelectorElement) -> Vec<Vec<String>> {
+    let automation_id = control.automation_id();
+    let role = control.role();
+    let label = if EDITABLE_ROLES.contains(&role.as_str()) {
+        ""
+    } else {
+        control.text()
+    };
+    let class = control.class_name();
+
+    vec![
+        exact_criteria(&[("automationid", &automation_id)]),
+        exact_criteria(&[("role", &role), ("text", label)]),
+        exact_criteria(&[("class", class), ("text", label)]),
+        exact_criteria(&[("role", &role)]),
+        exact_criteria(&[("class", class)]),
+    ]
+}
+
+/// The first candidate matching `target` alone among `elements`, or else the
+/// most selective one with the target's index among its matches
+fn unique_step<E: SelectorElement>(
+    element_type: &str,
+    target: &E,
+    candidates: &[Vec<String>],
+    elements: &[E],
+) -> Result<String, AutomationError> {
+    let mut fallback: Option<(usize, String, usize)> = None;
+
+    for criteria in candidates.iter().filter(|c| !c.is_empty()) {
+        let criteria = criteria.join(";");
+        let step = Selector::parse(&format!("{element_type}>{criteria}"))?;
+        let matches = elements
+            .iter()
+            .filter(|e| step.path[0].matches(*e))
+            .collect::<Vec<_>>();
+        let Some(index) = matches.iter().position(|e| e.key() == target.key()) else {
+            continue;
+        };
+
+        if matches.len() == 1 {
+            return Ok(criteria);
+        }
+        if fallback
+            .as_ref()
+            .is_none_or(|(count, ..)| matches.len() < *count)
+        {
+            fallback = Some((matches.len(), criteria, index));
+        }
+    }
+
+    fallback
+        .map(|(_, criteria, index)| format!("{criteria};index~{index}"))
+        .ok_or_else(|| {
+            AutomationError::Other(format!(
+                "Cannot generate {element_type} selector: no attribute identifies the element"
+            ))
+        })
+}
+
+fn control_index<E: SelectorElement>(
+    element: &E,
+    parent: &E,
+    step: &SelectorPath,
+) -> Result<usize, AutomationError> {
+    descendants(parent)?
+        .iter()
+        .filter(|c| step.matches(*c))
+        .position(|c| c.key() == element.key())
+        .ok_or_else(|| AutomationError::Other("Control not found in parent window".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_window() {
+        let selector = Selector::parse("Window>title~Notepad").unwrap();
+        assert_eq!(selector.path.len(), 1);
+        assert_eq!(selector.path[0].element_type, "Window");
+        assert_eq!(selector.path[0].criteria.len(), 1);
+        assert_eq!(selector.path[0].criteria[0].attribute, "title");
+        assert_eq!(selector.path[0].criteria[0].value, "Notepad");
+    }
+
+    #[test]
+    fn test_parse_window_and_control() {
+        let selector = Selector::parse("Window>title~Notepad>Control>class~Edit").unwrap();
+        assert_eq!(selector.path.len(), 2);
+        assert_eq!(selector.path[0].element_type, "Window");
+        assert_eq!(selector.path[1].element_type, "Control");
+    }
+
+    #[test]
+    fn test_parse_multiple_criteria() {
+        let selector =
+            Selector::parse("Window>title~Notepad;class~#32770>Control>class~Edit").unwrap();
+        assert_eq!(selector.path[0].criteria.len(), 2);
+        assert_eq!(selector.path[0].criteria[0].attribute, "title");
+        assert_eq!(selector.path[0].criteria[1].attribute, "class");
+    }
+
+    #[test]
+    fn test_parse_with_operators() {
+        let selector = Selector::parse("Window>title~=Notepad>Control>text~*Save").unwrap();
+        assert_eq!(selector.path[0].criteria[0].match_type, MatchType::Exact);
+        assert_eq!(
+            selector.path[1].criteria[0].match_type,
+            MatchType::StartsWith
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_attribute() {
+        let result = Selector::parse("Window>invalid~value");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_empty_selector() {
+        let result = Selector::parse("");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_no_criteria() {
+        let result = Selector::parse("Window");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_operator() {
+        let result = Selector::parse("Window>title-Notepad");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_match_string_exact() {
+        assert!(match_string("Notepad", "Notepad", MatchType::Exact, None));
+        assert!(!match_string("Notepad", "Note", MatchType::Exact, None));
+        assert!(match_string("notepad", "NOTEPAD", MatchType::Exact, None)); // case-insensitive
+    }
+
+    #[test]
+    fn test_match_string_contains() {
+        assert!(match_string("Notepad", "Note", MatchType::Contains, None));
+        assert!(match_string("Notepad", "pad", MatchType::Contains, None));
+        assert!(!match_string("Notepad", "xyz", MatchType::Contains, None));
+    }
+
+    #[test]
+    fn test_match_string_startswith() {
+        assert!(match_string("Notepad", "Note", MatchType::StartsWith, None));
+        assert!(!match_string("Notepad", "pad", MatchType::StartsWith, None));
+    }
+
+    #[test]
+    fn test_match_string_endswith() {
+        assert!(match_string("Notepad", "pad", MatchType::EndsWith, None));
+        assert!(!match_string("Notepad", "Note", MatchType::EndsWith, None));
+    }
+
+    #[test]
+    fn test_to_dsl() {
+        let original = "Window>title~Notepad>Control>class~Edit";
+        let selector = Selector::parse(original).unwrap();
+        assert_eq!(selector.to_dsl(), original);
+    }
+
+    #[test]
+    fn test_parse_nested_controls() {
+        let selector = Selector::parse(
+            "Window>title~MyApp>Control>class~GroupBox>Control>class~Button;text~OK",
+        )
+        .unwrap();
+        assert_eq!(selector.path.len(), 3);
+        assert_eq!(selector.path[2].criteria.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_all_match_types() {
+        let cases = vec![
+            ("Window>title~Contains", MatchType::Contains),
+            ("Window>title~=Exact", MatchType::Exact),
+            ("Window>title~*StartsWith", MatchType::StartsWith),
+            ("Window>title~$EndsWith", MatchType::EndsWith),
+        ];
+
+        for (dsl, expected_type) in cases {
+            let selector = Selector::parse(dsl).unwrap();
+            assert_eq!(selector.path[0].criteria[0].match_type, expected_type);
+        }
+    }
+
+    #[test]
+    fn test_parse_whitespace_handling() {
+        let selector =
+            Selector::parse("  Window > title ~ Notepad > Control > class ~ Edit  ").unwrap();
+        assert_eq!(selector.path.len(), 2);
+        assert_eq!(selector.path[0].criteria[0].value, "Notepad");
+    }
+
+    #[test]
+    fn test_criteria_parsing_errors() {
+        let invalid_cases = vec![
+            "Window>title",       // Missing value
+            "Window>title~",      // Empty value
+            "Window>~value",      // Missing attribute
+            "Window>title-value", // Wrong operator
+        ];
+
+        for invalid_dsl in invalid_cases {
+            let result = Selector::parse(invalid_dsl);
+            assert!(result.is_err(), "Expected error for: {invalid_dsl}");
+        }
+    }
+
+    #[test]
+    fn test_parse_regex_pattern_simple() {
+        let selector = Selector::parse("Window>title~regex:.*Notepad.*").unwrap();
+        assert_eq!(selector.path[0].criteria[0].match_type, MatchType::Regex);
+        assert!(selector.path[0].criteria[0].regex_obj.is_some());
+    }
+
+    #[test]
+    fn test_parse_regex_pattern_with_capture_groups() {
+        let selector = Selector::parse("Window>title~regex:^(.*Notepad.*)$").unwrap();
+        assert_eq!(selector.path[0].criteria[0].match_type, MatchType::Regex);
+        assert!(selector.path[0].criteria[0].regex_obj.is_some());
+        assert_eq!(selector.path[0].criteria.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_regex_invalid_syntax() {
+        let result = Selector::parse("Window>title~regex:[invalid");
+        assert!(result.is_err());
+        let error_msg = result.unwrap_err().to_string();
+        assert!(error_msg.contains("Invalid regex"));
+    }
+
+    #[test]
+    fn test_parse_regex_on_any_string_attribute() {
+        for attribute in ["title", "class", "text", "process", "automationid", "role"] {
+            let selector = Selector::parse(&format!("Window>{attribute}~regex:^a.*z$")).unwrap();
+            assert_eq!(selector.path[0].criteria[0].match_type, MatchType::Regex);
+        }
+    }
+
+    #[test]
+    fn test_parse_regex_rejected_on_non_string_attributes() {
+        for dsl in [
+            "Window>pid~regex:1.*",
+            "Window>enabled~regex:t.*",
+            "Window>index~regex:1",
+        ] {
+            assert!(Selector::parse(dsl).is_err(), "Expected error for: {dsl}");
+        }
+    }
+
+    #[test]
+    fn test_parse_regex_empty_pattern() {
+        let result = Selector::parse("Window>title~regex:");
+        assert!(result.is_err());
+        let error_msg = result.unwrap_err().to_string();
+        assert!(error_msg.contains("empty"));
+    }
+
+    #[test]
+    fn test_parse_mixed_regex_and_literal() {
+        let selector = Selector::parse("Window>title~regex:.*;class~Notepad").unwrap();
+        assert_eq!(selector.path[0].criteria.len(), 2);
+        assert_eq!(selector.path[0].criteria[0].match_type, MatchType::Regex);
+        assert_eq!(selector.path[0].criteria[1].match_type, MatchType::Contains);
+    }
+
+    #[test]
+    fn test_match_string_regex_basic() {
+        let regex = Regex::new("(?i).*Notepad").unwrap();
+        assert!(match_string(
+            "Untitled - Notepad",
+            "",
+            MatchType::Regex,
+            Some(&regex)
+        ));
+    }
+
+    #[test]
+    fn test_match_string_regex_case_insensitive() {
+        let regex = Regex::new("(?i)notepad").unwrap();
+        assert!(match_string(
+            "UNTITLED - NOTEPAD",
+            "",
+            MatchType::Regex,
+            Some(&regex)
+        ));
+        assert!(match_string("notepad", "", MatchType::Regex, Some(&regex)));
+    }
+
+    #[test]
+    fn test_match_string_regex_no_match() {
+        let regex = Regex::new("(?i)^Untitled").unwrap();
+        assert!(!match_string("Notepad", "", MatchType::Regex, Some(&regex)));
+    }
+
+    #[test]
+    fn test_match_string_regex_special_chars() {
+        let regex = Regex::new("(?i)\\[.*\\]").unwrap();
+        assert!(match_string(
+            "File [1].txt",
+            "",
+            MatchType::Regex,
+            Some(&regex)
+        ));
+    }
+
+    /// In-memory element tree for exercising the engine without a desktop
+    #[derive(Debug, Clone)]
+    #[allow(clippy::struct_excessive_bools)]
+    struct MockElement {
+        key: isize,
+        window: bool,
+        text: String,
+        class_name: String,
+        role: String,
+        automation_id: String,
+        pid: u32,
+        process_name: String,
+        enabled: bool,
+        visible: bool,
+        children: Vec<Self>,
+        /// List every descendant as a child, as `EnumChildWindows` does
+        flatten: bool,
+    }
+
+    impl MockElement {
+        fn window(key: isize, title: &str, class_name: &str) -> Self {
+            Self {
+                key,
+                window: true,
+                text: title.to_string(),
+                class_name: class_name.to_string(),
+                role: "frame".to_string(),
+                automation_id: String::new(),
+                pid: 0,
+                process_name: String::new(),
+                enabled: true,
+                visible: true,
+                children: Vec::new(),
+                flatten: false,
+            }
+        }
+
+        fn control(key: isize, text: &str, class_name: &str) -> Self {
+            Self {
+                window: false,
+                role: String::new(),
+                ..Self::window(key, text, class_name)
+            }
+        }
+
+        fn role(mut self, role: &str) -> Self {
+            self.role = role.to_string();
+            self
+        }
+
+        fn automation_id(mut self, automation_id: &str) -> Self {
+            self.automation_id = automation_id.to_string();
+            self
+        }
+
+        fn process(mut self, pid: u32, name: &str) -> Self {
+            self.pid = pid;
+            self.process_name = name.to_string();
+            self
+        }
+
+        fn disabled(mut self) -> Self {
+            self.enabled = false;
+            self
+        }
+
+        fn hidden(mut self) -> Self {
+            self.visible = false;
+            self
+        }
+
+        fn with_children(mut self, children: Vec<Self>) -> Self {
+            self.children = children;
+            self
+        }
+
+        fn flattened(mut self) -> Self {
+            self.flatten = true;
+            self
+        }
+
+        fn all_descendants(&self) -> Vec<Self> {
+            self.children
+                .iter()
+                .flat_map(|c| std::iter::once(c.clone()).chain(c.all_descendants()))
+                .collect()
+        }
+    }
+
+    impl SelectorElement for MockElement {
+        fn key(&self) -> isize {
+            self.key
+        }
+
+        fn is_window(&self) -> bool {
+            self.window
+        }
+
+        fn text(&self) -> &str {
+            &self.text
+        }
+
+        fn class_name(&self) -> &str {
+            &self.class_name
+        }
+
+        fn is_enabled(&self) -> bool {
+            self.enabled
+        }
+
+        fn is_visible(&self) -> bool {
+            self.visible
+        }
+
+        fn process_id(&self) -> u32 {
+            self.pid
+        }
+
+        fn process_name(&self) -> String {
+            self.process_name.clone()
+        }
+
+        fn automation_id(&self) -> String {
+            self.automation_id.clone()
+        }
+
+        fn role(&self) -> String {
+            self.role.clone()
+        }
+
+        fn children(&self) -> Result<Vec<Self>, AutomationError> {
+            if self.flatten {
+                Ok(self.all_descendants())
+            } else {
+                Ok(self.children.clone())
+            }
+        }
+    }
+
+    /// Two billing windows sharing control names and an invisible notepad
+    fn desktop() -> Vec<MockElement> {
+        vec![
+            MockElement::window(1, "Invoice 42 - Billing", "BillingApp")
+                .process(100, "billing.exe")
+                .with_children(vec![
+                    MockElement::control(10, "", "Panel")
+                        .role("panel")
+                        .with_children(vec![
+                            MockElement::control(11, "OK", "Button")
+                                .role("push button")
+                                .automation_id("okButton"),
+                            MockElement::control(12, "Cancel", "Button").role("push button"),
+                        ]),
+                    MockElement::control(13, "Paid", "Button")
+                        .role("check box")
+                        .disabled(),
+                    MockElement::control(14, "", "Edit")
+                        .role("text")
+                        .automation_id("amount"),
+                ]),
+            MockElement::window(2, "Invoice 43 - Billing", "BillingApp")
+                .process(100, "billing.exe")
+                .with_children(vec![
+                    MockElement::control(20, "OK", "Button").role("push button"),
+                ]),
+            MockElement::window(3, "Notes", "Notepad")
+                .process(200, "notepad.exe")
+                .hidden(),
+        ]
+    }
+
+    fn resolve_key(dsl: &str) -> Option<isize> {
+        let selector = Selector::parse(dsl).unwrap();
+        resolve(&selector, desktop()).ok().map(|e| e.key)
+    }
+
+    fn resolve_err(dsl: &str) -> AutomationError {
+        let selector = Selector::parse(dsl).unwrap();
+        resolve(&selector, desktop()).unwrap_err()
+    }
+
+    #[test]
+    fn test_window_matches_criteria_regex() {
+        let selector = Selector::parse("Window>title~regex:.*notepad").unwrap();
+        let path = &selector.path[0];
+
+        assert!(path.matches(&MockElement::window(1, "Untitled - Notepad", "#32770")));
+        assert!(!path.matches(&MockElement::window(1, "WordPad", "#32770")));
+    }
+
+    #[test]
+    fn test_regex_on_class_matches() {
+        let selector = Selector::parse("Window>class~regex:^rich(edit|text)\\d+w$").unwrap();
+        let path = &selector.path[0];
+
+        assert!(path.matches(&MockElement::window(1, "", "RichEdit20W")));
+        assert!(!path.matches(&MockElement::window(1, "", "Edit")));
+    }
+
+    #[test]
+    fn test_to_dsl_preserves_regex() {
+        let original = "Window>title~regex:.*Notepad.*";
+        let selector = Selector::parse(original).unwrap();
+        assert_eq!(selector.to_dsl(), original);
+    }
+
+    #[test]
+    fn test_escape_dsl_value_greater_than() {
+        assert_eq!(escape_dsl_value("Report > Analysis"), "Report \\> Analysis");
+    }
+
+    #[test]
+    fn test_escape_dsl_value_semicolon() {
+        assert_eq!(escape_dsl_value("Data; Export"), "Data\\; Export");
+    }
+
+    #[test]
+    fn test_escape_dsl_value_backslash() {
+        assert_eq!(escape_dsl_value("Path\\File"), "Path\\\\File");
+    }
+
+    #[test]
+    fn test_escape_dsl_value_multiple() {
+        assert_eq!(escape_dsl_value("A > B; C\\D"), "A \\> B\\; C\\\\D");
+    }
+
+    #[test]
+    fn test_escape_dsl_value_no_special() {
+        assert_eq!(escape_dsl_value("Normal text"), "Normal text");
+    }
+
+    #[test]
+    fn test_unescape_dsl_value() {
+        assert_eq!(
+            unescape_dsl_value("Report \\> Analysis"),
+            "Report > Analysis"
+        );
+        assert_eq!(unescape_dsl_value("Data\\; Export"), "Data; Export");
+        assert_eq!(unescape_dsl_value("Path\\\\File"), "Path\\File");
+    }
+
+    #[test]
+    fn test_parse_with_escaped_greater_than() {
+        let selector = Selector::parse("Window>title~Report \\> Analysis;class~MyApp").unwrap();
+        assert_eq!(selector.path[0].criteria[0].value, "Report > Analysis");
+        assert_eq!(selector.path[0].criteria[1].value, "MyApp");
+    }
+
+    #[test]
+    fn test_parse_with_escaped_semicolon() {
+        let selector = Selector::parse("Window>title~Data\\; Export").unwrap();
+        assert_eq!(selector.path[0].criteria[0].value, "Data; Export");
+    }
+
+    #[test]
+    fn test_parse_roundtrip_with_escaped_chars() {
+        let escaped_title = "Report \\> Analysis";
+        let selector = Selector::parse(&format!("Window>title~{escaped_title}")).unwrap();
+        assert_eq!(selector.path[0].criteria[0].value, "Report > Analysis");
+    }
+
+    #[test]
+    fn test_parse_new_attributes() {
+        let selector = Selector::parse(
+            "Window>process~=notepad.exe;pid~42>Control>automationid~15;role~=text;enabled~yes;visible~0",
+        )
+        .unwrap();
+        let attributes: Vec<_> = selector
+            .path
+            .iter()
+            .flat_map(|p| p.criteria.iter().map(|c| c.attribute.as_str()))
+            .collect();
+        assert_eq!(
+            attributes,
+            [
+                "process",
+                "pid",
+                "automationid",
+                "role",
+                "enabled",
+                "visible"
+            ]
+        );
+        assert_eq!(selector.path[1].criteria[2].value, "true");
+        assert_eq!(selector.path[1].criteria[3].value, "false");
+    }
+
+    #[test]
+    fn test_parse_attribute_names_are_case_insensitive() {
+        let selector = Selector::parse("Window>Title~a;PROCESS~b;AutomationId~c").unwrap();
+        let attributes: Vec<_> = selector.path[0]
+            .criteria
+            .iter()
+            .map(|c| c.attribute.as_str())
+            .collect();
+        assert_eq!(attributes, ["title", "process", "automationid"]);
+    }
+
+    #[test]
+    fn test_parse_invalid_attribute_values() {
+        let invalid_cases = vec![
+            "Window>pid~abc",
+            "Window>pid~-1",
+            "Window>pid~*12",
+            "Window>enabled~maybe",
+            "Window>visible~$true",
+            "Window>index~first",
+            "Window>index~-1",
+            "Window>index~1;index~2",
+            "Window>index!~1",
+        ];
+
+        for invalid_dsl in invalid_cases {
+            let result = Selector::parse(invalid_dsl);
+            assert!(result.is_err(), "Expected error for: {invalid_dsl}");
+        }
+    }
+
+    #[test]
+    fn test_parse_index_on_any_step() {
+        let selector =
+            Selector::parse("Window>title~App;index~1>Control>class~Button;index~2").unwrap();
+        assert_eq!(selector.path[0].index, Some(1));
+        assert_eq!(selector.path[1].index, Some(2));
+        // The index is not a matching criterion of its own
+        assert_eq!(selector.path[0].criteria.len(), 1);
+        assert_eq!(selector.path[1].criteria.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_index_only_step() {
+        let selector = Selector::parse("Window>title~App>Control>index~0").unwrap();
+        assert!(selector.path[1].criteria.is_empty());
+        assert_eq!(selector.path[1].index, Some(0));
+    }
+
+    #[test]
+    fn test_parse_descendant_combinator() {
+        let selector =
+            Selector::parse("Window>title~App>>Control>text~OK>Control>class~Button").unwrap();
+        assert_eq!(selector.path[0].combinator, Combinator::Child);
+        assert_eq!(selector.path[1].combinator, Combinator::Descendant);
+        assert_eq!(selector.path[2].combinator, Combinator::Child);
+
+        let spaced = Selector::parse("Window > title~App >> Control > text~OK").unwrap();
+        assert_eq!(spaced.path[1].combinator, Combinator::Descendant);
+    }
+
+    #[test]
+    fn test_parse_descendant_combinator_errors() {
+        let invalid_cases = vec![
+            ">>Window>title~App",
+            "Window>title~App>>",
+            "Window>title~App>>>Control>text~OK",
+            "Window>>title~App",
+            "Window>title~App>",
+        ];
+
+        for invalid_dsl in invalid_cases {
+            let result = Selector::parse(invalid_dsl);
+            assert!(result.is_err(), "Expected error for: {invalid_dsl}");
+        }
+    }
+
+    #[test]
+    fn test_parse_escaped_greater_than_is_not_a_combinator() {
+        let selector = Selector::parse("Window>title~A \\>\\> B").unwrap();
+        assert_eq!(selector.path.len(), 1);
+        assert_eq!(selector.path[0].criteria[0].value, "A >> B");
+    }
+
+    #[test]
+    fn test_parse_negation() {
+        let selector =
+            Selector::parse("Window>title!~Notepad;class !~= Edit;text!~regex:^x").unwrap();
+        let criteria = &selector.path[0].criteria;
+        assert!(criteria.iter().all(|c| c.negated));
+        assert_eq!(criteria[0].match_type, MatchType::Contains);
+        assert_eq!(criteria[1].attribute, "class");
+        assert_eq!(criteria[1].match_type, MatchType::Exact);
+        assert_eq!(criteria[2].match_type, MatchType::Regex);
+
+        let plain = Selector::parse("Window>title~Notepad").unwrap();
+        assert!(!plain.path[0].criteria[0].negated);
+    }
+
+    #[test]
+    fn test_parse_operator_characters_in_value() {
+        let selector = Selector::parse("Window>title~=a~=b!~c").unwrap();
+        let criteria = &selector.path[0].criteria[0];
+        assert_eq!(criteria.attribute, "title");
+        assert_eq!(criteria.match_type, MatchType::Exact);
+        assert_eq!(criteria.value, "a~=b!~c");
+    }
+
+    #[test]
+    fn test_negated_criteria_match() {
+        let selector = Selector::parse("Window>title~Untitled;title!~Notepad").unwrap();
+        let path = &selector.path[0];
+
+        assert!(path.matches(&MockElement::window(1, "Untitled - Paint", "")));
+        assert!(!path.matches(&MockElement::window(1, "Untitled - Notepad", "")));
+    }
+
+    #[test]
+    fn test_flag_criteria_match() {
+        let disabled = MockElement::control(1, "OK", "Button").disabled();
+        let enabled = MockElement::control(1, "OK", "Button");
+
+        let selector = Selector::parse("Window>title~App>Control>enabled~false").unwrap();
+        assert!(selector.path[1].matches(&disabled));
+        assert!(!selector.path[1].matches(&enabled));
+
+        let selector = Selector::parse("Window>title~App>Control>visible!~true").unwrap();
+        assert!(!selector.path[1].matches(&enabled));
+    }
+
+    #[test]
+    fn test_text_and_title_read_the_same_value() {
+        let control = MockElement::control(1, "Save", "Button");
+        for dsl in [
+            "Window>title~App>Control>text~=save",
+            "Window>title~App>Control>title~=save",
+        ] {
+            let selector = Selector::parse(dsl).unwrap();
+            assert!(selector.path[1].matches(&control));
+        }
+    }
+
+    #[test]
+    fn test_resolve_window_and_child() {
+        assert_eq!(resolve_key("Window>title~43>Control>text~=OK"), Some(20));
+        assert_eq!(resolve_key("Window>class~=Notepad"), Some(3));
+    }
+
+    #[test]
+    fn test_resolve_child_only_looks_one_level_down() {
+        assert!(resolve_key("Window>title~42>Control>text~=OK").is_none());
+        assert_eq!(
+            resolve_key("Window>title~42>Control>class~=Panel>Control>text~=OK"),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_resolve_descendant() {
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>text~=Cancel"),
+            Some(12)
+        );
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>class~=Button"),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_resolve_index_counts_matches_only() {
+        assert_eq!(resolve_key("Window>class~=BillingApp;index~1"), Some(2));
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>class~=Button;index~2"),
+            Some(13)
+        );
+        assert!(resolve_key("Window>title~42>>Control>class~=Button;index~3").is_none());
+    }
+
+    #[test]
+    fn test_resolve_negation_and_flags() {
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>class~=Button;text!~=OK"),
+            Some(12)
+        );
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>class~=Button;text!~a"),
+            Some(11)
+        );
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>enabled~false"),
+            Some(13)
+        );
+        assert_eq!(resolve_key("Window>visible~false"), Some(3));
+    }
+
+    #[test]
+    fn test_resolve_process_role_and_automation_id() {
+        assert_eq!(resolve_key("Window>process~=notepad.exe"), Some(3));
+        assert_eq!(resolve_key("Window>pid~100;title~43"), Some(2));
+        assert_eq!(resolve_key("Window>title~42>>Control>role~=text"), Some(14));
+        assert_eq!(
+            resolve_key("Window>title~42>>Control>automationid~=okButton"),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        assert!(matches!(
+            resolve_err("Window>title~Missing"),
+            AutomationError::WindowNotFound { .. }
+        ));
+        assert!(matches!(
+            resolve_err("Window>title~42>Control>text~Missing"),
+            AutomationError::WindowNotFound { .. }
+        ));
+        assert!(matches!(
+            resolve_err("Control>text~OK"),
+            AutomationError::Other(_)
+        ));
+        assert!(matches!(
+            resolve_err("Window>title~42>Window>title~43"),
+            AutomationError::Other(_)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_flattened_children_are_not_duplicated() {
+        let window = desktop().remove(0).flattened();
+        let selector = Selector::parse("Window>title~42>>Control>class~=Button;index~2").unwrap();
+        assert_eq!(
+            resolve(&selector, vec![window.clone()]).ok().map(|e| e.key),
+            Some(13)
+        );
+
+        let selector = Selector::parse("Window>title~42>Control>text~=OK").unwrap();
+        assert_eq!(
+            resolve(&selector, vec![window]).ok().map(|e| e.key),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_window_to_selector() {
+        let window = &desktop()[0];
+        assert_eq!(
+            window_to_selector(window).unwrap(),
+            "Window>title~Invoice 42 - Billing;class~BillingApp"
+        );
+
+        let escaped = MockElement::window(1, "A > B; C", "");
+        assert_eq!(
+            window_to_selector(&escaped).unwrap(),
+            "Window>title~A \\> B\\; C"
+        );
+
+        assert!(window_to_selector(&MockElement::window(1, "", "")).is_err());
+        assert!(window_to_selector(&MockElement::control(1, "OK", "Button")).is_err());
+    }
+
+    #[test]
+    fn test_control_to_selector_roundtrip() {
+        let windows = desktop();
+        let window = &windows[0];
+        for control in window.all_descendants() {
+            if control.text.is_empty() && control.class_name.is_empty() {
+                continue;
+            }
+            let dsl = control_to_selector(&control, window).unwrap();
+            let selector = Selector::parse(&dsl).unwrap();
+            assert_eq!(
+                resolve(&selector, desktop()).ok().map(|e| e.key),
+                Some(control.key),
+                "{dsl}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_control_to_selector_adds_index_for_ambiguous_controls() {
+        let window = MockElement::window(1, "Form", "").with_children(vec![
+            MockElement::control(10, "", "Edit"),
+            MockElement::control(11, "", "Edit"),
+        ]);
+        let second = window.children[1].clone();
+        assert_eq!(
+            control_to_selector(&window.children[0], &window).unwrap(),
+            "Window>title~Form>>Control>class~Edit"
+        );
+        assert_eq!(
+            control_to_selector(&second, &window).unwrap(),
+            "Window>title~Form>>Control>class~Edit;index~1"
+        );
+        assert!(control_to_selector(&window, &window).is_err());
+        assert!(control_to_selector(&second, &second).is_err());
+    }
+
+    #[test]
+    fn test_fuzzy_prefers_exact_match() {
+        let selector = Selector::parse("Window>class~=BillingApp>Control>text~=OK").unwrap();
+        assert!(resolve(&selector, desktop()).is_err());
+
+        let found =
+            resolve_fuzzy(&selector, desktop(), MatchMode::DEFAULT_FUZZY_THRESHOLD).unwrap();
+        assert_eq!(found.element.key, 20);
+        assert!(found.failed.is_empty());
+        assert!((found.score - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_fuzzy_survives_changed_title() {
+        let selector = Selector::parse(
+            "Window>title~=Invoice 42 - Billing v2;class~=BillingApp>>Control>automationid~=okButton;text~=OK",
+        )
+        .unwrap();
+        assert!(resolve(&selector, desktop()).is_err());
+
+        let found =
+            resolve_fuzzy(&selector, desktop(), MatchMode::DEFAULT_FUZZY_THRESHOLD).unwrap();
+        assert_eq!(found.element.key, 11);
+        assert_eq!(found.failed, ["path 0: title~=Invoice 42 - Billing v2"]);
+        // (2/5 + 7/7) / 2
+        assert!((found.score - 0.7).abs() < f32::EPSILON);
+
+        // A lighter control step than window step, as in title;process>Control>class
+        let selector = Selector::parse(
+            "Window>title~=Invoice 42 - Billing v2;class~=BillingApp>>Control>class~=Button",
+        )
+        .unwrap();
+        let found =
+            resolve_fuzzy(&selector, desktop(), MatchMode::DEFAULT_FUZZY_THRESHOLD).unwrap();
+        assert_eq!(found.element.key, 11);
+    }
+
+    #[test]
+    fn test_fuzzy_survives_shifted_index() {
+        let selector =
+            Selector::parse("Window>title~42>>Control>class~=Button;text~=Cancel;index~1").unwrap();
+        assert!(resolve(&selector, desktop()).is_err());
+
+        let found =
+            resolve_fuzzy(&selector, desktop(), MatchMode::DEFAULT_FUZZY_THRESHOLD).unwrap();
+        assert_eq!(found.element.key, 12);
+        assert_eq!(found.failed, ["path 1: index~1"]);
+    }
+
+    #[test]
+    fn test_fuzzy_picks_highest_score() {
+        // OK matches text and class, Cancel only class, Paid only role
+        let selector =
+            Selector::parse("Window>title~42>>Control>text~=OK;class~=Edit;role~=check box")
+                .unwrap();
+        let found = resolve_fuzzy(&selector, desktop(), 0.0).unwrap();
+        assert_eq!(found.element.key, 11);
+
+        let selector =
+            Selector::parse("Window>title~42>>Control>text~=Paid;class~=Panel;role~=text").unwrap();
+        let found = resolve_fuzzy(&selector, desktop(), 0.0).unwrap();
+        assert_eq!(found.element.key, 13);
+        assert_eq!(found.failed, ["path 1: class~=Panel", "path 1: role~=text"]);
+    }
+
+    #[test]
+    fn test_fuzzy_threshold() {
+        let selector =
+            Selector::parse("Window>title~=Invoice 42;class~=BillingApp>>Control>text~=OK")
+                .unwrap();
+        // (2/5 + 3/3) / 2
+        assert!(resolve_fuzzy(&selector, desktop(), 0.7).is_ok());
+        assert!(matches!(
+            resolve_fuzzy(&selector, desktop(), 0.75),
+            Err(AutomationError::WindowNotFound { .. })
+        ));
+
+        // A step matching no criterion at all never counts
+        let selector = Selector::parse("Window>title~Missing;class~=Nope").unwrap();
+        assert!(resolve_fuzzy(&selector, desktop(), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_resolve_with_mode() {
+        let selector = Selector::parse("Window>title~=Invoice 43 v2;class~=BillingApp").unwrap();
+        assert_eq!(MatchMode::default(), MatchMode::Strict);
+        assert!(resolve_with(&selector, desktop(), MatchMode::Strict).is_err());
+        assert_eq!(
+            resolve_with(&selector, desktop(), MatchMode::Fuzzy { threshold: 0.4 })
+                .map(|e| e.key)
+                .ok(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_criteria_display_roundtrip() {
+        let dsl = "Window>title!~=A \\> B;class~*Rich;text~$.txt;role~regex:^push;enabled~=false";
+        let selector = Selector::parse(dsl).unwrap();
+        let criteria: Vec<_> = selector.path[0]
+            .criteria
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(format!("Window>{}", criteria.join(";")), dsl);
+    }
+
+    #[test]
+    fn test_robust_selector_prefers_stable_attributes() {
+        let windows = desktop();
+        let window = &windows[0];
+        let controls = window.all_descendants();
+        let generate = |key: isize| {
+            let control = controls.iter().find(|c| c.key == key).unwrap();
+            robust_selector(control, window, &windows).unwrap()
+        };
+
+        let window_dsl =
+            "Window>process~=billing.exe;class~=BillingApp;title~=Invoice 42 - Billing";
+        assert_eq!(
+            robust_selector(window, window, &windows).unwrap(),
+            window_dsl
+        );
+        assert_eq!(
+            robust_selector(&windows[2], &windows[2], &windows).unwrap(),
+            "Window>process~=notepad.exe;class~=Notepad"
+        );
+        assert_eq!(
+            generate(11),
+            format!("{window_dsl}>>Control>automationid~=okButton")
+        );
+        assert_eq!(
+            generate(12),
+            format!("{window_dsl}>>Control>role~=push button;text~=Cancel")
+        );
+        assert_eq!(generate(10), format!("{window_dsl}>>Control>role~=panel"));
+    }
+
+    #[test]
+    fn test_robust_selector_falls_back_to_index() {
+        let windows = vec![
+            MockElement::window(1, "Form", "Dialog")
+                .process(100, "form.exe")
+                .with_children(vec![
+                    MockElement::control(10, "typed text", "Edit").role("text"),
+                    MockElement::control(11, "typed text", "Edit").role("text"),
+                ]),
+            MockElement::window(2, "Form", "Dialog").process(100, "form.exe"),
+        ];
+        let second = windows[0].children[1].clone();
+
+        // The typed text is not used, the duplicated window needs an index too
+        assert_eq!(robust_selector(&second, &windows[1], &windows).ok(), None);
+        assert_eq!(
+            robust_selector(&windows[1], &windows[1], &windows).unwrap(),
+            "Window>process~=form.exe;class~=Dialog;index~1"
+        );
+        assert_eq!(
+            robust_selector(&second, &windows[0], &windows).unwrap(),
+            "Window>process~=form.exe;class~=Dialog;index~0>>Control>role~=text;index~1"
+        );
+    }
+
+    #[test]
+    fn test_robust_selector_resolves_to_element() {
+        let windows = desktop();
+        for window in &windows {
+            for control in window.all_descendants() {
+                let dsl = robust_selector(&control, window, &windows).unwrap();
+                let selector = Selector::parse(&dsl).unwrap();
+                assert_eq!(
+                    resolve(&selector, desktop()).ok().map(|e| e.key),
+                    Some(control.key),
+                    "{dsl}"
+                );
+            }
+        }
+    }
+}
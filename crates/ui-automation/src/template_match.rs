@@ -0,0 +1,379 @@
+//! Locating a reference image on screen.
+//!
+//! Matching compares brightness only, with zero-mean normalized
+//! cross-correlation, so a template still matches when the screen is a bit
+//! lighter or darker than when it was captured. To keep a full-screen search
+//! fast, the search starts on halved copies of the screen and the template
+//! and refines the best few candidates on each finer level.
+
+use crate::{AutomationError, Rect, RgbaImage, capture_screen, screen_rect};
+
+/// Confidence a match needs when the workflow does not set one
+pub const DEFAULT_CONFIDENCE: f32 = 0.9;
+
+/// The template is not shrunk below this many pixels on its shorter side
+const MIN_TEMPLATE_SIDE: usize = 8;
+const MAX_PYRAMID_LEVELS: usize = 4;
+/// Candidates kept from the coarsest level
+const CANDIDATES: usize = 8;
+/// Pixels searched around a candidate on each finer level
+const REFINE_RADIUS: usize = 2;
+/// Variance below which an area counts as a flat colour
+const FLAT_VARIANCE: f64 = 1e-3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImageMatch {
+    /// Where the template was found
+    pub rect: Rect,
+    /// How well it matched, from 0 to 1
+    pub confidence: f32,
+}
+
+/// Finds the template on the screen; `rect` is in screen coordinates
+pub fn find_image_on_screen(
+    template: &RgbaImage,
+    confidence: f32,
+) -> Result<Option<ImageMatch>, AutomationError> {
+    let screen = screen_rect()?;
+    let capture = capture_screen()?;
+    Ok(
+        find_template(&capture, template, confidence).map(|found| ImageMatch {
+            rect: Rect {
+                left: found.rect.left + screen.left,
+                top: found.rect.top + screen.top,
+                ..found.rect
+            },
+            ..found
+        }),
+    )
+}
+
+/// Finds where `template` best matches inside `image`, if that match reaches
+/// `confidence`; `rect` is in pixels of `image`
+#[must_use]
+pub fn find_template(
+    image: &RgbaImage,
+    template: &RgbaImage,
+    confidence: f32,
+) -> Option<ImageMatch> {
+    let mut images = vec![Gray::from_rgba(image)];
+    let mut templates = vec![Gray::from_rgba(template)];
+    if templates[0].width == 0
+        || templates[0].height == 0
+        || templates[0].width > images[0].width
+        || templates[0].height > images[0].height
+    {
+        return None;
+    }
+    while templates.len() < MAX_PYRAMID_LEVELS {
+        let smallest = &templates[templates.len() - 1];
+        if smallest.width.min(smallest.height) / 2 < MIN_TEMPLATE_SIDE {
+            break;
+        }
+        templates.push(smallest.half());
+        images.push(images[images.len() - 1].half());
+    }
+
+    let coarsest = images.len() - 1;
+    let mut candidates = Matcher::new(&images[coarsest], &templates[coarsest]).best(CANDIDATES);
+    for level in (0..coarsest).rev() {
+        let matcher = Matcher::new(&images[level], &templates[level]);
+        candidates = candidates
+            .into_iter()
+            .map(|(_, x, y)| matcher.refine(x * 2, y * 2))
+            .collect();
+    }
+
+    let (score, x, y) = candidates.into_iter().max_by(|a, b| a.0.total_cmp(&b.0))?;
+    #[allow(clippy::cast_possible_truncation)]
+    let score = score as f32;
+    if score < confidence {
+        return None;
+    }
+    let pixels = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+    Some(ImageMatch {
+        rect: Rect {
+            left: pixels(x),
+            top: pixels(y),
+            width: pixels(templates[0].width),
+            height: pixels(templates[0].height),
+        },
+        confidence: score,
+    })
+}
+
+/// Brightness of each pixel
+struct Gray {
+    width: usize,
+    height: usize,
+    pixels: Vec<f64>,
+}
+
+impl Gray {
+    fn from_rgba(image: &RgbaImage) -> Self {
+        Self {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            pixels: image
+                .pixels()
+                .map(|pixel| {
+                    let [r, g, b, _] = pixel.0;
+                    0.114f64.mul_add(
+                        f64::from(b),
+                        0.299f64.mul_add(f64::from(r), 0.587 * f64::from(g)),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn at(&self, x: usize, y: usize) -> f64 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// The image at half the size, each pixel the mean of a 2×2 block
+    fn half(&self) -> Self {
+        let (width, height) = (self.width / 2, self.height / 2);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let (x2, y2) = (x * 2, y * 2);
+                pixels.push(
+                    (self.at(x2, y2)
+                        + self.at(x2 + 1, y2)
+                        + self.at(x2, y2 + 1)
+                        + self.at(x2 + 1, y2 + 1))
+                        / 4.0,
+                );
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Scores template positions on one pyramid level
+struct Matcher<'a> {
+    image: &'a Gray,
+    template: &'a Gray,
+    /// Template brightness minus its mean
+    centered: Vec<f64>,
+    template_mean: f64,
+    /// Sum of squares of `centered`
+    template_energy: f64,
+    /// Summed-area tables of the image and its squares, one row and column
+    /// larger than the image
+    sums: Vec<f64>,
+    squares: Vec<f64>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(image: &'a Gray, template: &'a Gray) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let template_mean =
+            template.pixels.iter().sum::<f64>() / template.pixels.len().max(1) as f64;
+        let centered: Vec<f64> = template.pixels.iter().map(|p| p - template_mean).collect();
+        let template_energy = centered.iter().map(|p| p * p).sum();
+
+        let stride = image.width + 1;
+        let mut sums = vec![0.0; stride * (image.height + 1)];
+        let mut squares = sums.clone();
+        for y in 0..image.height {
+            let (mut row_sum, mut row_squares) = (0.0, 0.0);
+            for x in 0..image.width {
+                let value = image.at(x, y);
+                row_sum += value;
+                row_squares += value * value;
+                let i = (y + 1) * stride + x + 1;
+                sums[i] = sums[i - stride] + row_sum;
+                squares[i] = squares[i - stride] + row_squares;
+            }
+        }
+
+        Self {
+            image,
+            template,
+            centered,
+            template_mean,
+            template_energy,
+            sums,
+            squares,
+        }
+    }
+
+    /// Sum and sum of squares of the image under the template at `(x, y)`
+    fn window(&self, x: usize, y: usize) -> (f64, f64) {
+        let stride = self.image.width + 1;
+        let (w, h) = (self.template.width, self.template.height);
+        let area = |table: &[f64]| {
+            table[(y + h) * stride + x + w]
+                - table[y * stride + x + w]
+                - table[(y + h) * stride + x]
+                + table[y * stride + x]
+        };
+        (area(&self.sums), area(&self.squares))
+    }
+
+    /// How well the template matches at `(x, y)`, from 0 to 1
+    fn score(&self, x: usize, y: usize) -> f64 {
+        let (width, height) = (self.template.width, self.template.height);
+        #[allow(clippy::cast_precision_loss)]
+        let area = (width * height) as f64;
+        let (sum, squares) = self.window(x, y);
+        let variance = sum.mul_add(-sum / area, squares).max(0.0);
+
+        if self.template_energy < FLAT_VARIANCE * area {
+            // A flat template matches an area by how close it is to its colour
+            let mean = self.template_mean;
+            let deviation = (mean * mean).mul_add(area, 2.0f64.mul_add(-mean * sum, squares));
+            return 1.0 - (deviation.max(0.0) / area).sqrt() / 255.0;
+        }
+        if variance < FLAT_VARIANCE * area {
+            return 0.0;
+        }
+
+        let mut product = 0.0;
+        for row_index in 0..height {
+            let row = &self.image.pixels[(y + row_index) * self.image.width + x..][..width];
+            let template_row = &self.centered[row_index * width..][..width];
+            product += row
+                .iter()
+                .zip(template_row)
+                .map(|(i, t)| i * t)
+                .sum::<f64>();
+        }
+        (product / (variance * self.template_energy).sqrt()).max(0.0)
+    }
+
+    const fn positions(&self) -> (usize, usize) {
+        (
+            self.image.width - self.template.width + 1,
+            self.image.height - self.template.height + 1,
+        )
+    }
+
+    /// The best `count` positions that lie at least half a template apart
+    fn best(&self, count: usize) -> Vec<(f64, usize, usize)> {
+        let (columns, rows) = self.positions();
+        let mut scored: Vec<(f64, usize, usize)> = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .map(|(x, y)| (self.score(x, y), x, y))
+            .collect();
+        scored.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+
+        let (half_width, half_height) = (self.template.width / 2, self.template.height / 2);
+        let mut best: Vec<(f64, usize, usize)> = Vec::with_capacity(count);
+        for candidate in scored {
+            if best.len() == count {
+                break;
+            }
+            let overlaps = best.iter().any(|&(_, x, y)| {
+                x.abs_diff(candidate.1) < half_width && y.abs_diff(candidate.2) < half_height
+            });
+            if !overlaps {
+                best.push(candidate);
+            }
+        }
+        best
+    }
+
+    /// The best position within a few pixels of `(x, y)`
+    fn refine(&self, x: usize, y: usize) -> (f64, usize, usize) {
+        let (columns, rows) = self.positions();
+        let (x, y) = (x.min(columns - 1), y.min(rows - 1));
+        let xs = x.saturating_sub(REFINE_RADIUS)..=(x + REFINE_RADIUS).min(columns - 1);
+        let ys = y.saturating_sub(REFINE_RADIUS)..=(y + REFINE_RADIUS).min(rows - 1);
+        ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+            .map(|(x, y)| (self.score(x, y), x, y))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap_or((0.0, x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, imageops};
+
+    /// Noise made of 5×5 blocks, smooth enough to survive downscaling
+    fn noise(width: u32, height: u32, seed: u32) -> RgbaImage {
+        let value = |bx: u32, by: u32| {
+            let mut state = seed ^ (bx.wrapping_mul(73_856_093) ^ by.wrapping_mul(19_349_663));
+            for _ in 0..3 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            }
+            ((state >> 16) % 200) as u8
+        };
+        RgbaImage::from_fn(width, height, |x, y| {
+            let v = value(x / 5, y / 5);
+            Rgba([v, v.wrapping_add(20), v / 2, 255])
+        })
+    }
+
+    fn crop(image: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> RgbaImage {
+        imageops::crop_imm(image, x, y, width, height).to_image()
+    }
+
+    #[test]
+    fn finds_the_template_where_it_was_cut_out() {
+        let image = noise(320, 200, 1);
+        let template = crop(&image, 123, 57, 48, 36);
+
+        let found = find_template(&image, &template, DEFAULT_CONFIDENCE).unwrap();
+        assert_eq!(
+            (
+                found.rect.left,
+                found.rect.top,
+                found.rect.width,
+                found.rect.height
+            ),
+            (123, 57, 48, 36)
+        );
+        assert!(found.confidence > 0.99, "{}", found.confidence);
+    }
+
+    #[test]
+    fn brightness_changes_do_not_prevent_a_match() {
+        let image = noise(320, 200, 2);
+        let template = crop(&image, 201, 140, 40, 40);
+        let mut brighter = image;
+        for pixel in brighter.pixels_mut() {
+            for channel in &mut pixel.0[..3] {
+                *channel = channel.saturating_add(30);
+            }
+        }
+
+        let found = find_template(&brighter, &template, DEFAULT_CONFIDENCE).unwrap();
+        assert_eq!((found.rect.left, found.rect.top), (201, 140));
+    }
+
+    #[test]
+    fn flat_templates_match_areas_of_their_colour() {
+        let mut image = noise(120, 80, 3);
+        let grey = Rgba([128, 128, 128, 255]);
+        for y in 30..50 {
+            for x in 70..100 {
+                image.put_pixel(x, y, grey);
+            }
+        }
+        let template = RgbaImage::from_pixel(20, 12, grey);
+
+        let found = find_template(&image, &template, DEFAULT_CONFIDENCE).unwrap();
+        assert!((70..=80).contains(&found.rect.left), "{:?}", found.rect);
+        assert!((30..=38).contains(&found.rect.top), "{:?}", found.rect);
+    }
+
+    #[test]
+    fn missing_or_oversized_templates_are_not_found() {
+        let image = noise(320, 200, 4);
+        let other = crop(&noise(320, 200, 5), 10, 10, 48, 36);
+        assert!(find_template(&image, &other, DEFAULT_CONFIDENCE).is_none());
+
+        let larger = noise(330, 40, 4);
+        assert!(find_template(&image, &larger, 0.0).is_none());
+        assert!(find_template(&image, &RgbaImage::new(0, 0), 0.0).is_none());
+    }
+}
@@ -3,13 +3,16 @@ use std::fmt::{self, Display, Formatter};
 use std::thread::{self, sleep};
 use std::time::{Duration, Instant};
 
+use image::RgbaImage;
 use windows::Win32::Foundation::{
-    COLORREF, CloseHandle, GetLastError, HANDLE, HWND, LPARAM, RECT, WAIT_OBJECT_0, WAIT_TIMEOUT,
-    WPARAM,
+    COLORREF, CloseHandle, GetLastError, HANDLE, HWND, LPARAM, POINT, RECT, WAIT_OBJECT_0,
+    WAIT_TIMEOUT, WPARAM,
 };
 use windows::Win32::Graphics::Gdi::{
-    BS_NULL, CreateBrushIndirect, CreateDCA, CreatePen, DeleteDC, DeleteObject, LOGBRUSH, PS_SOLID,
-    Rectangle, SelectObject,
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BS_NULL, BitBlt, CreateBrushIndirect,
+    CreateCompatibleBitmap, CreateCompatibleDC, CreateDCA, CreatePen, DIB_RGB_COLORS, DeleteDC,
+    DeleteObject, GetDC, GetDIBits, LOGBRUSH, PS_SOLID, Rectangle, ReleaseDC, SRCCOPY,
+    SelectObject,
 };
 use windows::Win32::System::Diagnostics::ToolHelp::{
     CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
@@ -20,24 +23,37 @@ use windows::Win32::System::Threading::{
     PROCESS_QUERY_INFORMATION, PROCESS_TERMINATE, STARTUPINFOW, WaitForSingleObject,
 };
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    INPUT, INPUT_0, INPUT_TYPE, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE,
-    MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN,
-    MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_WHEEL, MOUSEINPUT, SendInput, VIRTUAL_KEY,
+    GetAsyncKeyState, INPUT, INPUT_0, INPUT_TYPE, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_KEYUP,
+    KEYEVENTF_UNICODE, MAPVK_VK_TO_CHAR, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
+    MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_WHEEL, MOUSEINPUT,
+    MapVirtualKeyW, SendInput, VIRTUAL_KEY, VK_LBUTTON, VK_RBUTTON,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    BM_GETCHECK, BM_SETCHECK, EnumChildWindows, EnumWindows, GetClassNameW, GetForegroundWindow,
-    GetSystemMetrics, GetWindowRect, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
-    IsWindowVisible, IsZoomed, SM_CXSCREEN, SM_CYSCREEN, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE,
-    SW_SHOW, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SendMessageW, SetForegroundWindow, SetWindowPos,
-    ShowWindow, WM_CLOSE, WM_GETTEXT, WM_SETTEXT,
+    BM_GETCHECK, BM_SETCHECK, EnumChildWindows, EnumWindows, GA_ROOT, GWL_STYLE, GetAncestor,
+    GetClassNameW, GetCursorPos, GetDlgCtrlID, GetForegroundWindow, GetSystemMetrics,
+    GetWindowLongW, GetWindowRect, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
+    IsWindowVisible, IsZoomed, SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN,
+    SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, SW_SHOW,
+    SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SendMessageW, SetForegroundWindow, SetWindowPos,
+    ShowWindow, WM_CLOSE, WM_GETTEXT, WM_SETTEXT, WindowFromPoint,
 };
 use windows::core::BOOL;
 use windows::core::{PCWSTR, PWSTR};
 
-use super::selector;
+use crate::recorder::{HeldKey, InputState};
+use crate::selector::{self, MatchMode, Selector, SelectorElement};
 
 const BST_CHECKED: usize = 1;
 const BST_UNCHECKED: usize = 0;
+
+const BS_TYPEMASK: u32 = 0x0F;
+const BS_CHECKBOX: u32 = 0x02;
+const BS_AUTOCHECKBOX: u32 = 0x03;
+const BS_RADIOBUTTON: u32 = 0x04;
+const BS_3STATE: u32 = 0x05;
+const BS_AUTO3STATE: u32 = 0x06;
+const BS_GROUPBOX: u32 = 0x07;
+const BS_AUTORADIOBUTTON: u32 = 0x09;
 const INPUT_MOUSE: INPUT_TYPE = INPUT_TYPE(0);
 const INPUT_KEYBOARD: INPUT_TYPE = INPUT_TYPE(1);
 
@@ -71,6 +87,9 @@ const VK_UP: u16 = 0x26;
 const VK_RIGHT: u16 = 0x27;
 const VK_DOWN: u16 = 0x28;
 const VK_F1: u16 = 0x70;
+const VK_F12: u16 = 0x7B;
+const VK_LWIN: u16 = 0x5B;
+const VK_RWIN: u16 = 0x5C;
 
 const OVERLAY_COLOR_DEFAULT: (u8, u8, u8) = (0, 255, 0);
 const OVERLAY_DURATION_DEFAULT_MS: u32 = 2000;
@@ -82,6 +101,8 @@ const INPUT_SIZE: i32 = size_of::<INPUT>() as i32;
 const PROCESSENTRY32W_SIZE: u32 = size_of::<PROCESSENTRY32W>() as u32;
 #[allow(clippy::cast_possible_truncation)]
 const STARTUPINFOW_SIZE: u32 = size_of::<STARTUPINFOW>() as u32;
+#[allow(clippy::cast_possible_truncation)]
+const BITMAPINFOHEADER_SIZE: u32 = size_of::<BITMAPINFOHEADER>() as u32;
 
 #[derive(Debug)]
 pub enum AutomationError {
@@ -406,6 +427,16 @@ impl Rect {
             height: 0,
         }
     }
+
+    /// Whether the screen point lies inside the rectangle
+    #[must_use]
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.left && x < self.left + self.width && y >= self.top && y < self.top + self.height
+    }
+
+    fn area(&self) -> i64 {
+        i64::from(self.width) * i64::from(self.height)
+    }
 }
 
 impl Element {
@@ -530,6 +561,41 @@ impl Element {
         }
     }
 
+    /// The executable name of the owning process, e.g. `notepad.exe`.
+    #[must_use]
+    pub fn get_process_name(&self) -> String {
+        let pid = self.get_process_id();
+        process_entries()
+            .ok()
+            .and_then(|entries| entries.into_iter().find(|(entry_pid, _)| *entry_pid == pid))
+            .map(|(_, name)| name)
+            .unwrap_or_default()
+    }
+
+    /// The dialog control id, which UI Automation reports as the
+    /// `AutomationId` of Win32 controls; empty for windows.
+    #[must_use]
+    pub fn get_automation_id(&self) -> String {
+        if self.element_type != ElementType::Control {
+            return String::new();
+        }
+        match unsafe { GetDlgCtrlID(self.as_hwnd()) } {
+            0 => String::new(),
+            id => id.to_string(),
+        }
+    }
+
+    /// The accessible role ("push button", "check box", "text"...), derived
+    /// from the window class and button style.
+    #[must_use]
+    pub fn get_role(&self) -> String {
+        if self.element_type == ElementType::Window {
+            return "frame".to_string();
+        }
+        let style = unsafe { GetWindowLongW(self.as_hwnd(), GWL_STYLE) }.cast_unsigned();
+        role_for_class(&self.class_name, style).to_string()
+    }
+
     pub fn show_overlay(&self) -> Result<(), AutomationError> {
         show_overlay_on_rect(
             self.bounds,
@@ -719,8 +785,35 @@ impl Element {
     }
 }
 
-pub fn find_processes_by_name(name: &str) -> Result<Vec<Application>, AutomationError> {
-    let mut applications = Vec::new();
+/// Role names follow AT-SPI so selectors read the same on every platform.
+fn role_for_class(class_name: &str, style: u32) -> &'static str {
+    let class_lower = class_name.to_lowercase();
+    match class_lower.as_str() {
+        "button" => match style & BS_TYPEMASK {
+            BS_CHECKBOX | BS_AUTOCHECKBOX | BS_3STATE | BS_AUTO3STATE => "check box",
+            BS_RADIOBUTTON | BS_AUTORADIOBUTTON => "radio button",
+            BS_GROUPBOX => "panel",
+            _ => "push button",
+        },
+        "edit" => "text",
+        class if class.starts_with("richedit") => "text",
+        "static" => "label",
+        "combobox" | "comboboxex32" => "combo box",
+        "listbox" | "syslistview32" => "list",
+        "systreeview32" => "tree",
+        "systabcontrol32" => "page tab list",
+        "msctls_progress32" => "progress bar",
+        "msctls_trackbar32" => "slider",
+        "scrollbar" => "scroll bar",
+        "toolbarwindow32" => "tool bar",
+        "msctls_statusbar32" => "status bar",
+        _ => "unknown",
+    }
+}
+
+/// Process ids and executable names of all running processes.
+fn process_entries() -> Result<Vec<(u32, String)>, AutomationError> {
+    let mut entries = Vec::new();
 
     unsafe {
         let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
@@ -733,20 +826,15 @@ pub fn find_processes_by_name(name: &str) -> Result<Vec<Application>, Automation
 
         if Process32FirstW(snapshot, &raw mut entry).is_ok() {
             loop {
-                let process_name = {
-                    let end_pos = entry
-                        .szExeFile
-                        .iter()
-                        .position(|&c| c == 0)
-                        .unwrap_or(entry.szExeFile.len());
-                    String::from_utf16_lossy(&entry.szExeFile[..end_pos])
-                };
-
-                if process_name.to_lowercase().contains(&name.to_lowercase())
-                    && let Ok(app) = attach_to_process_by_pid(entry.th32ProcessID)
-                {
-                    applications.push(app);
-                }
+                let end_pos = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                entries.push((
+                    entry.th32ProcessID,
+                    String::from_utf16_lossy(&entry.szExeFile[..end_pos]),
+                ));
 
                 if Process32NextW(snapshot, &raw mut entry).is_err() {
                     break;
@@ -757,7 +845,17 @@ pub fn find_processes_by_name(name: &str) -> Result<Vec<Application>, Automation
         let _ = CloseHandle(snapshot);
     }
 
-    Ok(applications)
+    Ok(entries)
+}
+
+pub fn find_processes_by_name(name: &str) -> Result<Vec<Application>, AutomationError> {
+    let name_lower = name.to_lowercase();
+
+    Ok(process_entries()?
+        .into_iter()
+        .filter(|(_, process_name)| process_name.to_lowercase().contains(&name_lower))
+        .filter_map(|(pid, _)| attach_to_process_by_pid(pid).ok())
+        .collect())
 }
 
 pub fn attach_to_process_by_pid(pid: u32) -> Result<Application, AutomationError> {
@@ -920,85 +1018,100 @@ pub fn find_child_elements(parent_hwnd: HWND) -> Result<Vec<Element>, Automation
     }
 }
 
-pub fn find_element_by_selector(dsl: &str) -> Result<Element, AutomationError> {
-    let selector = selector::Selector::parse(dsl)?;
-    find_element_by_selector_obj(&selector)
+/// The top-level window under the screen point
+pub fn window_from_point(x: i32, y: i32) -> Result<Element, AutomationError> {
+    unsafe {
+        let hwnd = WindowFromPoint(POINT { x, y });
+        if hwnd.0.is_null() {
+            return Err(AutomationError::WindowNotFound {
+                title: format!("No window at ({x}, {y})"),
+            });
+        }
+        create_element_from_hwnd(GetAncestor(hwnd, GA_ROOT), ElementType::Window)
+    }
 }
 
-pub fn find_element_by_selector_obj(
-    selector: &selector::Selector,
-) -> Result<Element, AutomationError> {
-    if selector.path.is_empty() {
-        return Err(AutomationError::Other("Selector has no paths".to_string()));
+/// The innermost visible control of `window` under the screen point
+pub fn control_from_point(
+    window: &Element,
+    x: i32,
+    y: i32,
+) -> Result<Option<Element>, AutomationError> {
+    let mut found = None;
+    let mut children = find_child_elements(window.as_hwnd())?;
+    while let Some(control) = children
+        .into_iter()
+        .filter(|child| child.visible && child.bounds.contains(x, y))
+        .min_by_key(|child| child.bounds.area())
+    {
+        children = find_child_elements(control.as_hwnd())?;
+        found = Some(control);
     }
+    Ok(found)
+}
 
-    // First path must be Window
-    let first_path = &selector.path[0];
-    if first_path.element_type != "Window" {
-        return Err(AutomationError::Other(
-            "First selector path must be Window".to_string(),
-        ));
+impl SelectorElement for Element {
+    fn key(&self) -> isize {
+        self.id.0
     }
 
-    // Find the window
-    let mut windows = find_windows()?;
-    windows.retain(|w| {
-        selector::window_matches_criteria(&w.text, &w.class_name, &first_path.criteria)
-    });
+    fn is_window(&self) -> bool {
+        self.element_type == ElementType::Window
+    }
 
-    if windows.is_empty() {
-        return Err(AutomationError::WindowNotFound {
-            title: format!("No window matches selector: {}", selector.original),
-        });
+    fn text(&self) -> &str {
+        &self.text
     }
 
-    if windows.len() > 1 {
-        log::warn!("Selector matched {} windows, using first", windows.len());
+    fn class_name(&self) -> &str {
+        &self.class_name
     }
 
-    let mut current_element = windows.remove(0);
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
 
-    // If only one path, return the window
-    if selector.path.len() == 1 {
-        return Ok(current_element);
+    fn is_visible(&self) -> bool {
+        self.visible
     }
 
-    // Process remaining control paths
-    for (path_idx, path) in selector.path.iter().enumerate().skip(1) {
-        if path.element_type != "Control" {
-            return Err(AutomationError::Other(format!(
-                "Selector path {} must be Control, got {}",
-                path_idx, path.element_type
-            )));
-        }
+    fn process_id(&self) -> u32 {
+        self.get_process_id()
+    }
 
-        let controls = find_child_elements(current_element.id.as_hwnd())?;
-        let mut matches = controls
-            .into_iter()
-            .filter(|c| selector::control_matches_criteria(&c.text, &c.class_name, &path.criteria))
-            .collect::<Vec<_>>();
+    fn process_name(&self) -> String {
+        self.get_process_name()
+    }
 
-        if matches.is_empty() {
-            return Err(AutomationError::WindowNotFound {
-                title: format!(
-                    "No control matches selector at path {}: {}",
-                    path_idx, selector.original
-                ),
-            });
-        }
+    fn automation_id(&self) -> String {
+        self.get_automation_id()
+    }
 
-        if matches.len() > 1 {
-            log::warn!(
-                "Selector path {} matched {} controls, returning first",
-                path_idx,
-                matches.len()
-            );
-        }
+    fn role(&self) -> String {
+        self.get_role()
+    }
 
-        current_element = matches.remove(0);
+    fn children(&self) -> Result<Vec<Self>, AutomationError> {
+        find_child_elements(self.id.as_hwnd())
     }
+}
+
+pub fn find_element_by_selector(dsl: &str) -> Result<Element, AutomationError> {
+    let selector = Selector::parse(dsl)?;
+    find_element_by_selector_obj(&selector)
+}
 
-    Ok(current_element)
+pub fn find_element_by_selector_obj(selector: &Selector) -> Result<Element, AutomationError> {
+    find_element_by_selector_obj_with_mode(selector, MatchMode::Strict)
+}
+
+/// [`find_element_by_selector_obj`] in the given mode. In fuzzy mode the best
+/// scoring element is taken and the criteria it failed are logged.
+pub fn find_element_by_selector_obj_with_mode(
+    selector: &Selector,
+    mode: MatchMode,
+) -> Result<Element, AutomationError> {
+    selector::resolve_with(selector, find_windows()?, mode)
 }
 
 pub fn find_window_by_selector(dsl: &str) -> Result<Element, AutomationError> {
@@ -1021,15 +1134,6 @@ pub fn find_control_by_selector(dsl: &str) -> Result<Element, AutomationError> {
     Ok(element)
 }
 
-/// Generate a selector DSL string from a Window
-pub fn window_to_selector(element: &Element) -> Result<String, AutomationError> {
-    selector::window_to_selector(element)
-}
-
-pub fn control_to_selector(element: &Element, parent: &Element) -> Result<String, AutomationError> {
-    selector::control_to_selector(element, parent)
-}
-
 pub fn click(x: i32, y: i32) -> Result<(), AutomationError> {
     unsafe {
         let mut input = INPUT {
@@ -1091,6 +1195,205 @@ pub fn double_click(x: i32, y: i32) -> Result<(), AutomationError> {
     Ok(())
 }
 
+pub fn get_cursor_position() -> Result<(i32, i32), AutomationError> {
+    let mut point = POINT::default();
+    unsafe { GetCursorPos(&raw mut point) }
+        .map_err(|e| AutomationError::Win32Failure { code: e.code().0 })?;
+    Ok((point.x, point.y))
+}
+
+/// Whether the left mouse button is held down, wherever the pointer is
+#[must_use]
+pub fn is_left_button_down() -> bool {
+    // The most significant bit is set while the key is down
+    unsafe { GetAsyncKeyState(i32::from(VK_LBUTTON.0)) < 0 }
+}
+
+fn is_key_down(key_code: u16) -> bool {
+    unsafe { GetAsyncKeyState(i32::from(key_code)) < 0 }
+}
+
+/// Pointer position, mouse buttons and keys currently held, for the recorder
+pub fn input_state() -> Result<InputState, AutomationError> {
+    let cursor = get_cursor_position()?;
+    let shift = is_key_down(VK_SHIFT);
+
+    // The left/right variants of Shift, Ctrl and Alt (0xA0-0xA5) are covered
+    // by their generic codes
+    let keys = (VK_BACKSPACE..=0xFE)
+        .filter(|key_code| !(0xA0..=0xA5).contains(key_code) && is_key_down(*key_code))
+        .filter_map(|key_code| {
+            let name = key_name_for_virtual_key(key_code)?;
+            Some(HeldKey {
+                name,
+                text: text_for_virtual_key(key_code, shift),
+            })
+        })
+        .collect();
+
+    Ok(InputState {
+        cursor,
+        left_button: is_key_down(VK_LBUTTON.0),
+        right_button: is_key_down(VK_RBUTTON.0),
+        keys,
+    })
+}
+
+/// The character a key types on the current layout without modifiers
+fn char_for_virtual_key(key_code: u16) -> Option<char> {
+    let mapped = unsafe { MapVirtualKeyW(u32::from(key_code), MAPVK_VK_TO_CHAR) };
+    // The top bit marks dead keys, which type nothing on their own
+    char::from_u32(mapped & 0x7FFF_FFFF).filter(|ch| mapped & 0x8000_0000 == 0 && !ch.is_control())
+}
+
+/// The text a key types; with Shift held only letters are known
+fn text_for_virtual_key(key_code: u16, shift: bool) -> Option<char> {
+    let ch = char_for_virtual_key(key_code)?;
+    if !shift {
+        Some(ch.to_ascii_lowercase())
+    } else if ch.is_alphabetic() {
+        Some(ch.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
+/// The `key_sequence` name of a virtual key, the inverse of
+/// `press_key_by_name`
+fn key_name_for_virtual_key(key_code: u16) -> Option<String> {
+    let name = match key_code {
+        VK_BACKSPACE => "backspace",
+        VK_TAB => "tab",
+        VK_RETURN => "enter",
+        VK_ESCAPE => "escape",
+        VK_SPACE => "space",
+        VK_DELETE => "delete",
+        VK_INSERT => "insert",
+        VK_HOME => "home",
+        VK_END => "end",
+        VK_PAGE_UP => "pageup",
+        VK_PAGE_DOWN => "pagedown",
+        VK_UP => "up",
+        VK_DOWN => "down",
+        VK_LEFT => "left",
+        VK_RIGHT => "right",
+        VK_SHIFT => "shift",
+        VK_CONTROL => "ctrl",
+        VK_ALT => "alt",
+        VK_LWIN | VK_RWIN => "super",
+        VK_F1..=VK_F12 => return Some(format!("f{}", key_code - VK_F1 + 1)),
+        _ => {
+            return char_for_virtual_key(key_code)
+                .filter(|ch| !ch.is_whitespace())
+                .map(|ch| ch.to_lowercase().to_string());
+        }
+    };
+    Some(name.to_string())
+}
+
+/// The area of the virtual screen spanning all monitors, in screen coordinates
+pub fn screen_rect() -> Result<Rect, AutomationError> {
+    unsafe {
+        Ok(Rect {
+            left: GetSystemMetrics(SM_XVIRTUALSCREEN),
+            top: GetSystemMetrics(SM_YVIRTUALSCREEN),
+            width: GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            height: GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        })
+    }
+}
+
+/// Captures what is shown on screen inside `rect`, clipped to the screen
+pub fn capture_rect(rect: Rect) -> Result<RgbaImage, AutomationError> {
+    let screen = screen_rect()?;
+    let left = rect.left.clamp(screen.left, screen.left + screen.width);
+    let top = rect.top.clamp(screen.top, screen.top + screen.height);
+    let right = rect
+        .left
+        .saturating_add(rect.width)
+        .clamp(left, screen.left + screen.width);
+    let bottom = rect
+        .top
+        .saturating_add(rect.height)
+        .clamp(top, screen.top + screen.height);
+    let (width, height) = (right - left, bottom - top);
+    if width == 0 || height == 0 {
+        return Err(AutomationError::Other(format!(
+            "Area {}x{} at ({}, {}) is outside the screen",
+            rect.width, rect.height, rect.left, rect.top
+        )));
+    }
+
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: BITMAPINFOHEADER_SIZE,
+            biWidth: width,
+            // Negative height asks for top-down rows
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels =
+        vec![0u8; width.cast_unsigned() as usize * height.cast_unsigned() as usize * 4];
+
+    unsafe {
+        let screen_dc = GetDC(None);
+        if screen_dc.is_invalid() {
+            return Err(AutomationError::Other(
+                "Failed to get screen DC".to_string(),
+            ));
+        }
+        let memory_dc = CreateCompatibleDC(Some(screen_dc));
+        let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+        let previous = SelectObject(memory_dc, bitmap.into());
+
+        let copied = BitBlt(
+            memory_dc,
+            0,
+            0,
+            width,
+            height,
+            Some(screen_dc),
+            left,
+            top,
+            SRCCOPY,
+        );
+        let lines = GetDIBits(
+            memory_dc,
+            bitmap,
+            0,
+            height.cast_unsigned(),
+            Some(pixels.as_mut_ptr().cast()),
+            &raw mut info,
+            DIB_RGB_COLORS,
+        );
+
+        let _ = SelectObject(memory_dc, previous);
+        let _ = DeleteObject(bitmap.into());
+        let _ = DeleteDC(memory_dc);
+        ReleaseDC(None, screen_dc);
+
+        copied.map_err(|e| AutomationError::Win32Failure { code: e.code().0 })?;
+        if lines == 0 {
+            return Err(AutomationError::Other(
+                "Failed to read captured pixels".to_string(),
+            ));
+        }
+    }
+
+    // GDI stores pixels as BGRA with an undefined alpha
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = 255;
+    }
+    RgbaImage::from_raw(width.cast_unsigned(), height.cast_unsigned(), pixels)
+        .ok_or_else(|| AutomationError::Other("Captured image has a wrong size".to_string()))
+}
+
 pub fn move_mouse(x: i32, y: i32) -> Result<(), AutomationError> {
     unsafe {
         let input = INPUT {
@@ -1320,6 +1623,10 @@ pub fn press_key_by_name(name: &str) -> Result<(), AutomationError> {
         "shift" => VK_SHIFT,
         "control" | "ctrl" => VK_CONTROL,
         "alt" => VK_ALT,
+        // Letter and digit keys have their uppercase ASCII code as virtual key
+        _ if name_lower.len() == 1 && name_lower.as_bytes()[0].is_ascii_alphanumeric() => {
+            u16::from(name_lower.as_bytes()[0].to_ascii_uppercase())
+        }
         _ if name_lower.starts_with('f') && name_lower.len() <= 3 => {
             if let Ok(n) = name_lower[1..].parse::<u8>() {
                 if (1..=12).contains(&n) {
@@ -1428,6 +1735,7 @@ pub fn key_sequence(sequence: &str) -> Result<(), AutomationError> {
             "ctrl" | "control" => key_down(0xA2)?,
             "shift" => key_down(0xA0)?,
             "alt" => key_down(0xA4)?,
+            "super" | "win" | "meta" => key_down(VK_LWIN)?,
             _ => {
                 return Err(AutomationError::Other(format!(
                     "Unknown modifier: {modifier}",
@@ -1445,6 +1753,7 @@ pub fn key_sequence(sequence: &str) -> Result<(), AutomationError> {
             "ctrl" | "control" => key_up(0xA2)?,
             "shift" => key_up(0xA0)?,
             "alt" => key_up(0xA4)?,
+            "super" | "win" | "meta" => key_up(VK_LWIN)?,
             _ => {}
         }
     }
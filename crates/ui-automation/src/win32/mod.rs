@@ -1,2 +1 @@
 pub mod automation;
-pub mod selector;
@@ -0,0 +1,521 @@
+#![cfg(target_os = "linux")]
+//! Drives the AT-SPI backend against a private `dbus-daemon` standing in for
+//! the accessibility bus, with a fake accessible application served from
+//! this process. The tests are skipped when `dbus-daemon` is not installed.
+//! They check the protocol, not how real toolkits expose their widgets; the
+//! ignored `linux_gtk_tests.rs` covers a real GTK application.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use ui_automation::linux::automation::*;
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+use zbus::interface;
+use zbus::zvariant::OwnedObjectPath;
+
+const REGISTRY_NAME: &str = "org.a11y.atspi.Registry";
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+
+const STATE_CHECKED: u32 = 4;
+const STATE_ENABLED: u32 = 8;
+const STATE_FOCUSED: u32 = 12;
+const STATE_SHOWING: u32 = 25;
+
+struct DbusDaemon(Child);
+
+impl Drop for DbusDaemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// What the fake application's controls have been asked to do.
+#[derive(Default)]
+struct AppState {
+    clicks: u32,
+    checked: bool,
+    focused: Option<&'static str>,
+    text: String,
+    selection: Option<(i32, i32)>,
+}
+
+type Shared = Arc<Mutex<AppState>>;
+
+fn lock(state: &Shared) -> MutexGuard<'_, AppState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The accessibility bus plus the fake application served on it. Started
+/// once per test binary; the guard serializes tests over the shared state.
+struct Fixture {
+    _daemon: DbusDaemon,
+    _app: Connection,
+    state: Shared,
+}
+
+fn fixture() -> Option<MutexGuard<'static, Option<Fixture>>> {
+    static FIXTURE: OnceLock<Mutex<Option<Fixture>>> = OnceLock::new();
+
+    let fixture = FIXTURE.get_or_init(|| {
+        let child = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let Ok(mut child) = child else {
+            eprintln!("dbus-daemon is not installed; skipping AT-SPI tests");
+            return Mutex::new(None);
+        };
+
+        let mut address = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            let _ = BufReader::new(stdout).read_line(&mut address);
+        }
+        let address = address.trim().to_string();
+        // SAFETY: set once, before the backend first connects to the bus.
+        unsafe { std::env::set_var("AT_SPI_BUS_ADDRESS", &address) };
+
+        let daemon = DbusDaemon(child);
+        let state = Shared::default();
+        let app = serve_test_app(&address, &state);
+        Mutex::new(Some(Fixture {
+            _daemon: daemon,
+            _app: app,
+            state,
+        }))
+    });
+
+    let guard = fixture.lock().unwrap_or_else(PoisonError::into_inner);
+    guard.is_some().then_some(guard)
+}
+
+fn path(path: &str) -> OwnedObjectPath {
+    OwnedObjectPath::try_from(path).unwrap()
+}
+
+struct Accessible {
+    bus_name: String,
+    name: String,
+    role: &'static str,
+    children: Vec<&'static str>,
+    interfaces: Vec<&'static str>,
+    focus_key: &'static str,
+    state: Shared,
+}
+
+#[interface(name = "org.a11y.atspi.Accessible")]
+impl Accessible {
+    fn get_children(&self) -> Vec<(String, OwnedObjectPath)> {
+        self.children
+            .iter()
+            .map(|child| (self.bus_name.clone(), path(child)))
+            .collect()
+    }
+
+    fn get_role_name(&self) -> String {
+        self.role.to_string()
+    }
+
+    fn get_state(&self) -> Vec<u32> {
+        let state = lock(&self.state);
+        let mut bits = 1 << STATE_ENABLED | 1 << STATE_SHOWING;
+        if self.role == "check box" && state.checked {
+            bits |= 1 << STATE_CHECKED;
+        }
+        if state.focused == Some(self.focus_key) {
+            bits |= 1 << STATE_FOCUSED;
+        }
+        drop(state);
+        vec![bits, 0]
+    }
+
+    fn get_interfaces(&self) -> Vec<String> {
+        self.interfaces.iter().map(ToString::to_string).collect()
+    }
+
+    #[zbus(property)]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+struct Component {
+    extents: (i32, i32, i32, i32),
+    focus_key: &'static str,
+    state: Shared,
+}
+
+#[interface(name = "org.a11y.atspi.Component")]
+impl Component {
+    fn get_extents(&self, coord_type: u32) -> (i32, i32, i32, i32) {
+        assert_eq!(coord_type, 0, "extents are requested in screen coordinates");
+        self.extents
+    }
+
+    fn grab_focus(&self) -> bool {
+        lock(&self.state).focused = Some(self.focus_key);
+        true
+    }
+}
+
+struct Action {
+    name: &'static str,
+    state: Shared,
+}
+
+#[interface(name = "org.a11y.atspi.Action")]
+impl Action {
+    fn get_actions(&self) -> Vec<(String, String, String)> {
+        vec![
+            ("focus".to_string(), String::new(), String::new()),
+            (self.name.to_string(), String::new(), String::new()),
+        ]
+    }
+
+    fn do_action(&self, index: i32) -> bool {
+        let mut state = lock(&self.state);
+        match (index, self.name) {
+            (1, "toggle") => state.checked = !state.checked,
+            (1, _) => state.clicks += 1,
+            _ => return false,
+        }
+        true
+    }
+}
+
+struct Text {
+    state: Shared,
+}
+
+#[interface(name = "org.a11y.atspi.Text")]
+impl Text {
+    fn get_text(&self, start: i32, end: i32) -> String {
+        let text = lock(&self.state).text.clone();
+        let start = usize::try_from(start).unwrap_or(0);
+        let end = usize::try_from(end).unwrap_or(text.len());
+        text.get(start..end).unwrap_or_default().to_string()
+    }
+
+    fn get_n_selections(&self) -> i32 {
+        i32::from(lock(&self.state).selection.is_some())
+    }
+
+    #[zbus(out_args("startOffset", "endOffset"))]
+    fn get_selection(&self, index: i32) -> (i32, i32) {
+        assert_eq!(index, 0);
+        lock(&self.state).selection.unwrap_or((0, 0))
+    }
+
+    fn add_selection(&self, start: i32, end: i32) -> bool {
+        lock(&self.state).selection = Some((start, end));
+        true
+    }
+
+    fn set_selection(&self, index: i32, start: i32, end: i32) -> bool {
+        assert_eq!(index, 0);
+        lock(&self.state).selection = Some((start, end));
+        true
+    }
+
+    #[zbus(property)]
+    fn character_count(&self) -> i32 {
+        i32::try_from(lock(&self.state).text.len()).unwrap()
+    }
+
+    #[zbus(property)]
+    fn caret_offset(&self) -> i32 {
+        self.character_count()
+    }
+}
+
+struct EditableText {
+    state: Shared,
+}
+
+#[interface(name = "org.a11y.atspi.EditableText")]
+impl EditableText {
+    fn set_text_contents(&self, text: String) -> bool {
+        let mut state = lock(&self.state);
+        state.text = text;
+        state.selection = None;
+        true
+    }
+}
+
+/// Serves a registry root listing one application with an "Invoice" frame
+/// holding a button, a text entry and a check box.
+fn serve_test_app(address: &str, state: &Shared) -> Connection {
+    let conn = Builder::address(address)
+        .unwrap()
+        .name(REGISTRY_NAME)
+        .unwrap()
+        .build()
+        .unwrap();
+    let bus_name = conn.unique_name().unwrap().to_string();
+    serve_objects(&conn, &bus_name, state);
+    conn
+}
+
+/// One object of the fake application.
+struct Node {
+    path: &'static str,
+    name: &'static str,
+    role: &'static str,
+    children: &'static [&'static str],
+    /// Screen extents; objects with them implement `Component`.
+    extents: Option<(i32, i32, i32, i32)>,
+    /// Name of the object's action; objects with one implement `Action`.
+    action: Option<&'static str>,
+    /// Whether the object implements `Text` and `EditableText`.
+    editable: bool,
+}
+
+const NODES: &[Node] = &[
+    Node {
+        path: ROOT_PATH,
+        name: "main",
+        role: "desktop frame",
+        children: &["/app"],
+        extents: None,
+        action: None,
+        editable: false,
+    },
+    Node {
+        path: "/app",
+        name: "Test App",
+        role: "application",
+        children: &["/frame"],
+        extents: None,
+        action: None,
+        editable: false,
+    },
+    Node {
+        path: "/frame",
+        name: "Invoice",
+        role: "frame",
+        children: &["/button", "/entry", "/checkbox"],
+        extents: Some((100, 120, 400, 300)),
+        action: None,
+        editable: false,
+    },
+    Node {
+        path: "/button",
+        name: "Save",
+        role: "push button",
+        children: &[],
+        extents: Some((110, 380, 80, 30)),
+        action: Some("click"),
+        editable: false,
+    },
+    Node {
+        path: "/entry",
+        name: "",
+        role: "text",
+        children: &[],
+        extents: Some((110, 140, 200, 24)),
+        action: None,
+        editable: true,
+    },
+    Node {
+        path: "/checkbox",
+        name: "Paid",
+        role: "check box",
+        children: &[],
+        extents: Some((110, 180, 120, 20)),
+        action: Some("toggle"),
+        editable: false,
+    },
+];
+
+fn serve_objects(conn: &Connection, bus_name: &str, state: &Shared) {
+    let server = conn.object_server();
+
+    for &Node {
+        path,
+        name,
+        role,
+        children,
+        extents,
+        action,
+        editable,
+    } in NODES
+    {
+        let mut interfaces = vec!["org.a11y.atspi.Accessible"];
+        if let Some(extents) = extents {
+            interfaces.push("org.a11y.atspi.Component");
+            let component = Component {
+                extents,
+                focus_key: path,
+                state: state.clone(),
+            };
+            server.at(path, component).unwrap();
+        }
+        if let Some(name) = action {
+            interfaces.push("org.a11y.atspi.Action");
+            let action = Action {
+                name,
+                state: state.clone(),
+            };
+            server.at(path, action).unwrap();
+        }
+        if editable {
+            interfaces.extend(["org.a11y.atspi.Text", "org.a11y.atspi.EditableText"]);
+            let text = Text {
+                state: state.clone(),
+            };
+            server.at(path, text).unwrap();
+            let editable = EditableText {
+                state: state.clone(),
+            };
+            server.at(path, editable).unwrap();
+        }
+
+        let accessible = Accessible {
+            bus_name: bus_name.to_string(),
+            name: name.to_string(),
+            role,
+            children: children.to_vec(),
+            interfaces,
+            focus_key: path,
+            state: state.clone(),
+        };
+        server.at(path, accessible).unwrap();
+    }
+}
+
+/// The fake application's frame and its controls, by role name.
+fn test_window() -> (Element, Vec<Element>) {
+    let windows = find_application_windows(std::process::id()).unwrap();
+    let window = windows
+        .into_iter()
+        .find(|w| w.text == "Invoice")
+        .expect("frame is exposed as a window");
+    let controls = find_child_elements(window.id).unwrap();
+    (window, controls)
+}
+
+fn control<'a>(controls: &'a [Element], role: &str) -> &'a Element {
+    controls
+        .iter()
+        .find(|c| c.class_name == role)
+        .unwrap_or_else(|| panic!("no {role} control"))
+}
+
+#[test]
+fn accessibles_map_to_elements() {
+    let Some(_fixture) = fixture() else { return };
+    let (window, controls) = test_window();
+
+    assert_eq!(window.element_type, ElementType::Window);
+    assert_eq!(window.class_name, "frame");
+    assert!(window.id.0 < 0);
+    assert_eq!(
+        (
+            window.bounds.left,
+            window.bounds.top,
+            window.bounds.width,
+            window.bounds.height
+        ),
+        (100, 120, 400, 300)
+    );
+    assert_eq!(window.get_process_id(), std::process::id());
+
+    let roles: Vec<_> = controls.iter().map(|c| c.class_name.as_str()).collect();
+    assert_eq!(roles, ["push button", "text", "check box"]);
+    let button = control(&controls, "push button");
+    assert_eq!(button.element_type, ElementType::Control);
+    assert_eq!(button.text, "Save");
+    assert!(button.visible && button.enabled);
+    assert_eq!(
+        (
+            button.bounds.left,
+            button.bounds.top,
+            button.bounds.width,
+            button.bounds.height
+        ),
+        (110, 380, 80, 30)
+    );
+
+    // Handles are stable, so a second walk yields the same ids.
+    let (again, _) = test_window();
+    assert_eq!(again.id, window.id);
+    assert!(find_child_elements(button.id).unwrap().is_empty());
+}
+
+#[test]
+fn controls_can_be_clicked_toggled_and_focused() {
+    let Some(fixture) = fixture() else { return };
+    let state = fixture.as_ref().unwrap().state.clone();
+    let (window, controls) = test_window();
+
+    let clicks = lock(&state).clicks;
+    control(&controls, "push button").click().unwrap();
+    assert_eq!(lock(&state).clicks, clicks + 1);
+
+    let checkbox = control(&controls, "check box");
+    checkbox.check().unwrap();
+    assert!(checkbox.is_checked());
+    checkbox.check().unwrap();
+    assert!(checkbox.is_checked());
+    checkbox.uncheck().unwrap();
+    assert!(!checkbox.is_checked());
+
+    let entry = control(&controls, "text");
+    entry.focus().unwrap();
+    assert!(entry.is_focused());
+    assert!(!checkbox.is_focused());
+
+    // Accessible windows have no X11 window to manage.
+    assert!(entry.minimize().is_err());
+    assert!(matches!(
+        window.minimize(),
+        Err(AutomationError::NotSupported(_))
+    ));
+}
+
+#[test]
+fn text_can_be_set_read_and_selected() {
+    let Some(_fixture) = fixture() else { return };
+    let (_, mut controls) = test_window();
+    let index = controls
+        .iter()
+        .position(|c| c.class_name == "text")
+        .unwrap();
+    let mut entry = controls.remove(index);
+
+    entry.set_text("Invoice 42").unwrap();
+    assert_eq!(entry.get_text_live().unwrap(), "Invoice 42");
+
+    entry.select_text(8, 10).unwrap();
+    assert_eq!(entry.get_selected_range().unwrap(), (8, 10));
+    entry.select_all().unwrap();
+    assert_eq!(entry.get_selected_range().unwrap(), (0, 10));
+
+    let checkbox = control(&controls, "check box");
+    assert!(checkbox.set_text("nope").is_err());
+}
+
+#[test]
+fn controls_are_found_by_point_and_get_resolvable_selectors() {
+    let Some(_fixture) = fixture() else { return };
+    let (window, _) = test_window();
+
+    let button = control_from_point(&window, 150, 390).unwrap().unwrap();
+    assert_eq!(button.text, "Save");
+    assert!(control_from_point(&window, 450, 350).unwrap().is_none());
+
+    let windows = find_application_windows(std::process::id()).unwrap();
+    let dsl = ui_automation::robust_selector(&button, &window, &windows).unwrap();
+    assert!(
+        dsl.ends_with(">>Control>role~=push button;text~=Save"),
+        "{dsl}"
+    );
+
+    let selector = ui_automation::Selector::parse(&dsl).unwrap();
+    let windows = find_application_windows(std::process::id()).unwrap();
+    let found = ui_automation::selector::resolve(&selector, windows).unwrap();
+    assert_eq!(found.id, button.id);
+}
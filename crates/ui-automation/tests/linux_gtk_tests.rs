@@ -0,0 +1,115 @@
+#![cfg(target_os = "linux")]
+//! Drives a real GTK dialog (`zenity`) under Xvfb through the AT-SPI backend,
+//! with the system accessibility bus and registry instead of the fake ones in
+//! `linux_atspi_tests.rs`. It needs Xvfb, dbus-daemon, at-spi2-core and
+//! zenity, which CI does not install, so it is ignored by default:
+//!
+//! ```text
+//! cargo test -p ui-automation --test linux_gtk_tests -- --ignored
+//! ```
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use ui_automation::Selector;
+use ui_automation::linux::automation::*;
+use ui_automation::selector::resolve;
+
+/// How long the dialog gets to show up on the accessibility bus.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(15);
+
+struct Process(Child);
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Starts `program`, which prints an address on its first line of output.
+fn spawn_printing(program: &str, args: &[&str]) -> (Process, String) {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to start {program}: {e}"));
+    let mut line = String::new();
+    if let Some(stdout) = child.stdout.take() {
+        BufReader::new(stdout).read_line(&mut line).unwrap();
+    }
+    (Process(child), line.trim().to_string())
+}
+
+fn wait_for<T>(mut found: impl FnMut() -> Option<T>) -> T {
+    let started = Instant::now();
+    loop {
+        if let Some(value) = found() {
+            return value;
+        }
+        assert!(
+            started.elapsed() < STARTUP_TIMEOUT,
+            "dialog did not appear on the accessibility bus"
+        );
+        sleep(Duration::from_millis(200));
+    }
+}
+
+#[test]
+#[ignore = "needs Xvfb, dbus-daemon, at-spi2-core and zenity"]
+fn gtk_entry_dialog_is_filled_and_confirmed() {
+    let (_xvfb, display) = spawn_printing(
+        "Xvfb",
+        &[
+            "-displayfd",
+            "1",
+            "-screen",
+            "0",
+            "1024x768x24",
+            "-nolisten",
+            "tcp",
+        ],
+    );
+    let (_bus, address) = spawn_printing(
+        "dbus-daemon",
+        &["--session", "--nofork", "--print-address=1"],
+    );
+    // SAFETY: set before anything in this test binary connects to X or D-Bus.
+    unsafe {
+        std::env::set_var("DISPLAY", format!(":{display}"));
+        std::env::set_var("DBUS_SESSION_BUS_ADDRESS", &address);
+        std::env::remove_var("AT_SPI_BUS_ADDRESS");
+    }
+
+    let dialog = Command::new("zenity")
+        .args(["--entry", "--title=Invoice", "--text=Number"])
+        .env("NO_AT_BRIDGE", "0")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to start zenity");
+    let pid = dialog.id();
+    let mut dialog = Process(dialog);
+
+    let windows = wait_for(|| {
+        find_application_windows(pid)
+            .ok()
+            .filter(|windows| windows.iter().any(|w| w.id.0 < 0 && w.text == "Invoice"))
+    });
+    let entry = Selector::parse("Window>title~=Invoice>>Control>role~=text").unwrap();
+    let mut entry = resolve(&entry, windows).unwrap();
+    entry.set_text("INV-42").unwrap();
+    assert_eq!(entry.get_text_live().unwrap(), "INV-42");
+
+    let windows = find_application_windows(pid).unwrap();
+    let ok = Selector::parse("Window>title~=Invoice>>Control>role~=push button;text~=OK").unwrap();
+    resolve(&ok, windows).unwrap().click().unwrap();
+
+    let mut answer = String::new();
+    let stdout = dialog.0.stdout.take().unwrap();
+    BufReader::new(stdout).read_line(&mut answer).unwrap();
+    assert_eq!(answer.trim_end(), "INV-42");
+}
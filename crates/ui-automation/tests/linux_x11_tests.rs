@@ -0,0 +1,313 @@
+#![cfg(target_os = "linux")]
+//! Drives the X11 backend against a private Xvfb server and a small test
+//! window. The tests are skipped when `Xvfb` is not installed.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread::sleep;
+use std::time::Duration;
+
+use ui_automation::linux::automation::*;
+use ui_automation::{MatchMode, Selector};
+use x11rb::connection::Connection;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{
+    AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, KeyButMask, PropMode, Window,
+    WindowClass,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+struct Xvfb(Child);
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Starts Xvfb once for the whole test binary and points `DISPLAY` at it.
+/// The returned guard serializes tests, since they share pointer and focus.
+fn x_server() -> Option<MutexGuard<'static, Option<Xvfb>>> {
+    static SERVER: OnceLock<Mutex<Option<Xvfb>>> = OnceLock::new();
+
+    let server = SERVER.get_or_init(|| {
+        let child = Command::new("Xvfb")
+            .args([
+                "-displayfd",
+                "1",
+                "-screen",
+                "0",
+                "1024x768x24",
+                "-nolisten",
+                "tcp",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let Ok(mut child) = child else {
+            eprintln!("Xvfb is not installed; skipping X11 tests");
+            return Mutex::new(None);
+        };
+
+        let mut display = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            let _ = BufReader::new(stdout).read_line(&mut display);
+        }
+        // SAFETY: set once, before any test touches the X server.
+        unsafe { std::env::set_var("DISPLAY", format!(":{}", display.trim())) };
+        Mutex::new(Some(Xvfb(child)))
+    });
+
+    let guard = server
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    guard.is_some().then_some(guard)
+}
+
+/// The "application under test": a top-level window with EWMH/ICCCM
+/// properties that records the input events it receives.
+struct TestApp {
+    conn: RustConnection,
+    window: Window,
+    title: String,
+}
+
+impl TestApp {
+    fn open(title: &str, class: &str) -> Self {
+        let (conn, screen_num) = RustConnection::connect(None).unwrap();
+        let screen = &conn.setup().roots[screen_num];
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            screen.root_depth,
+            window,
+            screen.root,
+            100,
+            120,
+            400,
+            300,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            screen.root_visual,
+            &CreateWindowAux::new().event_mask(
+                EventMask::KEY_PRESS | EventMask::BUTTON_PRESS | EventMask::STRUCTURE_NOTIFY,
+            ),
+        )
+        .unwrap();
+
+        let atom = |name: &str| {
+            conn.intern_atom(false, name.as_bytes())
+                .unwrap()
+                .reply()
+                .unwrap()
+                .atom
+        };
+        let (net_wm_name, utf8, net_wm_pid) = (
+            atom("_NET_WM_NAME"),
+            atom("UTF8_STRING"),
+            atom("_NET_WM_PID"),
+        );
+        let (wm_protocols, wm_delete_window) = (atom("WM_PROTOCOLS"), atom("WM_DELETE_WINDOW"));
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            net_wm_name,
+            utf8,
+            title.as_bytes(),
+        )
+        .unwrap();
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_CLASS,
+            AtomEnum::STRING,
+            format!("{}\0{class}\0", class.to_lowercase()).as_bytes(),
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            net_wm_pid,
+            AtomEnum::CARDINAL,
+            &[std::process::id()],
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            wm_protocols,
+            AtomEnum::ATOM,
+            &[wm_delete_window],
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.sync().unwrap();
+
+        Self {
+            conn,
+            window,
+            title: title.to_string(),
+        }
+    }
+
+    fn element(&self) -> Element {
+        let element = find_element_by_selector(&format!("Window>title~={}", self.title)).unwrap();
+        assert_eq!(element.id.0, isize::try_from(self.window).unwrap());
+        element
+    }
+
+    /// Events delivered to the window since the last call.
+    fn events(&self) -> Vec<Event> {
+        sleep(Duration::from_millis(100));
+        self.conn.sync().unwrap();
+        std::iter::from_fn(|| self.conn.poll_for_event().unwrap()).collect()
+    }
+
+    /// The keysyms of the key presses received, honouring Shift.
+    fn typed_keysyms(&self) -> Vec<u32> {
+        let setup = self.conn.setup();
+        let mapping = self
+            .conn
+            .get_keyboard_mapping(setup.min_keycode, setup.max_keycode - setup.min_keycode + 1)
+            .unwrap()
+            .reply()
+            .unwrap();
+        let per_keycode = usize::from(mapping.keysyms_per_keycode);
+
+        self.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::KeyPress(key) => {
+                    let index = usize::from(key.detail - setup.min_keycode) * per_keycode;
+                    let level = usize::from(key.state.contains(KeyButMask::SHIFT));
+                    Some(mapping.keysyms[index + level])
+                }
+                _ => None,
+            })
+            .filter(|&keysym| !(0xffe1..=0xffee).contains(&keysym))
+            .collect()
+    }
+}
+
+#[test]
+fn windows_are_enumerated_with_title_class_and_pid() {
+    let Some(_server) = x_server() else { return };
+    let app = TestApp::open("Invoice 42 – Test App", "RpaTestApp");
+
+    let windows = find_windows().unwrap();
+    let window = windows
+        .iter()
+        .find(|w| w.id.0 == isize::try_from(app.window).unwrap())
+        .expect("test window is listed");
+    assert_eq!(window.text, "Invoice 42 – Test App");
+    assert_eq!(window.class_name, "RpaTestApp");
+    assert!(window.visible);
+    assert_eq!(
+        (
+            window.bounds.left,
+            window.bounds.top,
+            window.bounds.width,
+            window.bounds.height
+        ),
+        (100, 120, 400, 300)
+    );
+    assert_eq!(window.get_process_id(), std::process::id());
+
+    let found = find_element_by_selector("Window>title~invoice 42;class~=rpatestapp").unwrap();
+    assert_eq!(found.id, window.id);
+    assert!(find_element_by_selector("Window>title~=No such window").is_err());
+
+    // Only the class drifted, which the strict lookup does not tolerate
+    let drifted = Selector::parse("Window>title~=Invoice 42 – Test App;class~=RenamedApp").unwrap();
+    assert!(find_element_by_selector_obj(&drifted, None).is_err());
+    let found =
+        find_element_by_selector_obj_with_mode(&drifted, Some(1000), MatchMode::fuzzy()).unwrap();
+    assert_eq!(found.id, window.id);
+}
+
+#[test]
+fn windows_can_be_moved_resized_minimized_and_activated() {
+    let Some(_server) = x_server() else { return };
+    let app = TestApp::open("Test App geometry", "RpaTestApp");
+    let mut element = app.element();
+
+    element.move_to(40, 50).unwrap();
+    element.resize(320, 200).unwrap();
+    element.refresh().unwrap();
+    assert_eq!(
+        (
+            element.bounds.left,
+            element.bounds.top,
+            element.bounds.width,
+            element.bounds.height
+        ),
+        (40, 50, 320, 200)
+    );
+
+    element.minimize().unwrap();
+    assert!(element.is_minimized());
+    assert!(!element.is_visible());
+    element.restore().unwrap();
+    assert!(element.is_visible());
+
+    element.maximize().unwrap();
+    assert!(element.is_maximized());
+
+    element.activate().unwrap();
+    assert!(element.is_focused());
+    assert_eq!(get_foreground_window().unwrap().id, element.id);
+}
+
+#[test]
+fn mouse_and_keyboard_input_reaches_the_window() {
+    let Some(_server) = x_server() else { return };
+    let app = TestApp::open("Test App input", "RpaTestApp");
+    let element = app.element();
+    element.activate().unwrap();
+    app.events();
+
+    element.click().unwrap();
+    let presses: Vec<_> = app
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::ButtonPress(button) => Some((button.detail, button.event_x, button.event_y)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(presses, [(1, 200, 150)]);
+
+    type_text("Hi 5!").unwrap();
+    assert_eq!(
+        app.typed_keysyms(),
+        "Hi 5!".chars().map(u32::from).collect::<Vec<_>>()
+    );
+
+    key_sequence("ctrl+a").unwrap();
+    let ctrl_a = app.events().into_iter().any(
+        |event| matches!(event, Event::KeyPress(key) if key.state.contains(KeyButMask::CONTROL)),
+    );
+    assert!(ctrl_a);
+
+    press_key_by_name("enter").unwrap();
+    assert_eq!(app.typed_keysyms(), [0xff0d]);
+}
+
+#[test]
+fn titles_can_be_set_and_windows_asked_to_close() {
+    let Some(_server) = x_server() else { return };
+    let app = TestApp::open("Test App close", "RpaTestApp");
+    let mut element = app.element();
+
+    element.set_text("Renamed").unwrap();
+    assert_eq!(element.get_text_live().unwrap(), "Renamed");
+
+    element.close().unwrap();
+    let asked_to_close = app
+        .events()
+        .into_iter()
+        .any(|event| matches!(event, Event::ClientMessage(_)));
+    assert!(asked_to_close);
+}
@@ -0,0 +1,286 @@
+//! Selector resolution through the public [`SelectorElement`] trait, over an
+//! in-memory element tree, so it runs on every platform. The tests in
+//! `selector_tests.rs` repeat the main cases against Notepad on Windows.
+
+use ui_automation::automation::AutomationError;
+use ui_automation::selector::{resolve, resolve_fuzzy, resolve_with};
+use ui_automation::{
+    MatchMode, Selector, SelectorElement, control_to_selector, robust_selector, window_to_selector,
+};
+
+#[derive(Debug, Clone)]
+struct Node {
+    key: isize,
+    window: bool,
+    text: String,
+    class_name: String,
+    role: String,
+    automation_id: String,
+    pid: u32,
+    process_name: String,
+    enabled: bool,
+    visible: bool,
+    children: Vec<Self>,
+}
+
+impl Node {
+    fn window(key: isize, title: &str, class_name: &str, pid: u32, process: &str) -> Self {
+        Self {
+            key,
+            window: true,
+            text: title.to_string(),
+            class_name: class_name.to_string(),
+            role: "frame".to_string(),
+            automation_id: String::new(),
+            pid,
+            process_name: process.to_string(),
+            enabled: true,
+            visible: true,
+            children: Vec::new(),
+        }
+    }
+
+    fn control(key: isize, role: &str, text: &str, class_name: &str) -> Self {
+        Self {
+            key,
+            window: false,
+            text: text.to_string(),
+            class_name: class_name.to_string(),
+            role: role.to_string(),
+            automation_id: String::new(),
+            pid: 0,
+            process_name: String::new(),
+            enabled: true,
+            visible: true,
+            children: Vec::new(),
+        }
+    }
+
+    fn id(mut self, automation_id: &str) -> Self {
+        self.automation_id = automation_id.to_string();
+        self
+    }
+
+    const fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    const fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
+
+    fn containing(mut self, children: Vec<Self>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+impl SelectorElement for Node {
+    fn key(&self) -> isize {
+        self.key
+    }
+
+    fn is_window(&self) -> bool {
+        self.window
+    }
+
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn process_id(&self) -> u32 {
+        self.pid
+    }
+
+    fn process_name(&self) -> String {
+        self.process_name.clone()
+    }
+
+    fn automation_id(&self) -> String {
+        self.automation_id.clone()
+    }
+
+    fn role(&self) -> String {
+        self.role.clone()
+    }
+
+    fn children(&self) -> Result<Vec<Self>, AutomationError> {
+        Ok(self.children.clone())
+    }
+}
+
+/// An editor with a nested toolbar, a second editor window and a hidden
+/// settings dialog
+fn desktop() -> Vec<Node> {
+    vec![
+        Node::window(1, "report.txt - Notepad", "Notepad", 400, "notepad.exe").containing(vec![
+            Node::control(10, "tool bar", "", "ToolbarWindow32").containing(vec![
+                Node::control(11, "push button", "Save", "Button").id("save"),
+                Node::control(12, "push button", "Save As", "Button"),
+                Node::control(13, "push button", "Print", "Button").disabled(),
+            ]),
+            Node::control(14, "text", "Q3 revenue", "Edit").id("document"),
+            Node::control(15, "status bar", "Ln 1, Col 1", "msctls_statusbar32"),
+        ]),
+        Node::window(2, "notes; a>b - Notepad", "Notepad", 401, "notepad.exe")
+            .containing(vec![Node::control(20, "text", "", "Edit")]),
+        Node::window(3, "Settings", "#32770", 400, "notepad.exe")
+            .hidden()
+            .containing(vec![Node::control(30, "push button", "OK", "Button")]),
+    ]
+}
+
+fn find(dsl: &str) -> Result<Node, AutomationError> {
+    resolve(&Selector::parse(dsl).unwrap(), desktop())
+}
+
+fn key(dsl: &str) -> isize {
+    find(dsl).unwrap_or_else(|e| panic!("{dsl}: {e}")).key
+}
+
+#[test]
+fn test_resolve_window_by_title_and_class() {
+    assert_eq!(key("Window>title~report"), 1);
+    assert_eq!(key("Window>class~#32770"), 3);
+    assert_eq!(key("Window>title~Notepad;class~Notepad;pid~401"), 2);
+    assert_eq!(key("Window>process~=notepad.exe;visible~false"), 3);
+}
+
+#[test]
+fn test_resolve_match_operators_ignore_case() {
+    assert_eq!(key("Window>title~=REPORT.TXT - NOTEPAD"), 1);
+    assert_eq!(key("Window>title~*notes"), 2);
+    assert_eq!(key("Window>title~$settings"), 3);
+    assert!(find("Window>title~=report.txt").is_err());
+}
+
+#[test]
+fn test_resolve_regex() {
+    assert_eq!(key(r"Window>title~regex:^notes.*Notepad$"), 2);
+    assert_eq!(key(r"Window>title~report>>Control>text~regex:^Q\d"), 14);
+    assert_eq!(key(r"Window>class~regex:^#\d+$>Control>text~OK"), 30);
+    assert!(find(r"Window>title~regex:^Notepad$").is_err());
+}
+
+#[test]
+fn test_resolve_child_and_descendant_steps() {
+    // `>` looks one level down, `>>` at any depth
+    assert!(find("Window>title~report>Control>text~=Save").is_err());
+    assert_eq!(key("Window>title~report>>Control>text~=Save"), 11);
+    assert_eq!(
+        key("Window>title~report>Control>role~=tool bar>Control>text~Print"),
+        13
+    );
+}
+
+#[test]
+fn test_resolve_index_negation_and_state() {
+    assert_eq!(key("Window>title~report>>Control>role~=push button"), 11);
+    assert_eq!(
+        key("Window>title~report>>Control>role~=push button;index~1"),
+        12
+    );
+    assert_eq!(
+        key("Window>title~report>>Control>role~=push button;text!~Save"),
+        13
+    );
+    assert_eq!(
+        key("Window>title~report>>Control>role~=push button;enabled~false"),
+        13
+    );
+    assert_eq!(
+        key("Window>title~report>>Control>automationid~=document"),
+        14
+    );
+    assert!(find("Window>title~report>>Control>role~=push button;index~3").is_err());
+}
+
+#[test]
+fn test_resolve_errors() {
+    let missing_window = find("Window>title~Calculator").unwrap_err();
+    assert!(matches!(
+        missing_window,
+        AutomationError::WindowNotFound { .. }
+    ));
+    let missing_control = find("Window>title~report>>Control>class~ComboBox").unwrap_err();
+    assert!(missing_control.to_string().contains("path 1"));
+    assert!(matches!(
+        find("Window>title~report>Window>title~report"),
+        Err(AutomationError::Other(_))
+    ));
+}
+
+#[test]
+fn test_generated_selectors_resolve_to_their_element() {
+    let windows = desktop();
+    let notes = &windows[1];
+    let window_dsl = window_to_selector(notes).unwrap();
+    assert_eq!(
+        window_dsl,
+        r"Window>title~notes\; a\>b - Notepad;class~Notepad"
+    );
+    assert_eq!(key(&window_dsl), 2);
+
+    let report = &windows[0];
+    let save_as = &report.children[0].children[1];
+    let control_dsl = control_to_selector(save_as, report).unwrap();
+    assert_eq!(key(&control_dsl), 12);
+
+    let print = &report.children[0].children[2];
+    let robust = robust_selector(print, report, &windows).unwrap();
+    assert_eq!(
+        robust,
+        "Window>process~=notepad.exe;class~=Notepad;title~=report.txt - Notepad\
+         >>Control>role~=push button;text~=Print"
+    );
+    assert_eq!(key(&robust), 13);
+}
+
+#[test]
+fn test_resolve_with_fuzzy_mode_survives_drift() {
+    let drifted = Selector::parse(
+        "Window>title~=budget.txt - Notepad;class~=Notepad>>Control>automationid~=save;text~=Save",
+    )
+    .unwrap();
+    assert!(resolve_with(&drifted, desktop(), MatchMode::Strict).is_err());
+
+    let fuzzy = MatchMode::Fuzzy {
+        threshold: MatchMode::DEFAULT_FUZZY_THRESHOLD,
+    };
+    assert_eq!(resolve_with(&drifted, desktop(), fuzzy).unwrap().key, 11);
+    let found = resolve_fuzzy(&drifted, desktop(), 0.6).unwrap();
+    assert!(found.score < 1.0);
+    assert_eq!(found.failed, ["path 0: title~=budget.txt - Notepad"]);
+
+    // Nothing of the control step matches, so no threshold lets it through
+    let unrelated =
+        Selector::parse("Window>title~=report.txt - Notepad>>Control>automationid~=print").unwrap();
+    assert!(resolve_fuzzy(&unrelated, desktop(), 0.0).is_err());
+}
+
+#[test]
+fn test_selector_file_roundtrip_resolves() {
+    let path = std::env::temp_dir().join(format!("selector-{}.txt", std::process::id()));
+    let path = path.to_str().unwrap();
+    let selector = Selector::parse(r"Window>title~notes\; a\>b>>Control>class~=Edit").unwrap();
+    selector.to_file(path).unwrap();
+    let loaded = Selector::from_file(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(loaded.to_dsl(), selector.to_dsl());
+    assert_eq!(resolve(&loaded, desktop()).unwrap().key, 20);
+}
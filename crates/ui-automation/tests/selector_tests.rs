@@ -1,8 +1,10 @@
+#![cfg(windows)]
+
 use std::thread::sleep;
 use std::time::Duration;
 
+use ui_automation::selector::{self, control_to_selector, window_to_selector};
 use ui_automation::win32::automation::*;
-use ui_automation::win32::selector;
 
 #[test]
 fn test_selector_find_window_by_title() {
@@ -1020,3 +1022,210 @@ fn test_generated_selector_vs_manual_selector_notepad() {
     let _ = app.close();
     sleep(Duration::from_millis(100));
 }
+
+#[test]
+fn test_selector_find_by_process_and_pid() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let window = find_window_by_selector("Window>process~=notepad.exe;title~Notepad").unwrap();
+    assert_eq!(window.get_process_name().to_lowercase(), "notepad.exe");
+
+    let by_pid = format!("Window>pid~{};title~Notepad", window.get_process_id());
+    let found = find_window_by_selector(&by_pid).unwrap();
+    assert_eq!(found.id.0, window.id.0);
+
+    assert!(find_window_by_selector("Window>process~=no_such_process.exe").is_err());
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_regex_on_class_and_text() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let control =
+        find_control_by_selector("Window>class~regex:^notepad$>Control>class~regex:^(rich)?edit");
+    assert!(control.is_ok());
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_role_and_state_attributes() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let window = find_window_by_selector("Window>title~Notepad;role~=frame;visible~true").unwrap();
+    assert_eq!(window.get_role(), "frame");
+
+    let control = find_control_by_selector("Window>title~Notepad>Control>role~=text;enabled~yes");
+    assert!(control.is_ok());
+    assert_eq!(control.unwrap().get_role(), "text");
+
+    assert!(find_window_by_selector("Window>title~Notepad;visible~false").is_err());
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_automation_id() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let edit = find_control_by_selector("Window>title~Notepad>Control>class~Edit").unwrap();
+    let automation_id = edit.get_automation_id();
+
+    if !automation_id.is_empty() {
+        let selector_str = format!("Window>title~Notepad>Control>automationid~={automation_id}");
+        let found = find_control_by_selector(&selector_str).unwrap();
+        assert_eq!(found.id.0, edit.id.0);
+    }
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_descendant_combinator() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let child = find_control_by_selector("Window>title~Notepad>Control>class~Edit").unwrap();
+    let descendant = find_control_by_selector("Window>title~Notepad>>Control>class~Edit").unwrap();
+    assert_eq!(child.id.0, descendant.id.0);
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_index_picks_nth_match() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let window = find_window_by_selector("Window>title~Notepad").unwrap();
+    let controls = find_child_elements(window.id.as_hwnd()).unwrap();
+
+    for (index, expected) in controls.iter().enumerate() {
+        let selector_str = format!("Window>title~Notepad>Control>index~{index}");
+        let found = find_control_by_selector(&selector_str).unwrap();
+        assert_eq!(found.id.0, expected.id.0);
+    }
+
+    let out_of_range = format!("Window>title~Notepad>Control>index~{}", controls.len());
+    assert!(find_control_by_selector(&out_of_range).is_err());
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_negation() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let control = find_control_by_selector("Window>title~Notepad>Control>class!~Edit");
+    if let Ok(control) = control {
+        assert!(!control.class_name.to_lowercase().contains("edit"));
+    }
+
+    assert!(find_window_by_selector("Window>title~Notepad;title!~Notepad").is_err());
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_generated_index_resolves_to_same_control() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let window = find_window_by_selector("Window>title~Notepad").unwrap();
+    for control in find_child_elements(window.id.as_hwnd()).unwrap() {
+        let Ok(generated) = control_to_selector(&control, &window) else {
+            continue;
+        };
+        let found = find_control_by_selector(&generated).unwrap();
+        assert_eq!(found.id.0, control.id.0, "selector {generated}");
+    }
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn test_selector_parse_richer_dsl() {
+    let selector = selector::Selector::parse(
+        "Window>process~=notepad.exe;index~1>>Control>role~=push button;text!~Cancel;index~0",
+    )
+    .unwrap();
+    assert_eq!(selector.path.len(), 2);
+    assert_eq!(selector.path[0].index, Some(1));
+    assert_eq!(
+        selector.path[1].combinator,
+        selector::Combinator::Descendant
+    );
+    assert!(selector.path[1].criteria[1].negated);
+
+    let invalid_cases = vec![
+        "Window>pid~notepad",
+        "Window>enabled~sometimes",
+        "Window>title~Notepad>>",
+        "Window>index~1;index~2",
+    ];
+    for invalid_dsl in invalid_cases {
+        let result = selector::Selector::parse(invalid_dsl);
+        assert!(result.is_err(), "Expected error for: {invalid_dsl}");
+    }
+}
+
+#[test]
+fn test_selector_fuzzy_survives_changed_title() {
+    let result = launch_application("notepad.exe", "");
+    assert!(result.is_ok());
+
+    let app = result.unwrap();
+    sleep(Duration::from_millis(300));
+
+    let selector = selector::Selector::parse(
+        "Window>title~=Untitled - Notepad 2.0;process~=notepad.exe>Control>class~Edit",
+    )
+    .unwrap();
+    assert!(find_element_by_selector_obj(&selector).is_err());
+
+    let control = find_element_by_selector_obj_with_mode(&selector, selector::MatchMode::fuzzy());
+    assert!(control.is_ok());
+    assert_eq!(control.unwrap().element_type, ElementType::Control);
+
+    app.close().unwrap();
+    sleep(Duration::from_millis(100));
+}
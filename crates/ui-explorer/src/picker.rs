@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+use ui_automation::{
+    Element, control_from_point, find_element_by_selector, find_windows, get_cursor_position,
+    is_left_button_down, robust_selector, show_overlay_on_rect, window_from_point,
+};
+
+use crate::properties::{ElementType, SelectedElement};
+use crate::state::UiExplorerState;
+
+const HIGHLIGHT_COLOR: (u8, u8, u8) = (0, 120, 215);
+const HIGHLIGHT_BORDER_WIDTH: i32 = 3;
+/// How long one highlight stays up; it is renewed while the cursor stays
+const HIGHLIGHT_DURATION_MS: u32 = 400;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// "Pick element" mode: highlights the element under the cursor and captures
+/// it with the next click.
+#[derive(Clone, Default)]
+pub struct PickerState {
+    pub active: bool,
+    pub result: Option<Result<PickedElement, String>>,
+    /// Highlighted element and when its highlight was drawn
+    highlight: Option<(isize, Instant)>,
+    button_was_down: bool,
+    /// Selector of a new capture, until the host takes it
+    new_selector: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct PickedElement {
+    pub selector: String,
+    /// Set when the selector does not resolve back to the picked element
+    pub warning: Option<String>,
+}
+
+impl PickerState {
+    pub fn start(&mut self) {
+        self.active = true;
+        self.result = None;
+        self.highlight = None;
+        self.button_was_down = is_left_button_down();
+    }
+
+    pub fn cancel(&mut self) {
+        self.active = false;
+        self.highlight = None;
+    }
+
+    /// The selector of a newly captured element, returned once
+    pub fn take_new_selector(&mut self) -> Option<String> {
+        self.new_selector.take()
+    }
+
+    fn highlight(&mut self, element: &Element) {
+        let renew_after = Duration::from_millis(u64::from(HIGHLIGHT_DURATION_MS)) - POLL_INTERVAL;
+        let current = self
+            .highlight
+            .is_some_and(|(id, at)| id == element.id.0 && at.elapsed() < renew_after);
+        if current {
+            return;
+        }
+
+        let _ = show_overlay_on_rect(
+            element.bounds,
+            HIGHLIGHT_COLOR,
+            HIGHLIGHT_DURATION_MS,
+            HIGHLIGHT_BORDER_WIDTH,
+        );
+        self.highlight = Some((element.id.0, Instant::now()));
+    }
+}
+
+/// Runs one step of the pick mode: highlights the hovered element and, when
+/// the left button goes down, captures it and selects it in the explorer.
+pub fn update_picker(ctx: &egui::Context, state: &mut UiExplorerState) {
+    let picker = &mut state.picker;
+    if !picker.active {
+        return;
+    }
+    ctx.request_repaint_after(POLL_INTERVAL);
+
+    if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        picker.cancel();
+        return;
+    }
+
+    let button_down = is_left_button_down();
+    let pressed = button_down && !picker.button_was_down;
+    picker.button_was_down = button_down;
+
+    let Some((window, control)) = element_under_cursor() else {
+        return;
+    };
+
+    if !pressed {
+        picker.highlight(control.as_ref().unwrap_or(&window));
+        return;
+    }
+
+    picker.cancel();
+    let result = capture(&window, control.as_ref());
+    if let Ok(picked) = &result {
+        picker.new_selector = Some(picked.selector.clone());
+    }
+    picker.result = Some(result);
+    state.selected_node_id = None;
+    state.selected_element = Some(selected_element(&window, control.as_ref()));
+}
+
+/// The window and innermost control under the cursor; the explorer's own
+/// windows are skipped so its buttons stay usable while picking.
+fn element_under_cursor() -> Option<(Element, Option<Element>)> {
+    let (x, y) = get_cursor_position().ok()?;
+    let window = window_from_point(x, y).ok()?;
+    if window.get_process_id() == std::process::id() {
+        return None;
+    }
+    let control = control_from_point(&window, x, y).ok().flatten();
+    Some((window, control))
+}
+
+/// Generates the selector for the picked element and checks that it finds
+/// that element again.
+fn capture(window: &Element, control: Option<&Element>) -> Result<PickedElement, String> {
+    let element = control.unwrap_or(window);
+    let windows = find_windows().map_err(|e| e.to_string())?;
+    let selector = robust_selector(element, window, &windows).map_err(|e| e.to_string())?;
+
+    let warning = match find_element_by_selector(&selector) {
+        Ok(found) if found.id == element.id => None,
+        Ok(_) => Some("Selector resolves to a different element".to_string()),
+        Err(e) => Some(format!("Selector does not resolve: {}", e)),
+    };
+
+    Ok(PickedElement { selector, warning })
+}
+
+fn selected_element(window: &Element, control: Option<&Element>) -> SelectedElement {
+    SelectedElement {
+        element_type: if control.is_some() {
+            ElementType::Control
+        } else {
+            ElementType::Window
+        },
+        window_title: window.text.clone(),
+        window_class: window.class_name.clone(),
+        window_hwnd: window.id.0,
+        window_bounds: window.bounds,
+        control_class: control.map(|c| c.class_name.clone()),
+        control_text: control.map(|c| c.text.clone()),
+        control_hwnd: control.map(|c| c.id.0),
+        control_bounds: control.map(|c| c.bounds),
+    }
+}
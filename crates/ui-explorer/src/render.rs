@@ -1,11 +1,25 @@
-use crate::{properties::render_properties, state::UiExplorerState, window_tree::render_tree};
+use crate::{
+    picker::update_picker, properties::render_properties, state::UiExplorerState,
+    window_tree::render_tree,
+};
 
 pub fn render_ui_explorer_content(ui: &mut egui::Ui, state: &mut UiExplorerState) {
+    update_picker(ui.ctx(), state);
+
     ui.horizontal(|ui| {
         if ui.button("🔄 Refresh").clicked() {
             state.refresh_windows();
         }
 
+        if state.picker.active {
+            if ui.button("✖ Cancel").clicked() {
+                state.picker.cancel();
+            }
+            ui.label("Hover an element and click it to capture (Esc cancels)");
+        } else if ui.button("🎯 Pick element").clicked() {
+            state.picker.start();
+        }
+
         let prev_show_hidden = state.show_hidden_windows;
         ui.checkbox(&mut state.show_hidden_windows, "Show hidden windows");
 
@@ -23,6 +37,25 @@ pub fn render_ui_explorer_content(ui: &mut egui::Ui, state: &mut UiExplorerState
         }
     });
 
+    match &state.picker.result {
+        Some(Ok(picked)) => {
+            ui.horizontal(|ui| {
+                ui.label("Picked:");
+                ui.label(egui::RichText::new(&picked.selector).monospace());
+                if ui.button("📋 Copy").clicked() {
+                    ui.ctx().copy_text(picked.selector.clone());
+                }
+            });
+            if let Some(warning) = &picked.warning {
+                ui.label(format!("⚠ {}", warning));
+            }
+        }
+        Some(Err(e)) => {
+            ui.label(format!("❌ Could not capture element: {}", e));
+        }
+        None => {}
+    }
+
     ui.separator();
 
     egui::SidePanel::left("ui_explorer_side_panel")
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use shared::NanoId;
 use ui_automation::automation::{Element, Rect, find_windows};
 
+use crate::picker::PickerState;
 use crate::properties::{SelectedElement, WindowNode};
 
 fn is_real_window(window: &Element) -> bool {
@@ -26,6 +27,7 @@ pub struct UiExplorerState {
     pub show_hidden_windows: bool,
     pub expanded_nodes: HashSet<NanoId>,
     pub selected_node_id: Option<NanoId>,
+    pub picker: PickerState,
 }
 
 impl UiExplorerState {